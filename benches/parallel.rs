@@ -0,0 +1,35 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+extern crate rtf_parser;
+use rtf_parser::Lexer;
+
+// Best time of several runs, to limit the noise of the other processes
+fn measure<F: FnMut()>(runs: usize, mut run: F) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// Compare the sequential and the parallel lexers on the 500kB sample, and on a larger document made of the sample body repeated
+// Run with `cargo bench --features parallel`
+fn main() {
+    let sample = include_str!("../resources/tests/file-sample_500kB.rtf").trim();
+    let body = &sample[1..sample.len() - 1];
+    let large = format!("{{{}}}", body.repeat(100));
+    for (name, rtf, runs) in [("500kB", sample, 50), ("50MB", large.as_str(), 5)] {
+        assert_eq!(Lexer::scan(rtf).unwrap(), Lexer::scan_parallel(rtf).unwrap());
+        let sequential = measure(runs, || {
+            black_box(Lexer::scan(black_box(rtf)).unwrap());
+        });
+        let parallel = measure(runs, || {
+            black_box(Lexer::scan_parallel(black_box(rtf)).unwrap());
+        });
+        println!("{name} : sequential {sequential:.2?}, parallel {parallel:.2?}");
+    }
+}
@@ -0,0 +1,171 @@
+/// Python bindings of the rtf-parser crate : the document model is mapped to read-only Python classes
+use std::collections::BTreeMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rtf::{Alignment, Painter, Paragraph, RtfDocument, StyleBlock, Underline};
+
+/// Style of a run of text, the font and the color being references to the tables of the document
+#[pyclass(name = "Painter", module = "rtf_parser", frozen, get_all)]
+#[derive(Clone)]
+struct PyPainter {
+    color_ref: u16,
+    font_ref: u16,
+    /// Font size in half-points
+    font_size: u16,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    superscript: bool,
+    subscript: bool,
+    smallcaps: bool,
+    caps: bool,
+}
+
+impl From<&Painter> for PyPainter {
+    fn from(painter: &Painter) -> Self {
+        return Self {
+            color_ref: painter.color_ref,
+            font_ref: painter.font_ref,
+            font_size: painter.font_size,
+            bold: painter.bold,
+            italic: painter.italic,
+            underline: painter.underline != Underline::None,
+            strike: painter.strike,
+            superscript: painter.superscript,
+            subscript: painter.subscript,
+            smallcaps: painter.smallcaps,
+            caps: painter.caps,
+        };
+    }
+}
+
+/// Layout of a paragraph, the sizes being expressed in twips
+#[pyclass(name = "Paragraph", module = "rtf_parser", frozen, get_all)]
+#[derive(Clone)]
+struct PyParagraph {
+    /// "left", "right", "center" or "justify"
+    alignment: &'static str,
+    space_before: i32,
+    space_after: i32,
+    left_indent: i32,
+    right_indent: i32,
+    first_line_indent: i32,
+    list_label: String,
+    style: u16,
+    outline_level: Option<u8>,
+}
+
+impl From<&Paragraph> for PyParagraph {
+    fn from(paragraph: &Paragraph) -> Self {
+        #[rustfmt::skip]
+        let alignment = match paragraph.alignment {
+            Alignment::LeftAligned  => "left",
+            Alignment::RightAligned => "right",
+            Alignment::Center       => "center",
+            Alignment::Justify      => "justify",
+        };
+        return Self {
+            alignment,
+            space_before: paragraph.spacing.before.0,
+            space_after: paragraph.spacing.after.0,
+            left_indent: paragraph.indent.left.0,
+            right_indent: paragraph.indent.right.0,
+            first_line_indent: paragraph.indent.first_line.0,
+            list_label: paragraph.list_label.clone(),
+            style: paragraph.style,
+            outline_level: paragraph.outline_level,
+        };
+    }
+}
+
+/// A run of text with a single style
+#[pyclass(name = "StyleBlock", module = "rtf_parser", frozen, get_all)]
+#[derive(Clone)]
+struct PyStyleBlock {
+    text: String,
+    painter: PyPainter,
+    paragraph: PyParagraph,
+    hidden: bool,
+}
+
+impl From<&StyleBlock> for PyStyleBlock {
+    fn from(style_block: &StyleBlock) -> Self {
+        return Self {
+            text: style_block.text.clone(),
+            painter: PyPainter::from(&style_block.painter),
+            paragraph: PyParagraph::from(&style_block.paragraph),
+            hidden: style_block.painter.hidden,
+        };
+    }
+}
+
+#[pyclass(name = "Document", module = "rtf_parser", frozen)]
+struct PyDocument {
+    document: RtfDocument,
+}
+
+#[pymethods]
+impl PyDocument {
+    /// The text of the document, hidden text excluded
+    fn get_text(&self) -> String {
+        return self.document.get_text();
+    }
+
+    fn to_html(&self) -> String {
+        return self.document.to_html();
+    }
+
+    #[getter]
+    fn body(&self) -> Vec<PyStyleBlock> {
+        return self.document.body.iter().map(PyStyleBlock::from).collect();
+    }
+
+    /// The names of the fonts, by font reference
+    #[getter]
+    fn fonts(&self) -> BTreeMap<u16, String> {
+        return self.document.header.font_table.iter().map(|(font_ref, font)| (*font_ref, font.name.clone())).collect();
+    }
+
+    /// The (red, green, blue, alpha) colors, by color reference
+    #[getter]
+    fn colors(&self) -> BTreeMap<u16, (u8, u8, u8, u8)> {
+        let color_refs = self.document.header.color_table.keys().chain(self.document.header.expanded_color_table.keys());
+        return color_refs
+            .filter_map(|color_ref| {
+                let color = self.document.header.get_color(color_ref)?;
+                return Some((*color_ref, (color.red, color.green, color.blue, color.alpha)));
+            })
+            .collect();
+    }
+
+    fn __repr__(&self) -> String {
+        return format!("<rtf_parser.Document with {} style blocks>", self.document.body.len());
+    }
+}
+
+/// Parse an RTF document, raise a ValueError if it is not valid
+#[pyfunction]
+fn parse(rtf: &str) -> PyResult<PyDocument> {
+    let document = RtfDocument::try_from(rtf).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    return Ok(PyDocument { document });
+}
+
+/// Parse an RTF document and return its text, without building the Python objects of the document
+#[pyfunction]
+fn get_text(rtf: &str) -> PyResult<String> {
+    let document = RtfDocument::try_from(rtf).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    return Ok(document.get_text());
+}
+
+#[pymodule]
+fn rtf_parser(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse, module)?)?;
+    module.add_function(wrap_pyfunction!(get_text, module)?)?;
+    module.add_class::<PyDocument>()?;
+    module.add_class::<PyStyleBlock>()?;
+    module.add_class::<PyPainter>()?;
+    module.add_class::<PyParagraph>()?;
+    return Ok(());
+}
@@ -0,0 +1,8 @@
+use rtf_parser::RtfDocument;
+
+// Preview a rtf file in the terminal : cargo run --example rtf_cat --features terminal -- <file.rtf>
+fn main() {
+    let filename = std::env::args().nth(1).unwrap_or(String::from("./resources/tests/test-file.rtf"));
+    let document = RtfDocument::from_filepath(&filename).expect("Invalid RTF file");
+    println!("{}", document.to_ansi());
+}
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rtf_parser::{Lexer, Parser, PreservedDocument, RtfDocument};
+
+// The parsing must never panic : the input is either parsed or rejected with an error
+fuzz_target!(|data: &[u8]| {
+    if let Ok(tokens) = Lexer::scan_bytes(data) {
+        if let Ok(document) = Parser::new(tokens).parse() {
+            let _ = document.get_text();
+            let _ = document.outline();
+        }
+    }
+    let _ = RtfDocument::from_reader(data);
+    if let Ok(rtf) = std::str::from_utf8(data) {
+        let _ = PreservedDocument::parse(rtf).map(|document| document.to_rtf());
+    }
+});
@@ -0,0 +1,83 @@
+/* C API of rtf-parser, built with the `ffi` feature : cargo build --release --features ffi */
+
+#ifndef RTF_PARSER_H
+#define RTF_PARSER_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit it by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/* Opaque document, created by rtf_parse and released by rtf_document_free */
+typedef struct RtfDocument RtfDocument;
+
+/*
+ Result of the functions of the C API
+ */
+typedef enum RtfStatus {
+  RTF_STATUS_OK = 0,
+  RTF_STATUS_NULL_POINTER = 1,
+  /*
+   The document is not valid, the details are given by `rtf_last_error_message`
+   */
+  RTF_STATUS_PARSE_ERROR = 2,
+  /*
+   The library panicked, the details are given by `rtf_last_error_message`
+   */
+  RTF_STATUS_PANIC = 3,
+} RtfStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Parse the `length` bytes of `data` and write the created document to `document`.
+ On error, `document` is set to NULL.
+
+ # Safety
+ `data` must point to `length` readable bytes and `document` must be a valid pointer.
+ The document must be released with `rtf_document_free`.
+ */
+enum RtfStatus rtf_parse(const uint8_t *data, size_t length, RtfDocument **document);
+
+/*
+ Get the text of the document as a NUL-terminated UTF-8 string, hidden and deleted text excluded.
+ Returns NULL if `document` is NULL or if the library panicked.
+
+ # Safety
+ `document` must be NULL or a document created by `rtf_parse` and not yet released.
+ The string must be released with `rtf_string_free`.
+ */
+char *rtf_document_get_text(const RtfDocument *document);
+
+/*
+ Release a document created by `rtf_parse`, does nothing if `document` is NULL.
+
+ # Safety
+ `document` must be NULL or a document created by `rtf_parse`, released only once.
+ */
+void rtf_document_free(RtfDocument *document);
+
+/*
+ Release a string returned by the library, does nothing if `string` is NULL.
+
+ # Safety
+ `string` must be NULL or a string returned by the library, released only once.
+ */
+void rtf_string_free(char *string);
+
+/*
+ Get the message of the last error of the calling thread, as a NUL-terminated UTF-8 string (empty if there was no error).
+ The string is owned by the library and is valid until the next call to the library on the same thread.
+ */
+const char *rtf_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RTF_PARSER_H */
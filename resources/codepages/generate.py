@@ -0,0 +1,54 @@
+#!/usr/bin/env python3
+# Generate the tables of the double-byte code pages decoded with the cjk feature (see get_double_byte_table in src/codepage.rs).
+# The chars come from the codecs cp932, cp936, cp949 and cp950 of Python, which are built from the mappings of Windows
+# published by Unicode : https://www.unicode.org/Public/MAPPINGS/VENDORS/MICSFT/WINDOWS/CP932.TXT (and CP936, CP949, CP950)
+#
+# Each table holds the chars of the single bytes from 0x80 to 0xFF (0 for the lead bytes), followed by the chars of the lead bytes
+# from 0x81 to 0xFE, each one with the trail bytes from 0x40 to 0xFE, as UTF-16 little endian (0 if not defined).
+#
+# Usage : python3 resources/codepages/generate.py [--check]
+# With --check, the tables are compared with the generated ones instead of being written.
+
+import pathlib
+import struct
+import sys
+
+CODEPAGES = [932, 936, 949, 950]
+
+
+def decode(codepage, data):
+    try:
+        text = data.decode(f"cp{codepage}")
+    except UnicodeDecodeError:
+        return 0
+    return ord(text) if len(text) == 1 else 0
+
+
+def generate(codepage):
+    table = bytearray()
+    for byte in range(0x80, 0x100):
+        table += struct.pack("<H", decode(codepage, bytes([byte])))
+    for lead in range(0x81, 0xFF):
+        for trail in range(0x40, 0xFF):
+            table += struct.pack("<H", decode(codepage, bytes([lead, trail])))
+    return bytes(table)
+
+
+def main():
+    check = "--check" in sys.argv[1:]
+    directory = pathlib.Path(__file__).parent
+    outdated = []
+    for codepage in CODEPAGES:
+        path = directory / f"cp{codepage}.bin"
+        table = generate(codepage)
+        if check:
+            if not path.exists() or path.read_bytes() != table:
+                outdated.append(path.name)
+        else:
+            path.write_bytes(table)
+    if outdated:
+        sys.exit(f"The tables {', '.join(outdated)} differ from the generated ones")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,234 @@
+// Define the decoding of the bytes written as \'hh, according to the code page of the document (\ansicpgN, \mac, \pc, \pca)
+
+/// Decode a byte of a single-byte code page, None if the code page is not supported
+pub fn decode_byte(codepage: u16, byte: u8) -> Option<char> {
+    if byte < 0x80 {
+        return Some(char::from(byte));
+    }
+    let table = get_table(codepage)?;
+    return Some(table[byte as usize - 0x80]);
+}
+
+/// Check if the bytes of the code page can be decoded
+pub fn is_supported(codepage: u16) -> bool {
+    return get_table(codepage).is_some() || get_double_byte_table(codepage).is_some();
+}
+
+/// The code page of the character set of a font (\fcharsetN), None for the ANSI and default character sets, which use the code page of the document
+pub fn from_charset(charset: u8) -> Option<u16> {
+    #[rustfmt::skip]
+    return match charset {
+        77  => Some(10000), // Mac
+        128 => Some(932),   // Shift-JIS
+        129 => Some(949),   // Hangul
+        134 => Some(936),   // GB2312
+        136 => Some(950),   // Big5
+        204 => Some(1251),  // Russian
+        238 => Some(1250),  // Eastern European
+        _   => None,
+    };
+}
+
+/// Check if the byte is the first of the two bytes of a char, in a double-byte code page (Shift-JIS, GBK, ...)
+pub fn is_lead_byte(codepage: u16, byte: u8) -> bool {
+    return get_double_byte_table(codepage).is_some_and(|table| (0x81..0xFF).contains(&byte) && read_char(table, byte as usize - 0x80) == 0);
+}
+
+/// Decode the two bytes of a char of a double-byte code page, None if they don't form a char
+pub fn decode_double_byte(codepage: u16, lead: u8, trail: u8) -> Option<char> {
+    let table = get_double_byte_table(codepage)?;
+    if !(0x81..0xFF).contains(&lead) || !(0x40..0xFF).contains(&trail) {
+        return None;
+    }
+    let index = 128 + (lead as usize - 0x81) * TRAIL_BYTES_COUNT + (trail as usize - 0x40);
+    return char::from_u32(read_char(table, index) as u32).filter(|char| *char != '\0');
+}
+
+/// Decode the single byte of a double-byte code page, None if it's a lead byte or it's not defined
+fn decode_single_byte(codepage: u16, byte: u8) -> Option<char> {
+    let table = get_double_byte_table(codepage)?;
+    if byte < 0x80 {
+        return Some(char::from(byte));
+    }
+    return char::from_u32(read_char(table, byte as usize - 0x80) as u32).filter(|char| *char != '\0');
+}
+
+/// Pair the bytes written as \'hh of the double-byte code pages : the first byte of a char is kept until its second byte is read
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByteDecoder {
+    pending_lead_byte: Option<u8>,
+}
+
+impl ByteDecoder {
+    /// Decode a byte written as \'hh, returns None if it's the first byte of a char. The bytes of the code pages not supported are decoded as Windows-1252
+    pub fn decode(&mut self, codepage: u16, byte: u8) -> Option<char> {
+        if let Some(lead) = self.pending_lead_byte.take() {
+            return Some(decode_double_byte(codepage, lead, byte).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        if is_lead_byte(codepage, byte) {
+            self.pending_lead_byte = Some(byte);
+            return None;
+        }
+        return Some(
+            decode_single_byte(codepage, byte)
+                .or(decode_byte(codepage, byte))
+                .or(decode_byte(1252, byte))
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        );
+    }
+
+    /// Some writers don't escape the second byte of a char if it's ASCII (ex: \'83e) : it's taken from the start of the text.
+    /// Returns the char of a pending first byte, and the remaining text
+    pub fn decode_text<'a>(&mut self, codepage: u16, text: &'a str) -> (Option<char>, &'a str) {
+        let Some(lead) = self.pending_lead_byte.take() else {
+            return (None, text);
+        };
+        return match text.as_bytes().first() {
+            Some(&trail) if trail.is_ascii() => (Some(decode_double_byte(codepage, lead, trail).unwrap_or(char::REPLACEMENT_CHARACTER)), &text[1..]),
+            _ => (Some(char::REPLACEMENT_CHARACTER), text),
+        };
+    }
+}
+
+// The chars of the bytes from 0x80 to 0xFF, the lower half being ASCII for all the supported code pages
+fn get_table(codepage: u16) -> Option<&'static [char; 128]> {
+    #[rustfmt::skip]
+    return match codepage {
+        437   => Some(&CP437),
+        850   => Some(&CP850),
+        1250  => Some(&CP1250),
+        1251  => Some(&CP1251),
+        1252  => Some(&CP1252),
+        10000 => Some(&MAC_ROMAN),
+        _     => None,
+    };
+}
+
+// The tables of the double-byte code pages, generated by resources/codepages/generate.py from the mappings of Windows published by Unicode
+// (https://www.unicode.org/Public/MAPPINGS/VENDORS/MICSFT/WINDOWS/) : the chars of the single bytes from 0x80 to 0xFF (0 for the lead bytes),
+// followed by the chars of the lead bytes from 0x81 to 0xFE, each one with the trail bytes from 0x40 to 0xFE, as UTF-16 little endian (0 if not defined)
+#[cfg(feature = "cjk")]
+fn get_double_byte_table(codepage: u16) -> Option<&'static [u8]> {
+    #[rustfmt::skip]
+    return match codepage {
+        932 => Some(include_bytes!("../resources/codepages/cp932.bin")), // Japanese (Shift-JIS)
+        936 => Some(include_bytes!("../resources/codepages/cp936.bin")), // Simplified Chinese (GBK)
+        949 => Some(include_bytes!("../resources/codepages/cp949.bin")), // Korean
+        950 => Some(include_bytes!("../resources/codepages/cp950.bin")), // Traditional Chinese (Big5)
+        _   => None,
+    };
+}
+
+#[cfg(not(feature = "cjk"))]
+fn get_double_byte_table(_codepage: u16) -> Option<&'static [u8]> {
+    return None;
+}
+
+const TRAIL_BYTES_COUNT: usize = 0xFF - 0x40;
+
+fn read_char(table: &[u8], index: usize) -> u16 {
+    return u16::from_le_bytes([table[2 * index], table[2 * index + 1]]);
+}
+
+// IBM PC (\pc)
+const CP437: [char; 128] = [
+    '\u{00C7}', '\u{00FC}', '\u{00E9}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E5}', '\u{00E7}', '\u{00EA}', '\u{00EB}', '\u{00E8}', '\u{00EF}', '\u{00EE}', '\u{00EC}', '\u{00C4}', '\u{00C5}',
+    '\u{00C9}', '\u{00E6}', '\u{00C6}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00FB}', '\u{00F9}', '\u{00FF}', '\u{00D6}', '\u{00DC}', '\u{00A2}', '\u{00A3}', '\u{00A5}', '\u{20A7}', '\u{0192}',
+    '\u{00E1}', '\u{00ED}', '\u{00F3}', '\u{00FA}', '\u{00F1}', '\u{00D1}', '\u{00AA}', '\u{00BA}', '\u{00BF}', '\u{2310}', '\u{00AC}', '\u{00BD}', '\u{00BC}', '\u{00A1}', '\u{00AB}', '\u{00BB}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{2561}', '\u{2562}', '\u{2556}', '\u{2555}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255D}', '\u{255C}', '\u{255B}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252C}', '\u{251C}', '\u{2500}', '\u{253C}', '\u{255E}', '\u{255F}', '\u{255A}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256C}', '\u{2567}',
+    '\u{2568}', '\u{2564}', '\u{2565}', '\u{2559}', '\u{2558}', '\u{2552}', '\u{2553}', '\u{256B}', '\u{256A}', '\u{2518}', '\u{250C}', '\u{2588}', '\u{2584}', '\u{258C}', '\u{2590}', '\u{2580}',
+    '\u{03B1}', '\u{00DF}', '\u{0393}', '\u{03C0}', '\u{03A3}', '\u{03C3}', '\u{00B5}', '\u{03C4}', '\u{03A6}', '\u{0398}', '\u{03A9}', '\u{03B4}', '\u{221E}', '\u{03C6}', '\u{03B5}', '\u{2229}',
+    '\u{2261}', '\u{00B1}', '\u{2265}', '\u{2264}', '\u{2320}', '\u{2321}', '\u{00F7}', '\u{2248}', '\u{00B0}', '\u{2219}', '\u{00B7}', '\u{221A}', '\u{207F}', '\u{00B2}', '\u{25A0}', '\u{00A0}',
+];
+
+// IBM PC multilingual (\pca)
+const CP850: [char; 128] = [
+    '\u{00C7}', '\u{00FC}', '\u{00E9}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E5}', '\u{00E7}', '\u{00EA}', '\u{00EB}', '\u{00E8}', '\u{00EF}', '\u{00EE}', '\u{00EC}', '\u{00C4}', '\u{00C5}',
+    '\u{00C9}', '\u{00E6}', '\u{00C6}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00FB}', '\u{00F9}', '\u{00FF}', '\u{00D6}', '\u{00DC}', '\u{00F8}', '\u{00A3}', '\u{00D8}', '\u{00D7}', '\u{0192}',
+    '\u{00E1}', '\u{00ED}', '\u{00F3}', '\u{00FA}', '\u{00F1}', '\u{00D1}', '\u{00AA}', '\u{00BA}', '\u{00BF}', '\u{00AE}', '\u{00AC}', '\u{00BD}', '\u{00BC}', '\u{00A1}', '\u{00AB}', '\u{00BB}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{00C1}', '\u{00C2}', '\u{00C0}', '\u{00A9}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255D}', '\u{00A2}', '\u{00A5}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252C}', '\u{251C}', '\u{2500}', '\u{253C}', '\u{00E3}', '\u{00C3}', '\u{255A}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256C}', '\u{00A4}',
+    '\u{00F0}', '\u{00D0}', '\u{00CA}', '\u{00CB}', '\u{00C8}', '\u{0131}', '\u{00CD}', '\u{00CE}', '\u{00CF}', '\u{2518}', '\u{250C}', '\u{2588}', '\u{2584}', '\u{00A6}', '\u{00CC}', '\u{2580}',
+    '\u{00D3}', '\u{00DF}', '\u{00D4}', '\u{00D2}', '\u{00F5}', '\u{00D5}', '\u{00B5}', '\u{00FE}', '\u{00DE}', '\u{00DA}', '\u{00DB}', '\u{00D9}', '\u{00FD}', '\u{00DD}', '\u{00AF}', '\u{00B4}',
+    '\u{00AD}', '\u{00B1}', '\u{2017}', '\u{00BE}', '\u{00B6}', '\u{00A7}', '\u{00F7}', '\u{00B8}', '\u{00B0}', '\u{00A8}', '\u{00B7}', '\u{00B9}', '\u{00B3}', '\u{00B2}', '\u{25A0}', '\u{00A0}',
+];
+
+// Windows Central European, the undefined bytes are kept as C1 control chars
+const CP1250: [char; 128] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0083}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}', '\u{0088}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{015A}', '\u{0164}', '\u{017D}', '\u{0179}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{0098}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{015B}', '\u{0165}', '\u{017E}', '\u{017A}',
+    '\u{00A0}', '\u{02C7}', '\u{02D8}', '\u{0141}', '\u{00A4}', '\u{0104}', '\u{00A6}', '\u{00A7}', '\u{00A8}', '\u{00A9}', '\u{015E}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{017B}',
+    '\u{00B0}', '\u{00B1}', '\u{02DB}', '\u{0142}', '\u{00B4}', '\u{00B5}', '\u{00B6}', '\u{00B7}', '\u{00B8}', '\u{0105}', '\u{015F}', '\u{00BB}', '\u{013D}', '\u{02DD}', '\u{013E}', '\u{017C}',
+    '\u{0154}', '\u{00C1}', '\u{00C2}', '\u{0102}', '\u{00C4}', '\u{0139}', '\u{0106}', '\u{00C7}', '\u{010C}', '\u{00C9}', '\u{0118}', '\u{00CB}', '\u{011A}', '\u{00CD}', '\u{00CE}', '\u{010E}',
+    '\u{0110}', '\u{0143}', '\u{0147}', '\u{00D3}', '\u{00D4}', '\u{0150}', '\u{00D6}', '\u{00D7}', '\u{0158}', '\u{016E}', '\u{00DA}', '\u{0170}', '\u{00DC}', '\u{00DD}', '\u{0162}', '\u{00DF}',
+    '\u{0155}', '\u{00E1}', '\u{00E2}', '\u{0103}', '\u{00E4}', '\u{013A}', '\u{0107}', '\u{00E7}', '\u{010D}', '\u{00E9}', '\u{0119}', '\u{00EB}', '\u{011B}', '\u{00ED}', '\u{00EE}', '\u{010F}',
+    '\u{0111}', '\u{0144}', '\u{0148}', '\u{00F3}', '\u{00F4}', '\u{0151}', '\u{00F6}', '\u{00F7}', '\u{0159}', '\u{016F}', '\u{00FA}', '\u{0171}', '\u{00FC}', '\u{00FD}', '\u{0163}', '\u{02D9}',
+];
+
+// Windows Cyrillic
+const CP1251: [char; 128] = [
+    '\u{0402}', '\u{0403}', '\u{201A}', '\u{0453}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}', '\u{20AC}', '\u{2030}', '\u{0409}', '\u{2039}', '\u{040A}', '\u{040C}', '\u{040B}', '\u{040F}',
+    '\u{0452}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{0098}', '\u{2122}', '\u{0459}', '\u{203A}', '\u{045A}', '\u{045C}', '\u{045B}', '\u{045F}',
+    '\u{00A0}', '\u{040E}', '\u{045E}', '\u{0408}', '\u{00A4}', '\u{0490}', '\u{00A6}', '\u{00A7}', '\u{0401}', '\u{00A9}', '\u{0404}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{0407}',
+    '\u{00B0}', '\u{00B1}', '\u{0406}', '\u{0456}', '\u{0491}', '\u{00B5}', '\u{00B6}', '\u{00B7}', '\u{0451}', '\u{2116}', '\u{0454}', '\u{00BB}', '\u{0458}', '\u{0405}', '\u{0455}', '\u{0457}',
+    '\u{0410}', '\u{0411}', '\u{0412}', '\u{0413}', '\u{0414}', '\u{0415}', '\u{0416}', '\u{0417}', '\u{0418}', '\u{0419}', '\u{041A}', '\u{041B}', '\u{041C}', '\u{041D}', '\u{041E}', '\u{041F}',
+    '\u{0420}', '\u{0421}', '\u{0422}', '\u{0423}', '\u{0424}', '\u{0425}', '\u{0426}', '\u{0427}', '\u{0428}', '\u{0429}', '\u{042A}', '\u{042B}', '\u{042C}', '\u{042D}', '\u{042E}', '\u{042F}',
+    '\u{0430}', '\u{0431}', '\u{0432}', '\u{0433}', '\u{0434}', '\u{0435}', '\u{0436}', '\u{0437}', '\u{0438}', '\u{0439}', '\u{043A}', '\u{043B}', '\u{043C}', '\u{043D}', '\u{043E}', '\u{043F}',
+    '\u{0440}', '\u{0441}', '\u{0442}', '\u{0443}', '\u{0444}', '\u{0445}', '\u{0446}', '\u{0447}', '\u{0448}', '\u{0449}', '\u{044A}', '\u{044B}', '\u{044C}', '\u{044D}', '\u{044E}', '\u{044F}',
+];
+
+// Windows Western European (\ansi), the undefined bytes are kept as C1 control chars
+const CP1252: [char; 128] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+    '\u{00A0}', '\u{00A1}', '\u{00A2}', '\u{00A3}', '\u{00A4}', '\u{00A5}', '\u{00A6}', '\u{00A7}', '\u{00A8}', '\u{00A9}', '\u{00AA}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{00AF}',
+    '\u{00B0}', '\u{00B1}', '\u{00B2}', '\u{00B3}', '\u{00B4}', '\u{00B5}', '\u{00B6}', '\u{00B7}', '\u{00B8}', '\u{00B9}', '\u{00BA}', '\u{00BB}', '\u{00BC}', '\u{00BD}', '\u{00BE}', '\u{00BF}',
+    '\u{00C0}', '\u{00C1}', '\u{00C2}', '\u{00C3}', '\u{00C4}', '\u{00C5}', '\u{00C6}', '\u{00C7}', '\u{00C8}', '\u{00C9}', '\u{00CA}', '\u{00CB}', '\u{00CC}', '\u{00CD}', '\u{00CE}', '\u{00CF}',
+    '\u{00D0}', '\u{00D1}', '\u{00D2}', '\u{00D3}', '\u{00D4}', '\u{00D5}', '\u{00D6}', '\u{00D7}', '\u{00D8}', '\u{00D9}', '\u{00DA}', '\u{00DB}', '\u{00DC}', '\u{00DD}', '\u{00DE}', '\u{00DF}',
+    '\u{00E0}', '\u{00E1}', '\u{00E2}', '\u{00E3}', '\u{00E4}', '\u{00E5}', '\u{00E6}', '\u{00E7}', '\u{00E8}', '\u{00E9}', '\u{00EA}', '\u{00EB}', '\u{00EC}', '\u{00ED}', '\u{00EE}', '\u{00EF}',
+    '\u{00F0}', '\u{00F1}', '\u{00F2}', '\u{00F3}', '\u{00F4}', '\u{00F5}', '\u{00F6}', '\u{00F7}', '\u{00F8}', '\u{00F9}', '\u{00FA}', '\u{00FB}', '\u{00FC}', '\u{00FD}', '\u{00FE}', '\u{00FF}',
+];
+
+// Mac OS Roman (\mac)
+const MAC_ROMAN: [char; 128] = [
+    '\u{00C4}', '\u{00C5}', '\u{00C7}', '\u{00C9}', '\u{00D1}', '\u{00D6}', '\u{00DC}', '\u{00E1}', '\u{00E0}', '\u{00E2}', '\u{00E4}', '\u{00E3}', '\u{00E5}', '\u{00E7}', '\u{00E9}', '\u{00E8}',
+    '\u{00EA}', '\u{00EB}', '\u{00ED}', '\u{00EC}', '\u{00EE}', '\u{00EF}', '\u{00F1}', '\u{00F3}', '\u{00F2}', '\u{00F4}', '\u{00F6}', '\u{00F5}', '\u{00FA}', '\u{00F9}', '\u{00FB}', '\u{00FC}',
+    '\u{2020}', '\u{00B0}', '\u{00A2}', '\u{00A3}', '\u{00A7}', '\u{2022}', '\u{00B6}', '\u{00DF}', '\u{00AE}', '\u{00A9}', '\u{2122}', '\u{00B4}', '\u{00A8}', '\u{2260}', '\u{00C6}', '\u{00D8}',
+    '\u{221E}', '\u{00B1}', '\u{2264}', '\u{2265}', '\u{00A5}', '\u{00B5}', '\u{2202}', '\u{2211}', '\u{220F}', '\u{03C0}', '\u{222B}', '\u{00AA}', '\u{00BA}', '\u{03A9}', '\u{00E6}', '\u{00F8}',
+    '\u{00BF}', '\u{00A1}', '\u{00AC}', '\u{221A}', '\u{0192}', '\u{2248}', '\u{2206}', '\u{00AB}', '\u{00BB}', '\u{2026}', '\u{00A0}', '\u{00C0}', '\u{00C3}', '\u{00D5}', '\u{0152}', '\u{0153}',
+    '\u{2013}', '\u{2014}', '\u{201C}', '\u{201D}', '\u{2018}', '\u{2019}', '\u{00F7}', '\u{25CA}', '\u{00FF}', '\u{0178}', '\u{2044}', '\u{20AC}', '\u{2039}', '\u{203A}', '\u{FB01}', '\u{FB02}',
+    '\u{2021}', '\u{00B7}', '\u{201A}', '\u{201E}', '\u{2030}', '\u{00C2}', '\u{00CA}', '\u{00C1}', '\u{00CB}', '\u{00C8}', '\u{00CD}', '\u{00CE}', '\u{00CF}', '\u{00CC}', '\u{00D3}', '\u{00D4}',
+    '\u{F8FF}', '\u{00D2}', '\u{00DA}', '\u{00DB}', '\u{00D9}', '\u{0131}', '\u{02C6}', '\u{02DC}', '\u{00AF}', '\u{02D8}', '\u{02D9}', '\u{02DA}', '\u{00B8}', '\u{02DD}', '\u{02DB}', '\u{02C7}',
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_codepage_bytes() {
+        assert_eq!(decode_byte(1252, b'a'), Some('a'));
+        assert_eq!(decode_byte(1252, 0x80), Some('€'));
+        assert_eq!(decode_byte(1252, 0xE9), Some('é'));
+        assert_eq!(decode_byte(1251, 0xC0), Some('А'));
+        assert_eq!(decode_byte(437, 0x82), Some('é'));
+        assert_eq!(decode_byte(10000, 0x8E), Some('é'));
+        assert_eq!(decode_byte(932, 0x82), None);
+        assert_eq!(is_supported(932), cfg!(feature = "cjk"));
+    }
+
+    #[test]
+    #[cfg(feature = "cjk")]
+    fn pair_double_bytes() {
+        let mut decoder = ByteDecoder::default();
+        assert_eq!(decoder.decode(932, 0x93), None);
+        assert_eq!(decoder.decode(932, 0xFA), Some('日'));
+        assert_eq!(decoder.decode(932, 0xB1), Some('ｱ'));
+        assert_eq!(decoder.decode(936, 0xD6), None);
+        assert_eq!(decoder.decode_text(936, "\u{e9}text"), (Some(char::REPLACEMENT_CHARACTER), "\u{e9}text"));
+        assert_eq!(decoder.decode(936, 0x41), Some('A'));
+        assert!(is_lead_byte(950, 0xA4) && !is_lead_byte(932, 0xA4));
+        assert_eq!(decode_double_byte(949, 0xC7, 0xD1), Some('한'));
+    }
+}
@@ -0,0 +1,163 @@
+/// Define the fonts and the colors used by a document, and the removal of the unused ones from its header
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::mem;
+
+use crate::document::RtfDocument;
+use crate::header::{ColorRef, ColorTable, FontRef, FontTable};
+use crate::parser::{Painter, StyleBlock};
+use crate::table::Table;
+
+impl RtfDocument {
+    /// The fonts of the font table used by the text of the body and of the text boxes
+    pub fn used_fonts(&self) -> FontTable {
+        return self
+            .style_blocks()
+            .filter_map(|style_block| self.header.font_table.get_key_value(&style_block.painter.font_ref))
+            .map(|(font_ref, font)| (*font_ref, font.clone()))
+            .collect();
+    }
+
+    /// The colors used by the text of the body and of the text boxes, and by the cells of the tables, resolved as by `RtfHeader::get_color`
+    pub fn used_colors(&self) -> ColorTable {
+        let mut color_refs: BTreeSet<ColorRef> = BTreeSet::new();
+        for style_block in self.style_blocks() {
+            color_refs.extend([style_block.painter.color_ref, style_block.painter.underline_color]);
+        }
+        get_table_colors(&self.tables, &mut color_refs);
+        return color_refs
+            .into_iter()
+            .filter_map(|color_ref| self.header.get_color(&color_ref).map(|color| (color_ref, color.clone())))
+            .collect();
+    }
+
+    /// Remove the unused fonts and colors from the header, to write a minimal document.
+    /// The fonts are renumbered from 0 and the colors from 1, in the order of their references, and the references of the body are rewritten.
+    /// The default font (\deffN) is kept, and the invalid references are replaced by the default font and the automatic color.
+    pub fn compact(&mut self) {
+        let mut font_refs: BTreeSet<FontRef> = self.used_fonts().into_keys().collect();
+        if self.header.font_table.contains_key(&self.header.defaults.font_ref) {
+            font_refs.insert(self.header.defaults.font_ref);
+        }
+        let font_map: BTreeMap<FontRef, FontRef> = font_refs.into_iter().zip(0..).collect();
+        let color_map: BTreeMap<ColorRef, ColorRef> = self.used_colors().into_keys().zip(1..).collect();
+        let header = &mut self.header;
+        header.font_table = mem::take(&mut header.font_table)
+            .into_iter()
+            .filter_map(|(font_ref, font)| Some((*font_map.get(&font_ref)?, font)))
+            .collect();
+        header.color_table = mem::take(&mut header.color_table)
+            .into_iter()
+            .filter_map(|(color_ref, color)| Some((*color_map.get(&color_ref)?, color)))
+            .collect();
+        header.expanded_color_table = mem::take(&mut header.expanded_color_table)
+            .into_iter()
+            .filter_map(|(color_ref, color)| Some((*color_map.get(&color_ref)?, color)))
+            .collect();
+        let default_font_ref = font_map.get(&header.defaults.font_ref).copied().unwrap_or_default();
+        header.defaults.font_ref = default_font_ref;
+        let remap = RefMap {
+            fonts: &font_map,
+            colors: &color_map,
+            default_font_ref,
+        };
+        for style in header.stylesheet.values_mut().chain(header.character_styles.values_mut()) {
+            remap.painter(&mut style.painter);
+        }
+        for style_block in self.body.iter_mut().chain(self.shapes.iter_mut().flat_map(|shape| shape.text_box.iter_mut())) {
+            remap.painter(&mut style_block.painter);
+        }
+        remap.tables(&mut self.tables);
+    }
+
+    // The style blocks of the body and of the text boxes
+    fn style_blocks(&self) -> impl Iterator<Item = &StyleBlock> {
+        return self.body.iter().chain(self.shapes.iter().flat_map(|shape| shape.text_box.iter()));
+    }
+}
+
+fn get_table_colors(tables: &[Table], color_refs: &mut BTreeSet<ColorRef>) {
+    for cell in tables.iter().flat_map(|table| table.rows.iter()).flat_map(|row| row.cells.iter()) {
+        let borders = [cell.borders.top, cell.borders.left, cell.borders.bottom, cell.borders.right];
+        color_refs.insert(cell.background_color);
+        color_refs.extend(borders.iter().map(|border| border.color));
+        get_table_colors(&cell.nested_tables, color_refs);
+    }
+}
+
+// The new references of the fonts and the colors kept by `compact`
+struct RefMap<'a> {
+    fonts: &'a BTreeMap<FontRef, FontRef>,
+    colors: &'a BTreeMap<ColorRef, ColorRef>,
+    default_font_ref: FontRef,
+}
+
+impl RefMap<'_> {
+    fn font(&self, font_ref: FontRef) -> FontRef {
+        return self.fonts.get(&font_ref).copied().unwrap_or(self.default_font_ref);
+    }
+
+    // 0 is the automatic color
+    fn color(&self, color_ref: ColorRef) -> ColorRef {
+        return self.colors.get(&color_ref).copied().unwrap_or(0);
+    }
+
+    fn painter(&self, painter: &mut Painter) {
+        painter.font_ref = self.font(painter.font_ref);
+        painter.color_ref = self.color(painter.color_ref);
+        painter.underline_color = self.color(painter.underline_color);
+    }
+
+    fn tables(&self, tables: &mut [Table]) {
+        for cell in tables.iter_mut().flat_map(|table| table.rows.iter_mut()).flat_map(|row| row.cells.iter_mut()) {
+            cell.background_color = self.color(cell.background_color);
+            for border in [&mut cell.borders.top, &mut cell.borders.left, &mut cell.borders.bottom, &mut cell.borders.right] {
+                border.color = self.color(border.color);
+            }
+            self.tables(&mut cell.nested_tables);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::fragment::RtfFragment;
+
+    #[test]
+    fn compact_header() {
+        let rtf = r"{\rtf1\ansi\deff1{\fonttbl{\f0\fswiss Helvetica;}{\f1\froman Times;}{\f2\fmodern Courier;}}
+            {\colortbl;\red255\green0\blue0;\red0\green255\blue0;\red0\green0\blue255;}
+            \f2\cf3 Code {\f1\cf0 text}\par
+            \trowd\clcbpat1\cellx2000\pard\intbl Cell\cell\row}";
+        let mut document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.used_fonts().keys().collect::<Vec<_>>(), [&1, &2]);
+        assert_eq!(document.used_colors().keys().collect::<Vec<_>>(), [&1, &3]);
+        let text = document.get_text();
+        document.compact();
+        assert_eq!(document.header.font_table.values().map(|font| font.name.as_str()).collect::<Vec<_>>(), ["Times", "Courier"]);
+        assert_eq!(document.header.color_table.values().map(|color| (color.red, color.blue)).collect::<Vec<_>>(), [(255, 0), (0, 255)]);
+        assert_eq!(document.header.defaults.font_ref, 0);
+        assert_eq!((document.body[0].painter.font_ref, document.body[0].painter.color_ref), (1, 2));
+        assert_eq!((document.body[1].painter.font_ref, document.body[1].painter.color_ref), (0, 0));
+        assert_eq!(document.tables[0].rows[0].cells[0].background_color, 1);
+        assert_eq!(document.get_text(), text);
+        // The compacted document is written with the same fonts and colors
+        let compacted = RtfDocument::try_from(RtfFragment::write(&document.header, &document.body).as_str()).unwrap();
+        assert_eq!(compacted.body[0].painter, document.body[0].painter);
+        assert_eq!(compacted.header.font_table, document.header.font_table);
+    }
+
+    #[test]
+    fn compact_expanded_colors() {
+        // The blue is only in the expanded color table
+        let rtf = r"{\rtf1\ansi{\colortbl;\red255\green0\blue0;\red0\green255\blue0;}{\*\expandedcolortbl;;;\cssrgb\c0\c0\c50000;}\cf3 Blue text}";
+        let mut document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.used_colors().keys().collect::<Vec<_>>(), [&3]);
+        document.compact();
+        assert!(document.header.color_table.is_empty());
+        assert_eq!(document.body[0].painter.color_ref, 1);
+        assert_eq!(document.header.get_color(&1).map(|color| color.to_hex()), Some("#000080".into()));
+    }
+}
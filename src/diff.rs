@@ -0,0 +1,421 @@
+/// Define the comparison of two documents : text insertions, deletions, replacements and formatting changes
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::document::RtfDocument;
+use crate::paragraph::Paragraph;
+use crate::parser::Painter;
+
+/// A difference between two documents
+/// The ranges and positions are char offsets in the visible text of the body (see `RtfDocument::get_text`) of the old and of the new document
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[allow(clippy::large_enum_variant)]
+pub enum DocumentChange {
+    Inserted {
+        old_position: usize,
+        new_range: Range<usize>,
+        text: String,
+    },
+    Deleted {
+        old_range: Range<usize>,
+        new_position: usize,
+        text: String,
+    },
+    Replaced {
+        old_range: Range<usize>,
+        new_range: Range<usize>,
+        old_text: String,
+        new_text: String,
+    },
+    /// The text is the same but its painter or its paragraph changed
+    FormatChanged {
+        old_range: Range<usize>,
+        new_range: Range<usize>,
+        text: String,
+        old_painter: Painter,
+        new_painter: Painter,
+        old_paragraph: Paragraph,
+        new_paragraph: Paragraph,
+    },
+}
+
+/// An attribute of a `Painter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum PainterAttribute {
+    Color,
+    Font,
+    FontSize,
+    Bold,
+    Italic,
+    Underline,
+    UnderlineColor,
+    Superscript,
+    Subscript,
+    Smallcaps,
+    Strike,
+    BaselineOffset,
+    CharacterSpacing,
+    Outline,
+    Shadow,
+    Caps,
+    Hidden,
+    Language,
+    EastAsianLanguage,
+}
+
+/// The attributes that differ between two painters, in the order of the fields of `Painter`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PainterDiff {
+    pub attributes: Vec<PainterAttribute>,
+}
+
+impl PainterDiff {
+    pub fn between(old: &Painter, new: &Painter) -> Self {
+        #[rustfmt::skip]
+        let attributes = [
+            (PainterAttribute::Color,               old.color_ref != new.color_ref),
+            (PainterAttribute::Font,                old.font_ref != new.font_ref),
+            (PainterAttribute::FontSize,            old.font_size != new.font_size),
+            (PainterAttribute::Bold,                old.bold != new.bold),
+            (PainterAttribute::Italic,              old.italic != new.italic),
+            (PainterAttribute::Underline,           old.underline != new.underline),
+            (PainterAttribute::UnderlineColor,      old.underline_color != new.underline_color),
+            (PainterAttribute::Superscript,         old.superscript != new.superscript),
+            (PainterAttribute::Subscript,           old.subscript != new.subscript),
+            (PainterAttribute::Smallcaps,           old.smallcaps != new.smallcaps),
+            (PainterAttribute::Strike,              old.strike != new.strike),
+            (PainterAttribute::BaselineOffset,      old.baseline_offset != new.baseline_offset),
+            (PainterAttribute::CharacterSpacing,    old.character_spacing != new.character_spacing),
+            (PainterAttribute::Outline,             old.outline != new.outline),
+            (PainterAttribute::Shadow,              old.shadow != new.shadow),
+            (PainterAttribute::Caps,                old.caps != new.caps),
+            (PainterAttribute::Hidden,              old.hidden != new.hidden),
+            (PainterAttribute::Language,            old.language != new.language),
+            (PainterAttribute::EastAsianLanguage,   old.east_asian_language != new.east_asian_language),
+        ];
+        return Self {
+            attributes: attributes.into_iter().filter(|(_, differs)| *differs).map(|(attribute, _)| attribute).collect(),
+        };
+    }
+
+    /// The painters are equal
+    pub fn is_empty(&self) -> bool {
+        return self.attributes.is_empty();
+    }
+
+    pub fn contains(&self, attribute: PainterAttribute) -> bool {
+        return self.attributes.contains(&attribute);
+    }
+}
+
+impl Painter {
+    /// Get the attributes that differ from another painter
+    pub fn diff(&self, other: &Painter) -> PainterDiff {
+        return PainterDiff::between(self, other);
+    }
+}
+
+// The visible chars of the body with the index of the style block they belong to
+struct FlatText {
+    chars: Vec<char>,
+    blocks: Vec<usize>,
+    // Char ranges of the words, each other char being its own token
+    tokens: Vec<Range<usize>>,
+}
+
+impl FlatText {
+    fn from(document: &RtfDocument) -> Self {
+        let mut chars = vec![];
+        let mut blocks = vec![];
+        for (index, style_block) in document.body.iter().enumerate().filter(|(_, style_block)| style_block.painter.is_visible()) {
+            for c in style_block.text.chars() {
+                chars.push(c);
+                blocks.push(index);
+            }
+        }
+        let mut tokens: Vec<Range<usize>> = vec![];
+        for (index, c) in chars.iter().enumerate() {
+            match tokens.last_mut() {
+                Some(token) if c.is_alphanumeric() && chars[token.start].is_alphanumeric() => token.end = index + 1,
+                _ => tokens.push(index..index + 1),
+            }
+        }
+        return Self { chars, blocks, tokens };
+    }
+
+    fn get_token(&self, index: usize) -> &[char] {
+        return &self.chars[self.tokens[index].clone()];
+    }
+
+    // Get the char range of a range of tokens
+    fn get_char_range(&self, tokens: &Range<usize>) -> Range<usize> {
+        let start = self.tokens.get(tokens.start).map_or(self.chars.len(), |token| token.start);
+        let end = if tokens.is_empty() { start } else { self.tokens[tokens.end - 1].end };
+        return start..end;
+    }
+
+    fn get_text(&self, range: &Range<usize>) -> String {
+        return self.chars[range.clone()].iter().collect();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+// Above this number of edits, the changed tokens are replaced as a whole : the memory used by the search grows with its square
+const MAX_EDIT_DISTANCE: usize = 512;
+
+// Get the edit script to transform the old tokens to the new ones
+fn diff_tokens(old: &FlatText, new: &FlatText) -> Vec<Edit> {
+    let (old_len, new_len) = (old.tokens.len(), new.tokens.len());
+    // The common prefix and suffix are kept without searching
+    let prefix = (0..old_len.min(new_len)).take_while(|index| old.get_token(*index) == new.get_token(*index)).count();
+    let suffix = (0..old_len.min(new_len) - prefix)
+        .take_while(|index| old.get_token(old_len - 1 - index) == new.get_token(new_len - 1 - index))
+        .count();
+    let old_range = prefix..old_len - suffix;
+    let new_range = prefix..new_len - suffix;
+    let mut edits: Vec<Edit> = (0..prefix).map(|index| Edit::Equal(index, index)).collect();
+    match diff_token_ranges(old, new, old_range.clone(), new_range.clone()) {
+        Some(range_edits) => edits.extend(range_edits),
+        None => {
+            edits.extend(old_range.map(Edit::Delete));
+            edits.extend(new_range.map(Edit::Insert));
+        }
+    }
+    edits.extend((0..suffix).rev().map(|index| Edit::Equal(old_len - 1 - index, new_len - 1 - index)));
+    return edits;
+}
+
+// Myers' diff algorithm on the ranges of tokens, returns None if there are more than MAX_EDIT_DISTANCE edits
+fn diff_token_ranges(old: &FlatText, new: &FlatText, old_range: Range<usize>, new_range: Range<usize>) -> Option<Vec<Edit>> {
+    let (n, m) = (old_range.len() as isize, new_range.len() as isize);
+    let max = (n + m).min(MAX_EDIT_DISTANCE as isize);
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // Snapshot of the furthest reaching paths (diagonals -d-1..=d+1) before each step d
+    let mut trace: Vec<Vec<isize>> = vec![];
+    let mut found = false;
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let index = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) { v[index + 1] } else { v[index - 1] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && old.get_token(old_range.start + x as usize) == new.get_token(new_range.start + y as usize) {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+    let mut edits = vec![];
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let index = |k: isize| (k + d + 1) as usize;
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) { k + 1 } else { k - 1 };
+        let previous_x = v[index(previous_k)];
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Equal(old_range.start + x as usize - 1, new_range.start + y as usize - 1));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == previous_x {
+                Edit::Insert(new_range.start + y as usize - 1)
+            } else {
+                Edit::Delete(old_range.start + x as usize - 1)
+            });
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    edits.reverse();
+    return Some(edits);
+}
+
+impl RtfDocument {
+    /// Compare the document with a newer version : the text is compared word by word, then the formatting of the unchanged text char by char
+    pub fn diff(&self, new: &RtfDocument) -> Vec<DocumentChange> {
+        let old_text = FlatText::from(self);
+        let new_text = FlatText::from(new);
+        let mut changes: Vec<DocumentChange> = vec![];
+        // Pending deleted and inserted tokens, between two unchanged tokens
+        let mut deleted = 0..0;
+        let mut inserted = 0..0;
+        for edit in diff_tokens(&old_text, &new_text).into_iter().chain([Edit::Equal(old_text.tokens.len(), new_text.tokens.len())]) {
+            match edit {
+                Edit::Delete(index) => deleted.end = index + 1,
+                Edit::Insert(index) => inserted.end = index + 1,
+                Edit::Equal(old_index, new_index) => {
+                    let old_range = old_text.get_char_range(&deleted);
+                    let new_range = new_text.get_char_range(&inserted);
+                    #[rustfmt::skip]
+                    let change = match (deleted.is_empty(), inserted.is_empty()) {
+                        (true, true)   => None,
+                        (false, true)  => Some(DocumentChange::Deleted { text: old_text.get_text(&old_range), old_range, new_position: new_range.start }),
+                        (true, false)  => Some(DocumentChange::Inserted { text: new_text.get_text(&new_range), old_position: old_range.start, new_range }),
+                        (false, false) => Some(DocumentChange::Replaced { old_text: old_text.get_text(&old_range), new_text: new_text.get_text(&new_range), old_range, new_range }),
+                    };
+                    changes.extend(change);
+                    if old_index < old_text.tokens.len() {
+                        let old_chars = old_text.tokens[old_index].clone();
+                        let new_chars = new_text.tokens[new_index].clone();
+                        for (old_char, new_char) in old_chars.zip(new_chars) {
+                            self.diff_char_format(new, &old_text, &new_text, old_char, new_char, &mut changes);
+                        }
+                    }
+                    deleted = old_index + 1..old_index + 1;
+                    inserted = new_index + 1..new_index + 1;
+                }
+            }
+        }
+        return changes;
+    }
+
+    // Compare the formatting of a char, extending the last format change if it is the same
+    fn diff_char_format(&self, new: &RtfDocument, old_text: &FlatText, new_text: &FlatText, old_char: usize, new_char: usize, changes: &mut Vec<DocumentChange>) {
+        let old_block = &self.body[old_text.blocks[old_char]];
+        let new_block = &new.body[new_text.blocks[new_char]];
+        if old_block.painter == new_block.painter && old_block.paragraph == new_block.paragraph {
+            return;
+        }
+        if let Some(DocumentChange::FormatChanged {
+            old_range,
+            new_range,
+            text,
+            old_painter,
+            new_painter,
+            old_paragraph,
+            new_paragraph,
+        }) = changes.last_mut()
+        {
+            let is_same_change = old_range.end == old_char && new_range.end == new_char;
+            let is_same_format = *old_painter == old_block.painter && *new_painter == new_block.painter && *old_paragraph == old_block.paragraph && *new_paragraph == new_block.paragraph;
+            if is_same_change && is_same_format {
+                old_range.end += 1;
+                new_range.end += 1;
+                text.push(old_text.chars[old_char]);
+                return;
+            }
+        }
+        changes.push(DocumentChange::FormatChanged {
+            old_range: old_char..old_char + 1,
+            new_range: new_char..new_char + 1,
+            text: String::from(old_text.chars[old_char]),
+            old_painter: old_block.painter.clone(),
+            new_painter: new_block.painter.clone(),
+            old_paragraph: old_block.paragraph.clone(),
+            new_paragraph: new_block.paragraph.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_text() {
+        let old = RtfDocument::try_from(r"{\rtf1\ansi The price is 100 euros, paid by check.}").unwrap();
+        let new = RtfDocument::try_from(r"{\rtf1\ansi The total price is 120 euros, paid.}").unwrap();
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                DocumentChange::Inserted {
+                    old_position: 4,
+                    new_range: 4..10,
+                    text: String::from("total ")
+                },
+                DocumentChange::Replaced {
+                    old_range: 13..16,
+                    new_range: 19..22,
+                    old_text: String::from("100"),
+                    new_text: String::from("120")
+                },
+                DocumentChange::Deleted {
+                    old_range: 28..37,
+                    new_position: 34,
+                    text: String::from(" by check")
+                },
+            ]
+        );
+        assert_eq!(old.diff(&old), vec![]);
+    }
+
+    #[test]
+    fn diff_format() {
+        let old = RtfDocument::try_from(r"{\rtf1\ansi Some bold text}").unwrap();
+        let new = RtfDocument::try_from(r"{\rtf1\ansi Some {\b bold} text}").unwrap();
+        let changes = new.diff(&old);
+        assert_eq!(changes.len(), 1);
+        let DocumentChange::FormatChanged {
+            old_range,
+            new_range,
+            text,
+            old_painter,
+            new_painter,
+            ..
+        } = &changes[0]
+        else {
+            panic!("Expected a format change")
+        };
+        assert_eq!((old_range, new_range, text.as_str()), (&(5..9), &(5..9), "bold"));
+        assert!(old_painter.bold && !new_painter.bold);
+    }
+
+    #[test]
+    fn diff_painters() {
+        let document = RtfDocument::try_from(r"{\rtf1\ansi Plain {\b\fs28 bold}{\b\i\lang1036 italic}}").unwrap();
+        let [plain, bold, italic] = [0, 1, 2].map(|index| &document.body[index].painter);
+        assert_eq!(plain.diff(bold).attributes, [PainterAttribute::FontSize, PainterAttribute::Bold]);
+        let diff = bold.diff(italic);
+        assert!(diff.contains(PainterAttribute::Italic) && !diff.contains(PainterAttribute::Bold));
+        assert_eq!(diff.attributes, [PainterAttribute::FontSize, PainterAttribute::Italic, PainterAttribute::Language]);
+        assert!(plain.diff(plain).is_empty());
+        // The painters can be deduplicated in a set
+        let painters: std::collections::HashSet<&Painter> = document.body.iter().chain(&document.body).map(|style_block| &style_block.painter).collect();
+        assert_eq!(painters.len(), 3);
+    }
+
+    #[test]
+    fn diff_hidden_text() {
+        let old = RtfDocument::try_from(r"{\rtf1\ansi Some {\v hidden }text}").unwrap();
+        let new = RtfDocument::try_from(r"{\rtf1\ansi Some text}").unwrap();
+        assert_eq!(old.diff(&new), vec![]);
+    }
+
+    #[test]
+    fn diff_too_many_edits() {
+        // Beyond MAX_EDIT_DISTANCE, the changed text is replaced as a whole
+        let old = RtfDocument::try_from(format!(r"{{\rtf1\ansi Start {}end}}", "a ".repeat(MAX_EDIT_DISTANCE))).unwrap();
+        let new = RtfDocument::try_from(format!(r"{{\rtf1\ansi Start {}end}}", "b ".repeat(MAX_EDIT_DISTANCE))).unwrap();
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 1);
+        let DocumentChange::Replaced { old_range, new_range, .. } = &changes[0] else {
+            panic!("Expected a replacement")
+        };
+        assert_eq!((old_range, new_range), (&(6..6 + 2 * MAX_EDIT_DISTANCE - 1), &(6..6 + 2 * MAX_EDIT_DISTANCE - 1)));
+    }
+}
@@ -1,78 +1,501 @@
-use std::error::Error;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::slice::Iter;
+#[cfg(feature = "std")]
 use std::fs;
-use std::io::Read;
+#[cfg(feature = "std")]
+use std::io::{BufRead, Read};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::{wasm_bindgen, JsError};
 
+use crate::error::RtfError;
+use crate::field::{Field, FieldType};
 use crate::header::RtfHeader;
+use crate::image::Image;
 use crate::lexer::Lexer;
-use crate::parser::{Parser, StyleBlock};
+use crate::parser::{Parser, ParserOptions, StyleBlock};
+use crate::shape::Shape;
+use crate::table::Table;
+use crate::tokens::ControlWord;
+#[cfg(feature = "std")]
+use crate::tokens::OwnedToken;
+#[cfg(feature = "unicode-normalization")]
+use crate::unicode;
 
 // Interface to WASM to be used in JS
+// The errors are thrown as JS `Error`s, with the message of the `RtfError`
+#[cfg(feature = "wasm")]
 #[wasm_bindgen]
-pub fn parse_rtf(rtf: String) -> RtfDocument {
-    return RtfDocument::try_from(rtf).unwrap();
+pub fn parse_rtf(rtf: String) -> Result<RtfDocument, JsError> {
+    return Ok(RtfDocument::try_from(rtf)?);
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
-#[wasm_bindgen(getter_with_clone)]
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn parse_rtf_with_options(rtf: String, options: ParserOptions) -> Result<RtfDocument, JsError> {
+    return Ok(RtfDocument::from_str_with_options(&rtf, options)?);
+}
+
+/// Convert the document to HTML without transferring the whole `RtfDocument` to JS
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn rtf_to_html(rtf: String) -> Result<String, JsError> {
+    return Ok(RtfDocument::try_from(rtf)?.to_html());
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct RtfDocument {
     pub header: RtfHeader,
     pub body: Vec<StyleBlock>,
+    pub bookmarks: Vec<Bookmark>,
+    pub fields: Vec<Field>,
+    pub tables: Vec<Table>,
+    pub images: Vec<Image>,
+    pub page_breaks: Vec<PageBreak>,
+    pub shapes: Vec<Shape>,
+    /// The values attached by the destination handlers (see `Parser::on_destination`)
+    pub custom_destinations: Vec<CustomDestination>,
+}
+
+/// A named location of the document defined by \*\bkmkstart and \*\bkmkend
+/// The positions are char offsets in the visible text of the body (see `get_text`)
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Bookmark {
+    pub name: String,
+    pub start: usize,
+    /// None if the bookmark is never closed
+    pub end: Option<usize>,
+}
+
+/// A value attached to the document by the handler of a destination (`DestinationAction::Attach`)
+/// The position is a char offset in the visible text of the body (see `get_text`)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CustomDestination {
+    /// Name of the destination, as written in RTF (ex: \mydata)
+    pub name: String,
+    pub value: String,
+    pub position: usize,
+}
+
+/// A page break of the body : a required one (\page) or one computed by the application that wrote the document (\softpage)
+/// The position is a char offset in the visible text of the body (see `get_text`)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PageBreak {
+    pub position: usize,
+    pub soft: bool,
 }
 
 // Create a RTF document from a String content
 impl TryFrom<String> for RtfDocument {
-    type Error = Box<dyn Error>;
+    type Error = RtfError;
     fn try_from(file_content: String) -> Result<Self, Self::Error> {
-        let tokens = Lexer::scan(file_content.as_str())?;
-        let document = Parser::new(tokens).parse()?;
-        return Ok(document);
+        return Self::from_str_with_options(&file_content, ParserOptions::default());
     }
 }
 
 // Create a RTF document from file content
+/// Parse a document in one call, the `Lexer` and the `Parser` being the advanced API (ex: to handle the destinations).
+/// It only borrows the source, so the documents can be parsed on several threads at once.
+pub fn parse(rtf: &str, options: &ParserOptions) -> Result<RtfDocument, RtfError> {
+    return RtfDocument::from_str_with_options(rtf, *options);
+}
+
 impl TryFrom<&str> for RtfDocument {
-    type Error = Box<dyn Error>;
+    type Error = RtfError;
     fn try_from(file_content: &str) -> Result<Self, Self::Error> {
-        let tokens = Lexer::scan(file_content)?;
-        let document = Parser::new(tokens).parse()?;
-        return Ok(document);
+        return Self::from_str_with_options(file_content, ParserOptions::default());
+    }
+}
+
+// Create a RTF document from the bytes of a file, whose binary data (\binN) can be any bytes
+impl TryFrom<&[u8]> for RtfDocument {
+    type Error = RtfError;
+    fn try_from(file_content: &[u8]) -> Result<Self, Self::Error> {
+        return Self::from_bytes_with_options(file_content, ParserOptions::default());
     }
 }
 
 // Create an RTF document from a file
+#[cfg(feature = "std")]
 impl TryFrom<&mut fs::File> for RtfDocument {
-    type Error = Box<dyn Error>;
+    type Error = RtfError;
     fn try_from(file: &mut fs::File) -> Result<Self, Self::Error> {
-        let mut file_content = String::new();
-        file.read_to_string(&mut file_content)?;
-        return Self::try_from(file_content);
+        let mut file_content = Vec::new();
+        file.read_to_end(&mut file_content)?;
+        return Self::try_from(file_content.as_slice());
     }
 }
 
+/// The kind of a content, as guessed by `RtfDocument::sniff`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentKind {
+    /// A complete RTF document, starting with the {\rtf1 prolog
+    Document,
+    /// A part of an RTF document, to parse with `ParserOptions::fragment`
+    Fragment,
+    /// Not RTF
+    Unknown,
+}
+
+// Number of bytes read by `sniff` to find a control word
+const SNIFF_LENGTH: usize = 4096;
+
 impl RtfDocument {
+    /// Guess if the content is an RTF document, an RTF fragment (known control words without the prolog) or something else
+    pub fn sniff(content: &[u8]) -> ContentKind {
+        let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content).trim_ascii_start();
+        if content.starts_with(b"{\\rtf") {
+            return ContentKind::Document;
+        }
+        let start = &content[..content.len().min(SNIFF_LENGTH)];
+        let has_control_word = start.iter().enumerate().any(|(index, byte)| {
+            if *byte != b'\\' {
+                return false;
+            }
+            let name_length = start[index + 1..].iter().take_while(|c| c.is_ascii_alphabetic()).count();
+            let name = core::str::from_utf8(&start[index..index + 1 + name_length]).unwrap_or_default();
+            // A path like C:\Users is not RTF
+            return name_length > 0 && !matches!(ControlWord::from_name(name), ControlWord::Unknown(_));
+        });
+        return if has_control_word { ContentKind::Fragment } else { ContentKind::Unknown };
+    }
+
+    /// Check if the content is a complete RTF document
+    pub fn is_rtf(content: &[u8]) -> bool {
+        return Self::sniff(content) == ContentKind::Document;
+    }
+
+    /// Create an `RtfDocument` with limits on the resources used by the lexer and the parser
+    pub fn from_str_with_options(rtf: &str, options: ParserOptions) -> Result<RtfDocument, RtfError> {
+        return Self::from_bytes_with_options(rtf.as_bytes(), options);
+    }
+
+    pub fn from_bytes_with_options(rtf: &[u8], options: ParserOptions) -> Result<RtfDocument, RtfError> {
+        let tokens = Lexer::scan_bytes_with_options(rtf, &options)?;
+        let document = Parser::new(tokens).with_options(options).parse()?;
+        return Ok(document);
+    }
+
     /// Create an `RtfDocument` from a rtf file path
-    pub fn from_filepath(filename: &str) -> Result<RtfDocument, Box<dyn Error>> {
-        let file_content = fs::read_to_string(filename)?;
-        return Self::try_from(file_content);
+    #[cfg(feature = "std")]
+    pub fn from_filepath(filename: &str) -> Result<RtfDocument, RtfError> {
+        return Self::from_filepath_with_options(filename, ParserOptions::default());
     }
 
-    /// Get the raw text of an RTF document
+    #[cfg(feature = "std")]
+    pub fn from_filepath_with_options(filename: &str, options: ParserOptions) -> Result<RtfDocument, RtfError> {
+        let file_content = fs::read(filename)?;
+        return Self::from_bytes_with_options(&file_content, options);
+    }
+
+    /// Create an `RtfDocument` from a stream, scanned chunk by chunk
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<RtfDocument, RtfError> {
+        return Self::from_reader_with_options(reader, ParserOptions::default());
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_reader_with_options<R: BufRead>(reader: R, options: ParserOptions) -> Result<RtfDocument, RtfError> {
+        let owned_tokens = Lexer::scan_reader_with_options(reader, &options)?;
+        let tokens = owned_tokens.iter().map(OwnedToken::as_token).collect();
+        let document = Parser::new(tokens).with_options(options).parse()?;
+        return Ok(document);
+    }
+
+    /// Get the raw text of an RTF document, hidden text (\v) and deleted text (\deleted) excluded
     pub fn get_text(&self) -> String {
-        let mut result = String::new();
-        for style_block in &self.body {
-            result.push_str(&style_block.text);
+        return self.get_text_with_options(&TextOptions::default());
+    }
+
+    /// Get the text of an RTF document, with the breaks and the whitespaces written as defined by the options
+    pub fn get_text_with_options(&self, options: &TextOptions) -> String {
+        let text_boxes: Vec<&Shape> = if options.include_text_boxes { self.text_boxes().collect() } else { Vec::new() };
+        let character_fields: Vec<&Field> = self.fields.iter().filter(|field| field.is_character_field()).collect();
+        let mut writer = TextWriter::new(options);
+        writer.write(&self.body, &text_boxes, &character_fields);
+        return writer.finish();
+    }
+
+    /// Get the first `max_chars` chars of the raw text (see `get_text`), without writing the rest of the document
+    pub fn get_text_truncated(&self, max_chars: usize) -> String {
+        let mut text = String::new();
+        let mut remaining = max_chars;
+        for chunk in self.text_chunks() {
+            if remaining == 0 {
+                break;
+            }
+            let end = chunk.char_indices().nth(remaining).map_or(chunk.len(), |(index, _)| index);
+            text.push_str(&chunk[..end]);
+            remaining -= chunk[..end].chars().count();
+        }
+        return text;
+    }
+
+    /// Iterate over the raw text (see `get_text`) block by block, without allocating it
+    pub fn text_chunks(&self) -> impl Iterator<Item = &str> {
+        return self
+            .body
+            .iter()
+            .filter(|style_block| style_block.painter.is_visible() && !style_block.text.is_empty())
+            .map(|style_block| style_block.text.as_str());
+    }
+
+    /// The style blocks of the paragraphs having the paragraph style named `name` (see `RtfHeader::get_style_by_name`), ex: the "heading 1" of a template,
+    /// and the ones having the character style named `name` (ex: "Hyperlink")
+    pub fn blocks_with_style<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a StyleBlock> {
+        let style_ref = self.header.get_style_by_name(name).map(|(style_ref, _)| style_ref);
+        let character_style_ref = self.header.get_character_style_by_name(name).map(|(style_ref, _)| style_ref);
+        return self.body.iter().filter(move |style_block| {
+            return Some(style_block.paragraph.style) == style_ref || (character_style_ref.is_some() && style_block.painter.char_style_ref == character_style_ref);
+        });
+    }
+
+    /// The shapes having a text box, whose text is not part of the body
+    pub fn text_boxes(&self) -> impl Iterator<Item = &Shape> {
+        return self.shapes.iter().filter(|shape| !shape.text_box.is_empty());
+    }
+}
+
+// Write the text of style blocks as defined by the options
+pub(crate) struct TextWriter<'a> {
+    options: &'a TextOptions,
+    pub result: String,
+    last_is_space: bool,
+    last_is_carriage_return: bool,
+}
+
+impl<'a> TextWriter<'a> {
+    pub fn new(options: &'a TextOptions) -> Self {
+        return Self {
+            options,
+            result: String::new(),
+            last_is_space: false,
+            last_is_carriage_return: false,
+        };
+    }
+
+    // The written text, normalized if required by the options
+    pub fn finish(self) -> String {
+        #[cfg(feature = "unicode-normalization")]
+        if self.options.normalize_unicode {
+            return unicode::to_nfc(&self.result);
+        }
+        return self.result;
+    }
+
+    // The text boxes and the character fields are written at their position in the visible text, they must be sorted by position
+    pub fn write(&mut self, style_blocks: &[StyleBlock], text_boxes: &[&Shape], character_fields: &[&Field]) {
+        let mut text_boxes = text_boxes.iter().peekable();
+        let mut character_fields = character_fields.iter().peekable();
+        let mut position = 0;
+        for style_block in style_blocks {
+            let painter = &style_block.painter;
+            let visible = painter.is_visible();
+            let included = (!painter.hidden || self.options.include_hidden) && (!painter.deleted || self.options.include_deleted);
+            let text = if included { style_block.text.as_str() } else { "" };
+            for c in text.chars() {
+                // The positions are the ones of the visible text, the included hidden or deleted text is not counted
+                if visible {
+                    self.write_placed(position, &mut text_boxes, &mut character_fields);
+                    position += 1;
+                }
+                self.write_char(c);
+            }
+            if style_block.paragraph_end {
+                // The ones at the end of the paragraph are written before its break
+                self.write_placed(position, &mut text_boxes, &mut character_fields);
+                self.result.push_str(&self.options.paragraph_break);
+                self.last_is_space = false;
+                self.last_is_carriage_return = false;
+            }
+        }
+        self.write_placed(usize::MAX, &mut text_boxes, &mut character_fields);
+    }
+
+    // Write the text boxes and the character fields placed before the position
+    fn write_placed(&mut self, position: usize, text_boxes: &mut Peekable<Iter<&Shape>>, character_fields: &mut Peekable<Iter<&Field>>) {
+        while let Some(shape) = text_boxes.next_if(|shape| shape.position <= position) {
+            self.write(&shape.text_box, &[], &[]);
+        }
+        let options = self.options;
+        while let Some(field) = character_fields.next_if(|field| field.start <= position) {
+            let text = match field.field_type {
+                FieldType::Page => &options.page_number,
+                FieldType::Date => &options.date,
+                FieldType::Time => &options.time,
+                _ => continue,
+            };
+            if !text.is_empty() {
+                self.result.push_str(text);
+                self.last_is_space = false;
+            }
+        }
+    }
+
+    fn write_char(&mut self, c: char) {
+        let options = self.options;
+        let follows_carriage_return = core::mem::replace(&mut self.last_is_carriage_return, c == '\r');
+        #[rustfmt::skip]
+        match c {
+            '\n' if options.normalize_line_endings && follows_carriage_return => {}
+            '\r' if options.normalize_line_endings => self.result.push_str(&options.line_break),
+            ' ' | '\t' if options.collapse_whitespace => {
+                if !self.last_is_space {
+                    self.result.push(' ');
+                }
+                self.last_is_space = true;
+                return;
+            }
+            '\t'       => self.result.push_str(&options.tab),
+            '\n'       => self.result.push_str(&options.line_break),
+            '\u{a0}'   => options.non_breaking_space.write(c, &mut self.result),
+            '\u{ad}'   => options.optional_hyphen.write(c, &mut self.result),
+            '\u{2011}' => options.non_breaking_hyphen.write(c, &mut self.result),
+            _          => self.result.push(c),
+        };
+        self.last_is_space = false;
+    }
+}
+
+/// How the breaks and the whitespaces of the body are written by `RtfDocument::get_text_with_options`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextOptions {
+    /// Written at the end of each paragraph (\par), nothing by default
+    pub paragraph_break: String,
+    /// Written for each line break (\line, or a \ followed by a newline in the source), "\n" by default
+    pub line_break: String,
+    /// Written for each tab (\tab), "\t" by default
+    pub tab: String,
+    /// Collapse the consecutive spaces and tabs in a single space
+    pub collapse_whitespace: bool,
+    /// The non-breaking spaces (\~), written as U+00A0 by default
+    pub non_breaking_space: CharSubstitution,
+    /// The optional hyphens (\-), written as U+00AD (soft hyphen) by default
+    pub optional_hyphen: CharSubstitution,
+    /// The non-breaking hyphens (\_), written as U+2011 by default
+    pub non_breaking_hyphen: CharSubstitution,
+    /// Write the text of the text boxes (see `RtfDocument::text_boxes`) at the position of their shape, they are excluded by default
+    pub include_text_boxes: bool,
+    /// Written at the position of the page number character fields (\chpgn), whose value is only known when the document is displayed.
+    /// Nothing by default, ex: "{PAGE}" to keep a placeholder in the text of the templates
+    pub page_number: String,
+    /// Written at the position of the date character fields (\chdate, \chdpl, \chdpa), nothing by default
+    pub date: String,
+    /// Written at the position of the time character fields (\chtime), nothing by default
+    pub time: String,
+    /// Write the CR, LF and CRLF of the text (ex: \'0d\'0a or \u13 in the documents written on Windows) as a single `line_break`
+    pub normalize_line_endings: bool,
+    /// Normalize the text to the Unicode Normalization Form C, so the accents written as combining chars (ex: e\u769) are composed
+    #[cfg(feature = "unicode-normalization")]
+    pub normalize_unicode: bool,
+    /// Write the hidden text (\v), which is not displayed by the word processors
+    pub include_hidden: bool,
+    /// Write the text deleted while the revisions were tracked (\deleted)
+    pub include_deleted: bool,
+}
+
+/// What is written for a special char of the text
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum CharSubstitution {
+    /// Write the char
+    #[default]
+    Keep,
+    Drop,
+    Replace(String),
+}
+
+impl CharSubstitution {
+    fn write(&self, c: char, result: &mut String) {
+        match self {
+            CharSubstitution::Keep => result.push(c),
+            CharSubstitution::Drop => {}
+            CharSubstitution::Replace(replacement) => result.push_str(replacement),
+        }
+    }
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            paragraph_break: String::new(),
+            line_break: String::from("\n"),
+            tab: String::from("\t"),
+            collapse_whitespace: false,
+            non_breaking_space: CharSubstitution::Keep,
+            optional_hyphen: CharSubstitution::Keep,
+            non_breaking_hyphen: CharSubstitution::Keep,
+            include_text_boxes: false,
+            page_number: String::new(),
+            date: String::new(),
+            time: String::new(),
+            normalize_line_endings: false,
+            #[cfg(feature = "unicode-normalization")]
+            normalize_unicode: false,
+            include_hidden: false,
+            include_deleted: false,
         }
-        return result;
     }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
-    use crate::document::RtfDocument;
+    use crate::tokens::ControlWordMatching;
+    use proptest::prelude::*;
+
+    #[test]
+    fn get_truncated_text() {
+        let rtf = r"{\rtf1\ansi Caf\'e9 {\b cr\'e8me}{\v hidden} br\'fbl\'e9e\par End}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.text_chunks().collect::<Vec<_>>(), ["Café ", "crème", " brûlée", "End"]);
+        assert_eq!(document.text_chunks().collect::<String>(), document.get_text());
+        assert_eq!(document.get_text_truncated(0), "");
+        assert_eq!(document.get_text_truncated(7), "Café cr");
+        assert_eq!(document.get_text_truncated(10), "Café crème");
+        assert_eq!(document.get_text_truncated(14), "Café crème brû");
+        assert_eq!(document.get_text_truncated(100), document.get_text());
+    }
+
+    #[test]
+    fn parse_with_options() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0\fswiss Arial;}}\F0\B \'c0\'e1\fs+24 c\PAR}";
+        let options = ParserOptions {
+            matching: ControlWordMatching::Lenient,
+            default_codepage: Some(1251),
+            ..ParserOptions::default()
+        };
+        let document = parse(rtf, &options).unwrap();
+        assert_eq!(document.get_text(), "Абc");
+        assert!(document.body[0].painter.bold);
+        assert_eq!(document.body[0].painter.font_size, 24);
+        assert!(document.body[0].paragraph_end);
+        // The code page declared by the document is kept
+        assert_eq!(parse(r"{\rtf1\ansi\ansicpg1252 \'c0}", &options).unwrap().get_text(), "À");
+        assert_eq!(parse(rtf, &ParserOptions::default()).unwrap().get_text(), "Àá+24 c");
+        // The documents are parsed on several threads, from the same options
+        let texts: Vec<String> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| parse(rtf, &options).unwrap().get_text())).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert!(texts.iter().all(|text| text == "Абc"));
+    }
 
     #[test]
     fn get_text_from_document() {
@@ -82,6 +505,84 @@ pub(crate) mod tests {
     }
 
     #[test]
+    fn get_text_skip_hidden_text() {
+        let rtf = r#"{\rtf1\ansi Visible {\v hidden }text\par }"#;
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Visible text")
+    }
+
+    #[test]
+    fn get_text_skip_deleted_text() {
+        let rtf = r"{\rtf1\ansi Visible {\v hidden }{\deleted removed }text \chpgn\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Visible text ");
+        assert_eq!(crate::extract_text(rtf).unwrap(), document.get_text());
+        assert!(document.body[2].painter.deleted && !document.body[2].painter.is_visible());
+        // The character field stays at its position in the visible text
+        let options = TextOptions {
+            page_number: "#".into(),
+            ..TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "Visible text #");
+        let options = TextOptions { include_hidden: true, ..options };
+        assert_eq!(document.get_text_with_options(&options), "Visible hidden text #");
+        let options = TextOptions { include_deleted: true, ..options };
+        assert_eq!(document.get_text_with_options(&options), "Visible hidden removed text #");
+    }
+
+    #[test]
+    fn get_text_with_options() {
+        let rtf = "{\\rtf1\\ansi First\\tab  paragraph\\par Line\\line break\\\n  and   spaces\\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "First\t paragraphLine\nbreak\n  and   spaces");
+        let options = TextOptions {
+            paragraph_break: String::from("\r\n"),
+            line_break: String::from("\r\n"),
+            tab: String::from("    "),
+            ..TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "First     paragraph\r\nLine\r\nbreak\r\n  and   spaces\r\n");
+        let options = TextOptions {
+            paragraph_break: String::from("\n\n"),
+            collapse_whitespace: true,
+            ..TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "First paragraph\n\nLine\nbreak\n and spaces\n\n");
+    }
+
+    #[test]
+    fn get_text_with_normalized_line_endings() {
+        // The line breaks of the source are ignored whatever their platform, an escaped one is a line break
+        for rtf in ["{\\rtf1\\ansi Line\\\nbreak\nnext}", "{\\rtf1\\ansi Line\\\r\nbreak\r\nnext}", "{\\rtf1\\ansi Line\\\rbreak\rnext}"] {
+            assert_eq!(RtfDocument::try_from(rtf).unwrap().get_text(), "Line\nbreaknext", "{rtf:?}");
+        }
+        let rtf = r"{\rtf1\ansi CRLF\'0d\'0aCR\'0dLF\'0aLF CR\u13 ?\line end}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "CRLF\r\nCR\rLF\nLF CR\r\nend");
+        let options = TextOptions {
+            line_break: String::from("\r\n"),
+            normalize_line_endings: true,
+            ..TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "CRLF\r\nCR\r\nLF\r\nLF CR\r\nend");
+    }
+
+    #[test]
+    fn get_text_with_special_chars() {
+        let rtf = r"{\rtf1\ansi 10\~km, hy\-phen\-ation and non\_breaking}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "10\u{a0}km, hy\u{ad}phen\u{ad}ation and non\u{2011}breaking");
+        let options = TextOptions {
+            non_breaking_space: CharSubstitution::Replace(String::from(" ")),
+            optional_hyphen: CharSubstitution::Drop,
+            non_breaking_hyphen: CharSubstitution::Replace(String::from("-")),
+            ..TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "10 km, hyphenation and non-breaking");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
     fn create_document_from_file() {
         let mut file = fs::File::open("./resources/tests/test-file.rtf").unwrap();
         let document = RtfDocument::try_from(&mut file).unwrap();
@@ -89,9 +590,103 @@ pub(crate) mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn create_document_from_filepath() {
         let filename = "./resources/tests/test-file.rtf";
         let document = RtfDocument::from_filepath(filename).unwrap();
         assert_eq!(document.header.font_table.get(&0).unwrap().name, String::from("Helvetica"));
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn create_document_from_reader() {
+        let file = fs::File::open("./resources/tests/file-sample_500kB.rtf").unwrap();
+        let document = RtfDocument::from_reader(std::io::BufReader::new(file)).unwrap();
+        assert_eq!(document, RtfDocument::from_filepath("./resources/tests/file-sample_500kB.rtf").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn create_document_with_binary_data() {
+        // The binary data of the picture is not valid UTF-8
+        let rtf = b"{\\rtf1\\ansi Image : {\\pict\\pngblip\\bin4 \x89PN\xff} end}";
+        let document = RtfDocument::try_from(&rtf[..]).unwrap();
+        assert_eq!(document.images[0].data, b"\x89PN\xff");
+        assert_eq!(document.get_text(), "Image :  end");
+        assert_eq!(RtfDocument::from_reader(&rtf[..]).unwrap(), document);
+    }
+
+    #[test]
+    fn sniff_content() {
+        assert_eq!(RtfDocument::sniff(b"\xEF\xBB\xBF \n{\\rtf1\\ansi text}"), ContentKind::Document);
+        assert_eq!(RtfDocument::sniff(br"some {\b bold} text\par"), ContentKind::Fragment);
+        assert_eq!(RtfDocument::sniff(br"C:\Users\me\file.txt"), ContentKind::Unknown);
+        assert_eq!(RtfDocument::sniff(b"plain text"), ContentKind::Unknown);
+        assert!(RtfDocument::is_rtf(br"{\rtf1 text}"));
+        assert!(!RtfDocument::is_rtf(br"{\b text}"));
+    }
+
+    #[test]
+    fn parse_fragment() {
+        let rtf = r"end of a {\b bold} group}\par{\i unclosed";
+        assert!(RtfDocument::try_from(rtf).is_err());
+        let options = ParserOptions {
+            fragment: true,
+            ..ParserOptions::default()
+        };
+        let document = RtfDocument::from_str_with_options(rtf, options).unwrap();
+        assert_eq!(document.get_text(), "end of a bold groupunclosed");
+        assert!(document.body[1].painter.bold);
+        assert!(document.body.last().unwrap().painter.italic);
+    }
+
+    // RTF snippets that are hard to reach from the strategies : escapes cut in the middle, binary data, destinations, tables, ...
+    #[rustfmt::skip]
+    const SNIPPETS: [&str; 24] = [
+        r"\\", r"\{", r"\}", r"\~", "\r\n", r"\'e9", r"\'", r"\'g", r"\u233?", r"\uc0", r"\uc9", r"\*", r"\bin3 ",
+        r"{\*\fldinst HYPERLINK ", r"{\fldrslt ", r"{\*\bkmkstart a}", r"{\pict\pngblip 89504e", r"\trowd\cellx100 ",
+        r"\ls1\ilvl9 ", r"{\upr{a}{\*\ud{b}}}", r"{\fonttbl\f0\froman Times;}", r"{\colortbl;\red255;}", r"{\stylesheet{\s1 h", "}",
+    ];
+
+    #[rustfmt::skip]
+    const CONTROL_WORDS: [&str; 24] = [
+        "b", "i", "ul", "fs", "cf", "f", "u", "uc", "bin", "par", "pard", "line", "tab", "page", "intbl", "cell", "row",
+        "cellx", "ls", "ilvl", "itap", "sect", "plain", "unknown",
+    ];
+
+    // A control word with no value, an extreme value, or a value that doesn't fit in an i32
+    fn control_word() -> impl Strategy<Value = String> {
+        let value = prop_oneof![Just(String::new()), any::<i32>().prop_map(|value| value.to_string()), Just(String::from("99999999999"))];
+        return (proptest::sample::select(&CONTROL_WORDS[..]), value, any::<bool>()).prop_map(|(name, value, delimited)| format!("\\{name}{value}{}", if delimited { " " } else { "" }));
+    }
+
+    // The content of a document : text, control words and snippets, nested in groups and destinations
+    fn content() -> impl Strategy<Value = String> {
+        let leaf = prop_oneof!["[a-z é€\n]{1,8}", control_word(), proptest::sample::select(&SNIPPETS[..]).prop_map(String::from),];
+        return leaf.prop_recursive(8, 128, 8, |inner| {
+            let items = proptest::collection::vec(inner, 0..8);
+            return prop_oneof![
+                items.clone().prop_map(|items| format!("{{{}}}", items.concat())),
+                (control_word(), items).prop_map(|(destination, items)| format!("{{\\*{destination}{}}}", items.concat())),
+            ];
+        });
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2000))]
+
+        #[test]
+        fn parse_random_documents(items in proptest::collection::vec(content(), 0..16), closed in any::<bool>()) {
+            // Some of the documents are truncated
+            let rtf = format!(r"{{\rtf1\ansi{}{}", items.concat(), if closed { "}" } else { "" });
+            // The only invariant is to never panic : the document is either parsed or rejected with an error
+            if let Ok(document) = RtfDocument::try_from(rtf.as_str()) {
+                let _ = (document.get_text(), document.outline(), document.find("e"));
+                #[cfg(feature = "render")]
+                let _ = (document.to_html(), document.to_latex(), document.to_text_layout(40));
+            }
+            let _ = Lexer::scan_with_spans(&rtf);
+            let _ = crate::preserve::PreservedDocument::parse(rtf.as_bytes()).map(|document| document.to_rtf());
+        }
+    }
 }
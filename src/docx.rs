@@ -0,0 +1,299 @@
+/// Define the export of a document to a minimal DOCX (Office Open XML) package
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::document::RtfDocument;
+use crate::paragraph::{Alignment, Paragraph, SpaceBetweenLine, TabKind, TabLeader};
+use crate::parser::{Painter, Underline};
+use crate::units::Twips;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/></Types>"#;
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+const DOCUMENT_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:sz w:val="24"/></w:rPr></w:rPrDefault><w:pPrDefault/></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style></w:styles>"#;
+const DOCUMENT_START: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>"#;
+const DOCUMENT_END: &str = "</w:body></w:document>";
+
+impl RtfDocument {
+    /// Convert the document to a minimal DOCX package (the bytes of the .docx file) with its text, character and paragraph formatting.
+    /// The tables, images, fields and lists are exported as plain paragraphs.
+    pub fn to_docx(&self) -> Vec<u8> {
+        let document = self.get_docx_document();
+        return write_zip(&[
+            ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+            ("_rels/.rels", RELATIONSHIPS.as_bytes()),
+            ("word/_rels/document.xml.rels", DOCUMENT_RELATIONSHIPS.as_bytes()),
+            ("word/styles.xml", STYLES.as_bytes()),
+            ("word/document.xml", document.as_bytes()),
+        ]);
+    }
+
+    // The content of word/document.xml
+    fn get_docx_document(&self) -> String {
+        let mut xml = String::from(DOCUMENT_START);
+        for (paragraph, runs) in self.get_paragraph_runs(true) {
+            xml.push_str("<w:p>");
+            write_paragraph_properties(&mut xml, paragraph);
+            for (painter, text) in runs {
+                xml.push_str("<w:r>");
+                self.write_run_properties(&mut xml, painter);
+                let mut rest = text;
+                while let Some(index) = rest.find(['\t', '\n']) {
+                    write_text(&mut xml, &rest[..index]);
+                    xml.push_str(if rest[index..].starts_with('\t') { "<w:tab/>" } else { "<w:br/>" });
+                    rest = &rest[index + 1..];
+                }
+                write_text(&mut xml, rest);
+                xml.push_str("</w:r>");
+            }
+            xml.push_str("</w:p>");
+        }
+        xml.push_str(DOCUMENT_END);
+        return xml;
+    }
+
+    fn write_run_properties(&self, xml: &mut String, painter: &Painter) {
+        let mut properties = String::new();
+        if let Some(font) = self.header.font_table.get(&painter.font_ref) {
+            let name = escape_xml(&font.name);
+            let _ = write!(properties, r#"<w:rFonts w:ascii="{name}" w:hAnsi="{name}" w:cs="{name}"/>"#);
+        }
+        #[rustfmt::skip]
+        let toggles = [
+            (painter.bold,      "<w:b/>"),
+            (painter.italic,    "<w:i/>"),
+            (painter.caps,      "<w:caps/>"),
+            (painter.smallcaps, "<w:smallCaps/>"),
+            (painter.strike,    "<w:strike/>"),
+            (painter.outline,   "<w:outline/>"),
+            (painter.shadow,    "<w:shadow/>"),
+            (painter.hidden,    "<w:vanish/>"),
+        ];
+        for (_, tag) in toggles.iter().filter(|(enabled, _)| *enabled) {
+            properties.push_str(tag);
+        }
+        if painter.color_ref != 0 {
+            if let Some(color) = self.header.get_color(&painter.color_ref) {
+                let _ = write!(properties, r#"<w:color w:val="{:02X}{:02X}{:02X}"/>"#, color.red, color.green, color.blue);
+            }
+        }
+        if painter.character_spacing != Twips(0) {
+            let _ = write!(properties, r#"<w:spacing w:val="{}"/>"#, painter.character_spacing);
+        }
+        if painter.baseline_offset != 0 {
+            let _ = write!(properties, r#"<w:position w:val="{}"/>"#, painter.baseline_offset);
+        }
+        // Both are expressed in half-points
+        let _ = write!(properties, r#"<w:sz w:val="{}"/>"#, painter.font_size);
+        if let Some(underline) = get_underline_value(&painter.underline) {
+            let _ = write!(properties, r#"<w:u w:val="{underline}"/>"#);
+        }
+        if painter.superscript {
+            properties.push_str(r#"<w:vertAlign w:val="superscript"/>"#);
+        } else if painter.subscript {
+            properties.push_str(r#"<w:vertAlign w:val="subscript"/>"#);
+        }
+        let _ = write!(xml, "<w:rPr>{properties}</w:rPr>");
+    }
+}
+
+fn write_paragraph_properties(xml: &mut String, paragraph: &Paragraph) {
+    xml.push_str("<w:pPr>");
+    if !paragraph.tab_stops.is_empty() {
+        xml.push_str("<w:tabs>");
+        for tab_stop in &paragraph.tab_stops {
+            #[rustfmt::skip]
+            let kind = match tab_stop.kind {
+                TabKind::Left    => "left",
+                TabKind::Right   => "right",
+                TabKind::Center  => "center",
+                TabKind::Decimal => "decimal",
+                TabKind::Bar     => "bar",
+            };
+            // OOXML has no equal sign leader
+            #[rustfmt::skip]
+            let leader = match tab_stop.leader {
+                TabLeader::Dot                      => "dot",
+                TabLeader::Hyphen                   => "hyphen",
+                TabLeader::Underline                => "underscore",
+                TabLeader::Thick                    => "heavy",
+                TabLeader::None | TabLeader::Equal  => "none",
+            };
+            let _ = write!(xml, r#"<w:tab w:val="{kind}" w:leader="{leader}" w:pos="{}"/>"#, tab_stop.position);
+        }
+        xml.push_str("</w:tabs>");
+    }
+    let spacing = &paragraph.spacing;
+    let _ = write!(xml, r#"<w:spacing w:before="{}" w:after="{}""#, spacing.before, spacing.after);
+    if let SpaceBetweenLine::Value(line) = spacing.between_line {
+        // \slmult1 : the line spacing is a multiple of the single line spacing (240)
+        let rule = if spacing.line_multiplier == 1 { "auto" } else { "atLeast" };
+        let _ = write!(xml, r#" w:line="{line}" w:lineRule="{rule}""#);
+    }
+    xml.push_str("/>");
+    let indent = &paragraph.indent;
+    let _ = write!(xml, r#"<w:ind w:left="{}" w:right="{}" "#, indent.left, indent.right);
+    let _ = match indent.first_line {
+        first_line if first_line < Twips(0) => write!(xml, r#"w:hanging="{}"/>"#, -first_line),
+        first_line => write!(xml, r#"w:firstLine="{first_line}"/>"#),
+    };
+    #[rustfmt::skip]
+    let alignment = match paragraph.alignment {
+        Alignment::LeftAligned  => "left",
+        Alignment::RightAligned => "right",
+        Alignment::Center       => "center",
+        Alignment::Justify      => "both",
+    };
+    let _ = write!(xml, r#"<w:jc w:val="{alignment}"/>"#);
+    xml.push_str("</w:pPr>");
+    // The list labels are computed by the parser, so they are exported as text
+    if !paragraph.list_label.is_empty() {
+        let _ = write!(xml, r#"<w:r><w:t xml:space="preserve">{} </w:t></w:r>"#, escape_xml(&paragraph.list_label));
+    }
+}
+
+fn get_underline_value(underline: &Underline) -> Option<&'static str> {
+    #[rustfmt::skip]
+    return match underline {
+        Underline::None       => None,
+        Underline::Single     => Some("single"),
+        Underline::Double     => Some("double"),
+        Underline::Word       => Some("words"),
+        Underline::Dotted     => Some("dotted"),
+        Underline::Dash       => Some("dash"),
+        Underline::DashDot    => Some("dotDash"),
+        Underline::DashDotDot => Some("dotDotDash"),
+        Underline::LongDash   => Some("dashLong"),
+        Underline::Thick      => Some("thick"),
+        Underline::Wave       => Some("wave"),
+        Underline::DoubleWave => Some("wavyDouble"),
+    };
+}
+
+fn write_text(xml: &mut String, text: &str) {
+    if !text.is_empty() {
+        let _ = write!(xml, r#"<w:t xml:space="preserve">{}</w:t>"#, escape_xml(text));
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        #[rustfmt::skip]
+        match c {
+            '&'  => result.push_str("&amp;"),
+            '<'  => result.push_str("&lt;"),
+            '>'  => result.push_str("&gt;"),
+            '"'  => result.push_str("&quot;"),
+            // Control chars are not allowed in XML 1.0
+            c if c.is_control() => {}
+            c    => result.push(c),
+        };
+    }
+    return result;
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+    return !crc;
+}
+
+// Write a zip archive of uncompressed (stored) entries
+fn write_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    const VERSION: u16 = 20;
+    const DATE: u16 = (1 << 5) | 1; // 1980-01-01, the minimum date of the zip format
+    let mut archive: Vec<u8> = vec![];
+    let mut central_directory: Vec<u8> = vec![];
+    for (name, data) in entries {
+        let offset = archive.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+        // Local file header
+        archive.extend(0x04034b50u32.to_le_bytes());
+        for value in [VERSION, 0, 0, 0, DATE] {
+            archive.extend(value.to_le_bytes());
+        }
+        for value in [crc, size, size] {
+            archive.extend(value.to_le_bytes());
+        }
+        archive.extend((name.len() as u16).to_le_bytes());
+        archive.extend(0u16.to_le_bytes());
+        archive.extend(name.as_bytes());
+        archive.extend(*data);
+        // Central directory file header
+        central_directory.extend(0x02014b50u32.to_le_bytes());
+        for value in [VERSION, VERSION, 0, 0, 0, DATE] {
+            central_directory.extend(value.to_le_bytes());
+        }
+        for value in [crc, size, size] {
+            central_directory.extend(value.to_le_bytes());
+        }
+        for value in [name.len() as u16, 0, 0, 0, 0] {
+            central_directory.extend(value.to_le_bytes());
+        }
+        for value in [0, offset] {
+            central_directory.extend(value.to_le_bytes());
+        }
+        central_directory.extend(name.as_bytes());
+    }
+    let central_directory_offset = archive.len() as u32;
+    archive.extend(&central_directory);
+    // End of central directory record
+    archive.extend(0x06054b50u32.to_le_bytes());
+    for value in [0, 0, entries.len() as u16, entries.len() as u16] {
+        archive.extend(value.to_le_bytes());
+    }
+    for value in [central_directory.len() as u32, central_directory_offset] {
+        archive.extend(value.to_le_bytes());
+    }
+    archive.extend(0u16.to_le_bytes());
+    return archive;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_docx_document() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}{\colortbl;\red255\green0\blue0;}{\pard\qc\f0 Title & {\b\cf1 bold}\par}{\pard\ql A\tab B\par}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let xml = document.get_docx_document();
+        assert!(xml.contains(r#"<w:jc w:val="center"/></w:pPr><w:r><w:rPr><w:rFonts w:ascii="Helvetica" w:hAnsi="Helvetica" w:cs="Helvetica"/><w:sz w:val="24"/></w:rPr><w:t xml:space="preserve">Title &amp; </w:t></w:r>"#));
+        assert!(xml.contains(r#"<w:b/><w:color w:val="FF0000"/><w:sz w:val="24"/></w:rPr><w:t xml:space="preserve">bold</w:t></w:r></w:p>"#));
+        assert!(xml.contains(r#"<w:t xml:space="preserve">A</w:t><w:tab/><w:t xml:space="preserve">B</w:t></w:r></w:p></w:body>"#));
+        assert_eq!(xml.matches("<w:p>").count(), 2);
+    }
+
+    #[test]
+    fn export_docx_line_breaks() {
+        let document = RtfDocument::try_from(r"{\rtf1\ansi first\line second\par third\par}").unwrap();
+        let xml = document.get_docx_document();
+        assert_eq!(xml.matches("<w:p>").count(), 2);
+        assert!(xml.contains(r#"<w:t xml:space="preserve">first</w:t><w:br/><w:t xml:space="preserve">second</w:t></w:r></w:p>"#));
+    }
+
+    #[test]
+    fn export_docx_package() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+        let document = RtfDocument::try_from(r"{\rtf1\ansi Text}").unwrap();
+        let docx = document.to_docx();
+        assert_eq!(&docx[..4], b"PK\x03\x04");
+        assert_eq!(&docx[docx.len() - 22..docx.len() - 18], b"PK\x05\x06");
+        // Number of entries
+        assert_eq!(u16::from_le_bytes([docx[docx.len() - 12], docx[docx.len() - 11]]), 5);
+    }
+}
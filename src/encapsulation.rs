@@ -0,0 +1,245 @@
+/// Define the de-encapsulation of the HTML and the plain text encapsulated in RTF by the mail clients (\fromhtml1 and \fromtext, as specified by [MS-OXRTFEX])
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::codepage::ByteDecoder;
+use crate::error::RtfError;
+use crate::extract::{read_group, skip_group};
+use crate::header::{CharacterSet, FontRef, RtfHeader};
+use crate::lexer::Lexer;
+use crate::parser::{Parser, ParserError};
+use crate::tokens::{ControlWord, Token};
+use crate::unicode::UnicodeDecoder;
+
+/// The original format of a mail body encapsulated in RTF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum EncapsulatedFormat {
+    /// \fromhtml1 : the HTML tags are in \*\htmltag destinations
+    Html,
+    /// \fromtext : the body was written as plain text
+    Text,
+}
+
+impl EncapsulatedFormat {
+    pub fn from(token: &Token) -> Option<Self> {
+        return match token {
+            Token::ControlSymbol((ControlWord::FromHtml, property)) if property.get_value_or(0) == 1 => Some(Self::Html),
+            Token::ControlSymbol((ControlWord::FromText, _)) => Some(Self::Text),
+            _ => None,
+        };
+    }
+}
+
+/// A mail body de-encapsulated from RTF, see `extract_encapsulated`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncapsulatedBody {
+    pub format: EncapsulatedFormat,
+    /// The HTML or the plain text of the body, whose paragraphs end with CRLF
+    pub content: String,
+}
+
+// The state of a group needed to rebuild the body
+#[derive(Debug, Clone, Copy)]
+struct EncapsulationState {
+    // In a \*\htmltag destination : its text is an HTML tag
+    in_tag: bool,
+    // After a \htmlrtf : the text is only there for the RTF readers
+    suppressed: bool,
+    // None for the default font of the document (\deffN)
+    font_ref: Option<FontRef>,
+    unicode_ignore_count: i32,
+}
+
+impl Default for EncapsulationState {
+    fn default() -> Self {
+        return Self {
+            in_tag: false,
+            suppressed: false,
+            font_ref: None,
+            unicode_ignore_count: 1,
+        };
+    }
+}
+
+/// Rebuild the original HTML of a mail body encapsulated in RTF (ex: the body of an Outlook message), or None if the document doesn't encapsulate HTML (no \fromhtml1).
+/// The HTML tags are the text of the \*\htmltag destinations, and the text outside of them is the text of the HTML, except the parts written for the RTF readers only (\htmlrtf).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn extract_html(rtf: &str) -> Result<Option<String>, RtfError> {
+    return Ok(extract_encapsulated(rtf)?.filter(|body| body.format == EncapsulatedFormat::Html).map(|body| body.content));
+}
+
+/// Rebuild the original plain text of a mail body encapsulated in RTF, or None if the document doesn't encapsulate plain text (no \fromtext).
+/// Unlike `get_text`, the lines end with CRLF, as in the original message, and the parts written for the RTF readers only (\htmlrtf) are excluded.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn extract_encapsulated_text(rtf: &str) -> Result<Option<String>, RtfError> {
+    return Ok(extract_encapsulated(rtf)?.filter(|body| body.format == EncapsulatedFormat::Text).map(|body| body.content));
+}
+
+/// De-encapsulate the mail body of the document, whatever its original format, or None if it's a regular document.
+/// The paragraphs (\par) and the line breaks (\line) are written as CRLF, and the bytes (\'hh) are decoded with the code page of their font.
+pub fn extract_encapsulated(rtf: &str) -> Result<Option<EncapsulatedBody>, RtfError> {
+    let mut lexer = Lexer::new(rtf);
+    match lexer.next().transpose()? {
+        Some(Token::OpeningBracket) => {}
+        Some(token) => return Err(ParserError::InvalidToken(format!("Invalid first token : {:?} not a '{{'", token)).into()),
+        None => return Err(ParserError::NoMoreToken.into()),
+    }
+    let mut format = None;
+    let mut header = RtfHeader::default();
+    let mut state = EncapsulationState::default();
+    let mut state_stack: Vec<EncapsulationState> = vec![];
+    let mut unicode_decoder = UnicodeDecoder::default();
+    let mut byte_decoder = ByteDecoder::default();
+    let mut content = String::new();
+    while let Some(token) = lexer.next().transpose()? {
+        match token {
+            Token::OpeningBracket => {
+                #[rustfmt::skip]
+                match lexer.peek().transpose()?.cloned() {
+                    Some(Token::IgnorableDestination) => {
+                        lexer.next();
+                        // The \*\mhtmltag destinations hold the tags whose URL has been rewritten, the original one is in the following \*\htmltag
+                        let is_html_tag = matches!(lexer.peek().transpose()?, Some(Token::ControlSymbol((ControlWord::HtmlTag, _))));
+                        if is_html_tag && format == Some(EncapsulatedFormat::Html) {
+                            state_stack.push(state);
+                            unicode_decoder.begin_group();
+                            state.in_tag = true;
+                        } else {
+                            skip_group(&mut lexer)?;
+                        }
+                    }
+                    Some(Token::ControlSymbol((ControlWord::FontTable, _))) => header.font_table = Parser::parse_font_table(&read_group(&mut lexer)?)?,
+                    Some(Token::ControlSymbol((ControlWord::ColorTable
+                        | ControlWord::StyleSheet
+                        | ControlWord::ListTable
+                        | ControlWord::ListOverrideTable
+                        | ControlWord::FileTable
+                        | ControlWord::Picture, _))) => skip_group(&mut lexer)?,
+                    _ => {
+                        state_stack.push(state);
+                        unicode_decoder.begin_group();
+                    }
+                };
+            }
+            Token::ClosingBracket => {
+                state = state_stack.pop().unwrap_or_default();
+                unicode_decoder.end_group();
+            }
+            Token::ControlSymbol((control_word, property)) => {
+                #[rustfmt::skip]
+                match control_word {
+                    ControlWord::FromHtml
+                        | ControlWord::FromText     => format = EncapsulatedFormat::from(&Token::ControlSymbol((control_word, property))),
+                    ControlWord::HtmlRtf            => state.suppressed = property.as_bool(),
+                    ControlWord::FontNumber         => state.font_ref = property.get_value_as::<FontRef>().ok(),
+                    ControlWord::UnicodeIgnoreCount => state.unicode_ignore_count = property.get_value(),
+                    ControlWord::Unicode            => {
+                        if let Some(char) = unicode_decoder.decode(&property, state.unicode_ignore_count)? {
+                            push_content(&mut content, char.encode_utf8(&mut [0; 4]), state);
+                        }
+                    }
+                    ControlWord::HexByte            => {
+                        let byte = property.get_value_as::<u8>()?;
+                        if !unicode_decoder.skip_char()? {
+                            let codepage = Parser::get_codepage(state.font_ref.unwrap_or(header.defaults.font_ref), &header);
+                            if let Some(char) = byte_decoder.decode(codepage, byte) {
+                                push_content(&mut content, char.encode_utf8(&mut [0; 4]), state);
+                            }
+                        }
+                    }
+                    ControlWord::Par
+                        | ControlWord::Line         => push_content(&mut content, "\r\n", state),
+                    control_word if control_word.get_text().is_some() => {
+                        let is_fallback = unicode_decoder.skip_char()?;
+                        if let Some(control_word_text) = control_word.get_text().filter(|_| !is_fallback) {
+                            push_content(&mut content, control_word_text, state);
+                        }
+                    }
+                    control_word                    => {
+                        if let Some(character_set) = CharacterSet::from(&Token::ControlSymbol((control_word, property))) {
+                            header.character_set = character_set;
+                        }
+                        header.defaults.apply(&control_word, &property);
+                    }
+                };
+            }
+            Token::PlainText(plain_text) => {
+                let codepage = Parser::get_codepage(state.font_ref.unwrap_or(header.defaults.font_ref), &header);
+                let (char, plain_text) = byte_decoder.decode_text(codepage, plain_text);
+                if let Some(char) = char {
+                    push_content(&mut content, char.encode_utf8(&mut [0; 4]), state);
+                }
+                push_content(&mut content, unicode_decoder.skip_fallback(plain_text)?, state);
+            }
+            Token::CRLF => push_content(&mut content, "\r\n", state),
+            Token::IgnorableDestination | Token::Binary(_) => {}
+            Token::Empty => return Err(ParserError::ParseEmptyToken.into()),
+        };
+    }
+    return Ok(format.map(|format| EncapsulatedBody { format, content }));
+}
+
+fn push_content(content: &mut String, new_content: &str, state: EncapsulationState) {
+    if state.in_tag || !state.suppressed {
+        content.push_str(new_content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_encapsulated_html() {
+        let rtf = r"{\rtf1\ansi\ansicpg1252\fromhtml1 \deff0{\fonttbl{\f0\fswiss Arial;}{\f1\fmodern Courier New;}}
+{\colortbl\red0\green0\blue255;}
+{\*\htmltag19 <html>}{\*\htmltag34 <head>}{\*\htmltag41 <style>p \{ margin: 0 \}}{\*\htmltag241 </style>}{\*\htmltag42 </head>}
+{\*\htmltag50 <body>}\htmlrtf {\htmlrtf0
+{\*\htmltag64 <p>}\htmlrtf {\htmlrtf0 Caf\'e9 {\*\htmltag84 <b>}\htmlrtf\b \htmlrtf0 cr\u232\'e8me{\*\htmltag92 </b>}\htmlrtf\b0 \htmlrtf0  &amp; tea
+{\*\mhtmltag84 <img src=cid:1>}{\*\htmltag84 <img src=image.png>}\htmlrtf\par\htmlrtf0 }{\*\htmltag72 </p>}
+\htmlrtf }\htmlrtf0 {\*\htmltag58 </body>}{\*\htmltag27 </html>}}";
+        assert_eq!(
+            extract_html(rtf).unwrap().as_deref(),
+            Some("<html><head><style>p { margin: 0 }</style></head><body><p>Café <b>crème</b> &amp; tea<img src=image.png></p></body></html>")
+        );
+        // The paragraphs outside of the \htmlrtf parts are line breaks of the HTML
+        assert_eq!(
+            extract_html(r"{\rtf1\fromhtml1 {\*\htmltag <pre>}a\tab b\par c{\*\htmltag </pre>}}").unwrap().as_deref(),
+            Some("<pre>a\tb\r\nc</pre>")
+        );
+        assert_eq!(extract_html(r"{\rtf1\ansi Not {\b encapsulated}}").unwrap(), None);
+        assert_eq!(extract_html(r"{\rtf1\ansi\fromtext Plain text}").unwrap(), None);
+        assert!(extract_html(r"\rtf1").is_err());
+    }
+
+    #[test]
+    fn extract_encapsulated_plain_text() {
+        let rtf = r"{\rtf1\ansi\ansicpg1251\fromtext \fbidis\deff0{\fonttbl{\f0\fswiss\fcharset204 Arial;}{\f1\fmodern Courier New;}}
+{\*\generator Microsoft Exchange Server;}
+{\*\formatConverter converted from text;}
+\viewkind5\viewscale100
+{\*\bkmkstart BM_BEGIN}\pard\plain\f0\fs20 \'cf\'f0\'e8\'e2\'e5\'f2,\par
+{\f1 two\tab spaces}\line {\*\htmltag <b>}end\htmlrtf {\b ignored}\htmlrtf0 \par
+}";
+        let body = extract_encapsulated(rtf).unwrap().unwrap();
+        assert_eq!(body.format, EncapsulatedFormat::Text);
+        assert_eq!(body.content, "Привет,\r\ntwo\tspaces\r\nend\r\n");
+        assert_eq!(extract_encapsulated_text(rtf).unwrap(), Some(body.content));
+        assert_eq!(extract_html(rtf).unwrap(), None);
+        assert_eq!(extract_encapsulated(r"{\rtf1\ansi Not {\b encapsulated}}").unwrap(), None);
+        // The format is detected while the header is parsed
+        let document = crate::document::RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.header.encapsulation, Some(EncapsulatedFormat::Text));
+    }
+}
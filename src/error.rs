@@ -0,0 +1,144 @@
+/// Define the error of the crate, returned when a document can't be read : an error of the lexer, of the parser, or of the IO
+use core::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::{JsError, JsValue};
+
+use alloc::string::String;
+
+use crate::lexer::LexerError;
+use crate::tokens::Property;
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ParserError {
+    InvalidToken(String),
+    IgnorableDestinationParsingError,
+    MalformedPainterStack,
+    InvalidFontIdentifier(Property),
+    InvalidColorIdentifier(Property),
+    NoMoreToken,
+    ValueCastError(String),
+    UnicodeParsingError(i32),
+    ParseEmptyToken,
+    LimitExceeded(String),
+    /// The document is protected by a password ({\*\password ...} or {\*\passwordhash ...})
+    EncryptedDocument,
+}
+
+impl core::error::Error for ParserError {}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _ = write!(f, "[RTF Parser] : ");
+        return match self {
+            ParserError::InvalidToken(msg) => write!(f, "{}", msg),
+            ParserError::IgnorableDestinationParsingError => write!(f, "No ignorable destination should be left"),
+            ParserError::MalformedPainterStack => write!(f, "Malformed painter stack : Unbalanced number of brackets"),
+            ParserError::InvalidFontIdentifier(property) => write!(f, "Invalid font identifier : {:?}", property),
+            ParserError::InvalidColorIdentifier(property) => write!(f, "Invalid color identifier : {:?}", property),
+            ParserError::NoMoreToken => write!(f, "No more token to parse"),
+            ParserError::ValueCastError(_type) => write!(f, "Unable to cast i32 to {_type}"),
+            ParserError::UnicodeParsingError(value) => write!(f, "Unable to parse {value} value to unicode"),
+            ParserError::LimitExceeded(limit) => write!(f, "Limit exceeded : {limit}"),
+            ParserError::EncryptedDocument => write!(f, "The document is protected by a password"),
+            ParserError::ParseEmptyToken => write!(f, "Try to parse an empty token, this should never happen. If so, please open an issue in the github repository"),
+        };
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RtfError {
+    Lexer(LexerError),
+    Parser(ParserError),
+    /// The document can't be read from its file or its stream
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl core::error::Error for RtfError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        return match self {
+            RtfError::Lexer(error) => Some(error),
+            RtfError::Parser(error) => Some(error),
+            #[cfg(feature = "std")]
+            RtfError::Io(error) => Some(error),
+        };
+    }
+}
+
+impl fmt::Display for RtfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            RtfError::Lexer(error) => write!(f, "{error}"),
+            RtfError::Parser(error) => write!(f, "{error}"),
+            #[cfg(feature = "std")]
+            RtfError::Io(error) => write!(f, "[RTF IO] : {error}"),
+        };
+    }
+}
+
+impl From<LexerError> for RtfError {
+    fn from(error: LexerError) -> Self {
+        return RtfError::Lexer(error);
+    }
+}
+
+impl From<ParserError> for RtfError {
+    fn from(error: ParserError) -> Self {
+        return RtfError::Parser(error);
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for RtfError {
+    fn from(error: std::io::Error) -> Self {
+        return RtfError::Io(error);
+    }
+}
+
+// The errors are converted to JS `Error`s, for the functions exported to JS which return a `Result<_, JsValue>`
+#[cfg(feature = "wasm")]
+impl From<RtfError> for JsValue {
+    fn from(error: RtfError) -> Self {
+        return JsError::from(error).into();
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<LexerError> for JsValue {
+    fn from(error: LexerError) -> Self {
+        return JsError::from(error).into();
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<ParserError> for JsValue {
+    fn from(error: ParserError) -> Self {
+        return JsError::from(error).into();
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use alloc::string::ToString;
+    use core::error::Error;
+
+    use super::*;
+    use crate::document::RtfDocument;
+
+    #[test]
+    fn match_error_kinds() {
+        let error = RtfDocument::try_from(r"\rtf1\ansi text}").unwrap_err();
+        assert!(matches!(error, RtfError::Parser(ParserError::InvalidToken(_))));
+        assert_eq!(error.to_string(), error.source().unwrap().to_string());
+        let error = RtfDocument::try_from(b"{\\rtf1\\ansi \xff}".as_slice()).unwrap_err();
+        assert!(matches!(error, RtfError::Lexer(LexerError::InvalidUtf8(_))));
+        // The source of the lexer error is the UTF-8 error
+        let source = error.source().and_then(|source| source.source()).unwrap();
+        assert!(source.is::<core::str::Utf8Error>());
+        #[cfg(feature = "std")]
+        assert!(matches!(RtfDocument::from_filepath("missing.rtf"), Err(RtfError::Io(_))));
+    }
+}
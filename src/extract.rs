@@ -0,0 +1,212 @@
+/// Define a fast path to extract the text of a document, without building its style blocks
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::codepage::ByteDecoder;
+use crate::error::RtfError;
+use crate::header::{CharacterSet, FontRef, RtfHeader};
+use crate::lexer::{Lexer, LexerError};
+use crate::parser::{Parser, ParserError};
+use crate::tokens::{ControlWord, Token};
+use crate::unicode::UnicodeDecoder;
+
+// The state of a group needed to decode its text
+#[derive(Debug, Clone, Copy)]
+struct TextState {
+    hidden: bool,
+    deleted: bool,
+    // None for the default font of the document (\deffN)
+    font_ref: Option<FontRef>,
+    unicode_ignore_count: i32,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        return Self {
+            hidden: false,
+            deleted: false,
+            font_ref: None,
+            unicode_ignore_count: 1,
+        };
+    }
+}
+
+/// Extract the text of a document, as `RtfDocument::get_text`, without building the `RtfDocument`.
+/// The tokens are read one by one : the destinations which are not text are skipped, and only the state needed to decode the text is kept
+/// (\ucN, \v, \deleted and the font of the \'hh bytes). For the search indexing, it's several times faster than the parsing of the whole document.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn extract_text(rtf: &str) -> Result<String, RtfError> {
+    let mut lexer = Lexer::new(rtf);
+    match lexer.next().transpose()? {
+        Some(Token::OpeningBracket) => {}
+        Some(token) => return Err(ParserError::InvalidToken(format!("Invalid first token : {:?} not a '{{'", token)).into()),
+        None => return Err(ParserError::NoMoreToken.into()),
+    }
+    let mut header = RtfHeader::default();
+    let mut state = TextState::default();
+    let mut state_stack: Vec<TextState> = vec![];
+    let mut unicode_decoder = UnicodeDecoder::default();
+    let mut byte_decoder = ByteDecoder::default();
+    let mut text = String::new();
+    while let Some(token) = lexer.next().transpose()? {
+        match token {
+            Token::OpeningBracket => {
+                #[rustfmt::skip]
+                match lexer.peek().transpose()?.cloned() {
+                    Some(Token::IgnorableDestination) => {
+                        lexer.next();
+                        // The ignorable destinations are not text, except the Unicode text (\ud) and the properties of the nested tables
+                        if matches!(lexer.peek().transpose()?, Some(Token::ControlSymbol((ControlWord::UnicodeDestination | ControlWord::NestedTableProperties, _)))) {
+                            state_stack.push(state);
+                            unicode_decoder.begin_group();
+                        } else {
+                            skip_group(&mut lexer)?;
+                        }
+                    }
+                    Some(Token::ControlSymbol((ControlWord::FontTable, _))) => header.font_table = Parser::parse_font_table(&read_group(&mut lexer)?)?,
+                    Some(Token::ControlSymbol((ControlWord::ColorTable
+                        | ControlWord::StyleSheet
+                        | ControlWord::ListTable
+                        | ControlWord::ListOverrideTable
+                        | ControlWord::FileTable
+                        | ControlWord::FieldInstruction
+                        | ControlWord::Picture
+                        | ControlWord::NonShapePicture
+                        | ControlWord::NoNestedTables
+                        | ControlWord::ShapeResult
+                        | ControlWord::DrawingObject, _))) => skip_group(&mut lexer)?,
+                    _ => {
+                        state_stack.push(state);
+                        unicode_decoder.begin_group();
+                    }
+                };
+            }
+            Token::ClosingBracket => {
+                state = state_stack.pop().unwrap_or_default();
+                unicode_decoder.end_group();
+            }
+            Token::ControlSymbol((control_word, property)) => {
+                #[rustfmt::skip]
+                match control_word {
+                    ControlWord::Hidden             => state.hidden = property.as_bool(),
+                    ControlWord::Deleted            => state.deleted = property.as_bool(),
+                    ControlWord::Plain              => {
+                        state.hidden = false;
+                        state.deleted = false;
+                        state.font_ref = None;
+                    }
+                    ControlWord::FontNumber         => state.font_ref = property.get_value_as::<FontRef>().ok(),
+                    ControlWord::UnicodeIgnoreCount => state.unicode_ignore_count = property.get_value(),
+                    ControlWord::Unicode            => {
+                        if let Some(char) = unicode_decoder.decode(&property, state.unicode_ignore_count)? {
+                            push_text(&mut text, char.encode_utf8(&mut [0; 4]), state);
+                        }
+                    }
+                    ControlWord::HexByte            => {
+                        let byte = property.get_value_as::<u8>()?;
+                        if !unicode_decoder.skip_char()? {
+                            let codepage = Parser::get_codepage(state.font_ref.unwrap_or(header.defaults.font_ref), &header);
+                            if let Some(char) = byte_decoder.decode(codepage, byte) {
+                                push_text(&mut text, char.encode_utf8(&mut [0; 4]), state);
+                            }
+                        }
+                    }
+                    control_word if control_word.get_text().is_some() => {
+                        let is_fallback = unicode_decoder.skip_char()?;
+                        if let Some(control_word_text) = control_word.get_text().filter(|_| !is_fallback) {
+                            push_text(&mut text, control_word_text, state);
+                        }
+                    }
+                    control_word                    => {
+                        if let Some(character_set) = CharacterSet::from(&Token::ControlSymbol((control_word, property))) {
+                            header.character_set = character_set;
+                        }
+                        header.defaults.apply(&control_word, &property);
+                    }
+                };
+            }
+            Token::PlainText(plain_text) => {
+                let codepage = Parser::get_codepage(state.font_ref.unwrap_or(header.defaults.font_ref), &header);
+                let (char, plain_text) = byte_decoder.decode_text(codepage, plain_text);
+                if let Some(char) = char {
+                    push_text(&mut text, char.encode_utf8(&mut [0; 4]), state);
+                }
+                push_text(&mut text, unicode_decoder.skip_fallback(plain_text)?, state);
+            }
+            Token::CRLF => push_text(&mut text, "\n", state),
+            Token::IgnorableDestination | Token::Binary(_) => {}
+            Token::Empty => return Err(ParserError::ParseEmptyToken.into()),
+        };
+    }
+    return Ok(text);
+}
+
+fn push_text(text: &mut String, new_text: &str, state: TextState) {
+    if !state.hidden && !state.deleted {
+        text.push_str(new_text);
+    }
+}
+
+// Read the tokens of the group until its closing bracket, the opening bracket being already read
+pub(crate) fn read_group<'a>(lexer: &mut Lexer<'a>) -> Result<Vec<Token<'a>>, LexerError> {
+    let mut tokens = vec![];
+    let mut depth = 0;
+    for token in lexer {
+        let token = token?;
+        match token {
+            Token::OpeningBracket => depth += 1,
+            Token::ClosingBracket if depth == 0 => {
+                tokens.push(token);
+                break;
+            }
+            Token::ClosingBracket => depth -= 1,
+            _ => {}
+        }
+        tokens.push(token);
+    }
+    return Ok(tokens);
+}
+
+pub(crate) fn skip_group(lexer: &mut Lexer) -> Result<(), LexerError> {
+    let mut depth = 0;
+    for token in lexer {
+        match token? {
+            Token::OpeningBracket => depth += 1,
+            Token::ClosingBracket if depth == 0 => break,
+            Token::ClosingBracket => depth -= 1,
+            _ => {}
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::RtfDocument;
+    use crate::include_test_file;
+
+    #[test]
+    fn extract_text_as_document() {
+        for rtf in [
+            include_test_file!("test-file.rtf"),
+            include_test_file!("file-sample_500kB.rtf"),
+            include_test_file!("file-with-image.rtf"),
+            include_test_file!("list-item.rtf"),
+            r"{\rtf1 a\par b\line c\tab d\sect e\page f\cell g\row h}",
+            r"{\rtf1{\info{\title T}}a{\*\ud{y}}b{\field{\*\fldinst HYPERLINK}{\fldrslt link}}{\v hid}{\footnote foot}\plain{\fldinst PAGE}}",
+            r"{\rtf1 a{\pict\pngblip 0102}b{\*\bkmkstart x}c{\shp{\*\shpinst{\shptxt box}}{\shprslt res}}d{\do\dobxpage{\dptxbx{\dptxbxtext text}}}e}",
+            "{\\rtf1 a\\~b\\-c\\_d\\\ne\\u233 ?f{\\uc0\\u233 g}\\u233 \\'e9h\\'e9}",
+            r"{\rtf1\ansi\ansicpg1251{\fonttbl{\f0\fnil Arial;}{\f1\fnil\fcharset238 Arial CE;}}\f0 \'c0 {\f1 \'c0}}",
+        ] {
+            assert_eq!(extract_text(rtf).unwrap(), RtfDocument::try_from(rtf).unwrap().get_text());
+        }
+        assert!(matches!(extract_text(r"\rtf1 text}"), Err(RtfError::Parser(ParserError::InvalidToken(_)))));
+        assert!(matches!(extract_text(r"{\rtf1 text"), Err(RtfError::Lexer(_))));
+    }
+}
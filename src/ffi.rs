@@ -0,0 +1,175 @@
+/// Define the C API of the library, declared in include/rtf_parser.h
+/// The document is an opaque pointer, created by `rtf_parse` and released by `rtf_document_free`
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::ffi::c_char;
+use core::{ptr, slice};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::document::RtfDocument;
+
+/// Result of the functions of the C API
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtfStatus {
+    Ok = 0,
+    NullPointer = 1,
+    /// The document is not valid, the details are given by `rtf_last_error_message`
+    ParseError = 2,
+    /// The library panicked, the details are given by `rtf_last_error_message`
+    Panic = 3,
+}
+
+thread_local! {
+    // The message of the last error of the thread, kept until the next error
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+}
+
+// A panic must not unwind into the C code : it's caught, saved as the last error, and the fallback is returned
+fn catch_panic<T>(fallback: T, function: impl FnOnce() -> T) -> T {
+    return panic::catch_unwind(AssertUnwindSafe(function)).unwrap_or_else(|payload| {
+        let message = payload.downcast_ref::<&str>().map(|message| message.to_string()).or_else(|| payload.downcast_ref::<String>().cloned());
+        set_last_error(format!("The library panicked : {}", message.unwrap_or_default()));
+        fallback
+    });
+}
+
+/// Parse the `length` bytes of `data` and write the created document to `document`.
+/// On error, `document` is set to NULL.
+///
+/// # Safety
+/// `data` must point to `length` readable bytes and `document` must be a valid pointer.
+/// The document must be released with `rtf_document_free`.
+#[no_mangle]
+pub unsafe extern "C" fn rtf_parse(data: *const u8, length: usize, document: *mut *mut RtfDocument) -> RtfStatus {
+    if document.is_null() {
+        set_last_error(String::from("The document pointer is NULL"));
+        return RtfStatus::NullPointer;
+    }
+    *document = ptr::null_mut();
+    if data.is_null() {
+        set_last_error(String::from("The data pointer is NULL"));
+        return RtfStatus::NullPointer;
+    }
+    let bytes = slice::from_raw_parts(data, length);
+    return catch_panic(RtfStatus::Panic, || match RtfDocument::try_from(bytes) {
+        Ok(parsed_document) => {
+            *document = Box::into_raw(Box::new(parsed_document));
+            RtfStatus::Ok
+        }
+        Err(error) => {
+            set_last_error(error.to_string());
+            RtfStatus::ParseError
+        }
+    });
+}
+
+/// Get the text of the document as a NUL-terminated UTF-8 string, hidden and deleted text excluded.
+/// Returns NULL if `document` is NULL or if the library panicked.
+///
+/// # Safety
+/// `document` must be NULL or a document created by `rtf_parse` and not yet released.
+/// The string must be released with `rtf_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn rtf_document_get_text(document: *const RtfDocument) -> *mut c_char {
+    let Some(document) = document.as_ref() else {
+        return ptr::null_mut();
+    };
+    return catch_panic(ptr::null_mut(), || {
+        // A C string ends at the first NUL char
+        let text = CString::new(document.get_text().replace('\0', "")).unwrap_or_default();
+        return text.into_raw();
+    });
+}
+
+/// Release a document created by `rtf_parse`, does nothing if `document` is NULL.
+///
+/// # Safety
+/// `document` must be NULL or a document created by `rtf_parse`, released only once.
+#[no_mangle]
+pub unsafe extern "C" fn rtf_document_free(document: *mut RtfDocument) {
+    if !document.is_null() {
+        catch_panic((), || drop(Box::from_raw(document)));
+    }
+}
+
+/// Release a string returned by the library, does nothing if `string` is NULL.
+///
+/// # Safety
+/// `string` must be NULL or a string returned by the library, released only once.
+#[no_mangle]
+pub unsafe extern "C" fn rtf_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Get the message of the last error of the calling thread, as a NUL-terminated UTF-8 string (empty if there was no error).
+/// The string is owned by the library and is valid until the next call to the library on the same thread.
+#[no_mangle]
+pub extern "C" fn rtf_last_error_message() -> *const c_char {
+    return LAST_ERROR.with(|last_error| last_error.borrow().as_ptr());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn parse_with_ffi() {
+        let rtf = r"{\rtf1\ansi Voici du texte en {\b gras}.}";
+        let mut document: *mut RtfDocument = ptr::null_mut();
+        unsafe {
+            assert_eq!(rtf_parse(rtf.as_ptr(), rtf.len(), &mut document), RtfStatus::Ok);
+            let text = rtf_document_get_text(document);
+            assert_eq!(CStr::from_ptr(text).to_str().unwrap(), "Voici du texte en gras.");
+            rtf_string_free(text);
+            rtf_document_free(document);
+        }
+    }
+
+    #[test]
+    fn parse_with_ffi_errors() {
+        let rtf = r"\rtf1\ansi Not a group";
+        let mut document: *mut RtfDocument = ptr::null_mut();
+        unsafe {
+            assert_eq!(rtf_parse(rtf.as_ptr(), rtf.len(), &mut document), RtfStatus::ParseError);
+            assert!(document.is_null());
+            assert!(!CStr::from_ptr(rtf_last_error_message()).is_empty());
+            assert_eq!(rtf_parse(ptr::null(), 0, &mut document), RtfStatus::NullPointer);
+            assert!(rtf_document_get_text(document).is_null());
+            rtf_document_free(document);
+        }
+    }
+
+    #[test]
+    fn catch_panics() {
+        assert_eq!(catch_panic(RtfStatus::Panic, || panic!("Unexpected")), RtfStatus::Panic);
+        let message = unsafe { CStr::from_ptr(rtf_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "The library panicked : Unexpected");
+    }
+
+    #[test]
+    fn ffi_header() {
+        // The header is generated by cbindgen with the config of cbindgen.toml, run the test with UPDATE_FFI_HEADER=1 to write it
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).unwrap();
+        let bindings = cbindgen::Builder::new().with_config(config).with_src(root.join("src/ffi.rs")).generate().unwrap();
+        let mut header = vec![];
+        bindings.write(&mut header);
+        let path = root.join("include/rtf_parser.h");
+        if std::env::var_os("UPDATE_FFI_HEADER").is_some() {
+            std::fs::write(&path, &header).unwrap();
+        }
+        assert!(std::fs::read(&path).unwrap() == header, "rtf_parser.h is not up to date, run the tests with UPDATE_FFI_HEADER=1");
+    }
+}
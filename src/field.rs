@@ -0,0 +1,287 @@
+/// Define the fields ({\field{\*\fldinst ...}{\fldrslt ...}}) of a document
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+/// A field of the document, with its instruction and its last computed result
+/// The positions are char offsets in the visible text of the body (see `RtfDocument::get_text`), the result being the text between them
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Field {
+    /// The raw instruction of the \fldinst destination (ex: `HYPERLINK "https://example.com"`), or the control word of a character field (ex: \chpgn)
+    pub instruction: String,
+    pub field_type: FieldType,
+    /// The text of the \fldrslt destination, as displayed by the last application that computed the field
+    pub result: String,
+    pub start: usize,
+    pub end: usize,
+    /// The properties of a legacy form field (FORMTEXT, FORMCHECKBOX, FORMDROPDOWN), from the \formfield destination of the instruction
+    pub form_field: Option<FormField>,
+}
+
+impl Field {
+    pub fn new(instruction: String, position: usize) -> Self {
+        let instruction = instruction.trim().to_string();
+        return Self {
+            field_type: FieldType::from(instruction.as_str()),
+            instruction,
+            result: String::new(),
+            start: position,
+            end: position,
+            form_field: None,
+        };
+    }
+
+    /// A character field (\chpgn, \chdate, \chtime, ...) is a control word of the text : it has no result, its value is only known when the document is displayed
+    pub fn is_character_field(&self) -> bool {
+        return self.instruction.starts_with('\\');
+    }
+
+    /// Get the first argument of the instruction, without its quotes
+    /// ex: the url of an hyperlink, or the bookmark of a reference
+    pub fn get_argument(&self) -> Option<&str> {
+        let (_, arguments) = self.instruction.split_once(char::is_whitespace)?;
+        let arguments = arguments.trim_start();
+        if let Some(quoted) = arguments.strip_prefix('"') {
+            return quoted.split('"').next();
+        }
+        return arguments.split_whitespace().find(|argument| !argument.starts_with('\\'));
+    }
+
+    /// The value of a form field, as filled in : the text of a text field being its result
+    pub fn get_form_value(&self) -> Option<FormValue<'_>> {
+        let form_field = self.form_field.as_ref()?;
+        #[rustfmt::skip]
+        return Some(match form_field.form_field_type {
+            FormFieldType::Text     => FormValue::Text(&self.result),
+            FormFieldType::Checkbox => FormValue::Checkbox(form_field.is_checked()),
+            FormFieldType::Dropdown => FormValue::Dropdown(form_field.get_selected_item()),
+        });
+    }
+}
+
+/// A legacy form field ({\*\formfield ...} in the instruction of a FORMTEXT, FORMCHECKBOX or FORMDROPDOWN field)
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FormField {
+    pub form_field_type: FormFieldType,
+    /// The name of the form field (\ffname), as the bookmark of its value
+    pub name: String,
+    /// The default text of a text field (\ffdeftext)
+    pub default_text: String,
+    /// The default state of a checkbox or the index of the default item of a dropdown (\ffdefres)
+    pub default_result: u16,
+    /// The state of a checkbox (1 if checked) or the index of the selected item of a dropdown (\ffres), if it has been filled in
+    pub result: Option<u16>,
+    /// The items of a dropdown (\ffl)
+    pub items: Vec<String>,
+    /// The maximum length of the text of a text field (\ffmaxlen), 0 if unlimited
+    pub max_length: u16,
+}
+
+impl FormField {
+    /// The \ffres value of a form field which has not been filled in
+    pub const NO_RESULT: u16 = 25;
+
+    /// The current state of a checkbox, or its default one if it has not been filled in
+    pub fn is_checked(&self) -> bool {
+        return self.result.unwrap_or(self.default_result) != 0;
+    }
+
+    /// The current item of a dropdown, or its default one if it has not been filled in
+    pub fn get_selected_item(&self) -> Option<&str> {
+        let index = self.result.unwrap_or(self.default_result);
+        return self.items.get(index as usize).map(String::as_str);
+    }
+}
+
+/// Type of a form field (\fftypeN)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum FormFieldType {
+    #[default]
+    Text,
+    Checkbox,
+    Dropdown,
+}
+
+impl From<i32> for FormFieldType {
+    fn from(value: i32) -> Self {
+        return match value {
+            1 => FormFieldType::Checkbox,
+            2 => FormFieldType::Dropdown,
+            _ => FormFieldType::Text,
+        };
+    }
+}
+
+/// The value of a form field, see `Field::get_form_value`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormValue<'a> {
+    Text(&'a str),
+    Checkbox(bool),
+    Dropdown(Option<&'a str>),
+}
+
+/// Type of a field, defined by the first word of its instruction
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum FieldType {
+    Hyperlink,
+    Page,
+    NumPages,
+    PageRef,
+    Ref,
+    Date,
+    Time,
+    Toc,
+    MergeField,
+    FormText,
+    FormCheckbox,
+    FormDropdown,
+    #[default]
+    Unknown,
+}
+
+impl From<&str> for FieldType {
+    fn from(instruction: &str) -> Self {
+        let keyword = instruction.split_whitespace().next().unwrap_or("");
+        #[rustfmt::skip]
+        return match keyword.to_ascii_uppercase().as_str() {
+            "HYPERLINK"    => FieldType::Hyperlink,
+            "PAGE"         => FieldType::Page,
+            "NUMPAGES"     => FieldType::NumPages,
+            "PAGEREF"      => FieldType::PageRef,
+            "REF"          => FieldType::Ref,
+            "DATE"         => FieldType::Date,
+            "TIME"         => FieldType::Time,
+            "TOC"          => FieldType::Toc,
+            "MERGEFIELD"   => FieldType::MergeField,
+            "FORMTEXT"     => FieldType::FormText,
+            "FORMCHECKBOX" => FieldType::FormCheckbox,
+            "FORMDROPDOWN" => FieldType::FormDropdown,
+            r"\CHPGN"      => FieldType::Page,
+            r"\CHDATE"
+            | r"\CHDPL"
+            | r"\CHDPA"    => FieldType::Date,
+            r"\CHTIME"     => FieldType::Time,
+            _              => FieldType::Unknown,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{RtfDocument, TextOptions};
+
+    #[test]
+    fn parse_fields() {
+        let rtf = r#"{\rtf1\ansi Page {\field{\*\fldinst { PAGE }}{\fldrslt 1}} of {\field{\*\fldinst HYPERLINK "https://example.com"}{\fldrslt {\ul the link}}}, see {\field{\*\fldinst REF intro \\h}{\fldrslt}}}"#;
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Page 1 of the link, see ");
+        assert_eq!(
+            document.fields,
+            vec![
+                Field {
+                    instruction: "PAGE".into(),
+                    field_type: FieldType::Page,
+                    result: "1".into(),
+                    start: 5,
+                    end: 6,
+                    form_field: None,
+                },
+                Field {
+                    instruction: r#"HYPERLINK "https://example.com""#.into(),
+                    field_type: FieldType::Hyperlink,
+                    result: "the link".into(),
+                    start: 10,
+                    end: 18,
+                    form_field: None,
+                },
+                Field {
+                    instruction: r"REF intro \h".into(),
+                    field_type: FieldType::Ref,
+                    result: "".into(),
+                    start: 24,
+                    end: 24,
+                    form_field: None,
+                },
+            ]
+        );
+        assert_eq!(document.fields[0].get_argument(), None);
+        assert_eq!(document.fields[1].get_argument(), Some("https://example.com"));
+        assert_eq!(document.fields[2].get_argument(), Some("intro"));
+    }
+
+    #[test]
+    fn parse_nested_fields() {
+        // A field in the result of another one, and a \fldinst which is not an ignorable destination
+        let rtf = r#"{\rtf1\ansi {\field{\*\fldinst HYPERLINK "http://a"}{\fldrslt See {\field{\fldinst PAGE}{\fldrslt 2}} here}} end}"#;
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "See 2 here end");
+        let fields: Vec<(FieldType, &str, usize, usize)> = document.fields.iter().map(|field| (field.field_type, field.result.as_str(), field.start, field.end)).collect();
+        assert_eq!(fields, [(FieldType::Hyperlink, "See 2 here", 0, 10), (FieldType::Page, "2", 4, 5)]);
+        // Without a result
+        let rtf = r#"{\rtf1\ansi A{\field{\fldinst HYPERLINK "http://x"}}B}"#;
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "AB");
+        assert_eq!(document.fields[0].get_argument(), Some("http://x"));
+        assert_eq!((document.fields[0].start, document.fields[0].end), (1, 1));
+    }
+
+    #[test]
+    fn parse_character_fields() {
+        let rtf = r"{\rtf1\ansi{\footer Page \chpgn , printed on \chdpl  at \chtime\par}Body{\v \chdate}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Page , printed on  at Body");
+        let fields: Vec<(FieldType, &str, usize)> = document.fields.iter().map(|field| (field.field_type, field.instruction.as_str(), field.start)).collect();
+        assert_eq!(fields, [(FieldType::Page, r"\chpgn", 5), (FieldType::Date, r"\chdpl", 18), (FieldType::Time, r"\chtime", 22)]);
+        assert!(document.fields.iter().all(|field| field.is_character_field() && field.result.is_empty()));
+        let options = TextOptions {
+            paragraph_break: "\n".into(),
+            page_number: "{PAGE}".into(),
+            date: "{DATE}".into(),
+            time: "{TIME}".into(),
+            ..TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "Page {PAGE}, printed on {DATE} at {TIME}\nBody");
+    }
+
+    #[test]
+    fn parse_form_fields() {
+        let rtf = r"{\rtf1\ansi Name: {\field{\*\fldinst { FORMTEXT }{\*\formfield{\fftype0\ffmaxlen20{\*\ffname Name}{\*\ffdeftext Your name}}}{\*\datafield 0000}}{\fldrslt John}}, subscribed: {\field{\*\fldinst { FORMCHECKBOX }{\*\formfield{\fftype1\ffres1\ffdefres0{\*\ffname Check1}}}}{\fldrslt }}, country: {\field{\*\fldinst { FORMDROPDOWN }{\*\formfield{\fftype2\ffres25\ffdefres1{\*\ffname Country}{\*\ffl France}{\*\ffl Spain}{\*\ffl Italy}}}}{\fldrslt Spain}}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Name: John, subscribed: , country: Spain");
+        let [text, checkbox, dropdown] = document.fields.as_slice() else { panic!("Expected 3 fields") };
+        assert_eq!((text.field_type, text.instruction.as_str()), (FieldType::FormText, "FORMTEXT"));
+        assert_eq!(
+            text.form_field,
+            Some(FormField {
+                form_field_type: FormFieldType::Text,
+                name: "Name".into(),
+                default_text: "Your name".into(),
+                max_length: 20,
+                ..FormField::default()
+            })
+        );
+        assert_eq!(text.get_form_value(), Some(FormValue::Text("John")));
+        assert_eq!(checkbox.field_type, FieldType::FormCheckbox);
+        assert_eq!(checkbox.form_field.as_ref().map(|form_field| (form_field.name.as_str(), form_field.result)), Some(("Check1", Some(1))));
+        assert_eq!(checkbox.get_form_value(), Some(FormValue::Checkbox(true)));
+        let dropdown_form_field = dropdown.form_field.as_ref().unwrap();
+        assert_eq!(dropdown_form_field.items, vec!["France", "Spain", "Italy"]);
+        // \ffres25 : not filled in, the default item is selected
+        assert_eq!((dropdown_form_field.result, dropdown_form_field.default_result), (None, 1));
+        assert_eq!(dropdown.get_form_value(), Some(FormValue::Dropdown(Some("Spain"))));
+        let document = RtfDocument::try_from(r"{\rtf1 {\field{\*\fldinst PAGE}{\fldrslt 1}}}").unwrap();
+        assert_eq!(document.fields[0].get_form_value(), None);
+    }
+}
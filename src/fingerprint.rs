@@ -0,0 +1,352 @@
+/// Define the fingerprint of the content of a document, to find the documents with the same text and format
+use core::hash::Hasher;
+
+use crate::document::RtfDocument;
+use crate::header::{Color, FontFamily};
+use crate::image::{Image, ImageFormat};
+use crate::normalize::normalize_style_blocks;
+use crate::paragraph::{Alignment, DropCap, Frame, FramePosition, HorizontalAnchor, Paragraph, SpaceBetweenLine, TabKind, TabLeader, VerticalAnchor};
+use crate::parser::{Painter, StyleBlock, Underline};
+use crate::units::Twips;
+
+/// Version of the encoding of the content hashed by `RtfDocument::content_hash`, changed with the hashed fields or their encoding
+pub const CONTENT_HASH_VERSION: u8 = 2;
+
+impl RtfDocument {
+    /// A hash of the content of the document : the text of the body with its format, and the pictures.
+    /// It doesn't depend on the way the document is written : the header (generator, unused fonts and colors, numbering of the tables)
+    /// and the split of the text in blocks are ignored, the fonts, colors and styles being compared by value.
+    /// The fields are hashed with an explicit encoding, whose version (`CONTENT_HASH_VERSION`) is part of the hash : unlike the `Hash`
+    /// of the document, it doesn't depend on the platform, the run or the new fields of the model, so it can be stored.
+    pub fn content_hash(&self) -> u64 {
+        let mut body = self.body.clone();
+        normalize_style_blocks(&mut body);
+        let mut hasher = StableHasher::default();
+        hasher.write_u8(CONTENT_HASH_VERSION);
+        hasher.write_usize(body.len());
+        for style_block in &body {
+            self.hash_style_block(style_block, &mut hasher);
+        }
+        hasher.write_usize(self.images.len());
+        for image in &self.images {
+            hash_image(image, &mut hasher);
+        }
+        return hasher.finish();
+    }
+
+    fn hash_style_block(&self, style_block: &StyleBlock, hasher: &mut StableHasher) {
+        hasher.write_text(&style_block.text);
+        hasher.write_bool(style_block.paragraph_end);
+        self.hash_painter(&style_block.painter, hasher);
+        self.hash_paragraph(&style_block.paragraph, hasher);
+    }
+
+    // The references to the header are replaced by the values they refer to
+    fn hash_painter(&self, painter: &Painter, hasher: &mut StableHasher) {
+        let header = &self.header;
+        let font = header.font_table.get(&painter.font_ref);
+        hasher.write_option(font, |font, hasher| {
+            hasher.write_text(&font.name);
+            hasher.write_u8(font_family_code(&font.font_family));
+        });
+        hasher.write_option(header.get_color(&painter.color_ref), hash_color);
+        hasher.write_option(header.get_color(&painter.underline_color), hash_color);
+        let character_style = painter.char_style_ref.and_then(|style_ref| header.character_styles.get(&style_ref));
+        hasher.write_option(character_style, |style, hasher| hasher.write_text(&style.name));
+        hasher.write_u16(painter.font_size);
+        hasher.write_u8(underline_code(painter.underline));
+        hasher.write_i32(painter.baseline_offset);
+        hash_twips(painter.character_spacing, hasher);
+        for flag in [
+            painter.bold,
+            painter.italic,
+            painter.superscript,
+            painter.subscript,
+            painter.smallcaps,
+            painter.strike,
+            painter.outline,
+            painter.shadow,
+            painter.caps,
+            painter.hidden,
+            painter.deleted,
+        ] {
+            hasher.write_bool(flag);
+        }
+        hasher.write_option(painter.language, |language, hasher| hasher.write_u16(language));
+        hasher.write_option(painter.east_asian_language, |language, hasher| hasher.write_u16(language));
+    }
+
+    fn hash_paragraph(&self, paragraph: &Paragraph, hasher: &mut StableHasher) {
+        hasher.write_option(self.header.stylesheet.get(&paragraph.style), |style, hasher| hasher.write_text(&style.name));
+        hasher.write_u8(alignment_code(paragraph.alignment));
+        let spacing = &paragraph.spacing;
+        hash_twips(spacing.before, hasher);
+        hash_twips(spacing.after, hasher);
+        match spacing.between_line {
+            SpaceBetweenLine::Value(value) => {
+                hasher.write_u8(0);
+                hash_twips(value, hasher);
+            }
+            SpaceBetweenLine::Auto => hasher.write_u8(1),
+            SpaceBetweenLine::Invalid => hasher.write_u8(2),
+        }
+        hasher.write_i32(spacing.line_multiplier);
+        for length in [paragraph.indent.left, paragraph.indent.right, paragraph.indent.first_line, paragraph.tab_width] {
+            hash_twips(length, hasher);
+        }
+        hasher.write_usize(paragraph.tab_stops.len());
+        for tab_stop in &paragraph.tab_stops {
+            hash_twips(tab_stop.position, hasher);
+            hasher.write_u8(tab_kind_code(tab_stop.kind));
+            hasher.write_u8(tab_leader_code(tab_stop.leader));
+        }
+        // The list is identified by the labels of its items
+        hasher.write_u8(paragraph.list_level);
+        hasher.write_text(&paragraph.list_label);
+        for flag in [paragraph.page_break_before, paragraph.keep_together, paragraph.keep_with_next, paragraph.widow_control] {
+            hasher.write_bool(flag);
+        }
+        hasher.write_option(paragraph.outline_level, |level, hasher| hasher.write_u8(level));
+        hasher.write_option(paragraph.frame.as_ref(), hash_frame);
+    }
+}
+
+fn hash_frame(frame: &Frame, hasher: &mut StableHasher) {
+    for length in [frame.width, frame.height, frame.horizontal_distance, frame.vertical_distance] {
+        hash_twips(length, hasher);
+    }
+    #[rustfmt::skip]
+    hasher.write_u8(match frame.horizontal_anchor {
+        HorizontalAnchor::Column => 0,
+        HorizontalAnchor::Margin => 1,
+        HorizontalAnchor::Page   => 2,
+    });
+    #[rustfmt::skip]
+    hasher.write_u8(match frame.vertical_anchor {
+        VerticalAnchor::Margin    => 0,
+        VerticalAnchor::Page      => 1,
+        VerticalAnchor::Paragraph => 2,
+    });
+    for position in [frame.horizontal_position, frame.vertical_position] {
+        #[rustfmt::skip]
+        let code = match position {
+            FramePosition::Offset(_) => 0,
+            FramePosition::Start     => 1,
+            FramePosition::Center    => 2,
+            FramePosition::End       => 3,
+            FramePosition::Inside    => 4,
+            FramePosition::Outside   => 5,
+            FramePosition::Inline    => 6,
+        };
+        hasher.write_u8(code);
+        if let FramePosition::Offset(offset) = position {
+            hash_twips(offset, hasher);
+        }
+    }
+    hasher.write_bool(frame.no_wrap);
+    #[rustfmt::skip]
+    hasher.write_u8(match frame.drop_cap {
+        DropCap::None     => 0,
+        DropCap::InText   => 1,
+        DropCap::InMargin => 2,
+    });
+    hasher.write_u8(frame.drop_cap_lines);
+}
+
+fn hash_image(image: &Image, hasher: &mut StableHasher) {
+    #[rustfmt::skip]
+    hasher.write_u8(match image.format {
+        ImageFormat::Png     => 0,
+        ImageFormat::Jpeg    => 1,
+        ImageFormat::Emf     => 2,
+        ImageFormat::Wmf     => 3,
+        ImageFormat::MacPict => 4,
+        ImageFormat::Dib     => 5,
+        ImageFormat::Bitmap  => 6,
+        ImageFormat::Unknown => 7,
+    });
+    for value in [image.width, image.height, image.scale_x, image.scale_y] {
+        hasher.write_i32(value);
+    }
+    let crop = &image.crop;
+    for length in [image.goal_width, image.goal_height, crop.left, crop.right, crop.top, crop.bottom] {
+        hash_twips(length, hasher);
+    }
+    hasher.write_usize(image.data.len());
+    hasher.write(&image.data);
+    hasher.write_usize(image.position);
+}
+
+fn hash_color(color: &Color, hasher: &mut StableHasher) {
+    hasher.write(&[color.red, color.green, color.blue, color.alpha]);
+}
+
+fn hash_twips(length: Twips, hasher: &mut StableHasher) {
+    hasher.write_i32(length.0);
+}
+
+fn font_family_code(font_family: &FontFamily) -> u8 {
+    #[rustfmt::skip]
+    return match font_family {
+        FontFamily::Nil    => 0,
+        FontFamily::Roman  => 1,
+        FontFamily::Swiss  => 2,
+        FontFamily::Modern => 3,
+        FontFamily::Script => 4,
+        FontFamily::Decor  => 5,
+        FontFamily::Tech   => 6,
+        FontFamily::Bidi   => 7,
+    };
+}
+
+fn underline_code(underline: Underline) -> u8 {
+    #[rustfmt::skip]
+    return match underline {
+        Underline::None       => 0,
+        Underline::Single     => 1,
+        Underline::Double     => 2,
+        Underline::Word       => 3,
+        Underline::Dotted     => 4,
+        Underline::Dash       => 5,
+        Underline::DashDot    => 6,
+        Underline::DashDotDot => 7,
+        Underline::LongDash   => 8,
+        Underline::Thick      => 9,
+        Underline::Wave       => 10,
+        Underline::DoubleWave => 11,
+    };
+}
+
+fn alignment_code(alignment: Alignment) -> u8 {
+    #[rustfmt::skip]
+    return match alignment {
+        Alignment::LeftAligned  => 0,
+        Alignment::RightAligned => 1,
+        Alignment::Center       => 2,
+        Alignment::Justify      => 3,
+    };
+}
+
+fn tab_kind_code(kind: TabKind) -> u8 {
+    #[rustfmt::skip]
+    return match kind {
+        TabKind::Left    => 0,
+        TabKind::Right   => 1,
+        TabKind::Center  => 2,
+        TabKind::Decimal => 3,
+        TabKind::Bar     => 4,
+    };
+}
+
+fn tab_leader_code(leader: TabLeader) -> u8 {
+    #[rustfmt::skip]
+    return match leader {
+        TabLeader::None      => 0,
+        TabLeader::Dot       => 1,
+        TabLeader::Hyphen    => 2,
+        TabLeader::Underline => 3,
+        TabLeader::Thick     => 4,
+        TabLeader::Equal     => 5,
+    };
+}
+
+// FNV-1a, with the integers written in little endian and the lengths as u64, so the hash doesn't depend on the platform
+struct StableHasher {
+    state: u64,
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        return Self { state: 0xcbf29ce484222325 };
+    }
+}
+
+impl StableHasher {
+    fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    // The length is written before the bytes, so the consecutive texts are not ambiguous
+    fn write_text(&mut self, text: &str) {
+        self.write_usize(text.len());
+        self.write(text.as_bytes());
+    }
+
+    fn write_option<T>(&mut self, value: Option<T>, write_value: impl FnOnce(T, &mut Self)) {
+        self.write_bool(value.is_some());
+        if let Some(value) = value {
+            write_value(value, self);
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        return self.state;
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_u64(value as i64 as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_content() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0\froman Times;}{\f1\fswiss Arial;}}{\colortbl;\red255\green0\blue0;}\f1 Some {\b bold} {\cf1 red} text\par}";
+        // The same content, written by another generator with other tables and blocks
+        let other = r"{\rtf1\ansi{\fonttbl{\f4\fswiss Arial;}}{\colortbl;\red0\green0\blue255;\red255\green0\blue0;}{\*\generator Other}\f4 Some {\b bold}{ }{\cf2 red}{ text}\par}";
+        let hash = RtfDocument::try_from(rtf).unwrap().content_hash();
+        // The hashes are stored by the applications : the value only changes with CONTENT_HASH_VERSION
+        assert_eq!(hash, 0xc164_ccd0_de44_a28e);
+        assert_eq!(RtfDocument::try_from(other).unwrap().content_hash(), hash);
+        for different in [
+            r"{\rtf1\ansi{\fonttbl{\f0\froman Times;}}{\colortbl;\red255\green0\blue0;}\f0 Some {\b bold} {\cf1 red} text\par}",
+            r"{\rtf1\ansi{\fonttbl{\f1\fswiss Arial;}}{\colortbl;\red0\green255\blue0;}\f1 Some {\b bold} {\cf1 red} text\par}",
+            r"{\rtf1\ansi{\fonttbl{\f1\fswiss Arial;}}{\colortbl;\red255\green0\blue0;}\f1 Some {\i bold} {\cf1 red} text\par}",
+            r"{\rtf1\ansi{\fonttbl{\f1\fswiss Arial;}}{\colortbl;\red255\green0\blue0;}\f1 Some {\b bold} {\cf1 red} text}",
+            // The expanded color table is preferred to the color table
+            r"{\rtf1\ansi{\fonttbl{\f1\fswiss Arial;}}{\colortbl;\red255\green0\blue0;}{\*\expandedcolortbl;\cssrgb\c0\c0\c50000;}\f1 Some {\b bold} {\cf1 red} text\par}",
+        ] {
+            assert_ne!(RtfDocument::try_from(different).unwrap().content_hash(), hash);
+        }
+    }
+}
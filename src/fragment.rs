@@ -0,0 +1,267 @@
+/// Define the RTF fragments exchanged with the clipboard ("Copy as RTF", CF_RTF on Windows), and their writer
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::document::{ContentKind, RtfDocument};
+use crate::error::RtfError;
+use crate::header::{ColorRef, FontFamily, RtfHeader};
+use crate::paragraph::{Alignment, Paragraph, SpaceBetweenLine};
+use crate::parser::{Painter, ParserOptions, StyleBlock, Underline};
+use crate::units::Twips;
+
+/// A part of a document, with the header needed to resolve its fonts and colors
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RtfFragment {
+    pub header: RtfHeader,
+    pub body: Vec<StyleBlock>,
+}
+
+impl RtfFragment {
+    /// Parse a snippet from the clipboard : a complete document, or a fragment without the {\rtf1 prolog
+    pub fn parse(content: &str) -> Result<Self, RtfError> {
+        let options = ParserOptions {
+            fragment: RtfDocument::sniff(content.as_bytes()) != ContentKind::Document,
+            ..ParserOptions::default()
+        };
+        let document = RtfDocument::from_str_with_options(content, options)?;
+        return Ok(Self {
+            header: document.header,
+            body: document.body,
+        });
+    }
+
+    /// Write the fragment as a valid RTF document, to put it in the clipboard
+    pub fn to_rtf(&self) -> String {
+        return Self::write(&self.header, &self.body);
+    }
+
+    /// Write some StyleBlocks as a valid RTF document, with a minimal header : only the fonts and the colors they use
+    pub fn write(header: &RtfHeader, style_blocks: &[StyleBlock]) -> String {
+        let mut rtf = String::from(r"{\rtf1\ansi\deff0");
+        // The fonts keep their references, the color table is indexed so it's written up to the last color used
+        rtf.push_str(r"{\fonttbl");
+        let mut font_refs: Vec<u16> = style_blocks.iter().map(|style_block| style_block.painter.font_ref).collect();
+        font_refs.sort_unstable();
+        font_refs.dedup();
+        for font_ref in font_refs {
+            if let Some(font) = header.font_table.get(&font_ref) {
+                let alternate = font.alternate_name.as_ref().map(|name| format!(r"{{\*\falt {}}}", escape_text(name))).unwrap_or_default();
+                let _ = write!(rtf, r"{{\f{font_ref}{} {}{alternate};}}", get_font_family_name(&font.font_family), escape_text(&font.name));
+            }
+        }
+        rtf.push('}');
+        let max_color_ref = style_blocks
+            .iter()
+            .map(|style_block| style_block.painter.color_ref.max(style_block.painter.underline_color))
+            .max()
+            .unwrap_or(0);
+        if max_color_ref > 0 {
+            rtf.push_str(r"{\colortbl;");
+            for color_ref in 1..=max_color_ref {
+                if let Some(color) = header.get_color(&color_ref) {
+                    let _ = write!(rtf, r"\red{}\green{}\blue{}", color.red, color.green, color.blue);
+                }
+                rtf.push(';');
+            }
+            rtf.push('}');
+        }
+
+        let mut current_paragraph: Option<&Paragraph> = None;
+        for style_block in style_blocks {
+            if current_paragraph != Some(&style_block.paragraph) {
+                write_paragraph(&mut rtf, &style_block.paragraph);
+                current_paragraph = Some(&style_block.paragraph);
+            }
+            rtf.push('{');
+            write_painter(&mut rtf, header, &style_block.painter, max_color_ref);
+            rtf.push(' ');
+            rtf.push_str(&escape_text(&style_block.text));
+            rtf.push('}');
+            if style_block.paragraph_end {
+                rtf.push_str(r"\par");
+                // The paragraph properties are not reset by \par, but the next paragraph can be written with \pard
+                current_paragraph = None;
+            }
+        }
+        rtf.push('}');
+        return rtf;
+    }
+}
+
+fn write_paragraph(rtf: &mut String, paragraph: &Paragraph) {
+    rtf.push_str(r"\pard");
+    #[rustfmt::skip]
+    let alignment = match paragraph.alignment {
+        Alignment::LeftAligned  => "",
+        Alignment::RightAligned => r"\qr",
+        Alignment::Center       => r"\qc",
+        Alignment::Justify      => r"\qj",
+    };
+    rtf.push_str(alignment);
+    #[rustfmt::skip]
+    let values = [
+        (r"\sb", paragraph.spacing.before),
+        (r"\sa", paragraph.spacing.after),
+        (r"\li", paragraph.indent.left),
+        (r"\ri", paragraph.indent.right),
+        (r"\fi", paragraph.indent.first_line),
+    ];
+    for (control_word, value) in values.iter().filter(|(_, value)| *value != Twips(0)) {
+        let _ = write!(rtf, "{control_word}{value}");
+    }
+    if let SpaceBetweenLine::Value(value) = paragraph.spacing.between_line {
+        let _ = write!(rtf, r"\sl{value}\slmult{}", paragraph.spacing.line_multiplier);
+    }
+    #[rustfmt::skip]
+    let toggles = [
+        (paragraph.page_break_before,   r"\pagebb"),
+        (paragraph.keep_together,       r"\keep"),
+        (paragraph.keep_with_next,      r"\keepn"),
+        (paragraph.widow_control,       r"\widctlpar"),
+    ];
+    for (_, control_word) in toggles.iter().filter(|(enabled, _)| *enabled) {
+        rtf.push_str(control_word);
+    }
+    if let Some(outline_level) = paragraph.outline_level {
+        let _ = write!(rtf, r"\outlinelevel{outline_level}");
+    }
+}
+
+fn write_painter(rtf: &mut String, header: &RtfHeader, painter: &Painter, max_color_ref: ColorRef) {
+    if header.font_table.contains_key(&painter.font_ref) {
+        let _ = write!(rtf, r"\f{}", painter.font_ref);
+    }
+    let _ = write!(rtf, r"\fs{}", painter.font_size);
+    if painter.color_ref != 0 && painter.color_ref <= max_color_ref {
+        let _ = write!(rtf, r"\cf{}", painter.color_ref);
+    }
+    #[rustfmt::skip]
+    let toggles = [
+        (painter.bold,          r"\b"),
+        (painter.italic,        r"\i"),
+        (painter.strike,        r"\strike"),
+        (painter.superscript,   r"\super"),
+        (painter.subscript,     r"\sub"),
+        (painter.smallcaps,     r"\scaps"),
+        (painter.caps,          r"\caps"),
+        (painter.outline,       r"\outl"),
+        (painter.shadow,        r"\shad"),
+        (painter.hidden,        r"\v"),
+        (painter.deleted,       r"\deleted"),
+    ];
+    for (_, control_word) in toggles.iter().filter(|(enabled, _)| *enabled) {
+        rtf.push_str(control_word);
+    }
+    #[rustfmt::skip]
+    let underline = match painter.underline {
+        Underline::None       => "",
+        Underline::Single     => r"\ul",
+        Underline::Double     => r"\uldb",
+        Underline::Word       => r"\ulw",
+        Underline::Dotted     => r"\uld",
+        Underline::Dash       => r"\uldash",
+        Underline::DashDot    => r"\uldashd",
+        Underline::DashDotDot => r"\uldashdd",
+        Underline::LongDash   => r"\ulldash",
+        Underline::Thick      => r"\ulth",
+        Underline::Wave       => r"\ulwave",
+        Underline::DoubleWave => r"\ululdbwave",
+    };
+    rtf.push_str(underline);
+    if painter.underline_color != 0 && painter.underline_color <= max_color_ref {
+        let _ = write!(rtf, r"\ulc{}", painter.underline_color);
+    }
+    let _ = match painter.baseline_offset {
+        0 => Ok(()),
+        offset if offset > 0 => write!(rtf, r"\up{offset}"),
+        offset => write!(rtf, r"\dn{}", -offset),
+    };
+    if painter.character_spacing != Twips(0) {
+        let _ = write!(rtf, r"\expndtw{}", painter.character_spacing);
+    }
+    if let Some(language) = painter.language {
+        let _ = write!(rtf, r"\lang{language}");
+    }
+    if let Some(language) = painter.east_asian_language {
+        let _ = write!(rtf, r"\langfe{language}");
+    }
+}
+
+#[rustfmt::skip]
+fn get_font_family_name(font_family: &FontFamily) -> &'static str {
+    return match font_family {
+        FontFamily::Nil    => r"\fnil",
+        FontFamily::Roman  => r"\froman",
+        FontFamily::Swiss  => r"\fswiss",
+        FontFamily::Modern => r"\fmodern",
+        FontFamily::Script => r"\fscript",
+        FontFamily::Decor  => r"\fdecor",
+        FontFamily::Tech   => r"\ftech",
+        FontFamily::Bidi   => r"\fbidi",
+    };
+}
+
+// Escape the special chars, and write the non-ASCII chars as \uN? (N being a signed 16 bits UTF-16 code unit)
+fn escape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        #[rustfmt::skip]
+        match c {
+            '\\' | '{' | '}'    => {
+                result.push('\\');
+                result.push(c);
+            }
+            '\n'                => result.push_str("\\\n"),
+            '\t'                => result.push_str(r"\tab "),
+            '\u{a0}'            => result.push_str(r"\~"),
+            '\u{ad}'            => result.push_str(r"\-"),
+            '\u{2011}'          => result.push_str(r"\_"),
+            ' '..='~'           => result.push(c),
+            _                   => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    let _ = write!(result, r"\u{}?", *unit as i16);
+                }
+            }
+        };
+    }
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_clipboard_fragment() {
+        let fragment = RtfFragment::parse(r"{\b bold} and \i italic}").unwrap();
+        assert_eq!(fragment.body.iter().map(|style_block| style_block.text.as_str()).collect::<String>(), "bold and italic");
+        let document = RtfFragment::parse(r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}\f0 Complete}").unwrap();
+        assert_eq!(document.header.font_table.get(&0).unwrap().name, "Helvetica");
+    }
+
+    #[test]
+    fn write_fragment() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica{\*\falt Arial};\f1\froman Times;}{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}
+\f1\qc Title\par\pard\f0 Some {\b\cf2\lang1036 bold} text \{1\}\tab é★\
+end\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let written = RtfFragment::write(&document.header, &document.body[1..]);
+        // Only the font and the colors of the written blocks are in the header
+        assert!(written.starts_with(r"{\rtf1\ansi\deff0{\fonttbl{\f0\fswiss Helvetica{\*\falt Arial};}}{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}"));
+        assert!(written.contains(r"\u233?\u9733?"));
+        let parsed = RtfDocument::try_from(written.as_str()).unwrap();
+        assert_eq!(parsed.get_text(), "Some bold text {1}\té★\nend");
+        assert_eq!(
+            parsed.body.iter().map(|style_block| &style_block.painter).collect::<Vec<_>>(),
+            document.body[1..].iter().map(|style_block| &style_block.painter).collect::<Vec<_>>()
+        );
+        assert_eq!(RtfFragment::parse(&written).unwrap().to_rtf(), written);
+    }
+}
@@ -1,66 +1,380 @@
-use std::collections::HashMap;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
 use tsify::Tsify;
-use wasm_bindgen::prelude::wasm_bindgen;
 
+use crate::codepage;
+use crate::encapsulation::EncapsulatedFormat;
+use crate::error::RtfError;
+use crate::lexer::Lexer;
+use crate::list::{ListOverrideTable, ListTable};
 use crate::paragraph::Paragraph;
-use crate::parser::Painter;
-use crate::tokens::{ControlWord, Token};
+use crate::parser::{BodyStart, Painter, Parser};
+use crate::tokens::{ControlWord, Property, Token};
+use crate::units::Twips;
+
+// The tables are BTreeMaps, so they are iterated and serialized in the order of their references, whatever the order of the document
 
 /// The ColorRef represent the index of the color in the ColorTable
 /// It's use in the document's body to reference a specific color with the \cfN or \cbN control words
 pub type ColorRef = u16;
-pub type ColorTable = HashMap<ColorRef, Color>;
+pub type ColorTable = BTreeMap<ColorRef, Color>;
+
+/// The expanded color table (\*\expandedcolortbl) written by macOS, more accurate than the ColorTable
+/// It shares its indexes with the ColorTable
+pub type ExpandedColorTable = BTreeMap<ColorRef, ExpandedColor>;
 
 /// The FontRef represent the index of the color in the FontTable
 /// It's use in the document's body to reference a specific font with the \fN control word
 pub type FontRef = u16;
-pub type FontTable = HashMap<FontRef, Font>;
+pub type FontTable = BTreeMap<FontRef, Font>;
 
 /// The StyleRef represent the index of the style in the StyleSheet
 /// It's use in the document's body to reference a specific style with the \sN control word
 pub type StyleRef = u16;
-pub type StyleSheet = HashMap<StyleRef, Style>;
+pub type StyleSheet = BTreeMap<StyleRef, Style>;
 
 /// Style for the StyleSheet
-#[derive(Hash, Default, Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Hash, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Style {
+    /// The name of the style ("Normal", "heading 1", ...)
+    pub name: String,
     /// The style attributes
-    painter: Painter,
+    pub(crate) painter: Painter,
     /// The layout attributes
-    paragraph: Paragraph,
+    pub(crate) paragraph: Paragraph,
+    /// The format of the character style is added to the one of the paragraph, instead of replacing it (\additive)
+    pub additive: bool,
+}
+
+impl Style {
+    /// The name of the style and its aliases, written after it and separated by commas (ex: "heading 1,h1")
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        return self.name.split(',').map(str::trim).filter(|name| !name.is_empty());
+    }
+
+    /// Whether the name or one of the aliases of the style is `name`, ignoring the case ("Heading 1" is the built-in "heading 1")
+    pub fn has_name(&self, name: &str) -> bool {
+        let name = name.trim();
+        return self
+            .names()
+            .any(|style_name| style_name.chars().flat_map(char::to_lowercase).eq(name.chars().flat_map(char::to_lowercase)));
+    }
 }
 
 /// Information about the document, including references to fonts & styles
-#[derive(Default, Debug, Clone, PartialEq, Deserialize, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi)]
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct RtfHeader {
     pub character_set: CharacterSet,
     pub font_table: FontTable,
     pub color_table: ColorTable,
+    pub expanded_color_table: ExpandedColorTable,
+    /// The paragraph styles of the stylesheet (\sN)
     pub stylesheet: StyleSheet,
+    /// The character styles of the stylesheet (\*\csN), apart from the paragraph styles as they don't share their references
+    pub character_styles: StyleSheet,
+    pub list_table: ListTable,
+    pub list_override_table: ListOverrideTable,
+    pub defaults: DocumentDefaults,
+    /// The program which wrote the document ({\*\generator Riched20 10.0.22621}), None if it's not written
+    pub generator: Option<String>,
+    /// The original format of the mail body encapsulated in the document (\fromhtml1 or \fromtext), None for a regular document.
+    /// See `extract_encapsulated` to de-encapsulate it.
+    pub encapsulation: Option<EncapsulatedFormat>,
+    /// The blobs written by Word (\themedata, \colorschememapping, \datastore) decoded from hexadecimal, if `ParserOptions::capture_blobs` is set
+    pub blobs: BTreeMap<String, Vec<u8>>,
+}
+
+/// The document formatting defaults (\deffN, \deflangN, \deftabN, \viewkindN, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DocumentDefaults {
+    /// Font of the text without \fN (\deffN)
+    pub font_ref: FontRef,
+    /// Language of the text, as a Windows LCID (\deflangN), 0 if not defined
+    pub language: u16,
+    /// Language of the East Asian text (\deflangfeN), 0 if not defined
+    pub east_asian_language: u16,
+    /// Width of the default tab stops (\deftabN), 720 twips (1/2 inch) if not defined
+    pub tab_width: Twips,
+    /// The view of the document when it's opened (\viewkindN)
+    pub view_kind: ViewKind,
+}
+
+impl Default for DocumentDefaults {
+    fn default() -> Self {
+        Self {
+            font_ref: 0,
+            language: 0,
+            east_asian_language: 0,
+            tab_width: Twips(720),
+            view_kind: ViewKind::default(),
+        }
+    }
+}
+
+impl DocumentDefaults {
+    /// Apply a control word of the document formatting properties, returns false if it isn't one
+    pub fn apply(&mut self, control_word: &ControlWord, property: &Property) -> bool {
+        #[rustfmt::skip]
+        match control_word {
+            ControlWord::DefaultFont              => self.font_ref = property.get_value_as::<FontRef>().unwrap_or_default(),
+            ControlWord::DefaultLanguage          => self.language = property.get_value_as::<u16>().unwrap_or_default(),
+            ControlWord::DefaultEastAsianLanguage => self.east_asian_language = property.get_value_as::<u16>().unwrap_or_default(),
+            ControlWord::DefaultTabWidth          => self.tab_width = Twips(property.get_value_or(720)),
+            ControlWord::ViewKind                 => self.view_kind = ViewKind::from(property.get_value()),
+            _                                     => return false,
+        };
+        return true;
+    }
+}
+
+/// The view of the document when it's opened (\viewkindN)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum ViewKind {
+    #[default]
+    None, // 0
+    PageLayout,     // 1
+    Outline,        // 2
+    MasterDocument, // 3
+    Normal,         // 4
+    OnlineLayout,   // 5
+}
+
+impl From<i32> for ViewKind {
+    fn from(value: i32) -> Self {
+        return match value {
+            1 => ViewKind::PageLayout,
+            2 => ViewKind::Outline,
+            3 => ViewKind::MasterDocument,
+            4 => ViewKind::Normal,
+            5 => ViewKind::OnlineLayout,
+            _ => ViewKind::None,
+        };
+    }
+}
+
+impl RtfHeader {
+    /// Parse only the header of a document : the document is scanned until the start of its body, which is not read
+    pub fn parse(rtf: &str) -> Result<RtfHeader, RtfError> {
+        let mut body_start = BodyStart::default();
+        let mut tokens = vec![];
+        for token in Lexer::new(rtf) {
+            let token = token?;
+            if body_start.is_reached(&token) {
+                break;
+            }
+            tokens.push(token);
+        }
+        return Ok(Parser::new(tokens).parse_header_only()?);
+    }
+
+    /// Resolve a color reference, preferring the expanded color table entry if any
+    pub fn get_color(&self, color_ref: &ColorRef) -> Option<&Color> {
+        if let Some(expanded_color) = self.expanded_color_table.get(color_ref) {
+            return Some(&expanded_color.color);
+        }
+        return self.color_table.get(color_ref);
+    }
+
+    /// The fonts, ordered by reference
+    pub fn fonts(&self) -> impl Iterator<Item = (FontRef, &Font)> {
+        return self.font_table.iter().map(|(font_ref, font)| (*font_ref, font));
+    }
+
+    /// The colors ordered by reference, each one resolved as by `get_color`
+    pub fn colors(&self) -> impl Iterator<Item = (ColorRef, &Color)> {
+        let color_refs: BTreeSet<ColorRef> = self.color_table.keys().chain(self.expanded_color_table.keys()).copied().collect();
+        return color_refs.into_iter().filter_map(|color_ref| Some((color_ref, self.get_color(&color_ref)?)));
+    }
+
+    /// The styles, ordered by reference
+    pub fn styles(&self) -> impl Iterator<Item = (StyleRef, &Style)> {
+        return self.stylesheet.iter().map(|(style_ref, style)| (*style_ref, style));
+    }
+
+    /// Find a paragraph style by its name or one of its aliases, ignoring the case (see `Style::has_name`)
+    pub fn get_style_by_name(&self, name: &str) -> Option<(StyleRef, &Style)> {
+        return self.styles().find(|(_, style)| style.has_name(name));
+    }
+
+    /// Find a character style by its name or one of its aliases, ignoring the case (ex: "Hyperlink")
+    pub fn get_character_style_by_name(&self, name: &str) -> Option<(StyleRef, &Style)> {
+        return self.character_styles.iter().map(|(style_ref, style)| (*style_ref, style)).find(|(_, style)| style.has_name(name));
+    }
 }
 
-#[derive(Hash, Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
-#[wasm_bindgen(getter_with_clone)]
+#[derive(Hash, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Font {
     pub name: String,
     pub character_set: u8,
     pub font_family: FontFamily,
+    /// The font to use if this one is not installed ({\*\falt ...})
+    pub alternate_name: Option<String>,
+    /// The chars existing in both fonts are written with the East Asian font rather than this one (\fbias1)
+    pub east_asian_bias: bool,
+    pub embedded: Option<EmbeddedFont>,
+}
+
+/// A font embedded in the document ({\*\fontemb ...}), or the file it comes from ({\*\fontfile ...})
+#[derive(Hash, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct EmbeddedFont {
+    pub font_type: EmbeddedFontType,
+    /// Path of the font file, empty if not defined
+    pub file_name: String,
+    /// The data of the font, decoded from hexadecimal, empty if only its file is defined
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum EmbeddedFontType {
+    #[default]
+    Nil, // \ftnil
+    TrueType, // \fttruetype
 }
 
-#[derive(Hash, Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
-#[wasm_bindgen]
+#[derive(Hash, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Color {
     pub red: u8,
     pub green: u8,
     pub blue: u8,
+    /// Opacity of the color, only defined by the expanded color table (255 is opaque)
+    #[cfg_attr(feature = "serde", serde(default = "Color::opaque"))]
+    pub alpha: u8,
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        return Self::from_rgb(0, 0, 0);
+    }
+}
+
+impl Color {
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        return Self { red, green, blue, alpha: 255 };
+    }
+
+    pub const fn from_rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        return Self { red, green, blue, alpha };
+    }
+
+    /// Parse a color written as RRGGBB or RRGGBBAA, with or without a leading '#' (ex: "#FF2600")
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        // The digits are checked, so each pair is a valid u8
+        let component = |index: usize| -> u8 { u8::from_str_radix(&hex[2 * index..2 * index + 2], 16).unwrap_or_default() };
+        let alpha = if hex.len() == 8 { component(3) } else { 255 };
+        return Some(Self::from_rgba(component(0), component(1), component(2), alpha));
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        return self.alpha == 255;
+    }
+
+    /// The color as #RRGGBB, or #RRGGBBAA if it is not opaque
+    pub fn to_hex(&self) -> String {
+        if self.is_opaque() {
+            return format!("#{:02X}{:02X}{:02X}", self.red, self.green, self.blue);
+        }
+        return format!("#{:02X}{:02X}{:02X}{:02X}", self.red, self.green, self.blue, self.alpha);
+    }
+
+    /// The color as a CSS value : rgb(r, g, b), or rgba(r, g, b, a) if it is not opaque
+    pub fn to_css(&self) -> String {
+        if self.is_opaque() {
+            return format!("rgb({}, {}, {})", self.red, self.green, self.blue);
+        }
+        return format!("rgba({}, {}, {}, {:.3})", self.red, self.green, self.blue, self.alpha as f32 / 255.0);
+    }
+
+    // The default alpha of the deserialized colors
+    #[cfg(feature = "serde")]
+    fn opaque() -> u8 {
+        return 255;
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.to_hex());
+    }
+}
+
+/// Entry of the expanded color table : a color, with its alpha channel, and its color space
+#[derive(Hash, Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ExpandedColor {
+    pub color_space: ColorSpace,
+    pub color: Color,
+}
+
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum ColorSpace {
+    #[default]
+    Srgb, // \cssrgb
+    GenericRgb, // \csgenericrgb
+    Gray,       // \csgray
+}
+
+impl ExpandedColor {
+    /// Build a color from its \cN components, expressed in thousandth of percent
+    /// An optional last component defines the alpha channel
+    pub fn from_components(color_space: ColorSpace, components: &[i32]) -> Self {
+        let to_u8 = |component: Option<&i32>, default: u8| -> u8 { component.map_or(default, |value| ((value.clamp(&0, &100_000) * 255 + 50_000) / 100_000) as u8) };
+        let color = match color_space {
+            ColorSpace::Gray => {
+                let gray = to_u8(components.first(), 0);
+                Color::from_rgba(gray, gray, gray, to_u8(components.get(1), 255))
+            }
+            ColorSpace::Srgb | ColorSpace::GenericRgb => Color::from_rgba(to_u8(components.first(), 0), to_u8(components.get(1), 0), to_u8(components.get(2), 0), to_u8(components.get(3), 255)),
+        };
+        return Self { color_space, color };
+    }
+}
+
+impl ColorSpace {
+    pub fn from(control_word: &ControlWord) -> Option<Self> {
+        match control_word {
+            ControlWord::ColorSpaceSrgb => Some(Self::Srgb),
+            ControlWord::ColorSpaceGenericRgb => Some(Self::GenericRgb),
+            ControlWord::ColorSpaceGray => Some(Self::Gray),
+            _ => None,
+        }
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, PartialEq, Default, Clone, Hash, Deserialize, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi)]
+#[derive(Debug, PartialEq, Eq, Default, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub enum CharacterSet {
     #[default]
     Ansi,
@@ -74,15 +388,38 @@ impl CharacterSet {
     pub fn from(token: &Token) -> Option<Self> {
         match token {
             Token::ControlSymbol((ControlWord::Ansi, _)) => Some(Self::Ansi),
-            // TODO: implement the rest
+            Token::ControlSymbol((ControlWord::Mac, _)) => Some(Self::Mac),
+            Token::ControlSymbol((ControlWord::Pc, _)) => Some(Self::Pc),
+            Token::ControlSymbol((ControlWord::Pca, _)) => Some(Self::Pca),
+            Token::ControlSymbol((ControlWord::AnsiCodePage, property)) => property.get_value_as::<u16>().ok().map(Self::Ansicpg),
             _ => None,
         }
     }
+
+    /// The code page of the bytes written as \'hh
+    pub fn codepage(&self) -> u16 {
+        #[rustfmt::skip]
+        return match self {
+            CharacterSet::Ansi              => 1252,
+            CharacterSet::Mac               => 10000,
+            CharacterSet::Pc                => 437,
+            CharacterSet::Pca               => 850,
+            CharacterSet::Ansicpg(codepage) => *codepage,
+        };
+    }
+
+    /// Decode a byte written as \'hh, as Windows-1252 if the code page is not supported
+    pub fn decode_byte(&self, byte: u8) -> char {
+        return codepage::decode_byte(self.codepage(), byte)
+            .or(codepage::decode_byte(1252, byte))
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, PartialEq, Hash, Clone, Default, Deserialize, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub enum FontFamily {
     #[default]
     Nil,
@@ -110,3 +447,81 @@ impl FontFamily {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::RtfDocument;
+    use crate::include_test_file;
+
+    #[test]
+    fn parse_header_only() {
+        for rtf in [include_test_file!("test-file.rtf"), include_test_file!("file-sample_500kB.rtf"), include_test_file!("list-item.rtf")] {
+            let header = RtfHeader::parse(rtf).unwrap();
+            assert_eq!(header, RtfDocument::try_from(rtf).unwrap().header);
+        }
+        // The body is not read : a truncated document or an invalid body don't prevent the parsing of the header
+        let rtf = r"{\rtf1\ansi\ansicpg1251\deff1{\fonttbl{\f1\fswiss\fcharset204 Arial;}}{\colortbl;\red255\green0\blue0;}{\*\generator Writer}{\info{\title Text}}
+            \pard\plain \f1 Text \'ff";
+        let header = RtfHeader::parse(rtf).unwrap();
+        assert_eq!(header.character_set, CharacterSet::Ansicpg(1251));
+        assert_eq!(header.defaults.font_ref, 1);
+        assert_eq!(header.font_table[&1].name, "Arial");
+        assert_eq!(header.font_table[&1].character_set, 204);
+        assert_eq!(header.get_color(&1), Some(&Color::from_rgb(255, 0, 0)));
+        assert!(RtfDocument::try_from(rtf).is_err());
+    }
+
+    #[test]
+    fn find_style_by_name() {
+        let rtf = r"{\rtf1\ansi{\stylesheet{\s0 Normal;}{\s1\b heading 1,h1;}{\s2 Titre 2;}{\*\cs10 Emphasis;}}
+\pard\s1 Title\par\pard\s0 Text\par\pard\s1 Part}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let header = &document.header;
+        assert_eq!(header.stylesheet[&1].names().collect::<Vec<_>>(), ["heading 1", "h1"]);
+        assert_eq!(header.get_style_by_name("Heading 1").map(|(style_ref, _)| style_ref), Some(1));
+        assert_eq!(header.get_style_by_name("H1").map(|(style_ref, _)| style_ref), Some(1));
+        assert_eq!(header.get_style_by_name("TITRE 2").map(|(style_ref, _)| style_ref), Some(2));
+        assert_eq!(header.get_style_by_name("heading"), None);
+        // The character styles are not in the stylesheet
+        assert_eq!(header.get_style_by_name("Emphasis"), None);
+        let texts: Vec<&str> = document.blocks_with_style("Heading 1").map(|style_block| style_block.text.as_str()).collect();
+        assert_eq!(texts, ["Title", "Part"]);
+        assert_eq!(document.blocks_with_style("Missing").count(), 0);
+    }
+
+    #[test]
+    fn color_hex() {
+        assert_eq!(Color::from_hex("#FF2600"), Some(Color::from_rgb(255, 38, 0)));
+        assert_eq!(Color::from_hex("874efe80"), Some(Color::from_rgba(135, 78, 254, 128)));
+        assert_eq!(Color::from_hex("#FF26"), None);
+        assert_eq!(Color::from_hex("#GG2600"), None);
+        assert_eq!(Color::from_rgb(255, 38, 0).to_hex(), "#FF2600");
+        assert_eq!(Color::from_rgba(135, 78, 254, 128).to_string(), "#874EFE80");
+    }
+
+    #[test]
+    fn iterate_tables_in_order() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f2 Courier;}{\f0 Helvetica;}{\f1 Times;}}{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}
+{\*\expandedcolortbl;;\cssrgb\c0\c0\c50000;}{\stylesheet{\s3 Title;}{\s1 heading 1;}} text}";
+        let header = crate::document::RtfDocument::try_from(rtf).unwrap().header;
+        assert_eq!(
+            header.fonts().map(|(font_ref, font)| (font_ref, font.name.as_str())).collect::<Vec<_>>(),
+            [(0, "Helvetica"), (1, "Times"), (2, "Courier")]
+        );
+        assert_eq!(
+            header.colors().map(|(color_ref, color)| (color_ref, color.to_hex())).collect::<Vec<_>>(),
+            [(1, "#FF0000".into()), (2, "#000080".into())]
+        );
+        assert_eq!(
+            header.styles().map(|(style_ref, style)| (style_ref, style.name.as_str())).collect::<Vec<_>>(),
+            [(1, "heading 1"), (3, "Title")]
+        );
+    }
+
+    #[test]
+    fn color_css() {
+        assert_eq!(Color::default().to_css(), "rgb(0, 0, 0)");
+        assert_eq!(Color::from_rgba(135, 78, 254, 128).to_css(), "rgba(135, 78, 254, 0.502)");
+    }
+}
@@ -0,0 +1,156 @@
+/// Define the conversion of a document to an HTML fragment, with inline styles
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::document::RtfDocument;
+use crate::paragraph::{Alignment, Paragraph};
+use crate::parser::{Painter, Underline};
+use crate::render::Renderer;
+use crate::units::Twips;
+
+impl RtfDocument {
+    /// Convert the document to HTML : one <p> per paragraph and one <span> per run of text, styled with inline CSS.
+    /// Hidden text (\v) is excluded, the tables and the images are exported as plain paragraphs.
+    pub fn to_html(&self) -> String {
+        let mut renderer = HtmlRenderer { document: self, html: String::new() };
+        self.render(&mut renderer);
+        return renderer.html;
+    }
+
+    fn get_run_style(&self, painter: &Painter) -> String {
+        let mut declarations: Vec<String> = vec![];
+        if let Some(font) = self.header.font_table.get(&painter.font_ref) {
+            declarations.push(format!("font-family:'{}'", escape_html(&font.name.replace('\'', ""))));
+        }
+        if painter.font_size != Painter::default().font_size {
+            // The font size is expressed in half-points
+            declarations.push(format!("font-size:{}pt", painter.font_size as f32 / 2.0));
+        }
+        if painter.bold {
+            declarations.push(String::from("font-weight:bold"));
+        }
+        if painter.italic {
+            declarations.push(String::from("font-style:italic"));
+        }
+        #[rustfmt::skip]
+        let decorations: Vec<&str> = [
+            (painter.underline != Underline::None,  "underline"),
+            (painter.strike,                        "line-through"),
+        ].into_iter().filter(|(enabled, _)| *enabled).map(|(_, decoration)| decoration).collect();
+        if !decorations.is_empty() {
+            declarations.push(format!("text-decoration:{}", decorations.join(" ")));
+        }
+        // \cf0 is the automatic color
+        if painter.color_ref != 0 {
+            if let Some(color) = self.header.get_color(&painter.color_ref) {
+                declarations.push(format!("color:{}", color.to_css()));
+            }
+        }
+        if painter.smallcaps {
+            declarations.push(String::from("font-variant:small-caps"));
+        }
+        if painter.caps {
+            declarations.push(String::from("text-transform:uppercase"));
+        }
+        if painter.superscript {
+            declarations.push(String::from("vertical-align:super"));
+        } else if painter.subscript {
+            declarations.push(String::from("vertical-align:sub"));
+        }
+        return declarations.join(";");
+    }
+}
+
+struct HtmlRenderer<'a> {
+    document: &'a RtfDocument,
+    html: String,
+}
+
+impl Renderer for HtmlRenderer<'_> {
+    fn begin_paragraph(&mut self, paragraph: &Paragraph) {
+        let _ = write!(self.html, r#"<p style="{}">"#, get_paragraph_style(paragraph));
+        if !paragraph.list_label.is_empty() {
+            let _ = write!(self.html, "{} ", escape_html(&paragraph.list_label));
+        }
+    }
+
+    fn text(&mut self, text: &str, painter: &Painter) {
+        let text = escape_html(text);
+        let _ = match self.document.get_run_style(painter) {
+            style if style.is_empty() => write!(self.html, "{text}"),
+            style => write!(self.html, r#"<span style="{style}">{text}</span>"#),
+        };
+    }
+
+    fn end_paragraph(&mut self, _paragraph: &Paragraph) {
+        self.html.push_str("</p>\n");
+    }
+}
+
+// The sizes of the paragraph are expressed in twips, converted to points
+fn get_paragraph_style(paragraph: &Paragraph) -> String {
+    #[rustfmt::skip]
+    let alignment = match paragraph.alignment {
+        Alignment::LeftAligned  => "left",
+        Alignment::RightAligned => "right",
+        Alignment::Center       => "center",
+        Alignment::Justify      => "justify",
+    };
+    let mut style = format!(
+        "margin:{}pt 0 {}pt 0;text-align:{alignment};white-space:pre-wrap",
+        paragraph.spacing.before.to_points(),
+        paragraph.spacing.after.to_points()
+    );
+    let indent = &paragraph.indent;
+    #[rustfmt::skip]
+    let properties = [
+        ("margin-left",   indent.left),
+        ("margin-right",  indent.right),
+        ("text-indent",   indent.first_line),
+    ];
+    for (property, twips) in properties.iter().filter(|(_, twips)| *twips != Twips(0)) {
+        let _ = write!(style, ";{property}:{}pt", twips.to_points());
+    }
+    return style;
+}
+
+fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        #[rustfmt::skip]
+        match c {
+            '&'     => result.push_str("&amp;"),
+            '<'     => result.push_str("&lt;"),
+            '>'     => result.push_str("&gt;"),
+            '"'     => result.push_str("&quot;"),
+            '\''    => result.push_str("&#39;"),
+            _       => result.push(c),
+        };
+    }
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_to_html() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}{\colortbl;\red255\green0\blue0;}\f0\qc\sa120 Title <1>\par\pard Some {\b\cf1 bold}{\v hidden}{\fs32\ul\strike big}\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.to_html(),
+            concat!(
+                r#"<p style="margin:0pt 0 6pt 0;text-align:center;white-space:pre-wrap"><span style="font-family:'Helvetica'">Title &lt;1&gt;</span></p>"#,
+                "\n",
+                r#"<p style="margin:0pt 0 0pt 0;text-align:left;white-space:pre-wrap"><span style="font-family:'Helvetica'">Some </span>"#,
+                r#"<span style="font-family:'Helvetica';font-weight:bold;color:rgb(255, 0, 0)">bold</span>"#,
+                r#"<span style="font-family:'Helvetica';font-size:16pt;text-decoration:underline line-through">big</span></p>"#,
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,449 @@
+/// Define the pictures ({\pict ...}) of a document
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::tokens::ControlWord;
+use crate::units::Twips;
+
+/// A picture of the document, with its raw data (decoded from hexadecimal or read from \binN)
+/// The position is a char offset in the visible text of the body (see `RtfDocument::get_text`)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Image {
+    pub format: ImageFormat,
+    /// Width of the picture (\picwN), in pixels or in twips depending on the format
+    pub width: i32,
+    /// Height of the picture (\pichN), in pixels or in twips depending on the format
+    pub height: i32,
+    /// Desired width of the picture before its cropping and scaling (\picwgoalN), 0 if not defined
+    pub goal_width: Twips,
+    /// Desired height of the picture before its cropping and scaling (\pichgoalN), 0 if not defined
+    pub goal_height: Twips,
+    /// Horizontal scaling in percent (\picscalexN), 100 if not defined
+    pub scale_x: i32,
+    /// Vertical scaling in percent (\picscaleyN), 100 if not defined
+    pub scale_y: i32,
+    pub crop: ImageCrop,
+    pub data: Vec<u8>,
+    pub position: usize,
+}
+
+impl Default for Image {
+    fn default() -> Self {
+        Self {
+            format: Default::default(),
+            width: Default::default(),
+            height: Default::default(),
+            goal_width: Default::default(),
+            goal_height: Default::default(),
+            scale_x: 100,
+            scale_y: 100,
+            crop: Default::default(),
+            data: Default::default(),
+            position: Default::default(),
+        }
+    }
+}
+
+/// The lengths cut from each side of a picture (\piccroplN, \piccroprN, \piccroptN, \piccropbN), negative to add a margin
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ImageCrop {
+    pub left: Twips,
+    pub right: Twips,
+    pub top: Twips,
+    pub bottom: Twips,
+}
+
+/// Format of a picture, as declared by its control word
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum ImageFormat {
+    Png,     // \pngblip
+    Jpeg,    // \jpegblip
+    Emf,     // \emfblip
+    Wmf,     // \wmetafile
+    MacPict, // \macpict
+    Dib,     // \dibitmap
+    Bitmap,  // \wbitmap
+    #[default]
+    Unknown,
+}
+
+impl ImageFormat {
+    pub fn from(control_word: &ControlWord) -> Option<Self> {
+        #[rustfmt::skip]
+        return match control_word {
+            ControlWord::PictureFormatPng    => Some(ImageFormat::Png),
+            ControlWord::PictureFormatJpeg   => Some(ImageFormat::Jpeg),
+            ControlWord::PictureFormatEmf    => Some(ImageFormat::Emf),
+            ControlWord::PictureFormatWmf    => Some(ImageFormat::Wmf),
+            ControlWord::PictureFormatPict   => Some(ImageFormat::MacPict),
+            ControlWord::PictureFormatDib    => Some(ImageFormat::Dib),
+            ControlWord::PictureFormatBitmap => Some(ImageFormat::Bitmap),
+            _                                => None,
+        };
+    }
+
+    /// The format of the data, guessed from its signature (ex: the \x89PNG of a PNG), whatever the declared format
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        #[rustfmt::skip]
+        return match data {
+            [0x89, b'P', b'N', b'G', ..]                    => Some(ImageFormat::Png),
+            [0xFF, 0xD8, 0xFF, ..]                          => Some(ImageFormat::Jpeg),
+            [1, 0, 0, 0, ..] if data.get(40..44) == Some(b" EMF") => Some(ImageFormat::Emf),
+            // A placeable metafile, or the header of a metafile in memory (type 1) or on disk (type 2) of 9 words
+            [0xD7, 0xCD, 0xC6, 0x9A, ..] | [1 | 2, 0, 9, 0, ..] => Some(ImageFormat::Wmf),
+            // A bitmap file, or a device-independent bitmap starting with the size of its header
+            [b'B', b'M', ..] | [12 | 40 | 52 | 56 | 108 | 124, 0, 0, 0, ..] => Some(ImageFormat::Dib),
+            _                                               => None,
+        };
+    }
+
+    /// The MIME type of the files of the format, None if it has none (\wbitmap, unknown format)
+    pub fn mime_type(&self) -> Option<&'static str> {
+        #[rustfmt::skip]
+        return match self {
+            ImageFormat::Png     => Some("image/png"),
+            ImageFormat::Jpeg    => Some("image/jpeg"),
+            ImageFormat::Emf     => Some("image/emf"),
+            ImageFormat::Wmf     => Some("image/wmf"),
+            ImageFormat::MacPict => Some("image/x-pict"),
+            ImageFormat::Dib     => Some("image/bmp"),
+            ImageFormat::Bitmap | ImageFormat::Unknown => None,
+        };
+    }
+
+    /// Whether the browsers display the pictures of the format (ex: in a data URL of an <img>), the metafiles need to be rendered first
+    pub fn is_web_compatible(&self) -> bool {
+        return matches!(self, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Dib);
+    }
+}
+
+/// The size of a picture read from its data : the pixels of a bitmap, or the physical size of a metafile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageSize {
+    Pixels { width: u32, height: u32 },
+    Length { width: Twips, height: Twips },
+}
+
+impl Image {
+    /// The format of the data, or the declared one if it's not recognized (see `ImageFormat::detect`)
+    pub fn detected_format(&self) -> ImageFormat {
+        return ImageFormat::detect(&self.data).unwrap_or(self.format);
+    }
+
+    pub fn is_web_compatible(&self) -> bool {
+        return self.detected_format().is_web_compatible();
+    }
+
+    /// The size of the picture in the document : its desired size (\picwgoal, \pichgoal), or else its intrinsic size with the pixels at 96 dpi,
+    /// cropped and then scaled
+    pub fn display_size(&self) -> (Twips, Twips) {
+        let (width, height) = match self.intrinsic_size() {
+            _ if self.goal_width > Twips(0) && self.goal_height > Twips(0) => (self.goal_width, self.goal_height),
+            Some(ImageSize::Pixels { width, height }) => (Twips::from_inches(width as f32 / 96.0), Twips::from_inches(height as f32 / 96.0)),
+            Some(ImageSize::Length { width, height }) => (width, height),
+            None => (Twips(0), Twips(0)),
+        };
+        let crop = &self.crop;
+        let get_length = |length: Twips, start: Twips, end: Twips, scale: i32| {
+            let cropped_length = length.0 as i64 - start.0 as i64 - end.0 as i64;
+            return Twips((cropped_length.saturating_mul(scale as i64) / 100).clamp(0, i32::MAX as i64) as i32);
+        };
+        return (get_length(width, crop.left, crop.right, self.scale_x), get_length(height, crop.top, crop.bottom, self.scale_y));
+    }
+
+    /// The picture as the content of a file of its format, to embed it or to save it.
+    /// The data of the metafiles, device-independent bitmaps and QuickDraw pictures has no file header in RTF, it's added :
+    /// the placeable header of a .wmf (with the size of \picw and \pich, in 0.01 mm), the header of a .bmp, or the 512 empty bytes of a .pict.
+    pub fn decoded_bytes(&self) -> Cow<'_, [u8]> {
+        let data = self.data.as_slice();
+        return match self.detected_format() {
+            ImageFormat::Wmf if !data.starts_with(&PLACEABLE_METAFILE_KEY) => {
+                let mut bytes = Vec::with_capacity(22 + data.len());
+                bytes.extend_from_slice(&PLACEABLE_METAFILE_KEY);
+                bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // Handle and left and top of the bounding box
+                bytes.extend_from_slice(&(self.width.clamp(0, i16::MAX as i32) as u16).to_le_bytes());
+                bytes.extend_from_slice(&(self.height.clamp(0, i16::MAX as i32) as u16).to_le_bytes());
+                bytes.extend_from_slice(&HUNDREDTHS_OF_MILLIMETER_PER_INCH.to_le_bytes());
+                bytes.extend_from_slice(&[0, 0, 0, 0]); // Reserved
+                                                        // The checksum is the XOR of the 10 previous words
+                let checksum = bytes.chunks(2).fold(0, |checksum, word| checksum ^ u16::from_le_bytes([word[0], word[1]]));
+                bytes.extend_from_slice(&checksum.to_le_bytes());
+                bytes.extend_from_slice(data);
+                Cow::Owned(bytes)
+            }
+            ImageFormat::Dib if !data.starts_with(b"BM") => {
+                let file_size = (14 + data.len()) as u32;
+                let header_size = read_u32_le(data, 0).unwrap_or(40);
+                let bit_count = read_u16_le(data, if header_size == 12 { 10 } else { 14 }).unwrap_or(24);
+                let used_colors = if header_size == 12 { 0 } else { read_u32_le(data, 32).unwrap_or(0) };
+                let color_size = if header_size == 12 { 3 } else { 4 };
+                let palette_size = match used_colors {
+                    0 if bit_count <= 8 => (1 << bit_count) * color_size,
+                    used_colors => used_colors * color_size,
+                };
+                // The 3 masks of the 16 and 32 bits bitmaps (BI_BITFIELDS) follow the header of 40 bytes
+                let masks_size = if header_size == 40 && read_u32_le(data, 16) == Some(3) { 12 } else { 0 };
+                let mut bytes = Vec::with_capacity(file_size as usize);
+                bytes.extend_from_slice(b"BM");
+                bytes.extend_from_slice(&file_size.to_le_bytes());
+                bytes.extend_from_slice(&[0, 0, 0, 0]); // Reserved
+                bytes.extend_from_slice(&(14 + header_size + masks_size + palette_size).to_le_bytes());
+                bytes.extend_from_slice(data);
+                Cow::Owned(bytes)
+            }
+            ImageFormat::MacPict => {
+                let mut bytes = Vec::with_capacity(512 + data.len());
+                bytes.resize(512, 0);
+                bytes.extend_from_slice(data);
+                Cow::Owned(bytes)
+            }
+            _ => Cow::Borrowed(data),
+        };
+    }
+
+    /// The size of the picture read from its data, without decoding or rendering it.
+    /// The metafiles without size in their data (the .wmf in RTF) take the one of \picw and \pich, in 0.01 mm.
+    pub fn intrinsic_size(&self) -> Option<ImageSize> {
+        let data = self.data.as_slice();
+        let pixels = |width: u32, height: u32| Some(ImageSize::Pixels { width, height });
+        let length = |width: i32, height: i32, units_per_inch: i32| {
+            let to_twips = |length: i32| Twips((length as i64 * Twips::PER_INCH as i64 / units_per_inch as i64) as i32);
+            Some(ImageSize::Length {
+                width: to_twips(width),
+                height: to_twips(height),
+            })
+        };
+        return match self.detected_format() {
+            ImageFormat::Png => pixels(read_u32_be(data, 16)?, read_u32_be(data, 20)?),
+            ImageFormat::Jpeg => {
+                let (width, height) = read_jpeg_size(data)?;
+                pixels(width as u32, height as u32)
+            }
+            // The frame of the header is in 0.01 mm
+            ImageFormat::Emf => {
+                let frame = [8, 12, 16, 20].map(|offset| read_u32_le(data, 16 + offset).map(|value| value as i32));
+                let [Some(left), Some(top), Some(right), Some(bottom)] = frame else {
+                    return None;
+                };
+                length(right.saturating_sub(left), bottom.saturating_sub(top), HUNDREDTHS_OF_MILLIMETER_PER_INCH as i32)
+            }
+            ImageFormat::Wmf if data.starts_with(&PLACEABLE_METAFILE_KEY) => {
+                let bounds = [6, 8, 10, 12].map(|offset| read_u16_le(data, offset).map(|value| value as i16 as i32));
+                let [Some(left), Some(top), Some(right), Some(bottom)] = bounds else {
+                    return None;
+                };
+                let units_per_inch = read_u16_le(data, 14).filter(|units| *units > 0)?;
+                length(right - left, bottom - top, units_per_inch as i32)
+            }
+            ImageFormat::Wmf if self.width > 0 && self.height > 0 => length(self.width, self.height, HUNDREDTHS_OF_MILLIMETER_PER_INCH as i32),
+            ImageFormat::Dib => {
+                let header = if data.starts_with(b"BM") { data.get(14..)? } else { data };
+                // The height is negative for the bitmaps stored from top to bottom
+                match read_u32_le(header, 0)? {
+                    12 => pixels(read_u16_le(header, 4)? as u32, read_u16_le(header, 6)? as u32),
+                    _ => pixels(read_u32_le(header, 4)?, (read_u32_le(header, 8)? as i32).unsigned_abs()),
+                }
+            }
+            _ => None,
+        };
+    }
+}
+
+const PLACEABLE_METAFILE_KEY: [u8; 4] = [0xD7, 0xCD, 0xC6, 0x9A];
+const HUNDREDTHS_OF_MILLIMETER_PER_INCH: u16 = 2540;
+
+fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    return Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?));
+}
+
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    return Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?));
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    return Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?));
+}
+
+// The size is in the start of frame segment (SOF0 to SOF15, except the DHT, JPG and DAC markers)
+fn read_jpeg_size(data: &[u8]) -> Option<(u16, u16)> {
+    let mut offset = 2;
+    loop {
+        if *data.get(offset)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(offset + 1)?;
+        match marker {
+            // Fill byte
+            0xFF => offset += 1,
+            // Markers without segment
+            0x01 | 0xD0..=0xD7 => offset += 2,
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = u16::from_be_bytes([*data.get(offset + 5)?, *data.get(offset + 6)?]);
+                let width = u16::from_be_bytes([*data.get(offset + 7)?, *data.get(offset + 8)?]);
+                return Some((width, height));
+            }
+            _ => {
+                let length = u16::from_be_bytes([*data.get(offset + 2)?, *data.get(offset + 3)?]);
+                offset += 2 + length as usize;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::RtfDocument;
+
+    #[test]
+    fn parse_pictures() {
+        let rtf = "{\\rtf1\\ansi Before {\\pict\\pngblip\\picw2\\pich1 89504e\n470d0a} middle {\\*\\shppict{\\pict\\jpegblip\\bin4 {\\}a}}{\\nonshppict{\\pict\\wmetafile8 0102}} after}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Before  middle  after");
+        assert_eq!(
+            document.images,
+            vec![
+                Image {
+                    format: ImageFormat::Png,
+                    width: 2,
+                    height: 1,
+                    data: vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a],
+                    position: 7,
+                    ..Image::default()
+                },
+                Image {
+                    format: ImageFormat::Jpeg,
+                    width: 0,
+                    height: 0,
+                    data: b"{\\}a".to_vec(),
+                    position: 15,
+                    ..Image::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_display_size() {
+        let rtf = r"{\rtf1\ansi {\pict\pngblip\picw200\pich100\picwgoal3000\pichgoal1500\picscalex50\picscaley200\piccropl200\piccropr-200\piccropt100\piccropb400 89504e}}";
+        let image = &RtfDocument::try_from(rtf).unwrap().images[0];
+        assert_eq!((image.goal_width, image.goal_height), (Twips(3000), Twips(1500)));
+        assert_eq!((image.scale_x, image.scale_y), (50, 200));
+        assert_eq!(
+            image.crop,
+            ImageCrop {
+                left: Twips(200),
+                right: Twips(-200),
+                top: Twips(100),
+                bottom: Twips(400),
+            }
+        );
+        assert_eq!(image.display_size(), (Twips(1500), Twips(2000)));
+        // Without desired size, the pixels of a bitmap are displayed at 96 dpi
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x60\0\0\0\x30".to_vec();
+        let image = Image {
+            data: png,
+            scale_y: 50,
+            ..Image::default()
+        };
+        assert_eq!(image.display_size(), (Twips(1440), Twips(360)));
+        assert_eq!(Image::default().display_size(), (Twips(0), Twips(0)));
+    }
+
+    #[test]
+    fn detect_image_formats() {
+        let image = |format: ImageFormat, data: &[u8]| Image {
+            format,
+            data: data.to_vec(),
+            ..Image::default()
+        };
+        // A PNG declared as a JPEG
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 1, 0, 0, 0, 0, 0x80]);
+        let png = image(ImageFormat::Jpeg, &png);
+        assert_eq!(png.detected_format(), ImageFormat::Png);
+        assert_eq!(png.detected_format().mime_type(), Some("image/png"));
+        assert_eq!(png.intrinsic_size(), Some(ImageSize::Pixels { width: 256, height: 128 }));
+        assert_eq!(png.decoded_bytes(), png.data.as_slice());
+        let jpeg = image(ImageFormat::Unknown, &[0xFF, 0xD8, 0xFF, 0xE0, 0, 4, 0, 0, 0xFF, 0xC0, 0, 17, 8, 0, 32, 0, 64, 3]);
+        assert!(jpeg.is_web_compatible());
+        assert_eq!(jpeg.intrinsic_size(), Some(ImageSize::Pixels { width: 64, height: 32 }));
+        let mut emf = vec![1, 0, 0, 0, 108, 0, 0, 0];
+        emf.extend([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // Bounds
+        emf.extend([0, 0, 0, 0, 0, 0, 0, 0, 0xEC, 0x09, 0, 0, 0xF6, 0x04, 0, 0]); // Frame of 25.4 x 12.7 mm
+        emf.extend(b" EMF");
+        let emf = image(ImageFormat::Emf, &emf);
+        assert!(!emf.is_web_compatible());
+        assert_eq!(
+            emf.intrinsic_size(),
+            Some(ImageSize::Length {
+                width: Twips(1440),
+                height: Twips(720)
+            })
+        );
+        assert_eq!(ImageFormat::detect(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn add_file_headers() {
+        // A metafile without header, sized by \picw and \pich in 0.01 mm
+        let rtf = r"{\rtf1\ansi {\pict\wmetafile8\picw2540\pich1270 0100090000030a00000000000000}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let wmf = &document.images[0];
+        assert_eq!(wmf.detected_format(), ImageFormat::Wmf);
+        assert_eq!(
+            wmf.intrinsic_size(),
+            Some(ImageSize::Length {
+                width: Twips(1440),
+                height: Twips(720)
+            })
+        );
+        let bytes = wmf.decoded_bytes();
+        assert_eq!(bytes.len(), 22 + wmf.data.len());
+        assert_eq!(&bytes[..16], [0xD7, 0xCD, 0xC6, 0x9A, 0, 0, 0, 0, 0, 0, 0xEC, 0x09, 0xF6, 0x04, 0xEC, 0x09]);
+        let checksum = bytes[..20].chunks(2).fold(0, |checksum, word| checksum ^ u16::from_le_bytes([word[0], word[1]]));
+        assert_eq!(bytes[20..22], checksum.to_le_bytes());
+        let placeable = Image {
+            data: bytes.to_vec(),
+            ..Image::default()
+        };
+        assert_eq!(placeable.intrinsic_size(), wmf.intrinsic_size());
+        assert_eq!(placeable.decoded_bytes(), placeable.data.as_slice());
+        // A bitmap of 2 x 1 pixels, stored from top to bottom, in 24 bits without palette
+        let mut dib = vec![40, 0, 0, 0, 2, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 1, 0, 24, 0];
+        dib.resize(40 + 8, 0);
+        let dib = Image {
+            format: ImageFormat::Dib,
+            data: dib,
+            ..Image::default()
+        };
+        assert_eq!(dib.intrinsic_size(), Some(ImageSize::Pixels { width: 2, height: 1 }));
+        let bytes = dib.decoded_bytes();
+        assert_eq!(bytes[..14], [b'B', b'M', 62, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0]);
+        assert_eq!(
+            Image {
+                data: bytes.to_vec(),
+                ..Image::default()
+            }
+            .intrinsic_size(),
+            dib.intrinsic_size()
+        );
+        let pict = Image {
+            format: ImageFormat::MacPict,
+            data: vec![1, 2],
+            ..Image::default()
+        };
+        assert_eq!(pict.decoded_bytes().len(), 514);
+        assert_eq!(pict.intrinsic_size(), None);
+    }
+}
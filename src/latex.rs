@@ -0,0 +1,162 @@
+/// Define the conversion of a document to a LaTeX document
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::document::RtfDocument;
+use crate::paragraph::{Alignment, Paragraph};
+use crate::parser::{Painter, Underline};
+use crate::render::Renderer;
+
+// The underline and the strikethrough come from the ulem package, \normalem keeps \emph in italic
+const PREAMBLE: &str = "\\documentclass{article}\n\\usepackage[normalem]{ulem}\n\n\\begin{document}\n\n";
+
+impl RtfDocument {
+    /// Convert the document to a LaTeX document, to compile with pdflatex, xelatex or lualatex.
+    /// The runs are converted to \textbf, \textit, \uline, ... and the list items keep their label.
+    /// Hidden text (\v) is excluded, the tables and the images are exported as plain paragraphs.
+    pub fn to_latex(&self) -> String {
+        let mut renderer = LatexRenderer {
+            latex: String::from(PREAMBLE),
+            list_depth: 0,
+        };
+        self.render(&mut renderer);
+        renderer.close_lists(0);
+        renderer.latex.push_str("\\end{document}\n");
+        return renderer.latex;
+    }
+}
+
+struct LatexRenderer {
+    latex: String,
+    // Number of itemize environments opened
+    list_depth: usize,
+}
+
+impl LatexRenderer {
+    fn close_lists(&mut self, depth: usize) {
+        while self.list_depth > depth {
+            self.list_depth -= 1;
+            let _ = writeln!(self.latex, "{}\\end{{itemize}}", "  ".repeat(self.list_depth));
+        }
+    }
+}
+
+impl Renderer for LatexRenderer {
+    fn begin_paragraph(&mut self, paragraph: &Paragraph) {
+        if paragraph.list_label.is_empty() {
+            if self.list_depth > 0 {
+                self.close_lists(0);
+                self.latex.push('\n');
+            }
+            #[rustfmt::skip]
+            let alignment = match paragraph.alignment {
+                Alignment::Center       => "{\\centering ",
+                Alignment::RightAligned => "{\\raggedleft ",
+                // The text is justified by default
+                _                       => "",
+            };
+            self.latex.push_str(alignment);
+            return;
+        }
+        // The list items are nested in one itemize per level
+        let depth = paragraph.list_level as usize + 1;
+        self.close_lists(depth);
+        while self.list_depth < depth {
+            let _ = writeln!(self.latex, "{}\\begin{{itemize}}", "  ".repeat(self.list_depth));
+            self.list_depth += 1;
+        }
+        let _ = write!(self.latex, "{}\\item[{}] ", "  ".repeat(self.list_depth), escape_latex(&paragraph.list_label));
+    }
+
+    fn text(&mut self, text: &str, painter: &Painter) {
+        let mut latex = escape_latex(text);
+        #[rustfmt::skip]
+        let commands = [
+            (painter.bold,                          "textbf"),
+            (painter.italic,                        "textit"),
+            (painter.underline != Underline::None,  "uline"),
+            (painter.strike,                        "sout"),
+            (painter.smallcaps,                     "textsc"),
+            (painter.superscript,                   "textsuperscript"),
+            (painter.subscript,                     "textsubscript"),
+        ];
+        for (_, command) in commands.iter().filter(|(enabled, _)| *enabled) {
+            latex = format!("\\{command}{{{latex}}}");
+        }
+        if painter.font_size != Painter::default().font_size {
+            // The font size is expressed in half-points, the line spacing is 120% of the size
+            let size = painter.font_size as f32 / 2.0;
+            latex = format!("{{\\fontsize{{{size}pt}}{{{}pt}}\\selectfont {latex}}}", size * 1.2);
+        }
+        self.latex.push_str(&latex);
+    }
+
+    fn end_paragraph(&mut self, paragraph: &Paragraph) {
+        if !paragraph.list_label.is_empty() {
+            self.latex.push('\n');
+            return;
+        }
+        if matches!(paragraph.alignment, Alignment::Center | Alignment::RightAligned) {
+            self.latex.push_str("\\par}");
+        }
+        self.latex.push_str("\n\n");
+    }
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        #[rustfmt::skip]
+        match c {
+            '\\'                                => result.push_str("\\textbackslash{}"),
+            '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '^'                                 => result.push_str("\\textasciicircum{}"),
+            '~'                                 => result.push_str("\\textasciitilde{}"),
+            '\t'                                => result.push_str("\\quad "),
+            '\u{a0}'                            => result.push('~'),
+            '\u{ad}'                            => result.push_str("\\-"),
+            _                                   => result.push(c),
+        };
+    }
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_to_latex() {
+        let rtf = r"{\rtf1\ansi
+            {\*\listtable{\list{\listlevel\levelnfc23{\leveltext\'01\u8226 ?;}}{\listlevel\levelnfc0{\leveltext\'02\'01.;}}\listid1}}
+            {\*\listoverridetable{\listoverride\listid1\ls1}}
+            \pard\qc{\fs32\b Title}\par
+            \pard Some {\i italic}, {\ul underlined} and 50% {\super sup}\par
+            \pard\ls1\ilvl0 Item\par
+            \pard\ls1\ilvl1 Sub item\par
+            \pard\qj End\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.to_latex(),
+            [
+                PREAMBLE,
+                "{\\centering {\\fontsize{16pt}{19.2pt}\\selectfont \\textbf{Title}}\\par}\n\n",
+                "Some \\textit{italic}, \\uline{underlined} and 50\\% \\textsuperscript{sup}\n\n",
+                "\\begin{itemize}\n",
+                "  \\item[•] Item\n",
+                "  \\begin{itemize}\n",
+                "    \\item[1.] Sub item\n",
+                "  \\end{itemize}\n",
+                "\\end{itemize}\n",
+                "\n",
+                "End\n\n",
+                "\\end{document}\n",
+            ]
+            .concat()
+        );
+    }
+}
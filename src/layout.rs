@@ -0,0 +1,219 @@
+/// Define the conversion of a document to plain text, approximating its layout
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::document::RtfDocument;
+use crate::paragraph::{Alignment, Paragraph};
+use crate::parser::Painter;
+use crate::render::Renderer;
+use crate::units::Twips;
+
+/// Width of a char of the plain text, in twips (10 chars per inch)
+pub const TWIPS_PER_CHAR: i32 = 144;
+// Width of the default tab stops when the paragraph doesn't define it (\pardeftabN), in twips
+const DEFAULT_TAB_WIDTH: Twips = Twips(720);
+// Indentation of each level of a list, in chars
+const LIST_LEVEL_INDENT: usize = 2;
+
+impl RtfDocument {
+    /// Convert the document to plain text with lines of `width` chars, approximating the alignment, the tabs and the indentation of the lists.
+    /// Hidden text (\v) is excluded.
+    pub fn to_text_layout(&self, width: usize) -> String {
+        let mut renderer = TextLayoutRenderer {
+            width,
+            lines: vec![],
+            text: String::new(),
+        };
+        self.render(&mut renderer);
+        return renderer.lines.join("\n");
+    }
+
+    // Group the style blocks in paragraphs of runs : a paragraph ends with a \par or when the paragraph properties change.
+    // The line breaks (\line) stay in the text of the runs.
+    #[cfg(any(feature = "docx", feature = "pdf"))]
+    pub(crate) fn get_paragraph_runs(&self, include_hidden: bool) -> Vec<(&Paragraph, Vec<(&Painter, &str)>)> {
+        let mut paragraphs: Vec<(&Paragraph, Vec<(&Painter, &str)>)> = vec![];
+        let mut ended = true;
+        for style_block in &self.body {
+            if (include_hidden || !style_block.painter.hidden) && !style_block.painter.deleted && !style_block.text.is_empty() {
+                let run = (&style_block.painter, style_block.text.as_str());
+                match paragraphs.last_mut() {
+                    Some((paragraph, runs)) if !ended && **paragraph == style_block.paragraph => runs.push(run),
+                    _ => paragraphs.push((&style_block.paragraph, vec![run])),
+                }
+                ended = false;
+            }
+            ended |= style_block.paragraph_end;
+        }
+        return paragraphs;
+    }
+}
+
+struct TextLayoutRenderer {
+    width: usize,
+    lines: Vec<String>,
+    // Text of the current paragraph
+    text: String,
+}
+
+impl Renderer for TextLayoutRenderer {
+    fn text(&mut self, text: &str, _painter: &Painter) {
+        self.text.push_str(text);
+    }
+
+    fn end_paragraph(&mut self, paragraph: &Paragraph) {
+        let text = mem::take(&mut self.text);
+        self.lines.extend(layout_paragraph(paragraph, &text, self.width));
+    }
+}
+
+fn twips_to_chars(twips: Twips) -> usize {
+    return (twips.0 / TWIPS_PER_CHAR).max(0) as usize;
+}
+
+// Replace the tabs by spaces up to the next tab stop, `column` being the column of the first char of the text
+fn expand_tabs(text: &str, paragraph: &Paragraph, left_indent: usize, mut column: usize) -> String {
+    let default_tab_width = twips_to_chars(if paragraph.tab_width > Twips(0) { paragraph.tab_width } else { DEFAULT_TAB_WIDTH }).max(1);
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c != '\t' {
+            result.push(c);
+            column += 1;
+            continue;
+        }
+        // The tab stops are relative to the left indentation
+        let next_stop = paragraph
+            .tab_stops
+            .iter()
+            .map(|tab_stop| left_indent + twips_to_chars(tab_stop.position))
+            .find(|stop| *stop > column)
+            .unwrap_or_else(|| left_indent + ((column.saturating_sub(left_indent)) / default_tab_width + 1) * default_tab_width);
+        while column < next_stop {
+            result.push(' ');
+            column += 1;
+        }
+    }
+    return result;
+}
+
+// Wrap the text, the first line and the next ones having different widths
+fn wrap(text: &str, first_width: usize, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    let mut line = String::new();
+    let mut line_width = first_width;
+    for word in text.split(' ') {
+        let line_len = line.chars().count();
+        if line_len > 0 && line_len + 1 + word.chars().count() > line_width {
+            lines.push(line);
+            line = String::new();
+            line_width = width;
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    return lines;
+}
+
+// Distribute the missing spaces between the words of the line
+fn justify(line: &str, width: usize) -> String {
+    let words: Vec<&str> = line.split(' ').filter(|word| !word.is_empty()).collect();
+    let words_len: usize = words.iter().map(|word| word.chars().count()).sum();
+    if words.len() < 2 || words_len >= width {
+        return String::from(line);
+    }
+    let gaps = words.len() - 1;
+    let spaces = width - words_len;
+    let mut result = String::with_capacity(width);
+    for (index, word) in words.iter().enumerate() {
+        result.push_str(word);
+        if index < gaps {
+            // The first gaps get the remaining spaces
+            let gap = spaces / gaps + usize::from(index < spaces % gaps);
+            result.push_str(&" ".repeat(gap));
+        }
+    }
+    return result;
+}
+
+fn layout_paragraph(paragraph: &Paragraph, text: &str, width: usize) -> Vec<String> {
+    let label = if paragraph.list_label.is_empty() { String::new() } else { paragraph.list_label.clone() + " " };
+    let list_indent = if label.is_empty() { 0 } else { paragraph.list_level as usize * LIST_LEVEL_INDENT };
+    let left_indent = twips_to_chars(paragraph.indent.left) + list_indent;
+    let first_line_indent = (left_indent as i32 + paragraph.indent.first_line.0 / TWIPS_PER_CHAR).max(0) as usize;
+    let right_indent = twips_to_chars(paragraph.indent.right);
+    // The next lines of a list item are aligned with the text of the first one
+    let label_len = label.chars().count();
+    let text = expand_tabs(text, paragraph, left_indent, first_line_indent + label_len);
+    let first_width = width.saturating_sub(first_line_indent + label_len + right_indent).max(1);
+    let next_width = width.saturating_sub(left_indent + label_len + right_indent).max(1);
+    let lines = wrap(&text, first_width, next_width);
+    let line_count = lines.len();
+    return lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let (indent, line_width) = if index == 0 { (first_line_indent, first_width) } else { (left_indent, next_width) };
+            let line = line.trim_end();
+            let padding = line_width.saturating_sub(line.chars().count());
+            #[rustfmt::skip]
+            let (padding, line) = match paragraph.alignment {
+                Alignment::LeftAligned                         => (0, String::from(line)),
+                Alignment::RightAligned                        => (padding, String::from(line)),
+                Alignment::Center                              => (padding / 2, String::from(line)),
+                Alignment::Justify if index + 1 < line_count   => (0, justify(line, line_width)),
+                Alignment::Justify                             => (0, String::from(line)),
+            };
+            let prefix = if index == 0 { label.clone() } else { " ".repeat(label_len) };
+            return " ".repeat(indent) + &prefix + &" ".repeat(padding) + &line;
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_alignment() {
+        let rtf = r"{\rtf1\ansi {\pard\qc Centered\par}{\pard\qr Right\par}{\pard\qj Some justified text on several lines\par}{\pard Left}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.to_text_layout(20),
+            ["      Centered", "               Right", "Some  justified text", "on several lines", "Left"].join("\n")
+        );
+    }
+
+    #[test]
+    fn layout_paragraphs() {
+        // The paragraphs with the same properties are separated by \par
+        let rtf = r"{\rtf1\ansi \pard Hello\par World{\v hidden\par} !\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_text_layout(20), "Hello\nWorld\n!");
+    }
+
+    #[test]
+    fn layout_tabs() {
+        let rtf = r"{\rtf1\ansi \pard\tx1440\tx2880 A\tab B\tab C\tab D\
+\pard\pardeftab1440 1\tab 2\
+}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_text_layout(80), ["A         B         C    D", "1         2"].join("\n"));
+    }
+
+    #[test]
+    fn layout_lists() {
+        let rtf = r"{\rtf1\ansi
+            {\*\listtable{\list{\listlevel\levelnfc0{\leveltext\'02\'00.;}}{\listlevel\levelnfc4{\leveltext\'02\'01);}}\listid1}}
+            {\*\listoverridetable{\listoverride\listid1\ls1}}
+            \pard\ls1\ilvl0 First item\par
+            \pard\ls1\ilvl1 A sub item that is too long for a line\par
+        }";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_text_layout(24), ["1. First item", "  a) A sub item that is", "     too long for a line"].join("\n"));
+    }
+}
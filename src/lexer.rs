@@ -1,17 +1,45 @@
-use std::fmt;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+use core::fmt::Write;
+use core::mem;
 
-use crate::tokens::{ControlWord, Property, Token};
-use crate::utils::StrUtils;
-use crate::{recursive_tokenize, recursive_tokenize_with_init};
+#[cfg(feature = "std")]
+use std::io::BufRead;
+
+use crate::options::ParserOptions;
+#[cfg(feature = "std")]
+use crate::tokens::OwnedToken;
+use crate::tokens::{write_escaped_bytes, ControlWord, ControlWordMatching, Property, Token};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum LexerError {
     Error(String),
     InvalidUnicode(String),
     InvalidLastChar,
+    /// A limit of the `ParserOptions` is exceeded
+    LimitExceeded(String),
+    /// Outside of the binary data, the document is not valid UTF-8
+    InvalidUtf8(core::str::Utf8Error),
+    InvalidInteger(core::num::ParseIntError),
 }
 
-impl std::error::Error for LexerError {}
+impl core::error::Error for LexerError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        return match self {
+            LexerError::InvalidUtf8(error) => Some(error),
+            LexerError::InvalidInteger(error) => Some(error),
+            _ => None,
+        };
+    }
+}
 
 impl fmt::Display for LexerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -20,76 +48,827 @@ impl fmt::Display for LexerError {
             LexerError::InvalidLastChar => write!(f, "Invalid last char, should be '}}'"),
             LexerError::InvalidUnicode(uc) => write!(f, "Invalid unicode : {uc}"),
             LexerError::Error(msg) => write!(f, "{}", msg),
+            LexerError::LimitExceeded(limit) => write!(f, "Limit exceeded : {limit}"),
+            LexerError::InvalidUtf8(error) => write!(f, "Invalid UTF-8 : {error}"),
+            LexerError::InvalidInteger(error) => write!(f, "Invalid integer : {error}"),
         };
         return Ok(());
     }
 }
 
-impl From<std::str::Utf8Error> for LexerError {
-    fn from(value: std::str::Utf8Error) -> Self {
-        return LexerError::Error(value.to_string());
+impl From<core::str::Utf8Error> for LexerError {
+    fn from(value: core::str::Utf8Error) -> Self {
+        return LexerError::InvalidUtf8(value);
     }
 }
 
-impl From<std::num::ParseIntError> for LexerError {
-    fn from(value: std::num::ParseIntError) -> Self {
+#[cfg(feature = "std")]
+impl From<std::io::Error> for LexerError {
+    fn from(value: std::io::Error) -> Self {
         return LexerError::Error(value.to_string());
     }
 }
 
-pub struct Lexer;
+impl From<core::num::ParseIntError> for LexerError {
+    fn from(value: core::num::ParseIntError) -> Self {
+        return LexerError::InvalidInteger(value);
+    }
+}
+
+/// A byte range (end excluded) in the RTF source
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+// The number of tokens and the nesting depth of the groups, checked while scanning
+// so that a hostile document is rejected before all its tokens are allocated
+struct TokenLimits {
+    max_tokens: usize,
+    max_nesting_depth: usize,
+    count: usize,
+    depth: usize,
+    spans: Option<SpanRecorder>,
+    // A document truncated in transit can end with a slice that is not the last '}'
+    recover_unclosed_groups: bool,
+}
+
+// The source spans of the tokens, recorded while scanning
+struct SpanRecorder {
+    source_address: usize,
+    matching: ControlWordMatching,
+    spans: Vec<SourceSpan>,
+}
+
+impl TokenLimits {
+    fn new(options: &ParserOptions) -> Self {
+        return Self {
+            max_tokens: options.max_tokens,
+            max_nesting_depth: options.max_nesting_depth,
+            count: 0,
+            depth: 0,
+            spans: None,
+            recover_unclosed_groups: options.recover_unclosed_groups || options.fragment,
+        };
+    }
+
+    fn check(&mut self, tokens: &[Token]) -> Result<(), LexerError> {
+        self.count += tokens.len();
+        if self.count > self.max_tokens {
+            return Err(LexerError::LimitExceeded(format!("more than {} tokens", self.max_tokens)));
+        }
+        for token in tokens {
+            match token {
+                Token::OpeningBracket => self.depth += 1,
+                Token::ClosingBracket => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+            if self.depth > self.max_nesting_depth {
+                return Err(LexerError::LimitExceeded(format!("more than {} nested groups", self.max_nesting_depth)));
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl SpanRecorder {
+    // Record the spans of the tokens of a slice of the source, in the order they were pushed
+    fn record(&mut self, slice: &[u8], tokens: &[Token]) {
+        let slice_address = slice.as_ptr() as usize;
+        let mut cursor = 0;
+        for token in tokens {
+            let (start, end) = match token {
+                // The text and the binary data are slices of the source
+                Token::PlainText(text) => (text.as_ptr() as usize - slice_address, text.as_ptr() as usize - slice_address + text.len()),
+                Token::Binary(data) => (data.as_ptr() as usize - slice_address, data.as_ptr() as usize - slice_address + data.len()),
+                _ => {
+                    let start = cursor + slice[cursor..].iter().take_while(|c| c.is_ascii_whitespace()).count();
+                    (start, start + self.get_token_length(token, &slice[start..]))
+                }
+            };
+            cursor = end;
+            let offset = slice_address - self.source_address;
+            self.spans.push(SourceSpan {
+                start: offset + start,
+                end: offset + end,
+            });
+        }
+    }
+
+    // Get the length in the source of a token that is not a slice of it, `bytes` starting with the token
+    fn get_token_length(&self, token: &Token, bytes: &[u8]) -> usize {
+        #[rustfmt::skip]
+        return match token {
+            Token::OpeningBracket | Token::ClosingBracket         => 1,
+            Token::IgnorableDestination | Token::CRLF             => 2,
+            Token::ControlSymbol((ControlWord::HexByte, _))       => 4,
+            Token::ControlSymbol((control_word, _)) if control_word.is_symbol() => 2,
+            // The space delimiting the control word is part of it
+            Token::ControlSymbol(_)                               => {
+                let slice = core::str::from_utf8(bytes).unwrap_or("");
+                let (_, tail) = ControlWord::split(slice, self.matching);
+                slice.len() - tail.len()
+            }
+            _                                                     => 0,
+        };
+    }
+}
+
+// The destinations read as one binary token by the lexer, see `ParserOptions::capture_blobs`
+const BLOB_DESTINATIONS: [&str; 3] = [r"\themedata", r"\colorschememapping", r"\datastore"];
+
+/// Write the tokens back to RTF : scanning the result gives the same tokens.
+/// The result is bytes, as the binary data (\binN) is written as is and may not be valid UTF-8.
+pub fn tokens_to_bytes(tokens: &[Token]) -> Vec<u8> {
+    let mut result = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            // The data of a blob is written as is, without \binN
+            Token::Binary(data) if is_blob(tokens, index) => result.extend_from_slice(data),
+            Token::Binary(data) => {
+                result.extend_from_slice(format!(r"\bin{} ", data.len()).as_bytes());
+                result.extend_from_slice(data);
+            }
+            _ => result.extend_from_slice(token.to_string().as_bytes()),
+        }
+        if let Some(delimiter) = token_delimiter(token, tokens.get(index + 1)) {
+            result.push(delimiter as u8);
+        }
+    }
+    return result;
+}
+
+/// Write the tokens back to RTF as a string, as `tokens_to_bytes` does.
+/// The bytes of the binary data (\binN) which are not valid UTF-8 are escaped as \'hh : scanning the result gives the same tokens when there are none.
+pub fn tokens_to_string(tokens: &[Token]) -> String {
+    let mut result = String::new();
+    for (index, token) in tokens.iter().enumerate() {
+        // Writing to a String can't fail
+        let _ = match token {
+            Token::Binary(data) if is_blob(tokens, index) => write_escaped_bytes(&mut result, data),
+            _ => write!(result, "{token}"),
+        };
+        if let Some(delimiter) = token_delimiter(token, tokens.get(index + 1)) {
+            result.push(delimiter);
+        }
+    }
+    return result;
+}
+
+// The binary token is the data of a blob destination, written without \binN
+fn is_blob(tokens: &[Token], index: usize) -> bool {
+    return matches!(index.checked_sub(1).map(|previous| &tokens[previous]), Some(Token::ControlSymbol((control_word, _))) if BLOB_DESTINATIONS.contains(&control_word.name()));
+}
+
+// The char written between two tokens, which is not part of them
+fn token_delimiter(token: &Token, next_token: Option<&Token>) -> Option<char> {
+    #[rustfmt::skip]
+    return match token {
+        // A space delimits the control word from the text following it
+        Token::ControlSymbol((control_word, _)) if !control_word.is_symbol() && matches!(next_token, Some(Token::PlainText(_) | Token::Binary(_))) => Some(' '),
+        // Two texts are split by an unescaped line break, which is not part of the text, unless the second one starts with an escaped char
+        Token::PlainText(_) if matches!(next_token, Some(Token::PlainText(text)) if !text.starts_with(['\\', '{', '}'])) => Some('\n'),
+        _ => None,
+    };
+}
+
+/// `Lexer::scan` returns all the tokens of a document, while `Lexer::new` reads them one by one, as an iterator
+pub struct Lexer<'a> {
+    src: &'a [u8],
+    // The address of the source before its leading whitespaces are trimmed, the origin of the spans
+    source_address: usize,
+    cursor: ScanCursor,
+    matching: ControlWordMatching,
+    limits: TokenLimits,
+    // The tokens of the last slices scanned, the ones before `pending_index` being already returned
+    pending: Vec<Token<'a>>,
+    pending_index: usize,
+    // The error stopping the scan, returned after the pending tokens
+    error: Option<LexerError>,
+    finished: bool,
+}
+
+// The position of the scan in the source, to resume it slice after slice
+#[derive(Debug, Clone, Copy)]
+struct ScanCursor {
+    i: usize,
+    slice_start_index: usize,
+    current_index: usize,
+    previous_char: char,
+}
+
+// A scan of the document chunk after chunk, the bytes being given by the caller as they are read
+#[cfg(feature = "std")]
+pub(crate) struct ChunkScanner {
+    limits: TokenLimits,
+    matching: ControlWordMatching,
+    pub(crate) tokens: Vec<OwnedToken>,
+}
+
+#[cfg(feature = "std")]
+impl ChunkScanner {
+    pub(crate) fn new(options: &ParserOptions) -> Self {
+        return Self {
+            limits: TokenLimits::new(options),
+            matching: options.matching,
+            tokens: vec![],
+        };
+    }
+
+    // Scan the beginning of the bytes that can be scanned independently of the following bytes, and return its length
+    // The chunk ends before a bracket, so a multi-bytes char split between two reads is left to the next chunk
+    pub(crate) fn scan_chunk(&mut self, bytes: &[u8]) -> Result<usize, LexerError> {
+        // Trim the leading whitespaces of the document
+        let whitespaces = if self.tokens.is_empty() {
+            bytes.iter().take_while(|c| c.is_ascii_whitespace()).count()
+        } else {
+            0
+        };
+        let chunk_end = Lexer::get_chunk_end(&bytes[whitespaces..]);
+        if chunk_end == 0 {
+            return Ok(whitespaces);
+        }
+        let chunk = &bytes[whitespaces..whitespaces + chunk_end];
+        self.tokens.extend(Lexer::scan_chunk(chunk, self.matching, &mut self.limits)?.iter().map(OwnedToken::from));
+        #[cfg(feature = "tracing")]
+        tracing::trace!(length = chunk_end, tokens = self.tokens.len(), "chunk scanned");
+        return Ok(whitespaces + chunk_end);
+    }
+
+    // The last chunk is scanned as a whole document
+    pub(crate) fn scan_last_chunk(&mut self, bytes: &[u8]) -> Result<(), LexerError> {
+        let tokens = Lexer::scan_bytes_with(bytes, self.matching, &mut self.limits)?;
+        self.tokens.extend(tokens.iter().map(OwnedToken::from));
+        return Ok(());
+    }
+}
+
+impl Default for ScanCursor {
+    fn default() -> Self {
+        return Self {
+            i: 0,
+            slice_start_index: 0,
+            current_index: 0,
+            previous_char: ' ',
+        };
+    }
+}
+
+impl<'a> Lexer<'a> {
+    /// Create a lexer scanning the tokens of the document lazily, as they are read
+    pub fn new(src: &'a str) -> Self {
+        return Self {
+            src: src.as_bytes().trim_ascii(),
+            source_address: src.as_ptr() as usize,
+            cursor: ScanCursor::default(),
+            matching: ControlWordMatching::Strict,
+            limits: TokenLimits::new(&ParserOptions::default()),
+            pending: vec![],
+            pending_index: 0,
+            error: None,
+            finished: false,
+        };
+    }
+
+    /// Limit the tokens and the nesting depth, and read the control words as defined by `options.matching`, as `Lexer::scan_with_options`
+    pub fn with_options(mut self, options: &ParserOptions) -> Self {
+        let spans = self.limits.spans.take();
+        return Self {
+            limits: TokenLimits { spans, ..TokenLimits::new(options) },
+            ..self
+        }
+        .with_matching(options.matching);
+    }
+
+    pub fn with_matching(mut self, matching: ControlWordMatching) -> Self {
+        if let Some(recorder) = self.limits.spans.as_mut() {
+            recorder.matching = matching;
+        }
+        return Self { matching, ..self };
+    }
+
+    /// Record the byte range in the source of each token, given by `next_with_span` or `spanned`.
+    /// Without it, the tokens are scanned without their spans.
+    pub fn with_spans(mut self) -> Self {
+        self.limits.spans = Some(SpanRecorder {
+            source_address: self.source_address,
+            matching: self.matching,
+            spans: vec![],
+        });
+        return self;
+    }
+
+    /// Get the next token and its byte range in the source, which is empty if the lexer was not created `with_spans`
+    pub fn next_with_span(&mut self) -> Option<Result<(Token<'a>, SourceSpan), LexerError>> {
+        self.fill();
+        let span = self.limits.spans.as_ref().and_then(|recorder| recorder.spans.get(self.pending_index)).copied();
+        return self.next().map(|token| token.map(|token| (token, span.unwrap_or_default())));
+    }
+
+    /// Iterate over the tokens and their byte range in the source, ex: to highlight the syntax of a document
+    pub fn spanned(self) -> impl Iterator<Item = Result<(Token<'a>, SourceSpan), LexerError>> {
+        let mut lexer = self.with_spans();
+        return core::iter::from_fn(move || lexer.next_with_span());
+    }
+
+    /// Get the next token without consuming it
+    pub fn peek(&mut self) -> Option<Result<&Token<'a>, LexerError>> {
+        self.fill();
+        return match self.pending.get(self.pending_index) {
+            Some(token) => Some(Ok(token)),
+            None => self.error.clone().map(Err),
+        };
+    }
+
+    // Scan the next slices of the source until some tokens are pending, or the end of the source
+    fn fill(&mut self) {
+        while self.pending_index == self.pending.len() && !self.finished {
+            // The buffer is reused once all its tokens are returned
+            self.pending.clear();
+            self.pending_index = 0;
+            if let Some(recorder) = self.limits.spans.as_mut() {
+                recorder.spans.clear();
+            }
+            let tokens = &mut self.pending;
+            let result = match Self::scan_next_slice(self.src, &mut self.cursor, tokens, self.matching, &mut self.limits) {
+                Ok(true) => Ok(()),
+                Ok(false) => {
+                    self.finished = true;
+                    core::str::from_utf8(&self.src[self.cursor.slice_start_index..self.cursor.current_index])
+                        .map_err(LexerError::from)
+                        .and_then(|last_slice| Self::scan_last_slice(last_slice, tokens, self.matching, &mut self.limits))
+                }
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                self.finished = true;
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, LexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill();
+        return match self.pending.get_mut(self.pending_index) {
+            Some(token) => {
+                self.pending_index += 1;
+                Some(Ok(mem::replace(token, Token::Empty)))
+            }
+            None => self.error.take().map(Err),
+        };
+    }
+}
+
+impl Lexer<'_> {
+    pub fn scan(src: &str) -> Result<Vec<Token<'_>>, LexerError> {
+        return Self::scan_with_matching(src, ControlWordMatching::Strict);
+    }
+
+    /// Scan the document, reading the control words as defined by `matching` (ex: `ControlWordMatching::Lenient` to accept \PAR)
+    pub fn scan_with_matching(src: &str, matching: ControlWordMatching) -> Result<Vec<Token<'_>>, LexerError> {
+        return Self::scan_bytes_with(src.as_bytes(), matching, &mut TokenLimits::new(&ParserOptions::default()));
+    }
+
+    /// Scan the document, failing with a `LexerError::LimitExceeded` as soon as `max_tokens` or `max_nesting_depth` is exceeded,
+    /// and reading the control words as defined by `options.matching`
+    pub fn scan_with_options<'a>(src: &'a str, options: &ParserOptions) -> Result<Vec<Token<'a>>, LexerError> {
+        return Self::scan_bytes_with_options(src.as_bytes(), options);
+    }
+
+    /// Scan a document given as bytes : the data of \binN can be any bytes, while the rest of the document must be valid UTF-8
+    pub fn scan_bytes(src: &[u8]) -> Result<Vec<Token<'_>>, LexerError> {
+        return Self::scan_bytes_with_options(src, &ParserOptions::default());
+    }
+
+    pub fn scan_bytes_with_options<'a>(src: &'a [u8], options: &ParserOptions) -> Result<Vec<Token<'a>>, LexerError> {
+        return Self::scan_bytes_with(src, options.matching, &mut TokenLimits::new(options));
+    }
+
+    /// Scan the document and get the byte range in `src` of each token
+    pub fn scan_with_spans(src: &str) -> Result<(Vec<Token<'_>>, Vec<SourceSpan>), LexerError> {
+        return Self::scan_with_spans_and_options(src, &ParserOptions::default());
+    }
+
+    /// Scan the document with its limits, as `Lexer::scan_with_options`, and get the byte range in `src` of each token.
+    /// With `recover_unclosed_groups`, an unfinished document being edited is scanned too.
+    pub fn scan_with_spans_and_options<'a>(src: &'a str, options: &ParserOptions) -> Result<(Vec<Token<'a>>, Vec<SourceSpan>), LexerError> {
+        return Self::scan_bytes_with_spans_and_options(src.as_bytes(), options);
+    }
 
-impl Lexer {
-    pub fn scan(src: &str) -> Result<Vec<Token>, LexerError> {
-        let src = src.trim(); // Sanitize src : Trim the leading whitespaces
+    /// Scan a document given as bytes, as `Lexer::scan_bytes`, and get the byte range in `src` of each token
+    pub fn scan_bytes_with_spans(src: &[u8]) -> Result<(Vec<Token<'_>>, Vec<SourceSpan>), LexerError> {
+        return Self::scan_bytes_with_spans_and_options(src, &ParserOptions::default());
+    }
+
+    pub fn scan_bytes_with_spans_and_options<'a>(src: &'a [u8], options: &ParserOptions) -> Result<(Vec<Token<'a>>, Vec<SourceSpan>), LexerError> {
+        let mut limits = TokenLimits::new(options);
+        limits.spans = Some(SpanRecorder {
+            source_address: src.as_ptr() as usize,
+            matching: options.matching,
+            spans: vec![],
+        });
+        let tokens = Self::scan_bytes_with(src, options.matching, &mut limits)?;
+        let spans = limits.spans.map_or(vec![], |recorder| recorder.spans);
+        return Ok((tokens, spans));
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "scan", level = "debug", skip_all, fields(length = src.len())))]
+    fn scan_bytes_with<'a>(src: &'a [u8], matching: ControlWordMatching, limits: &mut TokenLimits) -> Result<Vec<Token<'a>>, LexerError> {
+        let src = src.trim_ascii(); // Sanitize src : Trim the leading whitespaces
 
         let mut tokens: Vec<Token> = vec![];
-        let mut slice_start_index = 0;
-        let mut current_index = 0;
-        let mut previous_char = ' ';
+        let last_slice = Self::scan_slices(src, &mut tokens, matching, limits)?;
+        Self::scan_last_slice(last_slice, &mut tokens, matching, limits)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(tokens = tokens.len(), "document scanned");
+        return Ok(tokens);
+    }
+
+    // Manage the last slice of the document (should always be "}")
+    fn scan_last_slice<'a>(last_slice: &'a str, tokens: &mut Vec<Token<'a>>, matching: ControlWordMatching, limits: &mut TokenLimits) -> Result<(), LexerError> {
+        if last_slice.is_empty() {
+            return Ok(());
+        }
+        if last_slice == "}" {
+            Self::push_tokens(tokens, &[Token::ClosingBracket], last_slice.as_bytes(), limits)?;
+        } else if !limits.recover_unclosed_groups {
+            return Err(LexerError::InvalidLastChar);
+        } else if let Ok(last_tokens) = Self::tokenize(last_slice, matching) {
+            // The groups are closed by the parser, a control word cut in the middle is dropped
+            Self::push_tokens(tokens, &last_tokens, last_slice.as_bytes(), limits)?;
+        }
+        return Ok(());
+    }
+
+    /// Scan a RTF stream chunk by chunk, without requiring the whole document in memory as one `&str`
+    #[cfg(feature = "std")]
+    pub fn scan_reader<R: BufRead>(reader: R) -> Result<Vec<OwnedToken>, LexerError> {
+        return Self::scan_reader_with_options(reader, &ParserOptions::default());
+    }
+
+    /// Scan a RTF stream chunk by chunk, failing as soon as `max_tokens` or `max_nesting_depth` is exceeded
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "scan_reader", level = "debug", skip_all))]
+    pub fn scan_reader_with_options<R: BufRead>(mut reader: R, options: &ParserOptions) -> Result<Vec<OwnedToken>, LexerError> {
+        let mut scanner = ChunkScanner::new(options);
+        // Bytes read but not scanned yet
+        let mut pending: Vec<u8> = vec![];
+        loop {
+            let data = reader.fill_buf()?;
+            if data.is_empty() {
+                break;
+            }
+            pending.extend_from_slice(data);
+            let read = data.len();
+            reader.consume(read);
+            let scanned = scanner.scan_chunk(&pending)?;
+            pending.drain(..scanned);
+        }
+        scanner.scan_last_chunk(&pending)?;
+        return Ok(scanner.tokens);
+    }
+
+    /// Scan the document on the threads of the rayon pool : it's split in chunks at the brackets, which are scanned in parallel
+    #[cfg(feature = "parallel")]
+    pub fn scan_parallel(src: &str) -> Result<Vec<Token<'_>>, LexerError> {
+        let thread_count = rayon::current_num_threads();
+        if thread_count < 2 {
+            return Self::scan(src);
+        }
+        return Self::scan_in_chunks(src, thread_count);
+    }
+
+    #[cfg(feature = "parallel")]
+    fn scan_in_chunks(src: &str, chunk_count: usize) -> Result<Vec<Token<'_>>, LexerError> {
+        use rayon::prelude::*;
+
+        let src = src.trim();
+        let boundaries = Self::get_chunk_boundaries(src.as_bytes(), chunk_count);
+        if boundaries.len() <= 2 {
+            return Self::scan(src);
+        }
+        let chunks: Vec<&[u8]> = boundaries.windows(2).map(|bounds| &src.as_bytes()[bounds[0]..bounds[1]]).collect();
+        let last_index = chunks.len() - 1;
+        let chunks_tokens: Vec<_> = chunks
+            .par_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                // The last chunk is scanned as the end of the document
+                if index == last_index {
+                    return Self::scan_bytes(chunk);
+                }
+                return Self::scan_chunk(chunk, ControlWordMatching::Strict, &mut TokenLimits::new(&ParserOptions::default()));
+            })
+            .collect();
+        let mut tokens = Vec::with_capacity(chunks_tokens.iter().map(|chunk_tokens| chunk_tokens.as_ref().map_or(0, Vec::len)).sum());
+        for chunk_tokens in chunks_tokens {
+            tokens.extend(chunk_tokens?);
+        }
+        return Ok(tokens);
+    }
+
+    // Split the text in about `count` chunks of the same size that can be scanned independently, and return their boundaries
+    // Each chunk starts at the first bracket after its expected start
+    #[cfg(feature = "parallel")]
+    fn get_chunk_boundaries(text: &[u8], count: usize) -> Vec<usize> {
+        let count = count.max(1);
+        let chunk_start = |index: usize| index * text.len() / count;
+        let mut boundaries = vec![0];
+        let mut next_chunk = 1;
+        // Add the bracket as a boundary if it's past the start of the next chunk, and return the start of the chunk after it
+        let mut add_boundary = |bracket_index: usize| -> Option<usize> {
+            if bracket_index >= chunk_start(next_chunk) && bracket_index > 0 {
+                boundaries.push(bracket_index);
+                while next_chunk < count && chunk_start(next_chunk) <= bracket_index {
+                    next_chunk += 1;
+                }
+            }
+            return (next_chunk < count).then(|| chunk_start(next_chunk));
+        };
+        if text.windows(4).any(|window| window == br"\bin") {
+            // The binary data must be skipped, so the whole text has to be walked through
+            Self::walk_brackets(text, |bracket_index| add_boundary(bracket_index).is_some());
+        } else {
+            // Jump to the next bracket that is not escaped after each chunk start
+            let mut offset = chunk_start(1);
+            while let Some(index) = text.get(offset..).and_then(|tail| tail.iter().position(|c| *c == b'{' || *c == b'}')) {
+                let bracket_index = offset + index;
+                if bracket_index == 0 || text[bracket_index - 1] == b'\\' {
+                    offset = bracket_index + 1;
+                    continue;
+                }
+                let Some(next_start) = add_boundary(bracket_index) else {
+                    break;
+                };
+                offset = next_start.max(bracket_index + 1);
+            }
+        }
+        boundaries.push(text.len());
+        return boundaries;
+    }
+
+    // Scan a chunk of the document that ends before a bracket
+    #[cfg(feature = "std")]
+    fn scan_chunk<'a>(src: &'a [u8], matching: ControlWordMatching, limits: &mut TokenLimits) -> Result<Vec<Token<'a>>, LexerError> {
+        let mut tokens = vec![];
+        let last_slice = Self::scan_slices(src, &mut tokens, matching, limits)?;
+        if !last_slice.is_empty() {
+            Self::push_slice_tokens(&mut tokens, last_slice, matching, limits)?;
+        }
+        return Ok(tokens);
+    }
+
+    // Get the end of the part of the text that can be scanned independently of the following text
+    // It's the last bracket that is not escaped nor part of some binary data, or 0 if there is none
+    #[cfg(feature = "std")]
+    fn get_chunk_end(bytes: &[u8]) -> usize {
+        let mut chunk_end = 0;
+        Self::walk_brackets(bytes, |index| {
+            chunk_end = index;
+            return true;
+        });
+        return chunk_end;
+    }
+
+    // Call `on_bracket` with the index of each bracket that is not escaped nor part of some binary data, while it returns true
+    // The walk stops at a \binN whose parameter or data may continue after the end of the bytes
+    #[cfg(feature = "std")]
+    fn walk_brackets(bytes: &[u8], mut on_bracket: impl FnMut(usize) -> bool) {
+        let mut previous_char = b' ';
+        let mut i = 0;
+        while i < bytes.len() {
+            let c = bytes[i];
+            match c {
+                b'{' | b'}' if previous_char != b'\\' && !on_bracket(i) => return,
+                b'\\' if previous_char != b'\\' && bytes[i + 1..].starts_with(b"bin") => {
+                    // The parameter of \binN may continue in the next read
+                    let parameter_end = i + 4 + bytes[i + 4..].iter().take_while(|c| c.is_ascii_digit()).count();
+                    if parameter_end == bytes.len() {
+                        return;
+                    }
+                    match Self::get_binary_range(bytes, i + 1) {
+                        Ok(Some((_, data_end))) => {
+                            i = data_end;
+                            previous_char = b' ';
+                            continue;
+                        }
+                        Ok(None) => {}
+                        Err(_) => return, // The binary data is not entirely read yet
+                    }
+                }
+                _ => {}
+            }
+            previous_char = c;
+            i += 1;
+        }
+    }
+
+    // Scan the src and push its tokens, except the ones of the last slice which is returned
+    // The text between the binary data must be valid UTF-8
+    fn scan_slices<'a>(src: &'a [u8], tokens: &mut Vec<Token<'a>>, matching: ControlWordMatching, limits: &mut TokenLimits) -> Result<&'a str, LexerError> {
+        let mut cursor = ScanCursor::default();
+        while Self::scan_next_slice(src, &mut cursor, tokens, matching, limits)? {}
+        return Ok(core::str::from_utf8(&src[cursor.slice_start_index..cursor.current_index])?);
+    }
+
+    // Scan the src from the cursor until some tokens are pushed. Returns false at the end of the src, the last slice being left
+    fn scan_next_slice<'a>(src: &'a [u8], cursor: &mut ScanCursor, tokens: &mut Vec<Token<'a>>, matching: ControlWordMatching, limits: &mut TokenLimits) -> Result<bool, LexerError> {
+        let ScanCursor {
+            mut i,
+            mut slice_start_index,
+            mut current_index,
+            mut previous_char,
+        } = *cursor;
+        let tokens_length = tokens.len();
 
         // This is faster than using an iterator
         let len = src.len();
-        let bytes = src.as_bytes();
-        let mut i = 0;
-        while i < len {
+        let bytes = src;
+        while i < len && tokens.len() == tokens_length {
             let c = bytes[i] as char;
             i += 1;
 
+            // The hexadecimal blobs written by Word can weigh several MB : the group is read as one binary token instead of char by char
+            if c == '{' && previous_char != '\\' {
+                if let Some((name_end, data_start, group_end)) = Self::get_blob_range(src, i) {
+                    if slice_start_index < current_index {
+                        let slice = core::str::from_utf8(&src[slice_start_index..current_index])?;
+                        Self::push_slice_tokens(tokens, slice, matching, limits)?;
+                    }
+                    let name = core::str::from_utf8(&src[i + 2..name_end])?;
+                    let mut blob_tokens = vec![Token::OpeningBracket, Token::IgnorableDestination, Token::ControlSymbol((ControlWord::from_name(name), Property::None))];
+                    if data_start < group_end {
+                        blob_tokens.push(Token::Binary(&bytes[data_start..group_end]));
+                    }
+                    blob_tokens.push(Token::ClosingBracket);
+                    Self::push_tokens(tokens, &blob_tokens, &src[current_index..group_end + 1], limits)?;
+                    i = group_end + 1;
+                    current_index = i;
+                    slice_start_index = i;
+                    previous_char = '}';
+                    continue;
+                }
+            }
             match c {
                 // TODO: Handle char over code 127 for escaped chars
                 // Handle Escaped chars : "\" + any charcode below 127
-                '{' | '}' | '\\' | '\n' if previous_char == '\\' => {}
-                '{' | '}' | '\\' | '\n' => {
-                    // End of slice chars
+                '{' | '}' | '\\' | '\n' | '\r' if previous_char == '\\' => {}
+                // Binary data : the N bytes following \binN must not be interpreted as RTF
+                '\\' if bytes[i..].starts_with(b"bin") => {
+                    if let Some((data_start, data_end)) = Self::get_binary_range(src, i)? {
+                        if slice_start_index < current_index {
+                            let slice = core::str::from_utf8(&src[slice_start_index..current_index])?;
+                            Self::push_slice_tokens(tokens, slice, matching, limits)?;
+                        }
+                        Self::push_tokens(tokens, &[Token::Binary(&bytes[data_start..data_end])], &bytes[data_start..data_end], limits)?;
+                        i = data_end;
+                        current_index = data_end;
+                        slice_start_index = data_end;
+                        previous_char = ' ';
+                        continue;
+                    }
                     if slice_start_index < current_index {
-                        // Close slice
-                        let slice = &src[slice_start_index..current_index];
-                        // Get the corresponding token(s)
-                        let slice_tokens = Self::tokenize(slice)?;
-                        tokens.extend_from_slice(&slice_tokens.as_slice());
+                        let slice = core::str::from_utf8(&src[slice_start_index..current_index])?;
+                        Self::push_slice_tokens(tokens, slice, matching, limits)?;
                         slice_start_index = current_index;
                     }
                 }
+                // End of slice chars : the CR of the documents written on Windows or classic Mac OS end a slice like the LF
+                '{' | '}' | '\\' | '\n' | '\r' if slice_start_index < current_index => {
+                    // Close slice
+                    let slice = core::str::from_utf8(&src[slice_start_index..current_index])?;
+                    // Get the corresponding token(s)
+                    Self::push_slice_tokens(tokens, slice, matching, limits)?;
+                    slice_start_index = current_index;
+                }
                 // Others chars
                 _ => {}
             }
             current_index += 1;
             previous_char = c;
         }
-        // Manage last token (should always be "}")
-        if slice_start_index < current_index {
-            let slice = &src[slice_start_index..current_index];
-            if slice != "}" {
-                return Err(LexerError::InvalidLastChar);
+        *cursor = ScanCursor {
+            i,
+            slice_start_index,
+            current_index,
+            previous_char,
+        };
+        return Ok(tokens.len() > tokens_length);
+    }
+
+    // Push the tokens of a slice of the source
+    fn push_tokens<'a>(tokens: &mut Vec<Token<'a>>, new_tokens: &[Token<'a>], slice: &[u8], limits: &mut TokenLimits) -> Result<(), LexerError> {
+        limits.check(new_tokens)?;
+        if let Some(recorder) = limits.spans.as_mut() {
+            recorder.record(slice, new_tokens);
+        }
+        tokens.extend_from_slice(new_tokens);
+        return Ok(());
+    }
+
+    // Tokenize a slice of the source and push its tokens, without allocating them apart
+    fn push_slice_tokens<'a>(tokens: &mut Vec<Token<'a>>, slice: &'a str, matching: ControlWordMatching, limits: &mut TokenLimits) -> Result<(), LexerError> {
+        let start = tokens.len();
+        Self::tokenize_into(slice, matching, tokens)?;
+        if let Err(error) = limits.check(&tokens[start..]) {
+            tokens.truncate(start);
+            return Err(error);
+        }
+        if let Some(recorder) = limits.spans.as_mut() {
+            recorder.record(slice.as_bytes(), &tokens[start..]);
+        }
+        return Ok(());
+    }
+
+    /// Get the range of the data of a blob group ({\*\themedata ...}), `index` being the index following its opening bracket
+    /// Returns the end of the destination name, the start of the data and the index of the closing bracket, or None if it's not a blob or it's not closed
+    fn get_blob_range(bytes: &[u8], index: usize) -> Option<(usize, usize, usize)> {
+        let name_start = index + 2;
+        if !bytes[index..].starts_with(br"\*") {
+            return None;
+        }
+        let name = BLOB_DESTINATIONS
+            .iter()
+            .find(|name| bytes[name_start..].starts_with(name.as_bytes()) && !bytes.get(name_start + name.len()).is_some_and(u8::is_ascii_alphanumeric))?;
+        let name_end = name_start + name.len();
+        let data_start = if bytes.get(name_end) == Some(&b' ') { name_end + 1 } else { name_end };
+        let mut depth = 0;
+        let mut i = data_start;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1, // Escaped char
+                b'{' => depth += 1,
+                b'}' if depth == 0 => return Some((name_end, data_start, i)),
+                b'}' => depth -= 1,
+                _ => {}
             }
-            tokens.push(Token::ClosingBracket);
+            i += 1;
         }
-        return Ok(tokens);
+        return None;
+    }
+
+    /// Get the byte range of the data following the \binN control word, `index` being the index of "bin" in `src`
+    /// Returns None if the control word is not \binN (ex: \bin without parameter)
+    pub(crate) fn get_binary_range(bytes: &[u8], index: usize) -> Result<Option<(usize, usize)>, LexerError> {
+        let parameter_start = index + 3;
+        let parameter_end = parameter_start + bytes[parameter_start..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if parameter_start == parameter_end {
+            return Ok(None);
+        }
+        let length: usize = core::str::from_utf8(&bytes[parameter_start..parameter_end])?.parse()?;
+        // A space delimiting the control word is not part of the data
+        let data_start = if bytes.get(parameter_end) == Some(&b' ') { parameter_end + 1 } else { parameter_end };
+        let data_end = data_start.saturating_add(length);
+        if data_end > bytes.len() {
+            return Err(LexerError::Error(format!("\\bin{length} exceeds the end of the document")));
+        }
+        return Ok(Some((data_start, data_end)));
+    }
+
+    // Tokenize the text following a control word. Unlike the other slices of text, a tail made only of spaces or tabs is kept :
+    // it's the text between the control word and the next bracket or control word (ex: "\b  {" is a bold space).
+    // The line breaks of the source are not text. Returns the tail left to tokenize.
+    fn tokenize_tail<'a>(tail: &'a str, ret: &mut Vec<Token<'a>>) -> Option<&'a str> {
+        let spaces = tail.trim_end_matches(['\r', '\n']);
+        if !spaces.is_empty() && spaces.bytes().all(|c| c == b' ' || c == b'\t') {
+            ret.push(Token::PlainText(spaces));
+            return None;
+        }
+        return Some(tail);
     }
 
     /// Get a string slice cut but the scanner and return the coreesponding token(s)
-    fn tokenize(slice: &str) -> Result<Vec<Token>, LexerError> {
+    fn tokenize(slice: &str, matching: ControlWordMatching) -> Result<Vec<Token<'_>>, LexerError> {
+        let mut tokens = vec![];
+        Self::tokenize_into(slice, matching, &mut tokens)?;
+        return Ok(tokens);
+    }
+
+    // Push the tokens of the slice
+    fn tokenize_into<'a>(slice: &'a str, matching: ControlWordMatching, tokens: &mut Vec<Token<'a>>) -> Result<(), LexerError> {
+        let mut tail = Self::tokenize_head(slice, matching, tokens)?;
+        // The tail is read in a loop rather than recursively, so a long chain of escapes can't overflow the stack.
+        // An invalid tail is ignored, the tokens preceding it are kept.
+        while let Some(slice) = tail.filter(|tail| !tail.is_empty()) {
+            tail = Self::tokenize_head(slice, matching, tokens).unwrap_or(None);
+        }
+        return Ok(());
+    }
+
+    // Push the token(s) at the start of the slice and return the tail left to tokenize
+    fn tokenize_head<'a>(slice: &'a str, matching: ControlWordMatching, ret: &mut Vec<Token<'a>>) -> Result<Option<&'a str>, LexerError> {
+        let mut slice = slice;
+        // Ignore the CRLF if it's not escaped
+        while slice.trim_start_matches(' ').starts_with(['\r', '\n']) && slice.trim_matches(' ').len() > 1 {
+            slice = &slice[1..];
+        }
         let mut starting_chars = slice.trim_matches(' ').chars().take(2);
         return match (starting_chars.next(), starting_chars.next()) {
             // If it starts with \ : escaped text or control word
@@ -97,61 +876,67 @@ impl Lexer {
                 '{' | '}' | '\\' => {
                     // Handle escaped chars
                     let tail = slice.get(1..).unwrap_or("");
-                    return Ok(vec![Token::PlainText(tail)]); // No recursive tokenize here, juste some plain text because the char is escaped
+                    ret.push(Token::PlainText(tail)); // No tail to tokenize here, juste some plain text because the char is escaped
+                    return Ok(None);
                 }
                 '\'' => {
                     // Escaped unicode in hex value : \'f0
                     let tail = slice.get(1..).unwrap_or("");
-                    if tail.len() < 2 {
+                    let Some(hex) = tail.get(1..3) else {
                         return Err(LexerError::InvalidUnicode(tail.into()));
-                    }
-                    let byte = u8::from_str_radix(&tail[1..3], 16)?; // f0
-                    let mut ret = vec![Token::ControlSymbol((ControlWord::Unicode, Property::Value(byte as i32)))];
-                    recursive_tokenize!(&tail[3..], ret);
-                    return Ok(ret);
+                    };
+                    let byte = u8::from_str_radix(hex, 16)?; // f0
+                    ret.push(Token::ControlSymbol((ControlWord::HexByte, Property::Value(byte as i32))));
+                    // \'hh has no delimiter : all the spaces following it are text
+                    return Ok(Self::tokenize_tail(&tail[3..], ret));
                 }
                 '\n' => {
                     // CRLF
-                    let mut ret = vec![Token::CRLF];
-                    if let Some(tail) = slice.get(2..) {
-                        recursive_tokenize!(tail, ret);
-                    }
-                    return Ok(ret);
+                    ret.push(Token::CRLF);
+                    return Ok(slice.get(2..));
                 }
-                'a'..='z' => {
+                '\r' => {
+                    // An escaped CR or CRLF is the same line break as an escaped LF
+                    ret.push(Token::CRLF);
+                    return Ok(slice.get(2..).map(|tail| tail.strip_prefix('\n').unwrap_or(tail)));
+                }
+                c if c.is_ascii_alphabetic() => {
                     // Identify control word
                     // ex: parse "\b Words in bold" -> (Token::ControlWord(ControlWord::Bold), Token::ControlWordArgument("Words in bold")
-                    let (mut ident, tail) = slice.split_first_whitespace();
-                    // if ident end with semicolon, strip it for correct value parsing
-                    ident = if ident.chars().last().unwrap_or(' ') == ';' { &ident[0..ident.len() - 1] } else { ident };
-                    let control_word = ControlWord::from(ident)?;
-                    let mut ret = vec![Token::ControlSymbol(control_word)];
-                    recursive_tokenize!(tail, ret);
-
-                    // \u1234 \u1234 is ok, but \u1234  \u1234 is lost a space, \u1234   \u1234 lost two spaces, and so on
-                    // \u1234  1 -> No need to walk in here, it will enter plain text
-                    if control_word.0 == ControlWord::Unicode && tail.len() > 0 && tail.trim() == "" {
-                        ret.push(Token::PlainText(tail));
-                    }
-                    return Ok(ret);
+                    let (ident, tail) = ControlWord::split(slice.trim_start_matches(' '), matching);
+                    let control_word = ControlWord::from_with_matching(ident, matching)?;
+                    ret.push(Token::ControlSymbol(control_word));
+                    // The space delimiting the control word is removed by split, the next ones are text
+                    return Ok(Self::tokenize_tail(tail, ret));
+                }
+                '~' | '-' | '_' => {
+                    ret.push(Token::ControlSymbol((ControlWord::from_name(&slice[..2]), Property::None)));
+                    return Ok(Self::tokenize_tail(&slice[2..], ret));
                 }
-                '*' => Ok(vec![Token::IgnorableDestination]),
-                _ => Ok(vec![]),
+                '*' => {
+                    ret.push(Token::IgnorableDestination);
+                    return Ok(None);
+                }
+                // The other control symbols are ignored, not the text following them
+                c => Ok(Self::tokenize_tail(&slice[1 + c.len_utf8()..], ret)),
             },
-            (Some('\n'), Some(_)) => recursive_tokenize!(&slice[1..]), // Ignore the CRLF if it's not escaped
             // Handle brackets
-            (Some('{'), None) => Ok(vec![Token::OpeningBracket]),
-            (Some('}'), None) => Ok(vec![Token::ClosingBracket]),
-            (Some('{'), Some(_)) => recursive_tokenize_with_init!(Token::OpeningBracket, &slice[1..]),
-            (Some('}'), Some(_)) => recursive_tokenize_with_init!(Token::ClosingBracket, &slice[1..]),
+            (Some('{'), tail) => {
+                ret.push(Token::OpeningBracket);
+                return Ok(tail.and(Some(&slice[1..])));
+            }
+            (Some('}'), tail) => {
+                ret.push(Token::ClosingBracket);
+                return Ok(tail.and(Some(&slice[1..])));
+            }
             (None, None) => Err(LexerError::Error(format!("Empty token {}", &slice))),
             // Else, it's plain text
             _ => {
                 let text = slice.trim();
-                if text == "" {
-                    return Ok(vec![]);
+                if !text.is_empty() {
+                    ret.push(Token::PlainText(slice));
                 }
-                return Ok(vec![Token::PlainText(slice)]);
+                return Ok(None);
             }
         };
     }
@@ -159,15 +944,20 @@ impl Lexer {
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use crate::lexer::Lexer;
-    use crate::tokens::ControlWord::{Ansi, Bold, ColorBlue, ColorNumber, ColorRed, FontNumber, FontSize, FontTable, Italic, Par, Pard, Rtf, Underline, Unicode, Unknown};
+    use crate::lexer::{Lexer, SourceSpan};
+    use crate::tokens::ControlWord::{
+        Ansi, Bold, ColorBlue, ColorNumber, ColorRed, DataStore, ExpandedColorTable, FontNumber, FontSize, FontTable, HexByte, Italic, NonBreakingSpace, OptionalHyphen, Par, Pard, Picture, Rtf,
+        ThemeData, Underline, Unicode, Unknown,
+    };
+    use crate::tokens::ControlWordMatching;
+    #[cfg(feature = "std")]
+    use crate::tokens::OwnedToken;
     use crate::tokens::Property::*;
     use crate::tokens::Token::*;
-    use crate::tokens::{ControlWord, Property};
 
     #[test]
     fn simple_tokenize_test() {
-        let tokens = Lexer::tokenize(r"\b Words in bold").unwrap();
+        let tokens = Lexer::tokenize(r"\b Words in bold", ControlWordMatching::Strict).unwrap();
         assert_eq!(tokens, vec![ControlSymbol((Bold, None)), PlainText("Words in bold"),]);
     }
 
@@ -240,7 +1030,7 @@ if (a == b) \{\
         let tokens = Lexer::scan(text);
         assert_eq!(
             tokens.unwrap(),
-            vec![OpeningBracket, IgnorableDestination, ControlSymbol((Unknown(r"\expandedcolortbl;"), None)), ClosingBracket,]
+            vec![OpeningBracket, IgnorableDestination, ControlSymbol((ExpandedColorTable, None)), PlainText(";;"), ClosingBracket,]
         )
     }
 
@@ -250,7 +1040,7 @@ if (a == b) \{\
         let tokens = Lexer::scan(text);
         assert_eq!(
             tokens.unwrap(),
-            vec![OpeningBracket, ControlSymbol((ColorRed, Value(255))), ControlSymbol((ColorBlue, Value(255))), ClosingBracket]
+            vec![OpeningBracket, ControlSymbol((ColorRed, Value(255))), ControlSymbol((ColorBlue, Value(255))), PlainText(";"), ClosingBracket]
         );
     }
 
@@ -302,13 +1092,257 @@ if (a == b) \{\
         )
     }
 
+    #[test]
+    fn control_word_delimiters() {
+        // Only the first space after a control word is its delimiter, the \'hh have no delimiter, the line breaks are never text
+        #[rustfmt::skip]
+        let cases = [
+            ("{\\b text}",         vec![ControlSymbol((Bold, None)), PlainText("text")]),
+            ("{\\b  text}",        vec![ControlSymbol((Bold, None)), PlainText(" text")]),
+            ("{\\b   text}",       vec![ControlSymbol((Bold, None)), PlainText("  text")]),
+            ("{\\b }",             vec![ControlSymbol((Bold, None))]),
+            ("{\\b  }",            vec![ControlSymbol((Bold, None)), PlainText(" ")]),
+            ("{\\b   {}}",         vec![ControlSymbol((Bold, None)), PlainText("  "), OpeningBracket, ClosingBracket]),
+            ("{\\b  \\i x}",       vec![ControlSymbol((Bold, None)), PlainText(" "), ControlSymbol((Italic, None)), PlainText("x")]),
+            ("{\\b\ttext}",        vec![ControlSymbol((Bold, None)), PlainText("\ttext")]),
+            ("{\\b \t}",           vec![ControlSymbol((Bold, None)), PlainText("\t")]),
+            ("{\\fs24  x}",        vec![ControlSymbol((FontSize, Value(24))), PlainText(" x")]),
+            ("{\\fs-10  }",        vec![ControlSymbol((FontSize, Value(-10))), PlainText(" ")]),
+            ("{\\b;}",             vec![ControlSymbol((Bold, None)), PlainText(";")]),
+            ("{\\b \r\n}",         vec![ControlSymbol((Bold, None))]),
+            ("{\\b  \r\n}",        vec![ControlSymbol((Bold, None)), PlainText(" ")]),
+            ("{\\b\n text}",       vec![ControlSymbol((Bold, None)), PlainText(" text")]),
+            ("{\\'e9 x}",          vec![ControlSymbol((HexByte, Value(0xe9))), PlainText(" x")]),
+            ("{\\'e9 }",           vec![ControlSymbol((HexByte, Value(0xe9))), PlainText(" ")]),
+            ("{\\~ x}",            vec![ControlSymbol((NonBreakingSpace, None)), PlainText(" x")]),
+            ("{\\-x}",             vec![ControlSymbol((OptionalHyphen, None)), PlainText("x")]),
+            ("{\\|x}",             vec![PlainText("x")]),
+            ("{\\u233  }",         vec![ControlSymbol((Unicode, Value(233))), PlainText(" ")]),
+            ("{\\u233   \\u233 x}", vec![ControlSymbol((Unicode, Value(233))), PlainText("  "), ControlSymbol((Unicode, Value(233))), PlainText("x")]),
+        ];
+        for (rtf, expected) in cases {
+            let tokens = Lexer::scan(rtf).unwrap();
+            assert_eq!(tokens[1..tokens.len() - 1], expected, "{rtf:?}");
+        }
+    }
+
+    #[test]
+    fn scan_generator_quirks() {
+        // Punctuation or text right after the parameter, ';' delimiter kept as text, double space and CR after a control word
+        let rtf = "{\\b0,\\f0Helvetica\\blue0;\\i  text\\par\r\n}";
+        assert_eq!(
+            Lexer::scan(rtf).unwrap(),
+            vec![
+                OpeningBracket,
+                ControlSymbol((Bold, Value(0))),
+                PlainText(","),
+                ControlSymbol((FontNumber, Value(0))),
+                PlainText("Helvetica"),
+                ControlSymbol((ColorBlue, Value(0))),
+                PlainText(";"),
+                ControlSymbol((Italic, None)),
+                PlainText(" text"),
+                ControlSymbol((Par, None)),
+                ClosingBracket,
+            ]
+        );
+        // Uppercase names and + signs are only read in lenient mode
+        let rtf = r"{\PAR\B Bold\fs+24 Big}";
+        assert_eq!(
+            Lexer::scan(rtf).unwrap(),
+            vec![
+                OpeningBracket,
+                ControlSymbol((Unknown(r"\PAR"), None)),
+                ControlSymbol((Unknown(r"\B"), None)),
+                PlainText("Bold"),
+                ControlSymbol((FontSize, None)),
+                PlainText("+24 Big"),
+                ClosingBracket
+            ]
+        );
+        assert_eq!(
+            Lexer::scan_with_matching(rtf, ControlWordMatching::Lenient).unwrap(),
+            vec![
+                OpeningBracket,
+                ControlSymbol((Par, None)),
+                ControlSymbol((Bold, None)),
+                PlainText("Bold"),
+                ControlSymbol((FontSize, Value(24))),
+                PlainText("Big"),
+                ClosingBracket
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_binary_data() {
+        let rtf = r"{\pict\bin6 {}\b\}\par}";
+        let tokens = Lexer::scan(rtf).unwrap();
+        assert_eq!(
+            tokens,
+            [OpeningBracket, ControlSymbol((Picture, None)), Binary(b"{}\\b\\}"), ControlSymbol((Par, None)), ClosingBracket]
+        );
+        assert!(Lexer::scan(r"{\bin100 abc}").is_err());
+        // The data is not valid UTF-8
+        let rtf = b"{\\pict\\bin4 \xff\x80\xe9\x00\\par}";
+        let tokens = Lexer::scan_bytes(rtf).unwrap();
+        assert_eq!(
+            tokens,
+            [OpeningBracket, ControlSymbol((Picture, None)), Binary(b"\xff\x80\xe9\x00"), ControlSymbol((Par, None)), ClosingBracket]
+        );
+        // Outside of the binary data, the document must be valid UTF-8
+        assert!(Lexer::scan_bytes(b"{\\pict\\bin1 \xff\xff}").is_err());
+    }
+
+    #[test]
+    fn scan_blob_destinations() {
+        let rtf = "{\\rtf1{\\*\\themedata 504b\n0304}{\\*\\datastore}{\\*\\themedatax 01} Text}";
+        let (tokens, spans) = Lexer::scan_with_spans(rtf).unwrap();
+        assert_eq!(
+            tokens[2..7],
+            [OpeningBracket, IgnorableDestination, ControlSymbol((ThemeData, None)), Binary(b"504b\n0304"), ClosingBracket]
+        );
+        assert_eq!(tokens[7..11], [OpeningBracket, IgnorableDestination, ControlSymbol((DataStore, None)), ClosingBracket]);
+        // Not a blob destination
+        assert_eq!(tokens[13..15], [ControlSymbol((Unknown(r"\themedatax"), None)), PlainText("01")]);
+        assert_eq!(&rtf[spans[5].start..spans[5].end], "504b\n0304");
+        assert_eq!(crate::lexer::tokens_to_bytes(&tokens), rtf.as_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn scan_reader_by_chunks() {
+        let rtf = "{\\rtf1\\ansi{\\fonttbl\\f0\\fswiss Helvetica;}\\f0 Voici du texte en {\\b gras} : {\\pict\\bin5 {}\\{\\} et des accents éèà {\\i italique}.\\par}";
+        let expected: Vec<OwnedToken> = Lexer::scan(rtf).unwrap().iter().map(OwnedToken::from).collect();
+        // Small reads split the control words, the binary data and the multi-bytes chars
+        for capacity in [1, 3, 7, 64] {
+            let reader = std::io::BufReader::with_capacity(capacity, rtf.as_bytes());
+            let tokens = Lexer::scan_reader(reader).unwrap();
+            assert_eq!(tokens, expected);
+        }
+        // The binary data is not valid UTF-8
+        let rtf = b"{\\pict\\bin4 \xff\x80\xe9\x00\\par}";
+        let expected: Vec<OwnedToken> = Lexer::scan_bytes(rtf).unwrap().iter().map(OwnedToken::from).collect();
+        for capacity in [1, 5, 64] {
+            let reader = std::io::BufReader::with_capacity(capacity, &rtf[..]);
+            assert_eq!(Lexer::scan_reader(reader).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn scan_in_parallel() {
+        let rtf = crate::include_test_file!("file-sample_500kB.rtf");
+        let with_binary = r"{\rtf1 {\b bold} {\pict\bin8 }{}\{\}{} text}{\i italic} \{escaped\} end}";
+        for rtf in [rtf, with_binary] {
+            let tokens = Lexer::scan(rtf).unwrap();
+            for chunk_count in [2, 3, 16] {
+                assert_eq!(Lexer::scan_in_chunks(rtf, chunk_count).unwrap(), tokens);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn balance_parallel_chunks() {
+        let rtf = format!("{{{}}}", r"{\b bold} \{escaped\} text ".repeat(10_000));
+        let with_binary = format!("{{{}}}", r"{\pict\bin8 }{}\{\}{} text}".repeat(10_000));
+        for text in [rtf.as_str(), with_binary.as_str()] {
+            let boundaries = Lexer::get_chunk_boundaries(text.as_bytes(), 4);
+            assert_eq!(boundaries.len(), 5);
+            for bounds in boundaries.windows(2) {
+                assert!((bounds[1] - bounds[0]).abs_diff(text.len() / 4) < 1000);
+            }
+        }
+    }
+
     #[test]
     fn should_handle_escaped_char() {
         let rtf = r"{je suis une b\'eate}"; // ê = 0xea = 234
         let tokens = Lexer::scan(rtf).unwrap();
         assert_eq!(
             tokens,
-            [OpeningBracket, PlainText("je suis une b"), ControlSymbol((Unicode, Value(234))), PlainText("te"), ClosingBracket,]
+            [OpeningBracket, PlainText("je suis une b"), ControlSymbol((HexByte, Value(234))), PlainText("te"), ClosingBracket,]
         );
     }
+
+    #[test]
+    fn scan_token_spans() {
+        let rtf = "  {\\rtf1 {\\*\\pict\\bin3 a}b} \\b0 text\\'ea\\\n}";
+        let (tokens, spans) = Lexer::scan_with_spans(rtf).unwrap();
+        assert_eq!(tokens.len(), spans.len());
+        let sources: Vec<&str> = spans.iter().map(|span| &rtf[span.start..span.end]).collect();
+        assert_eq!(sources, ["{", "\\rtf1 ", "{", "\\*", "\\pict", "a}b", "}", "\\b0 ", "text", "\\'ea", "\\\n", "}"]);
+        // The lazy lexer gives the same spans
+        let spanned: Vec<_> = Lexer::new(rtf).spanned().collect::<Result<_, _>>().unwrap();
+        assert_eq!(spanned, tokens.into_iter().zip(spans).collect::<Vec<_>>());
+        let mut lexer = Lexer::new(rtf);
+        assert_eq!(lexer.next_with_span().unwrap().unwrap(), (OpeningBracket, SourceSpan::default()));
+        // An unfinished document
+        let options = crate::options::ParserOptions {
+            recover_unclosed_groups: true,
+            ..Default::default()
+        };
+        let rtf = r"{\rtf1 {\b bold} te";
+        let (tokens, spans) = Lexer::scan_with_spans_and_options(rtf, &options).unwrap();
+        assert_eq!(tokens.last(), Some(&PlainText(" te")));
+        assert_eq!(spans.last(), Some(&SourceSpan { start: 16, end: 19 }));
+        let spanned: Vec<_> = Lexer::new(rtf).with_options(&options).spanned().collect::<Result<_, _>>().unwrap();
+        assert_eq!(spanned, tokens.into_iter().zip(spans).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn tokenize_chained_escapes() {
+        // Tokenized one after the other, without overflowing the stack
+        let slice = "\\\n".repeat(100_000);
+        let tokens = Lexer::tokenize(&slice, ControlWordMatching::Strict).unwrap();
+        assert_eq!(tokens.len(), 100_000);
+        assert!(tokens.iter().all(|token| *token == CRLF));
+        let slice = "}".repeat(100_000) + "text";
+        let tokens = Lexer::tokenize(&slice, ControlWordMatching::Strict).unwrap();
+        assert_eq!(tokens.len(), 100_001);
+        assert_eq!(tokens.last(), Some(&PlainText("text")));
+    }
+
+    #[test]
+    fn scan_lazily() {
+        let rtf = "  {\\rtf1 {\\*\\pict\\bin3 a}b}{\\*\\themedata 504b}\\b0 text\\'ea\\\n}";
+        let mut lexer = Lexer::new(rtf);
+        assert_eq!(lexer.peek().unwrap().unwrap(), &OpeningBracket);
+        assert_eq!(lexer.next().unwrap().unwrap(), OpeningBracket);
+        assert_eq!(lexer.peek().unwrap().unwrap(), &ControlSymbol((Rtf, Value(1))));
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
+        assert_eq!(tokens, Lexer::scan(rtf).unwrap()[1..]);
+        // The tokens before the error are returned
+        let mut lexer = Lexer::new(r"{\rtf1\b text");
+        assert_eq!(lexer.by_ref().take_while(Result::is_ok).count(), 2);
+        assert!(lexer.next().is_none());
+        let mut lexer = Lexer::new(r"{\rtf1 {{{deep}}}}").with_options(&crate::options::ParserOptions {
+            max_nesting_depth: 2,
+            ..crate::options::ParserOptions::default()
+        });
+        assert!(lexer.any(|token| matches!(token, Err(crate::lexer::LexerError::LimitExceeded(_)))));
+    }
+
+    #[test]
+    fn tokens_to_rtf() {
+        let rtf = r"{\rtf1 {\*\pict\bin3 a}b}\b0 text\'ea\u-3 ?\{\}\i  more\
+}";
+        let tokens = Lexer::scan(rtf).unwrap();
+        assert_eq!(crate::lexer::tokens_to_bytes(&tokens), b"{\\rtf1{\\*\\pict \\bin3 a}b}\\b0 text\\'ea\\u-3 ?\\{\\}\\i  more\\\n}");
+        assert_eq!(ControlSymbol((FontSize, Value(24))).to_string(), r"\fs24");
+        for rtf in [rtf, crate::include_test_file!("test-file.rtf"), crate::include_test_file!("file-sample_500kB.rtf")] {
+            let tokens = Lexer::scan(rtf).unwrap();
+            assert_eq!(Lexer::scan_bytes(&crate::lexer::tokens_to_bytes(&tokens)).unwrap(), tokens);
+        }
+        let tokens = Lexer::scan(rtf).unwrap();
+        assert_eq!(crate::lexer::tokens_to_string(&tokens), "{\\rtf1{\\*\\pict \\bin3 a}b}\\b0 text\\'ea\\u-3 ?\\{\\}\\i  more\\\n}");
+        assert_eq!(Lexer::scan(&crate::lexer::tokens_to_string(&tokens)).unwrap(), tokens);
+        // The binary data which is not UTF-8 is written as is by tokens_to_bytes, and escaped in a string
+        let rtf = b"{\\pict\\bin3 \xff\xd8\x00}";
+        let tokens = Lexer::scan_bytes(rtf).unwrap();
+        assert_eq!(crate::lexer::tokens_to_bytes(&tokens), b"{\\pict \\bin3 \xff\xd8\x00}");
+        assert_eq!(crate::lexer::tokens_to_string(&tokens), "{\\pict \\bin3 \\'ff\\'d8\0}");
+        assert_eq!(tokens[2].to_string(), "\\bin3 \\'ff\\'d8\0");
+    }
 }
@@ -4,20 +4,144 @@
 // Explanations on specification here : https://www.oreilly.com/library/view/rtf-pocket-guide/9781449302047/ch01.html
 
 #![allow(irrefutable_let_patterns)]
+#![allow(clippy::needless_return)]
+// Without the std feature, the library only relies on core + alloc
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+// The cdylib needs a panic handler and a global allocator : without the std feature, they still come from std on the targets having one
+#[cfg(all(not(feature = "std"), not(test), not(target_os = "none")))]
+extern crate std;
 
 // Public API of the crate
+pub mod codepage;
+#[cfg(feature = "parser")]
+pub mod compact;
+#[cfg(feature = "parser")]
+pub mod diff;
+#[cfg(feature = "parser")]
 pub mod document;
+#[cfg(feature = "docx")]
+pub mod docx;
+#[cfg(feature = "parser")]
+pub mod encapsulation;
+pub mod error;
+#[cfg(feature = "parser")]
+pub mod extract;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "parser")]
+pub mod field;
+#[cfg(feature = "parser")]
+pub mod fingerprint;
+#[cfg(feature = "parser")]
+pub mod fragment;
+#[cfg(feature = "parser")]
 pub mod header;
+#[cfg(feature = "render")]
+pub mod html;
+#[cfg(feature = "parser")]
+pub mod image;
+#[cfg(feature = "render")]
+pub mod latex;
+#[cfg(feature = "render")]
+pub mod layout;
 pub mod lexer;
+#[cfg(feature = "parser")]
+pub mod lint;
+#[cfg(feature = "parser")]
+pub mod list;
+#[cfg(feature = "parser")]
+pub mod normalize;
+pub mod options;
+#[cfg(feature = "parser")]
+pub mod outline;
+#[cfg(feature = "parser")]
 pub mod paragraph;
+#[cfg(feature = "parser")]
 pub mod parser;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+#[cfg(feature = "parser")]
+pub mod preserve;
+#[cfg(all(feature = "std", feature = "parser"))]
+pub mod progress;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "parser")]
+pub mod run;
+#[cfg(feature = "parser")]
+pub mod search;
+#[cfg(feature = "parser")]
+pub mod shape;
+// `Arc` requires the atomic operations on pointers
+#[cfg(all(target_has_atomic = "ptr", feature = "parser"))]
+pub mod shared;
+#[cfg(feature = "parser")]
+pub mod table;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod tokens;
+pub mod tree;
+pub mod unicode;
+pub mod units;
 mod utils;
+#[cfg(feature = "parser")]
+pub mod validate;
 
 // Re-export all the symbols to the global rtf-parser namespace
+#[cfg(feature = "parser")]
+pub use diff::*;
+#[cfg(feature = "parser")]
 pub use document::*;
+#[cfg(feature = "parser")]
+pub use encapsulation::*;
+pub use error::*;
+#[cfg(feature = "parser")]
+pub use extract::*;
+#[cfg(feature = "parser")]
+pub use field::*;
+#[cfg(feature = "parser")]
+pub use fragment::*;
+#[cfg(feature = "parser")]
 pub use header::*;
+#[cfg(feature = "parser")]
+pub use image::*;
+#[cfg(feature = "render")]
+pub use layout::*;
 pub use lexer::*;
+#[cfg(feature = "parser")]
+pub use lint::*;
+#[cfg(feature = "parser")]
+pub use list::*;
+pub use options::*;
+#[cfg(feature = "parser")]
+pub use outline::*;
+#[cfg(feature = "parser")]
 pub use paragraph::*;
+#[cfg(feature = "parser")]
 pub use parser::*;
+#[cfg(feature = "parser")]
+pub use preserve::*;
+#[cfg(all(feature = "std", feature = "parser"))]
+pub use progress::*;
+#[cfg(feature = "render")]
+pub use render::*;
+#[cfg(feature = "parser")]
+pub use run::*;
+#[cfg(feature = "parser")]
+pub use search::*;
+#[cfg(feature = "parser")]
+pub use shape::*;
+#[cfg(all(target_has_atomic = "ptr", feature = "parser"))]
+pub use shared::*;
+#[cfg(feature = "parser")]
+pub use table::*;
 pub use tokens::*;
+pub use tree::*;
+pub use unicode::*;
+pub use units::*;
+#[cfg(feature = "parser")]
+pub use validate::*;
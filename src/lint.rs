@@ -0,0 +1,388 @@
+/// Define the linting of a RTF source : its structure, the order of its header, its references and its deprecated control words
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::header::{ColorRef, FontRef, RtfHeader, StyleRef};
+use crate::lexer::{Lexer, SourceSpan};
+use crate::options::ParserOptions;
+use crate::parser::{BodyStart, Parser};
+use crate::tokens::{ControlWord, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintSeverity {
+    /// The document is valid, but could be smaller or cleaner
+    Info,
+    /// The document is read, but some readers may not display it as expected
+    Warning,
+    /// The document is invalid, the readers can reject it
+    Error,
+}
+
+/// The problems found by `lint`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// The source can't be scanned, ex: an invalid \'hh byte
+    InvalidToken(String),
+    /// The document doesn't start with {\rtfN
+    MissingRtfHeader,
+    UnclosedGroup,
+    UnexpectedClosingBracket,
+    /// Content following the closing bracket of the document
+    ContentAfterDocument,
+    /// A table of the header is written before the previous one in the order of the specification (\fonttbl, \filetbl, \colortbl, \stylesheet, \listtable, \listoverridetable)
+    HeaderTableOrder {
+        table: String,
+        after: String,
+    },
+    /// A table of the header is written after the start of the body
+    HeaderTableInBody(String),
+    UndeclaredFont(FontRef),
+    UndeclaredColor(ColorRef),
+    UndeclaredStyle(StyleRef),
+    UndeclaredCharacterStyle(StyleRef),
+    UnusedFont(FontRef),
+    UnusedColor(ColorRef),
+    DeprecatedControlWord {
+        name: String,
+        replacement: Option<&'static str>,
+    },
+}
+
+/// A problem found by `lint`, with its location in the source
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LintDiagnostic {
+    pub severity: LintSeverity,
+    pub kind: LintKind,
+    pub span: SourceSpan,
+}
+
+// The tables of the header, in the order of the specification
+const HEADER_TABLES: [ControlWord<'static>; 6] = [
+    ControlWord::FontTable,
+    ControlWord::FileTable,
+    ControlWord::ColorTable,
+    ControlWord::StyleSheet,
+    ControlWord::ListTable,
+    ControlWord::ListOverrideTable,
+];
+
+// The control words declared obsolete by the specification, and the ones replacing them
+#[rustfmt::skip]
+const DEPRECATED_CONTROL_WORDS: [(&str, Option<&str>); 9] = [
+    // Macintosh Edition Manager (publish and subscribe)
+    (r"\pub",         None),
+    (r"\pubauto",     None),
+    (r"\bkmkpub",     None),
+    (r"\objalias",    None),
+    (r"\objsect",     None),
+    (r"\nextfile",    None),
+    // Side by side paragraphs, written as a table
+    (r"\sbys",        Some(r"\trowd")),
+    (r"\softcol",     None),
+    (r"\softlheight", None),
+];
+
+impl LintKind {
+    #[rustfmt::skip]
+    pub fn severity(&self) -> LintSeverity {
+        return match self {
+            LintKind::InvalidToken(_)
+            | LintKind::MissingRtfHeader
+            | LintKind::UnclosedGroup
+            | LintKind::UnexpectedClosingBracket
+            | LintKind::ContentAfterDocument          => LintSeverity::Error,
+            LintKind::HeaderTableOrder { .. }
+            | LintKind::HeaderTableInBody(_)
+            | LintKind::UndeclaredFont(_)
+            | LintKind::UndeclaredColor(_)
+            | LintKind::UndeclaredStyle(_)
+            | LintKind::UndeclaredCharacterStyle(_)
+            | LintKind::DeprecatedControlWord { .. }  => LintSeverity::Warning,
+            LintKind::UnusedFont(_)
+            | LintKind::UnusedColor(_)                => LintSeverity::Info,
+        };
+    }
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            LintSeverity::Info => write!(f, "info"),
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        };
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            LintKind::InvalidToken(error) => write!(f, "Invalid token : {error}"),
+            LintKind::MissingRtfHeader => write!(f, "The document doesn't start with {{\\rtf1"),
+            LintKind::UnclosedGroup => write!(f, "Unclosed group"),
+            LintKind::UnexpectedClosingBracket => write!(f, "Closing bracket without group"),
+            LintKind::ContentAfterDocument => write!(f, "Content after the end of the document"),
+            LintKind::HeaderTableOrder { table, after } => write!(f, "{table} should be written before {after}"),
+            LintKind::HeaderTableInBody(table) => write!(f, "{table} should be written in the header, before the body"),
+            LintKind::UndeclaredFont(font_ref) => write!(f, "Font {font_ref} is not in the font table"),
+            LintKind::UndeclaredColor(color_ref) => write!(f, "Color {color_ref} is not in the color table"),
+            LintKind::UndeclaredStyle(style_ref) => write!(f, "Style {style_ref} is not in the stylesheet"),
+            LintKind::UndeclaredCharacterStyle(style_ref) => write!(f, "Character style {style_ref} is not in the stylesheet"),
+            LintKind::UnusedFont(font_ref) => write!(f, "Font {font_ref} is never used"),
+            LintKind::UnusedColor(color_ref) => write!(f, "Color {color_ref} is never used"),
+            LintKind::DeprecatedControlWord { name, replacement: Some(replacement) } => write!(f, "{name} is deprecated, use {replacement}"),
+            LintKind::DeprecatedControlWord { name, replacement: None } => write!(f, "{name} is deprecated"),
+        };
+    }
+}
+
+/// Write the diagnostic as `error 12..15 : Closing bracket without group`
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{} {}..{} : {}", self.severity, self.span.start, self.span.end, self.kind);
+    }
+}
+
+impl LintDiagnostic {
+    fn new(kind: LintKind, span: SourceSpan) -> Self {
+        return Self {
+            severity: kind.severity(),
+            kind,
+            span,
+        };
+    }
+}
+
+/// Check a RTF source before sending it, ex: the output of another system, and report its problems sorted by position.
+/// Unlike the parser, which reads most of the invalid documents, it reports the unbalanced groups, the tables of the header out of order,
+/// the fonts, colors and styles used without being declared (or declared without being used) and the deprecated control words.
+pub fn lint(rtf: &str) -> Vec<LintDiagnostic> {
+    let options = ParserOptions {
+        recover_unclosed_groups: true,
+        ..ParserOptions::default()
+    };
+    let (tokens, spans) = match Lexer::scan_with_spans_and_options(rtf, &options) {
+        Ok(tokens_and_spans) => tokens_and_spans,
+        Err(error) => return vec![LintDiagnostic::new(LintKind::InvalidToken(error.to_string()), SourceSpan { start: 0, end: rtf.len() })],
+    };
+    let mut diagnostics = lint_structure(&tokens, &spans);
+    // The references are only checked in the documents whose header can be read
+    if let Ok(header) = Parser::new(tokens.clone()).parse_header_only() {
+        diagnostics.extend(lint_references(&tokens, &spans, &header));
+    }
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.start, diagnostic.span.end));
+    return diagnostics;
+}
+
+// Check the groups, the order of the header and the control words
+fn lint_structure(tokens: &[Token], spans: &[SourceSpan]) -> Vec<LintDiagnostic> {
+    let mut diagnostics = vec![];
+    if !matches!(tokens, [Token::OpeningBracket, Token::ControlSymbol((ControlWord::Rtf, _)), ..]) {
+        diagnostics.push(LintDiagnostic::new(LintKind::MissingRtfHeader, spans.first().copied().unwrap_or_default()));
+    }
+    // The indexes of the opening brackets of the open groups
+    let mut open_groups: Vec<usize> = vec![];
+    let mut is_document_closed = false;
+    let mut body_start = BodyStart::default();
+    let mut is_body_started = false;
+    // The last table of the header, with its index in `HEADER_TABLES`
+    let mut last_table: Option<(usize, ControlWord)> = None;
+    for (index, token) in tokens.iter().enumerate() {
+        let span = spans[index];
+        is_body_started = is_body_started || body_start.is_reached(token);
+        match token {
+            Token::ClosingBracket if open_groups.pop().is_none() => diagnostics.push(LintDiagnostic::new(LintKind::UnexpectedClosingBracket, span)),
+            Token::ClosingBracket => is_document_closed = open_groups.is_empty(),
+            Token::PlainText(text) if is_document_closed && text.trim().is_empty() => {}
+            // The whole content following the document is reported once
+            _ if is_document_closed => {
+                let end = spans.last().map_or(span.end, |span| span.end);
+                diagnostics.push(LintDiagnostic::new(LintKind::ContentAfterDocument, SourceSpan { start: span.start, end }));
+                break;
+            }
+            Token::OpeningBracket => {
+                // A table of the header is a group of the document, its control word can follow a \*
+                if let (1, Some(table)) = (open_groups.len(), get_header_table(&tokens[index + 1..])) {
+                    let table_index = HEADER_TABLES.iter().position(|control_word| *control_word == table).unwrap_or_default();
+                    if is_body_started {
+                        diagnostics.push(LintDiagnostic::new(LintKind::HeaderTableInBody(table.name().to_string()), span));
+                    } else if let Some((_, last_table)) = last_table.filter(|(last_index, _)| *last_index > table_index) {
+                        let kind = LintKind::HeaderTableOrder {
+                            table: table.name().to_string(),
+                            after: last_table.name().to_string(),
+                        };
+                        diagnostics.push(LintDiagnostic::new(kind, span));
+                    }
+                    last_table = Some((table_index, table));
+                }
+                open_groups.push(index);
+            }
+            Token::ControlSymbol((control_word, _)) => {
+                if let Some((name, replacement)) = DEPRECATED_CONTROL_WORDS.iter().find(|(name, _)| *name == control_word.name()) {
+                    let kind = LintKind::DeprecatedControlWord {
+                        name: name.to_string(),
+                        replacement: *replacement,
+                    };
+                    diagnostics.push(LintDiagnostic::new(kind, span));
+                }
+            }
+            _ => {}
+        }
+    }
+    diagnostics.extend(open_groups.into_iter().map(|index| LintDiagnostic::new(LintKind::UnclosedGroup, spans[index])));
+    return diagnostics;
+}
+
+// Get the table of the header starting a group, `tokens` following its opening bracket
+fn get_header_table<'a>(tokens: &[Token<'a>]) -> Option<ControlWord<'a>> {
+    return tokens.iter().find(|token| !matches!(token, Token::CRLF | Token::IgnorableDestination)).and_then(|token| match token {
+        Token::ControlSymbol((control_word, _)) if HEADER_TABLES.contains(control_word) => Some(*control_word),
+        _ => None,
+    });
+}
+
+// Check the references to the fonts, colors and styles against the tables of the header declaring them
+fn lint_references(tokens: &[Token], spans: &[SourceSpan], header: &RtfHeader) -> Vec<LintDiagnostic> {
+    let mut diagnostics = vec![];
+    let mut used_fonts = BTreeSet::from([header.defaults.font_ref]);
+    let mut used_colors = BTreeSet::new();
+    // The depth of the table of the header declaring the references (\fonttbl, \colortbl, \stylesheet)
+    let mut depth: usize = 0;
+    let mut table: Option<(usize, ControlWord)> = None;
+    for (index, token) in tokens.iter().enumerate() {
+        let Token::ControlSymbol((control_word, property)) = token else {
+            match token {
+                Token::OpeningBracket => {
+                    depth += 1;
+                    if let Some(header_table) = get_header_table(&tokens[index + 1..]).filter(|_| table.is_none()) {
+                        table = Some((depth, header_table));
+                    }
+                }
+                Token::ClosingBracket => {
+                    table = table.filter(|(table_depth, _)| *table_depth < depth);
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+            continue;
+        };
+        let table = table.map(|(_, table)| table);
+        let value = property.get_value();
+        #[rustfmt::skip]
+        let kind = match control_word {
+            ControlWord::FontNumber if table != Some(ControlWord::FontTable) => {
+                let font_ref = value as FontRef;
+                used_fonts.insert(font_ref);
+                let is_default_font = header.font_table.is_empty() && font_ref == header.defaults.font_ref;
+                (!is_default_font && !header.font_table.contains_key(&font_ref)).then_some(LintKind::UndeclaredFont(font_ref))
+            }
+            ControlWord::ColorNumber
+            | ControlWord::UnderlineColor
+            | ControlWord::CellBackgroundColor
+            | ControlWord::BorderColor => {
+                let color_ref = value as ColorRef;
+                used_colors.insert(color_ref);
+                (color_ref != 0 && header.get_color(&color_ref).is_none()).then_some(LintKind::UndeclaredColor(color_ref))
+            }
+            ControlWord::ParStyle if table != Some(ControlWord::StyleSheet) => {
+                let style_ref = value as StyleRef;
+                (style_ref != 0 && !header.stylesheet.contains_key(&style_ref)).then_some(LintKind::UndeclaredStyle(style_ref))
+            }
+            ControlWord::CharStyle if table != Some(ControlWord::StyleSheet) => {
+                let style_ref = value as StyleRef;
+                (!header.character_styles.contains_key(&style_ref)).then_some(LintKind::UndeclaredCharacterStyle(style_ref))
+            }
+            _ => None,
+        };
+        diagnostics.extend(kind.map(|kind| LintDiagnostic::new(kind, spans[index])));
+    }
+    // The unused entries are reported at their table
+    let table_span = |table: ControlWord| {
+        let index = tokens.iter().position(|token| matches!(token, Token::ControlSymbol((control_word, _)) if *control_word == table));
+        return index.map(|index| spans[index]).unwrap_or_default();
+    };
+    let unused_fonts = header.font_table.keys().filter(|font_ref| !used_fonts.contains(*font_ref));
+    diagnostics.extend(unused_fonts.map(|font_ref| LintDiagnostic::new(LintKind::UnusedFont(*font_ref), table_span(ControlWord::FontTable))));
+    // The automatic color is never reported
+    let unused_colors = header.color_table.keys().filter(|color_ref| **color_ref != 0 && !used_colors.contains(*color_ref));
+    diagnostics.extend(unused_colors.map(|color_ref| LintDiagnostic::new(LintKind::UnusedColor(*color_ref), table_span(ControlWord::ColorTable))));
+    return diagnostics;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_kinds(rtf: &str) -> Vec<LintKind> {
+        return lint(rtf).into_iter().map(|diagnostic| diagnostic.kind).collect();
+    }
+
+    #[test]
+    fn lint_valid_document() {
+        let rtf = r"{\rtf1\ansi\deff0{\fonttbl{\f0\fswiss Helvetica;}{\f1 Times;}}{\colortbl;\red255\green0\blue0;}{\stylesheet{\s1\f1 heading 1;}}
+\pard\s1\cf1 Title\par\pard Text\par}";
+        assert!(lint(rtf).is_empty());
+    }
+
+    #[test]
+    fn lint_structure_errors() {
+        let rtf = r"{\rtf1 {\b bold}} {\i more}}";
+        let diagnostics = lint(rtf);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, LintKind::ContentAfterDocument);
+        assert_eq!(&rtf[diagnostics[0].span.start..diagnostics[0].span.end], "{\\i more}}");
+        assert_eq!(diagnostics[0].to_string(), "error 18..28 : Content after the end of the document");
+        let rtf = r"{\rtf1 {\b bold text";
+        let diagnostics = lint(rtf);
+        assert_eq!(diagnostics.iter().map(|diagnostic| diagnostic.span.start).collect::<Vec<_>>(), [0, 7]);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.kind == LintKind::UnclosedGroup && diagnostic.severity == LintSeverity::Error));
+        assert_eq!(get_kinds(r"\rtf1 text}"), [LintKind::MissingRtfHeader, LintKind::UnexpectedClosingBracket]);
+        assert!(matches!(get_kinds(r"{\rtf1 \'zz}")[..], [LintKind::InvalidToken(_)]));
+    }
+
+    #[test]
+    fn lint_header_order() {
+        let rtf = r"{\rtf1\ansi{\colortbl;\red255\green0\blue0;}{\fonttbl{\f0 Arial;}}\f0\cf1 Text{\stylesheet{\s0 Normal;}}}";
+        assert_eq!(
+            get_kinds(rtf),
+            [
+                LintKind::HeaderTableOrder {
+                    table: r"\fonttbl".into(),
+                    after: r"\colortbl".into()
+                },
+                LintKind::HeaderTableInBody(r"\stylesheet".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_declared_references() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0 Arial;}{\f1 Times;}{\f2 Courier;}}{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}{\stylesheet{\s1\f2 heading 1;}{\*\cs10 Strong;}}
+\pard\s1 Title\par\pard\s7\f3\cf2 Text {\cs10 strong}{\cs11\cf5 unknown}\sbys\par}";
+        assert_eq!(
+            get_kinds(rtf),
+            [
+                LintKind::UnusedFont(1),
+                LintKind::UnusedColor(1),
+                LintKind::UndeclaredStyle(7),
+                LintKind::UndeclaredFont(3),
+                LintKind::UndeclaredCharacterStyle(11),
+                LintKind::UndeclaredColor(5),
+                LintKind::DeprecatedControlWord {
+                    name: r"\sbys".into(),
+                    replacement: Some(r"\trowd")
+                },
+            ]
+        );
+        let diagnostic = &lint(rtf)[2];
+        assert_eq!(&rtf[diagnostic.span.start..diagnostic.span.end], r"\s7");
+        assert_eq!(diagnostic.kind.to_string(), "Style 7 is not in the stylesheet");
+        // The default font of a document without font table is not reported
+        assert!(lint(r"{\rtf1\ansi\deff0\f0 Text}").is_empty());
+    }
+}
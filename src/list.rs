@@ -0,0 +1,263 @@
+/// Define the lists (\listtable, \listoverridetable) and the numbering of the list paragraphs
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::header::RtfHeader;
+
+/// The ListId represent the identifier of a list in the ListTable (\listidN)
+pub type ListId = i32;
+pub type ListTable = BTreeMap<ListId, List>;
+
+/// The ListOverrideRef represent the index of a list override in the ListOverrideTable
+/// It's use in the document's body to reference a specific list with the \lsN control word
+pub type ListOverrideRef = u16;
+pub type ListOverrideTable = BTreeMap<ListOverrideRef, ListOverride>;
+
+/// Maximum number of levels of a list
+pub const LIST_LEVEL_COUNT: usize = 9;
+
+#[derive(Hash, Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct List {
+    pub id: ListId,
+    pub levels: Vec<ListLevel>,
+}
+
+#[derive(Hash, Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ListLevel {
+    pub number_format: NumberFormat,
+    pub start_at: i32,
+    /// The template of the label : the chars below \u{9} are placeholders for the number of the corresponding level
+    pub text: String,
+}
+
+/// A reference to a list, that can override the start of its levels
+#[derive(Hash, Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ListOverride {
+    pub list_id: ListId,
+    /// The overridden start of each level, in order (\lfolevel\listoverridestartat\levelstartatN)
+    pub start_at: Vec<Option<i32>>,
+}
+
+/// Numbering format of a list level (\levelnfcN)
+#[derive(Hash, Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum NumberFormat {
+    #[default]
+    Decimal, // 0
+    UpperRoman,         // 1
+    LowerRoman,         // 2
+    UpperLetter,        // 3
+    LowerLetter,        // 4
+    Ordinal,            // 5
+    DecimalLeadingZero, // 22
+    Bullet,             // 23
+    None,               // 255
+}
+
+impl From<i32> for NumberFormat {
+    fn from(value: i32) -> Self {
+        #[rustfmt::skip]
+        return match value {
+            1   => NumberFormat::UpperRoman,
+            2   => NumberFormat::LowerRoman,
+            3   => NumberFormat::UpperLetter,
+            4   => NumberFormat::LowerLetter,
+            5   => NumberFormat::Ordinal,
+            22  => NumberFormat::DecimalLeadingZero,
+            23  => NumberFormat::Bullet,
+            255 => NumberFormat::None,
+            _   => NumberFormat::Decimal,
+        };
+    }
+}
+
+impl NumberFormat {
+    /// Format a number of a list
+    pub fn format(&self, number: i32) -> String {
+        return match self {
+            NumberFormat::Decimal => number.to_string(),
+            NumberFormat::UpperRoman => to_roman(number),
+            NumberFormat::LowerRoman => to_roman(number).to_lowercase(),
+            NumberFormat::UpperLetter => to_letters(number),
+            NumberFormat::LowerLetter => to_letters(number).to_lowercase(),
+            NumberFormat::Ordinal => {
+                let suffix = match (number % 10, number % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("{number}{suffix}")
+            }
+            NumberFormat::DecimalLeadingZero => format!("{number:02}"),
+            NumberFormat::Bullet | NumberFormat::None => String::new(),
+        };
+    }
+}
+
+fn to_roman(mut number: i32) -> String {
+    const NUMERALS: [(i32, &str); 13] = [(1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"), (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I")];
+    if number <= 0 {
+        return number.to_string();
+    }
+    let mut result = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            result.push_str(numeral);
+            number -= value;
+        }
+    }
+    return result;
+}
+
+// 1 -> A, 26 -> Z, 27 -> AA, 28 -> BB (as Word does)
+fn to_letters(number: i32) -> String {
+    if number <= 0 {
+        return number.to_string();
+    }
+    let letter = (b'A' + ((number - 1) % 26) as u8) as char;
+    return letter.to_string().repeat(((number - 1) / 26 + 1) as usize);
+}
+
+// Replace the private use chars of the Symbol and Wingdings fonts used for the bullets
+fn map_bullet_char(c: char) -> char {
+    return match c {
+        '\u{F0B7}' => '•',
+        '\u{F0A7}' => '▪',
+        '\u{F0D8}' => '➢',
+        '\u{F0FC}' => '✓',
+        '\u{F06F}' => 'o',
+        c => c,
+    };
+}
+
+/// Keep track of the counters of each list to compute the labels of the list paragraphs
+#[derive(Debug, Default)]
+pub(crate) struct ListNumbering {
+    counters: BTreeMap<ListOverrideRef, [Option<i32>; LIST_LEVEL_COUNT]>,
+}
+
+impl ListNumbering {
+    /// Increment the counter of the level and return the label of the paragraph ("1.", "a)", "•", "1.2.3", ...)
+    pub fn next_label(&mut self, header: &RtfHeader, list_override_ref: ListOverrideRef, level: u8) -> String {
+        let Some(list_override) = header.list_override_table.get(&list_override_ref) else {
+            return String::new();
+        };
+        let Some(list) = header.list_table.get(&list_override.list_id) else {
+            return String::new();
+        };
+        let level = (level as usize).min(LIST_LEVEL_COUNT - 1);
+        let Some(list_level) = list.levels.get(level) else {
+            return String::new();
+        };
+        let start_at = |level: usize| -> i32 {
+            if let Some(Some(start_at)) = list_override.start_at.get(level) {
+                return *start_at;
+            }
+            return list.levels.get(level).map_or(1, |list_level| list_level.start_at);
+        };
+        let counters = self.counters.entry(list_override_ref).or_default();
+        counters[level] = Some(counters[level].map_or(start_at(level), |counter| counter + 1));
+        // Restart the sub-levels
+        counters[level + 1..].fill(None);
+
+        let mut label = String::new();
+        for c in list_level.text.chars() {
+            let placeholder = c as usize;
+            if placeholder < LIST_LEVEL_COUNT {
+                let number = counters[placeholder].unwrap_or_else(|| start_at(placeholder));
+                let number_format = list.levels.get(placeholder).map_or(NumberFormat::Decimal, |list_level| list_level.number_format);
+                label.push_str(&number_format.format(number));
+            } else {
+                label.push(map_bullet_char(c));
+            }
+        }
+        return label;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::RtfDocument;
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(NumberFormat::UpperRoman.format(1994), "MCMXCIV");
+        assert_eq!(NumberFormat::LowerRoman.format(4), "iv");
+        assert_eq!(NumberFormat::UpperLetter.format(28), "BB");
+        assert_eq!(NumberFormat::LowerLetter.format(3), "c");
+        assert_eq!(NumberFormat::Ordinal.format(12), "12th");
+        assert_eq!(NumberFormat::Ordinal.format(22), "22nd");
+        assert_eq!(NumberFormat::DecimalLeadingZero.format(7), "07");
+    }
+
+    #[test]
+    fn compute_list_labels() {
+        let rtf = r"{\rtf1\ansi
+            {\*\listtable
+                {\list\listtemplateid1
+                    {\listlevel\levelnfc0\levelstartat1{\leveltext\'02\'00.;}{\levelnumbers\'01;}}
+                    {\listlevel\levelnfc4\levelstartat1{\leveltext\'02\'01);}{\levelnumbers\'01;}}
+                    {\listlevel\levelnfc0\levelstartat1{\leveltext\'05\'00.\'01.\'02;}{\levelnumbers\'01\'03\'05;}}
+                \listid10}
+                {\list\listtemplateid2
+                    {\listlevel\levelnfc23\levelstartat1{\leveltext\'01\u-3913 ?;}}
+                \listid20}
+            }
+            {\listoverridetable
+                {\listoverride\listid10\listoverridecount0\ls1}
+                {\listoverride\listid20\listoverridecount0\ls2}
+                {\listoverride\listid10\listoverridecount1{\lfolevel\listoverridestartat\levelstartat5}\ls3}
+            }
+            \pard\ls1\ilvl0 First\par
+            \pard\ls1\ilvl1 Sub\par
+            \pard\ls1\ilvl1 {\b Bold} sub\par
+            \pard\ls1\ilvl2 Deep\par
+            \pard\ls1\ilvl0 Second\par
+            \pard\ls1\ilvl1 Sub again\par
+            \pard\ls2 Bullet\par
+            \pard\ls3 Fifth\par
+            \pard Not in a list\par
+        }";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let labels: Vec<(&str, &str)> = document.body.iter().map(|block| (block.paragraph.list_label.as_str(), block.text.trim())).collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("1.", "First"),
+                ("a)", "Sub"),
+                ("b)", "Bold"),
+                ("b)", "sub"),
+                ("1.b.1", "Deep"),
+                ("2.", "Second"),
+                ("a)", "Sub again"),
+                ("•", "Bullet"),
+                ("5.", "Fifth"),
+                ("", "Not in a list"),
+            ]
+        );
+    }
+
+    #[test]
+    fn clamp_list_level() {
+        let rtf = r"{\rtf1\ansi{\*\listtable{\list{\listlevel\levelnfc0{\leveltext\'02\'00.;}}\listid1}}{\listoverridetable{\listoverride\listid1\ls1}}
+            \pard\ls1\ilvl300 Item\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.body[0].paragraph.list_level as usize, LIST_LEVEL_COUNT - 1);
+    }
+}
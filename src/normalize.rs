@@ -0,0 +1,124 @@
+/// Define the normalization of the style blocks of a document, to get a single block per run of the same format
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::document::RtfDocument;
+use crate::lexer::SourceSpan;
+use crate::parser::{SourceMapping, StyleBlock};
+
+impl RtfDocument {
+    /// Normalize the style blocks of the body and of the text boxes, without changing their text :
+    /// - the blocks of a paragraph get the paragraph properties of its last block, which are the ones applied by \par
+    /// - the empty blocks are removed, the end of paragraph of an empty block being moved to the previous block if it doesn't end one
+    /// - the adjacent blocks with the same painter and paragraph are merged, within a paragraph
+    pub fn normalize(&mut self) {
+        normalize_style_blocks(&mut self.body);
+        for shape in self.shapes.iter_mut() {
+            normalize_style_blocks(&mut shape.text_box);
+        }
+    }
+}
+
+pub(crate) fn normalize_style_blocks(style_blocks: &mut Vec<StyleBlock>) {
+    // The paragraph properties are the ones of the end of the paragraph
+    let mut paragraph_start = 0;
+    for index in 0..style_blocks.len() {
+        if style_blocks[index].paragraph_end || index == style_blocks.len() - 1 {
+            let paragraph = style_blocks[index].paragraph.clone();
+            for style_block in &mut style_blocks[paragraph_start..index] {
+                style_block.paragraph = paragraph.clone();
+            }
+            paragraph_start = index + 1;
+        }
+    }
+    let mut normalized: Vec<StyleBlock> = Vec::with_capacity(style_blocks.len());
+    for style_block in mem::take(style_blocks) {
+        match normalized.last_mut() {
+            Some(last) if !last.paragraph_end && style_block.text.is_empty() => last.paragraph_end = style_block.paragraph_end,
+            // An empty block ending a paragraph is kept after the end of another one : it's an empty paragraph
+            _ if style_block.text.is_empty() && !style_block.paragraph_end => {}
+            Some(last) if !last.paragraph_end && last.painter == style_block.painter && last.paragraph == style_block.paragraph => {
+                let offset = last.text.len();
+                last.source_spans.extend(style_block.source_spans.into_iter().map(|mapping| SourceMapping {
+                    text: SourceSpan {
+                        start: mapping.text.start + offset,
+                        end: mapping.text.end + offset,
+                    },
+                    ..mapping
+                }));
+                last.text.push_str(&style_block.text);
+                last.paragraph_end = style_block.paragraph_end;
+            }
+            _ => normalized.push(style_block),
+        }
+    }
+    *style_blocks = normalized;
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec;
+
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::paragraph::{Alignment, Paragraph};
+    use crate::parser::{Painter, Parser};
+
+    fn style_block(text: &str, bold: bool, alignment: Alignment, paragraph_end: bool) -> StyleBlock {
+        return StyleBlock {
+            painter: Painter { bold, ..Painter::default() },
+            paragraph: Paragraph { alignment, ..Paragraph::default() },
+            text: String::from(text),
+            paragraph_end,
+            ..StyleBlock::default()
+        };
+    }
+
+    #[test]
+    fn normalize_style_blocks() {
+        let mut document = RtfDocument {
+            body: vec![
+                style_block("Centered ", false, Alignment::LeftAligned, false),
+                style_block("", true, Alignment::LeftAligned, false),
+                style_block("title", false, Alignment::Center, false),
+                style_block("", false, Alignment::Center, true),
+                style_block("Some ", false, Alignment::LeftAligned, false),
+                style_block("bold", true, Alignment::LeftAligned, false),
+                style_block(" text", true, Alignment::LeftAligned, true),
+                style_block("", false, Alignment::LeftAligned, true),
+                style_block("End", false, Alignment::LeftAligned, false),
+            ],
+            ..RtfDocument::default()
+        };
+        let text = document.get_text();
+        document.normalize();
+        assert_eq!(
+            document.body,
+            vec![
+                style_block("Centered title", false, Alignment::Center, true),
+                style_block("Some ", false, Alignment::LeftAligned, false),
+                style_block("bold text", true, Alignment::LeftAligned, true),
+                style_block("", false, Alignment::LeftAligned, true),
+                style_block("End", false, Alignment::LeftAligned, false),
+            ]
+        );
+        assert_eq!(document.get_text(), text);
+    }
+
+    #[test]
+    fn normalize_source_spans() {
+        // The alignment of the paragraph changes in the middle of its text
+        let rtf = r"{\rtf1\ansi Some {\b bold}\qc{\b  text}\par}";
+        let (tokens, spans) = Lexer::scan_with_spans(rtf).unwrap();
+        let mut document = Parser::new_with_spans(tokens, spans).parse().unwrap();
+        assert_eq!(document.body.len(), 3);
+        document.normalize();
+        assert_eq!(document.body.len(), 2);
+        let style_block = &document.body[1];
+        assert_eq!((style_block.text.as_str(), style_block.paragraph.alignment), ("bold text", Alignment::Center));
+        for mapping in &style_block.source_spans {
+            assert_eq!(&style_block.text[mapping.text.start..mapping.text.end], &rtf[mapping.source.start..mapping.source.end]);
+        }
+    }
+}
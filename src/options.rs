@@ -0,0 +1,66 @@
+/// Define the options of the parsing, also limiting the resources used by the lexer
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::tokens::ControlWordMatching;
+
+/// The options of the parsing : limits on the resources used by the parser, to safely parse untrusted documents, recovery of the truncated documents,
+/// strictness of the reading of the control words, code page of the bytes and what is extracted from the document.
+/// A limit exceeded stops the parsing with a `ParserError::LimitExceeded` (or a `LexerError::LimitExceeded` while scanning), by default nothing is limited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ParserOptions {
+    /// Maximum depth of nested groups
+    pub max_nesting_depth: usize,
+    /// Maximum number of tokens of the document
+    pub max_tokens: usize,
+    /// Maximum length of the text of the body, in bytes
+    pub max_text_length: usize,
+    /// Maximum size of the data of a picture, in bytes
+    pub max_image_size: usize,
+    /// Close the groups left open at the end of the document (ex: a document truncated in transit) instead of rejecting it,
+    /// with a `ParserWarning::UnclosedGroups`
+    pub recover_unclosed_groups: bool,
+    /// Parse a fragment of a document, which doesn't start with the {\rtf1 prolog (ex: a part of the clipboard content).
+    /// The groups closed but not opened in the fragment, and those opened but not closed, are balanced.
+    pub fragment: bool,
+    /// Decode the blobs written by Word ({\*\themedata ...}, {\*\colorschememapping ...}, {\*\datastore ...}) in `RtfHeader::blobs`.
+    /// They are skipped by default.
+    pub capture_blobs: bool,
+    /// Written in the body at the end of each cell of a table (\cell, \nestcell), ex: '\t' to keep the columns in the text.
+    /// It is not part of the text of the cells (see `TableCell::text`), nothing is written by default.
+    pub cell_separator: Option<char>,
+    /// Written in the body at the end of each row of a table (\row, \nestrow), after the separator of its last cell, ex: '\n'
+    pub row_separator: Option<char>,
+    /// Replace the references to the fonts, colors and styles missing from the header by the default ones (\deffN, the automatic color \cf0
+    /// and the Normal style \s0), so the style blocks only reference existing entries. They are still reported as warnings.
+    pub clamp_invalid_refs: bool,
+    /// How the control words are read, `ControlWordMatching::Lenient` accepting the ones of some generators (\PAR, \fs+24)
+    pub matching: ControlWordMatching,
+    /// The code page of the bytes (\'hh) of the documents which don't declare it with \ansicpgN, ex: 1251 for the Cyrillic documents of an old generator.
+    /// By default, it's the one of the character set, Windows-1252 for \ansi.
+    pub default_codepage: Option<u16>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            max_nesting_depth: usize::MAX,
+            max_tokens: usize::MAX,
+            max_text_length: usize::MAX,
+            max_image_size: usize::MAX,
+            recover_unclosed_groups: false,
+            fragment: false,
+            capture_blobs: false,
+            cell_separator: None,
+            row_separator: None,
+            clamp_invalid_refs: false,
+            matching: ControlWordMatching::Strict,
+            default_codepage: None,
+        }
+    }
+}
@@ -0,0 +1,280 @@
+/// Define the outline of a document : the tree of its headings, to build a table of contents or a navigation pane
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::document::RtfDocument;
+use crate::header::RtfHeader;
+use crate::paragraph::Paragraph;
+use crate::parser::{Painter, StyleBlock};
+
+// Without an explicit heading, a paragraph longer than this is body text whatever its font size
+const MAX_HEADING_LENGTH: usize = 120;
+const MAX_HEADING_LEVEL: u8 = 9;
+
+/// A heading of the document and the sections under it
+/// The positions are char offsets in the visible text of the body (see `get_text`)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Section {
+    /// The text of the heading
+    pub title: String,
+    /// Level of the heading, 1 being the highest
+    pub level: u8,
+    /// Start of the heading
+    pub start: usize,
+    /// End of the section (exclusive) : the start of the next heading of the same or a higher level, or the end of the text
+    pub end: usize,
+    pub children: Vec<Section>,
+}
+
+// A line of the visible text, with the properties used to detect the headings
+struct TextParagraph<'a> {
+    paragraph: &'a Paragraph,
+    text: String,
+    start: usize,
+    // The smallest font size of the non-blank text of the paragraph
+    font_size: Option<u16>,
+}
+
+impl RtfDocument {
+    /// Build the tree of the headings of the document.
+    /// The headings are the paragraphs with an outline level (\outlinelevelN) or a "heading N" style.
+    /// If the document has none, the short paragraphs written bigger than the body text are the headings, the biggest font size being the level 1.
+    pub fn outline(&self) -> Vec<Section> {
+        let paragraphs = self.get_text_paragraphs();
+        let mut headings: Vec<(u8, &TextParagraph)> = paragraphs
+            .iter()
+            .filter_map(|text_paragraph| self.get_heading_level(text_paragraph.paragraph).map(|level| (level, text_paragraph)))
+            .collect();
+        if headings.is_empty() {
+            headings = get_font_size_headings(&paragraphs);
+        }
+
+        let text_length = paragraphs.last().map_or(0, |text_paragraph| text_paragraph.start + text_paragraph.text.chars().count());
+        let mut roots: Vec<Section> = vec![];
+        // The sections of the current branch of the tree, from the root to the last heading
+        let mut stack: Vec<Section> = vec![];
+        for (level, text_paragraph) in headings.into_iter().filter(|(_, text_paragraph)| !text_paragraph.text.trim().is_empty()) {
+            while stack.last().is_some_and(|section| section.level >= level) {
+                close_section(&mut stack, &mut roots, text_paragraph.start);
+            }
+            stack.push(Section {
+                title: String::from(text_paragraph.text.trim()),
+                level,
+                start: text_paragraph.start,
+                end: text_length,
+                children: vec![],
+            });
+        }
+        while !stack.is_empty() {
+            close_section(&mut stack, &mut roots, text_length);
+        }
+        return roots;
+    }
+
+    fn get_heading_level(&self, paragraph: &Paragraph) -> Option<u8> {
+        return get_heading_level(&self.header, paragraph);
+    }
+
+    // Split the visible text in paragraphs : a paragraph ends with a \par, a line break or when the paragraph properties change
+    fn get_text_paragraphs(&self) -> Vec<TextParagraph<'_>> {
+        let mut paragraphs: Vec<TextParagraph> = vec![];
+        let mut ended = true;
+        let mut offset = 0;
+        for style_block in self.body.iter().filter(|style_block| style_block.painter.is_visible()) {
+            for (index, line) in style_block.text.split('\n').enumerate() {
+                if index > 0 {
+                    offset += 1; // The line break
+                    ended = true;
+                }
+                if line.is_empty() {
+                    continue;
+                }
+                let font_size = Some(style_block.painter.font_size).filter(|_| !line.trim().is_empty());
+                match paragraphs.last_mut() {
+                    Some(text_paragraph) if !ended && *text_paragraph.paragraph == style_block.paragraph => {
+                        text_paragraph.text.push_str(line);
+                        text_paragraph.font_size = match (text_paragraph.font_size, font_size) {
+                            (Some(size), Some(other_size)) => Some(size.min(other_size)),
+                            (size, other_size) => size.or(other_size),
+                        };
+                    }
+                    _ => paragraphs.push(TextParagraph {
+                        paragraph: &style_block.paragraph,
+                        text: String::from(line),
+                        start: offset,
+                        font_size,
+                    }),
+                }
+                offset += line.chars().count();
+                ended = false;
+            }
+            ended |= style_block.paragraph_end;
+        }
+        return paragraphs;
+    }
+}
+
+impl StyleBlock {
+    /// Guess if the block is a heading, for the documents without structure : its paragraph has an outline level or a "heading N" or "Title" style,
+    /// or the block is a short text ending its paragraph, written bigger than the normal style of the document or in bold
+    pub fn is_probable_heading(&self, header: &RtfHeader) -> bool {
+        if get_heading_level(header, &self.paragraph).is_some() {
+            return true;
+        }
+        if header.stylesheet.get(&self.paragraph.style).is_some_and(|style| style.name.eq_ignore_ascii_case("title")) {
+            return true;
+        }
+        let text = self.text.trim();
+        if text.is_empty() || text.chars().count() > MAX_HEADING_LENGTH || !self.paragraph_end || !self.painter.is_visible() {
+            return false;
+        }
+        // The font size of the normal style (\s0), or the default one
+        let body_size = header.stylesheet.get(&0).map_or(Painter::default().font_size, |style| style.painter.font_size);
+        return self.painter.font_size > body_size || self.painter.bold;
+    }
+
+    /// How much the text stands out from the body text, 0 for a plain text : 2 for the bold, 1 for each of the italic, the underline
+    /// and the capitals, and 1 for each 2 points bigger than 12 points
+    pub fn emphasis_score(&self) -> u8 {
+        let painter = &self.painter;
+        let mut score = 2 * painter.bold as u8 + painter.italic as u8 + painter.underline.is_underlined() as u8 + (painter.caps || painter.smallcaps) as u8;
+        score += (painter.font_size.saturating_sub(Painter::default().font_size) / 4).min(u8::MAX as u16) as u8;
+        return score;
+    }
+}
+
+// The explicit level of a heading : its outline level, or the number of its "heading N" style
+fn get_heading_level(header: &RtfHeader, paragraph: &Paragraph) -> Option<u8> {
+    if let Some(outline_level) = paragraph.outline_level {
+        return Some(outline_level + 1);
+    }
+    let style = header.stylesheet.get(&paragraph.style)?;
+    let level = style.name.to_lowercase().strip_prefix("heading")?.trim().parse::<u8>().ok()?;
+    return Some(level).filter(|level| (1..=MAX_HEADING_LEVEL).contains(level));
+}
+
+// The headings guessed from the font sizes, compared to the most used font size of the document
+fn get_font_size_headings<'a, 'b>(paragraphs: &'b [TextParagraph<'a>]) -> Vec<(u8, &'b TextParagraph<'a>)> {
+    let mut size_lengths: Vec<(u16, usize)> = vec![];
+    for text_paragraph in paragraphs {
+        if let Some(font_size) = text_paragraph.font_size {
+            let length = text_paragraph.text.chars().count();
+            match size_lengths.iter_mut().find(|(size, _)| *size == font_size) {
+                Some((_, total_length)) => *total_length += length,
+                None => size_lengths.push((font_size, length)),
+            }
+        }
+    }
+    let Some((body_size, _)) = size_lengths.iter().max_by_key(|(_, length)| *length).copied() else {
+        return vec![];
+    };
+    let is_heading = |text_paragraph: &TextParagraph| text_paragraph.font_size.is_some_and(|size| size > body_size) && text_paragraph.text.trim().chars().count() <= MAX_HEADING_LENGTH;
+    // The biggest font size is the first level
+    let mut heading_sizes: Vec<u16> = paragraphs
+        .iter()
+        .filter(|text_paragraph| is_heading(text_paragraph))
+        .filter_map(|text_paragraph| text_paragraph.font_size)
+        .collect();
+    heading_sizes.sort_unstable_by(|size, other_size| other_size.cmp(size));
+    heading_sizes.dedup();
+    return paragraphs
+        .iter()
+        .filter(|text_paragraph| is_heading(text_paragraph))
+        .filter_map(|text_paragraph| {
+            let rank = heading_sizes.iter().position(|size| Some(*size) == text_paragraph.font_size)?;
+            return Some(((rank as u8 + 1).min(MAX_HEADING_LEVEL), text_paragraph));
+        })
+        .collect();
+}
+
+// Pop the last section of the branch, and add it to its parent
+fn close_section(stack: &mut Vec<Section>, roots: &mut Vec<Section>, end: usize) {
+    let Some(mut section) = stack.pop() else {
+        return;
+    };
+    section.end = end;
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(section),
+        None => roots.push(section),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outline_from_styles() {
+        let rtf = r"{\rtf1\ansi{\stylesheet{\s0 Normal;}{\s1\b\fs32 heading 1;}{\s2\b\fs28 Heading 2;}{\*\cs10 heading 3;}}
+\pard\s1 Intro\par\pard\s0 Text\par\pard\s2 Part A\par\pard\s0 More\par\pard\s1 End\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.header.stylesheet.len(), 3);
+        assert_eq!(document.header.stylesheet.get(&2).unwrap().name, "Heading 2");
+        assert_eq!(document.get_text(), "IntroTextPart AMoreEnd");
+        assert_eq!(
+            document.outline(),
+            [
+                Section {
+                    title: String::from("Intro"),
+                    level: 1,
+                    start: 0,
+                    end: 19,
+                    children: vec![Section {
+                        title: String::from("Part A"),
+                        level: 2,
+                        start: 9,
+                        end: 19,
+                        children: vec![]
+                    }],
+                },
+                Section {
+                    title: String::from("End"),
+                    level: 1,
+                    start: 19,
+                    end: 22,
+                    children: vec![]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn outline_from_outline_levels() {
+        let rtf = r"{\rtf1\ansi\outlinelevel1 Sub\par\pard\outlinelevel0 Title\par\pard Body\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let outline = document.outline();
+        assert_eq!(outline.iter().map(|section| (section.title.as_str(), section.level)).collect::<Vec<_>>(), [("Sub", 2), ("Title", 1)]);
+        assert_eq!((outline[1].start, outline[1].end), (3, 12));
+    }
+
+    #[test]
+    fn outline_from_font_sizes() {
+        let rtf = r"{\rtf1\ansi{\fs40 Title}\par{\fs32 Chapter}\par Some body text\par{\fs32 Other }{\fs24 chapter}\par More body text\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let outline = document.outline();
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].title, "Title");
+        assert_eq!(outline[0].children.iter().map(|section| section.title.as_str()).collect::<Vec<_>>(), ["Chapter"]);
+    }
+
+    #[test]
+    fn classify_style_blocks() {
+        let rtf = r"{\rtf1\ansi{\stylesheet{\s0\fs22 Normal;}{\s1 Title;}}
+            \pard\s1 Report\par\pard\s0{\fs28 Summary}\par\fs22 Some {\b\i bold} text.\par{\b Conclusion}\par{\ul\caps\fs32 Loud}\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let headings: Vec<&str> = document
+            .body
+            .iter()
+            .filter(|block| block.is_probable_heading(&document.header))
+            .map(|block| block.text.as_str())
+            .collect();
+        assert_eq!(headings, ["Report", "Summary", "Conclusion", "Loud"]);
+        let scores: Vec<(&str, u8)> = document.body.iter().map(|block| (block.text.as_str(), block.emphasis_score())).collect();
+        assert_eq!(scores, [("Report", 0), ("Summary", 1), ("Some ", 0), ("bold", 3), (" text.", 0), ("Conclusion", 2), ("Loud", 4)]);
+    }
+}
@@ -1,22 +1,225 @@
 /// Define the paragraph related structs and enums
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
 use tsify::Tsify;
-use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::tokens::ControlWord;
+use crate::header::{DocumentDefaults, StyleRef};
+use crate::list::ListOverrideRef;
+use crate::tokens::{ControlWord, Property};
+use crate::units::Twips;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Hash, Deserialize, Serialize)]
-#[wasm_bindgen]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Paragraph {
+    /// Reference to the style of the paragraph in the stylesheet (\sN), see `RtfHeader::get_style_by_name`
+    pub style: StyleRef,
     pub alignment: Alignment,
     pub spacing: Spacing,
     pub indent: Indentation,
-    pub tab_width: i32,
+    /// Width of the default tab stops of the paragraph (\pardeftabN), the one of the document (\deftabN) if not defined
+    pub tab_width: Twips,
+    pub tab_stops: Vec<TabStop>,
+    /// Reference to the list of the paragraph (\lsN), 0 if the paragraph is not in a list
+    pub list_override: ListOverrideRef,
+    /// Level of the paragraph in its list (\ilvlN)
+    pub list_level: u8,
+    /// The computed label of the list item ("1.", "a)", "•", "1.2.3", ...)
+    pub list_label: String,
+    /// The paragraph starts on a new page (\pagebb)
+    pub page_break_before: bool,
+    /// The lines of the paragraph are not split across pages (\keep)
+    pub keep_together: bool,
+    /// The paragraph is on the same page as the next one (\keepn)
+    pub keep_with_next: bool,
+    /// The first and last lines of the paragraph are not left alone on a page (\widctlpar, \nowidctlpar)
+    pub widow_control: bool,
+    /// Level of the paragraph in the outline of the document, from 0 to 8 (\outlinelevelN), None for the body text
+    pub outline_level: Option<u8>,
+    /// The position and the size of a paragraph placed out of the flow of the text (\absw, \posx, ...), None for the paragraphs of the flow
+    pub frame: Option<Frame>,
+}
+
+impl Paragraph {
+    /// The paragraph without formatting, with the default tab width of the document
+    pub fn with_defaults(defaults: &DocumentDefaults) -> Self {
+        return Self {
+            tab_width: defaults.tab_width,
+            ..Self::default()
+        };
+    }
+}
+
+/// A frame : the position and the size of a paragraph placed out of the flow of the text, and its drop cap
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Frame {
+    /// Width of the frame (\abswN), 0 for the width of its text
+    pub width: Twips,
+    /// Height of the frame (\abshN) : at least N if positive, exactly -N if negative, 0 for the height of its text
+    pub height: Twips,
+    pub horizontal_anchor: HorizontalAnchor,
+    pub vertical_anchor: VerticalAnchor,
+    pub horizontal_position: FramePosition,
+    pub vertical_position: FramePosition,
+    /// Distance between the frame and the text flowing around it, horizontally (\dfrmtxtxN) and vertically (\dfrmtxtyN)
+    pub horizontal_distance: Twips,
+    pub vertical_distance: Twips,
+    /// The text doesn't flow around the frame (\nowrap)
+    pub no_wrap: bool,
+    pub drop_cap: DropCap,
+    /// Number of lines of the drop cap (\dropcapliN)
+    pub drop_cap_lines: u8,
+}
+
+impl Frame {
+    pub fn is_frame_control_word(control_word: &ControlWord) -> bool {
+        return matches!(
+            control_word,
+            ControlWord::FrameWidth
+                | ControlWord::FrameHeight
+                | ControlWord::FrameAnchorColumn
+                | ControlWord::FrameAnchorMargin
+                | ControlWord::FrameAnchorPage
+                | ControlWord::FrameAnchorTopMargin
+                | ControlWord::FrameAnchorTopPage
+                | ControlWord::FrameAnchorParagraph
+                | ControlWord::FramePositionX
+                | ControlWord::FramePositionNegativeX
+                | ControlWord::FrameLeft
+                | ControlWord::FrameCenterX
+                | ControlWord::FrameRight
+                | ControlWord::FrameInsideX
+                | ControlWord::FrameOutsideX
+                | ControlWord::FramePositionY
+                | ControlWord::FramePositionNegativeY
+                | ControlWord::FrameTop
+                | ControlWord::FrameCenterY
+                | ControlWord::FrameBottom
+                | ControlWord::FrameInsideY
+                | ControlWord::FrameOutsideY
+                | ControlWord::FrameInline
+                | ControlWord::FrameTextDistance
+                | ControlWord::FrameTextDistanceX
+                | ControlWord::FrameTextDistanceY
+                | ControlWord::FrameNoWrap
+                | ControlWord::DropCapLines
+                | ControlWord::DropCapType
+        );
+    }
+
+    pub fn apply(&mut self, control_word: &ControlWord, property: &Property) {
+        #[rustfmt::skip]
+        match control_word {
+            ControlWord::FrameWidth             => self.width = Twips(property.get_value()),
+            ControlWord::FrameHeight            => self.height = Twips(property.get_value()),
+            ControlWord::FrameAnchorColumn      => self.horizontal_anchor = HorizontalAnchor::Column,
+            ControlWord::FrameAnchorMargin      => self.horizontal_anchor = HorizontalAnchor::Margin,
+            ControlWord::FrameAnchorPage        => self.horizontal_anchor = HorizontalAnchor::Page,
+            ControlWord::FrameAnchorTopMargin   => self.vertical_anchor = VerticalAnchor::Margin,
+            ControlWord::FrameAnchorTopPage     => self.vertical_anchor = VerticalAnchor::Page,
+            ControlWord::FrameAnchorParagraph   => self.vertical_anchor = VerticalAnchor::Paragraph,
+            ControlWord::FramePositionX
+                | ControlWord::FramePositionNegativeX => self.horizontal_position = FramePosition::Offset(Twips(property.get_value())),
+            ControlWord::FrameLeft              => self.horizontal_position = FramePosition::Start,
+            ControlWord::FrameCenterX           => self.horizontal_position = FramePosition::Center,
+            ControlWord::FrameRight             => self.horizontal_position = FramePosition::End,
+            ControlWord::FrameInsideX           => self.horizontal_position = FramePosition::Inside,
+            ControlWord::FrameOutsideX          => self.horizontal_position = FramePosition::Outside,
+            ControlWord::FramePositionY
+                | ControlWord::FramePositionNegativeY => self.vertical_position = FramePosition::Offset(Twips(property.get_value())),
+            ControlWord::FrameTop               => self.vertical_position = FramePosition::Start,
+            ControlWord::FrameCenterY           => self.vertical_position = FramePosition::Center,
+            ControlWord::FrameBottom            => self.vertical_position = FramePosition::End,
+            ControlWord::FrameInsideY           => self.vertical_position = FramePosition::Inside,
+            ControlWord::FrameOutsideY          => self.vertical_position = FramePosition::Outside,
+            ControlWord::FrameInline            => self.vertical_position = FramePosition::Inline,
+            ControlWord::FrameTextDistance      => {
+                self.horizontal_distance = Twips(property.get_value());
+                self.vertical_distance = Twips(property.get_value());
+            }
+            ControlWord::FrameTextDistanceX     => self.horizontal_distance = Twips(property.get_value()),
+            ControlWord::FrameTextDistanceY     => self.vertical_distance = Twips(property.get_value()),
+            ControlWord::FrameNoWrap            => self.no_wrap = true,
+            ControlWord::DropCapLines           => self.drop_cap_lines = property.get_value_as::<u8>().unwrap_or_default(),
+            ControlWord::DropCapType            => self.drop_cap = DropCap::from(property.get_value()),
+            _                                   => {}
+        };
+    }
+}
+
+/// What the horizontal position of a frame is relative to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum HorizontalAnchor {
+    #[default]
+    Column, // \phcol
+    Margin, // \phmrg
+    Page,   // \phpg
+}
+
+/// What the vertical position of a frame is relative to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum VerticalAnchor {
+    #[default]
+    Margin, // \pvmrg
+    Page,      // \pvpg
+    Paragraph, // \pvpara
+}
+
+/// Position of a frame relative to its anchor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum FramePosition {
+    /// Distance from the left or the top of the anchor (\posxN, \posyN, or \posnegxN, \posnegyN if negative)
+    Offset(Twips),
+    Start,   // \posxl, \posyt
+    Center,  // \posxc, \posyc
+    End,     // \posxr, \posyb
+    Inside,  // \posxi, \posyin
+    Outside, // \posxo, \posyout
+    Inline,  // \posyil : the frame is in the line of the text
+}
+
+impl Default for FramePosition {
+    fn default() -> Self {
+        return FramePosition::Offset(Twips(0));
+    }
+}
+
+/// Kind of the drop cap of a frame (\dropcaptN)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum DropCap {
+    #[default]
+    None,
+    InText,   // 1
+    InMargin, // 2
+}
+
+impl From<i32> for DropCap {
+    fn from(value: i32) -> Self {
+        return match value {
+            1 => DropCap::InText,
+            2 => DropCap::InMargin,
+            _ => DropCap::None,
+        };
+    }
 }
 
 /// Alignement of a paragraph (left, right, center, justify)
-#[derive(Debug, Default, Clone, Copy, PartialEq, Hash, Deserialize, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub enum Alignment {
     #[default]
     LeftAligned, // \ql
@@ -38,19 +241,21 @@ impl From<&ControlWord<'_>> for Alignment {
 }
 
 /// The vertical margin before / after a block of text
-#[derive(Debug, Default, Clone, Copy, PartialEq, Hash, Deserialize, Serialize)]
-#[wasm_bindgen]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Spacing {
-    pub before: i32,
-    pub after: i32,
+    pub before: Twips,
+    pub after: Twips,
     pub between_line: SpaceBetweenLine,
     pub line_multiplier: i32,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Hash, Deserialize, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub enum SpaceBetweenLine {
-    Value(i32),
+    Value(Twips),
     #[default]
     Auto,
     Invalid,
@@ -64,17 +269,81 @@ impl From<i32> for SpaceBetweenLine {
     fn from(value: i32) -> Self {
         return match value {
             1000 => SpaceBetweenLine::Auto,
-            val if val < 0 => SpaceBetweenLine::Value(val.abs()),
-            val => SpaceBetweenLine::Value(val),
+            val if val < 0 => SpaceBetweenLine::Value(Twips(val.abs())),
+            val => SpaceBetweenLine::Value(Twips(val)),
         };
     }
 }
 
 // This struct can not be an enum because left-indent and right-ident can both be defined at the same time
-#[derive(Default, Debug, Clone, Copy, PartialEq, Hash, Deserialize, Serialize)]
-#[wasm_bindgen]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Indentation {
-    pub left: i32,
-    pub right: i32,
-    pub first_line: i32,
+    pub left: Twips,
+    pub right: Twips,
+    pub first_line: Twips,
+}
+
+/// A tab stop defined by the \txN (or \tbN) control word
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct TabStop {
+    /// Position of the tab stop from the left margin
+    pub position: Twips,
+    pub kind: TabKind,
+    pub leader: TabLeader,
+}
+
+/// Alignment of the text relative to the tab stop
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum TabKind {
+    #[default]
+    Left, // default
+    Right,   // \tqr
+    Center,  // \tqc
+    Decimal, // \tqdec
+    Bar,     // \tbN
+}
+
+impl From<&ControlWord<'_>> for TabKind {
+    fn from(cw: &ControlWord) -> Self {
+        return match cw {
+            ControlWord::TabRight    => TabKind::Right,
+            ControlWord::TabCenter   => TabKind::Center,
+            ControlWord::TabDecimal  => TabKind::Decimal,
+            ControlWord::TabBar      => TabKind::Bar,
+            _  /* default */         => TabKind::Left,
+        };
+    }
+}
+
+/// Character used to fill the space before the tab stop
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum TabLeader {
+    #[default]
+    None,
+    Dot,       // \tldot
+    Hyphen,    // \tlhyph
+    Underline, // \tlul
+    Thick,     // \tlth
+    Equal,     // \tleq
+}
+
+impl From<&ControlWord<'_>> for TabLeader {
+    fn from(cw: &ControlWord) -> Self {
+        return match cw {
+            ControlWord::TabLeaderDot       => TabLeader::Dot,
+            ControlWord::TabLeaderHyphen    => TabLeader::Hyphen,
+            ControlWord::TabLeaderUnderline => TabLeader::Underline,
+            ControlWord::TabLeaderThick     => TabLeader::Thick,
+            ControlWord::TabLeaderEqual     => TabLeader::Equal,
+            _  /* default */                => TabLeader::None,
+        };
+    }
 }
@@ -1,13 +1,38 @@
-use std::collections::HashMap;
-use std::{fmt, mem};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::{fmt, iter, mem};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
 
-use crate::document::RtfDocument;
-use crate::header::{CharacterSet, Color, ColorRef, ColorTable, Font, FontFamily, FontRef, FontTable, RtfHeader, StyleSheet};
-use crate::paragraph::{Alignment, Paragraph, SpaceBetweenLine};
+use crate::codepage;
+use crate::codepage::ByteDecoder;
+use crate::document::{Bookmark, CustomDestination, PageBreak, RtfDocument};
+use crate::encapsulation::EncapsulatedFormat;
+use crate::field::{Field, FormField, FormFieldType};
+use crate::header::{
+    CharacterSet, Color, ColorRef, ColorSpace, ColorTable, DocumentDefaults, EmbeddedFont, EmbeddedFontType, ExpandedColor, ExpandedColorTable, Font, FontFamily, FontRef, FontTable, RtfHeader, Style,
+    StyleRef, StyleSheet,
+};
+use crate::image::{Image, ImageFormat};
+use crate::lexer::SourceSpan;
+use crate::list::{List, ListLevel, ListNumbering, ListOverride, ListOverrideRef, ListOverrideTable, ListTable, LIST_LEVEL_COUNT};
+use crate::paragraph::{Alignment, Frame, Paragraph, SpaceBetweenLine, TabKind, TabLeader, TabStop};
+use crate::shape::{Shape, ShapeAnchor, ShapeProperty};
+use crate::table::TableBuilder;
 use crate::tokens::{ControlWord, Property, Token};
+use crate::tree::GroupTree;
+use crate::unicode::UnicodeDecoder;
+use crate::units::Twips;
+
+// The error and the options of the parser are defined apart, for the lexer to use them without the `parser` feature
+pub use crate::error::ParserError;
+pub use crate::options::ParserOptions;
 
 // Use to specify control word in parse_header
 macro_rules! header_control_word {
@@ -19,27 +44,63 @@ macro_rules! header_control_word {
     };
 }
 
-#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
-#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Default, PartialEq, Hash, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct StyleBlock {
     pub painter: Painter,
     pub paragraph: Paragraph,
     pub text: String,
+    /// The block is the last one of its paragraph (\par)
+    pub paragraph_end: bool,
+    /// The parts of the RTF source each run of the text comes from, only filled by `Parser::new_with_spans`
+    pub source_spans: Vec<SourceMapping>,
+}
+
+/// A run of the text of a StyleBlock and the byte range of the RTF source it comes from
+/// ex: the text "é" comes from the source "\'e9" or "\u233"
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct SourceMapping {
+    /// Byte range in the text of the block
+    pub text: SourceSpan,
+    /// Byte range in the RTF source
+    pub source: SourceSpan,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Deserialize, Serialize)]
-#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct Painter {
     pub color_ref: ColorRef,
     pub font_ref: FontRef,
+    /// Font size expressed in half-points, as defined by the \fsN control word
     pub font_size: u16,
     pub bold: bool,
     pub italic: bool,
-    pub underline: bool,
+    pub underline: Underline,
+    pub underline_color: ColorRef,
     pub superscript: bool,
     pub subscript: bool,
     pub smallcaps: bool,
     pub strike: bool,
+    /// Vertical offset of the baseline in half-points (positive is raised, negative is lowered)
+    pub baseline_offset: i32,
+    /// Space added (or removed if negative) between characters
+    pub character_spacing: Twips,
+    pub outline: bool,
+    pub shadow: bool,
+    pub caps: bool,
+    pub hidden: bool,
+    /// Reference to the character style of the run in the stylesheet (\csN), None if it has none
+    pub char_style_ref: Option<StyleRef>,
+    /// Text deleted while the revisions were tracked (\deleted), until the deletion is accepted
+    pub deleted: bool,
+    /// Language of the run, as a Windows LCID (\langN), None if it's the default language of the document
+    pub language: Option<u16>,
+    /// Language of the East Asian text of the run (\langfeN)
+    pub east_asian_language: Option<u16>,
 }
 
 impl Default for Painter {
@@ -47,56 +108,277 @@ impl Default for Painter {
         Self {
             color_ref: Default::default(),
             font_ref: Default::default(),
-            font_size: 12,
+            font_size: 24, // 12pt
             bold: Default::default(),
             italic: Default::default(),
             underline: Default::default(),
+            underline_color: Default::default(),
             superscript: Default::default(),
             subscript: Default::default(),
             smallcaps: Default::default(),
             strike: Default::default(),
+            baseline_offset: Default::default(),
+            character_spacing: Default::default(),
+            outline: Default::default(),
+            shadow: Default::default(),
+            caps: Default::default(),
+            hidden: Default::default(),
+            char_style_ref: Default::default(),
+            deleted: Default::default(),
+            language: Default::default(),
+            east_asian_language: Default::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum ParserError {
-    InvalidToken(String),
-    IgnorableDestinationParsingError,
-    MalformedPainterStack,
-    InvalidFontIdentifier(Property),
-    InvalidColorIdentifier(Property),
-    NoMoreToken,
-    ValueCastError(String),
-    UnicodeParsingError(i32),
-    ParseEmptyToken,
+impl Painter {
+    /// The painter of the text without formatting, in the default font of the document
+    pub fn with_defaults(defaults: &DocumentDefaults) -> Self {
+        return Self {
+            font_ref: defaults.font_ref,
+            ..Self::default()
+        };
+    }
+
+    /// Get the font size in points
+    pub fn font_size_pt(&self) -> f32 {
+        return self.font_size as f32 / 2.0;
+    }
+
+    /// Whether the text is displayed : the hidden text (\v) and the deleted text (\deleted) are not part of the text of the document (see `get_text`)
+    pub fn is_visible(&self) -> bool {
+        return !self.hidden && !self.deleted;
+    }
+
+    /// Apply a toggle control word (ex: \b, \b0, \super0, \nosupersub), returns false if it's not one
+    pub fn apply_toggle(&mut self, control_word: &ControlWord, property: &Property) -> bool {
+        let on = property.as_bool();
+        #[rustfmt::skip]
+        match control_word {
+            ControlWord::Bold           => self.bold = on,
+            ControlWord::Italic         => self.italic = on,
+            ControlWord::Smallcaps      => self.smallcaps = on,
+            ControlWord::Strikethrough  => self.strike = on,
+            ControlWord::Outline        => self.outline = on,
+            ControlWord::Shadow         => self.shadow = on,
+            ControlWord::Caps           => self.caps = on,
+            ControlWord::Hidden         => self.hidden = on,
+            ControlWord::Deleted        => self.deleted = on,
+            // A run is either in superscript or in subscript
+            ControlWord::Superscript    => {
+                self.superscript = on;
+                self.subscript &= !on;
+            }
+            ControlWord::Subscript      => {
+                self.subscript = on;
+                self.superscript &= !on;
+            }
+            ControlWord::NoSuperSub     => {
+                self.superscript = false;
+                self.subscript = false;
+            }
+            _                           => return false,
+        };
+        return true;
+    }
+}
+
+/// Style of the underline (\ul, \uldb, \ulw, ...)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum Underline {
+    #[default]
+    None, // \ulnone
+    Single,     // \ul
+    Double,     // \uldb
+    Word,       // \ulw
+    Dotted,     // \uld
+    Dash,       // \uldash
+    DashDot,    // \uldashd
+    DashDotDot, // \uldashdd
+    LongDash,   // \ulldash
+    Thick,      // \ulth
+    Wave,       // \ulwave
+    DoubleWave, // \ululdbwave
+}
+
+impl Underline {
+    pub fn from(control_word: &ControlWord, property: &Property) -> Self {
+        // \ul0 or \uldb0 turn the underline off
+        if !property.as_bool() && *property != Property::None {
+            return Underline::None;
+        }
+        return match control_word {
+            ControlWord::Underline           => Underline::Single,
+            ControlWord::UnderlineDouble     => Underline::Double,
+            ControlWord::UnderlineWord       => Underline::Word,
+            ControlWord::UnderlineDotted     => Underline::Dotted,
+            ControlWord::UnderlineDash       => Underline::Dash,
+            ControlWord::UnderlineDashDot    => Underline::DashDot,
+            ControlWord::UnderlineDashDotDot => Underline::DashDotDot,
+            ControlWord::UnderlineLongDash   => Underline::LongDash,
+            ControlWord::UnderlineThick      => Underline::Thick,
+            ControlWord::UnderlineWave       => Underline::Wave,
+            ControlWord::UnderlineDoubleWave => Underline::DoubleWave,
+            _  /* default */                 => Underline::None,
+        };
+    }
+
+    pub fn is_underlined(&self) -> bool {
+        return *self != Underline::None;
+    }
 }
 
-impl std::error::Error for ParserError {}
+/// Something the parser couldn't fully understand, without failing the whole parsing
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParserWarning {
+    UnknownControlWord(String),
+    /// An ignorable destination ({\*\destination ...}) that is not supported
+    SkippedDestination(String),
+    InvalidColorRef(ColorRef),
+    InvalidFontRef(FontRef),
+    InvalidStyleRef(StyleRef),
+    /// The \'xx chars are decoded as Windows-1252 instead of the code page of the document (\ansicpgN)
+    CodepageFallback(i32),
+    /// Number of groups closed at the end of the document, see `ParserOptions::recover_unclosed_groups`
+    UnclosedGroups(usize),
+    /// A parameter not matching the `ParameterKind` of the control word (ex: \fs-10, \b2, \par1), the control word is applied if it can be
+    InvalidParameter(String, Property),
+}
 
-impl fmt::Display for ParserError {
+impl fmt::Display for ParserWarning {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let _ = write!(f, "[RTF Parser] : ");
         return match self {
-            ParserError::InvalidToken(msg) => write!(f, "{}", msg),
-            ParserError::IgnorableDestinationParsingError => write!(f, "No ignorable destination should be left"),
-            ParserError::MalformedPainterStack => write!(f, "Malformed painter stack : Unbalanced number of brackets"),
-            ParserError::InvalidFontIdentifier(property) => write!(f, "Invalid font identifier : {:?}", property),
-            ParserError::InvalidColorIdentifier(property) => write!(f, "Invalid color identifier : {:?}", property),
-            ParserError::NoMoreToken => write!(f, "No more token to parse"),
-            ParserError::ValueCastError(_type) => write!(f, "Unable to cast i32 to {_type}"),
-            ParserError::UnicodeParsingError(value) => write!(f, "Unable to parse {value} value to unicode"),
-            ParserError::ParseEmptyToken => write!(f, "Try to parse an empty token, this should never happen. If so, please open an issue in the github repository"),
+            ParserWarning::UnknownControlWord(name) => write!(f, "Unknown control word : {name}"),
+            ParserWarning::SkippedDestination(name) => write!(f, "Skipped destination : {name}"),
+            ParserWarning::InvalidColorRef(color_ref) => write!(f, "Invalid color reference : {color_ref}"),
+            ParserWarning::InvalidFontRef(font_ref) => write!(f, "Invalid font reference : {font_ref}"),
+            ParserWarning::InvalidStyleRef(style_ref) => write!(f, "Invalid style reference : {style_ref}"),
+            ParserWarning::CodepageFallback(codepage) => write!(f, "Code page {codepage} is not supported, Windows-1252 is used instead"),
+            ParserWarning::UnclosedGroups(count) => write!(f, "{count} groups are not closed at the end of the document"),
+            ParserWarning::InvalidParameter(name, property) => write!(f, "Invalid parameter for {name} : {property:?}"),
         };
     }
 }
 
+// Add a warning, if it has not been already reported
+fn add_warning(warnings: &mut Vec<ParserWarning>, warning: ParserWarning) {
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
+
+// Find the start of the body while the tokens are read : the first paragraph or text outside of the groups of the header
+#[derive(Debug, Default)]
+pub(crate) struct BodyStart {
+    depth: usize,
+    // The depth of the header group being read (\fonttbl, \colortbl, {\*\generator ...}, ...)
+    header_group_depth: Option<usize>,
+    group_opened: bool,
+}
+
+impl BodyStart {
+    // Read the next token, returns true if it's the first token of the body
+    pub(crate) fn is_reached(&mut self, token: &Token) -> bool {
+        let group_opened = mem::take(&mut self.group_opened);
+        match token {
+            Token::OpeningBracket => {
+                self.depth += 1;
+                self.group_opened = true;
+            }
+            Token::ClosingBracket => {
+                if self.header_group_depth == Some(self.depth) {
+                    self.header_group_depth = None;
+                }
+                self.depth = self.depth.saturating_sub(1);
+            }
+            Token::CRLF => self.group_opened = group_opened,
+            _ if self.header_group_depth.is_some() => {}
+            Token::IgnorableDestination
+            | Token::ControlSymbol((
+                ControlWord::FontTable | ControlWord::FileTable | ControlWord::ColorTable | ControlWord::StyleSheet | ControlWord::ListTable | ControlWord::ListOverrideTable,
+                _,
+            )) if group_opened => {
+                self.header_group_depth = Some(self.depth);
+            }
+            Token::ControlSymbol((ControlWord::Unknown(r"\info" | r"\revtbl"), _)) if group_opened => self.header_group_depth = Some(self.depth),
+            Token::PlainText(text) => return !text.trim().is_empty(),
+            Token::ControlSymbol((ControlWord::Pard | ControlWord::Plain | ControlWord::Sectd | ControlWord::Par | ControlWord::HexByte | ControlWord::Unicode, _)) => return true,
+            _ => {}
+        };
+        return false;
+    }
+}
+
+// The kind of a group of the font table, which defines how its text is read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FontGroup {
+    Entry,
+    Alternate,    // {\*\falt ...}
+    Embedded,     // {\*\fontemb ...}
+    EmbeddedFile, // {\*\fontfile ...}
+    Skipped,
+}
+
+// This state keeps track of the values that depend on the position in the document, regardless of the scope nesting
+#[derive(Debug, Default)]
+struct DocumentState {
+    pub table_builder: TableBuilder,
+    pub list_numbering: ListNumbering,
+    // Whether some text has already been added to the current paragraph
+    pub paragraph_started: bool,
+    // Length of the text of the body, in bytes
+    pub text_length: usize,
+    // Length of the visible text of the body (see RtfDocument::get_text), in chars
+    pub visible_length: usize,
+    pub max_text_length: usize,
+    pub unicode_decoder: UnicodeDecoder,
+    pub byte_decoder: ByteDecoder,
+    // Fields whose group is not closed yet, the last one being the innermost
+    pub open_fields: Vec<OpenField>,
+    // The last shape of RtfDocument::shapes while its group is open
+    pub open_shape: Option<OpenShape>,
+}
+
+// A shape (\shp) or a drawing object (\do) being parsed
+#[derive(Debug, Clone, Copy)]
+struct OpenShape {
+    // Depth of the \shp or \do group
+    depth: usize,
+    // Depth of the \shptxt or \dptxbxtext group while it's open : its text is added to the shape instead of the body
+    text_depth: Option<usize>,
+}
+
+impl DocumentState {
+    fn is_in_text_box(&self) -> bool {
+        return self.open_shape.is_some_and(|shape| shape.text_depth.is_some());
+    }
+}
+
+// A field being parsed
+#[derive(Debug)]
+struct OpenField {
+    // Index of the field in RtfDocument::fields
+    index: usize,
+    // Depth of the \field group
+    depth: usize,
+    // Depth of the \fldrslt group while it's open
+    result_depth: Option<usize>,
+}
+
 // This state keeps track of each value that depends on the scope nesting
 #[derive(Debug, Clone, PartialEq, Hash)]
 struct ParserState {
     pub painter: Painter,
     pub paragraph: Paragraph,
     pub unicode_ignore_count: i32,
+    // Kind and leader of the next tab stop, set before its \txN position
+    pub pending_tab_stop: TabStop,
+    // Nesting level of the paragraph in tables (\intbl, \itapN), 0 if it's not in a table
+    pub table_level: u16,
 }
 
 impl Default for ParserState {
@@ -105,26 +387,134 @@ impl Default for ParserState {
             painter: Default::default(),
             paragraph: Default::default(),
             unicode_ignore_count: 1,
+            pending_tab_stop: Default::default(),
+            table_level: 0,
         }
     }
 }
 
+/// What to do with a control word unknown to the parser, as decided by the handler set with `Parser::on_unknown_control_word`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ControlWordAction {
+    /// The control word is not handled, it's reported as a warning
+    Ignored,
+    /// The handler took care of the control word (ex: by updating the painter or the paragraph)
+    Handled,
+    /// Insert some text in the document, with the current painter and paragraph
+    InsertText(String),
+}
+
+/// Handler of the unknown control words : it receives the name (ex: \mycontrolword), the property and the current painter and paragraph
+pub type ControlWordHandler<'a> = Box<dyn FnMut(&str, Property, &mut Painter, &mut Paragraph) -> ControlWordAction + 'a>;
+
+/// What to do with a destination group, as decided by the handler set with `Parser::on_destination`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DestinationAction {
+    /// The destination is parsed as if it had no handler
+    Ignored,
+    /// The handler took care of the destination, which is skipped
+    Handled,
+    /// Skip the destination and insert some text in the document, with the current painter and paragraph
+    InsertText(String),
+    /// Skip the destination and attach a value to the document, in `RtfDocument::custom_destinations`
+    Attach(String),
+}
+
+/// Handler of a destination : it receives the tokens of the whole group, brackets included (ex: {\*\mydata ...})
+pub type DestinationHandler<'a> = Box<dyn FnMut(&[Token<'a>]) -> DestinationAction + 'a>;
+
 pub struct Parser<'a> {
     tokens: Vec<Token<'a>>,
     parsed_item: Vec<bool>,
     cursor: usize,
+    spans: Vec<SourceSpan>,
+    options: ParserOptions,
+    warnings: Vec<ParserWarning>,
+    unknown_control_word_handler: Option<ControlWordHandler<'a>>,
+    // The handlers by destination name
+    destination_handlers: BTreeMap<String, DestinationHandler<'a>>,
 }
 
+// Read an ignorable destination of the header ({\*\generator ...}), given the tokens of its group without its opening bracket
+type HeaderDestinationParser = for<'b> fn(&[Token<'b>], &mut RtfHeader) -> Result<(), ParserError>;
+
+// The ignorable destinations of the header read by the parser. The other ones are skipped wherever they are, unless they have a handler (see `Parser::on_destination`)
+const HEADER_DESTINATIONS: [(ControlWord<'static>, HeaderDestinationParser); 4] = [
+    (ControlWord::ExpandedColorTable, |tokens, header| {
+        header.expanded_color_table = Parser::parse_expanded_color_table(tokens)?;
+        return Ok(());
+    }),
+    (ControlWord::ListTable, |tokens, header| {
+        header.list_table = Parser::parse_list_table(tokens)?;
+        return Ok(());
+    }),
+    (ControlWord::ListOverrideTable, |tokens, header| {
+        header.list_override_table = Parser::parse_list_override_table(tokens)?;
+        return Ok(());
+    }),
+    (ControlWord::Generator, |tokens, header| {
+        header.generator = Parser::parse_generator(tokens);
+        return Ok(());
+    }),
+];
+
 impl<'a> Parser<'a> {
     pub fn new(tokens: Vec<Token<'a>>) -> Self {
         return Self {
             parsed_item: vec![false; tokens.len()],
             tokens,
             cursor: 0,
+            spans: vec![],
+            options: ParserOptions::default(),
+            warnings: vec![],
+            unknown_control_word_handler: None,
+            destination_handlers: BTreeMap::new(),
         };
     }
 
-    pub fn get_tokens(&self) -> Vec<&Token> {
+    /// Create a parser that records the source mappings of each StyleBlock
+    /// `spans` are the spans of the tokens, as returned by `Lexer::scan_with_spans`
+    pub fn new_with_spans(tokens: Vec<Token<'a>>, spans: Vec<SourceSpan>) -> Self {
+        return Self { spans, ..Self::new(tokens) };
+    }
+
+    /// Limit the resources used by the parser
+    pub fn with_options(self, options: ParserOptions) -> Self {
+        return Self { options, ..self };
+    }
+
+    /// Handle the control words of the body that are unknown to the parser (ex: vendor-specific ones)
+    pub fn on_unknown_control_word<F>(self, handler: F) -> Self
+    where
+        F: FnMut(&str, Property, &mut Painter, &mut Paragraph) -> ControlWordAction + 'a,
+    {
+        return Self {
+            unknown_control_word_handler: Some(Box::new(handler)),
+            ..self
+        };
+    }
+
+    /// Handle the destination groups of the given name, as written in RTF (ex: \pict, \fldinst or a vendor-specific \mydata).
+    /// The tables of the header (\fonttbl, \colortbl, \stylesheet, ...) are not passed to the handlers. A destination can only have one handler, the last one being kept.
+    pub fn on_destination<F>(mut self, name: &str, handler: F) -> Self
+    where
+        F: FnMut(&[Token<'a>]) -> DestinationAction + 'a,
+    {
+        self.destination_handlers.insert(name.to_string(), Box::new(handler));
+        return self;
+    }
+
+    // Get the span of the token at `index` in the source, if the spans are recorded
+    fn get_source_span(&self, index: usize) -> Option<SourceSpan> {
+        return self.spans.get(index).copied();
+    }
+
+    /// Get the tree of the groups of the tokens, the empty tokens (already parsed) excluded
+    pub fn get_tree(&self) -> Result<GroupTree<'a>, ParserError> {
+        return GroupTree::from_tokens(&self.tokens);
+    }
+
+    pub fn get_tokens(&self) -> Vec<&Token<'_>> {
         // It ignores the empty tokens, that replaced already parsed tokens istead of deleting them for performance reasons
         return self.tokens.iter().filter(|t| *t != &Token::Empty).collect();
     }
@@ -148,13 +538,111 @@ impl<'a> Parser<'a> {
         return Ok(());
     }
 
+    // Append the missing closing brackets at the end of the document
+    fn close_unclosed_groups(&mut self) {
+        let mut depth: usize = 0;
+        for token in self.tokens.iter() {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        if depth == 0 {
+            return;
+        }
+        self.tokens.extend(iter::repeat(Token::ClosingBracket).take(depth));
+        self.parsed_item.resize(self.tokens.len(), false);
+        add_warning(&mut self.warnings, ParserWarning::UnclosedGroups(depth));
+    }
+
+    // Enclose the fragment in a group, after the missing opening brackets and before the missing closing ones
+    fn wrap_fragment(&mut self) {
+        let mut depth: usize = 0;
+        let mut unopened: usize = 0;
+        for token in self.tokens.iter() {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => unopened += 1,
+                Token::ClosingBracket => depth -= 1,
+                _ => {}
+            }
+        }
+        let opening = iter::repeat(Token::OpeningBracket).take(unopened + 1);
+        self.tokens.splice(0..0, opening);
+        self.tokens.extend(iter::repeat(Token::ClosingBracket).take(depth + 1));
+        self.parsed_item.resize(self.tokens.len(), false);
+        if !self.spans.is_empty() {
+            // The added brackets are not in the source
+            self.spans.splice(0..0, iter::repeat(SourceSpan::default()).take(unopened + 1));
+        }
+    }
+
+    // Check the limits that can be known before parsing : the number of tokens and the nesting depth
+    fn check_limits(&self) -> Result<(), ParserError> {
+        if self.tokens.len() > self.options.max_tokens {
+            return Err(ParserError::LimitExceeded(format!("more than {} tokens", self.options.max_tokens)));
+        }
+        let mut depth: usize = 0;
+        for token in self.tokens.iter() {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth > self.options.max_nesting_depth {
+                return Err(ParserError::LimitExceeded(format!("more than {} nested groups", self.options.max_nesting_depth)));
+            }
+        }
+        return Ok(());
+    }
+
+    /// Parse the document, and report what the parser couldn't fully understand
+    pub fn parse_with_warnings(&mut self) -> Result<(RtfDocument, Vec<ParserWarning>), ParserError> {
+        let document = self.parse()?;
+        return Ok((document, mem::take(&mut self.warnings)));
+    }
+
+    /// Parse the header only (fonts, colors, styles, lists, character set), the tokens following the start of the body are ignored
+    pub fn parse_header_only(&mut self) -> Result<RtfHeader, ParserError> {
+        self.warnings.clear();
+        let mut body_start = BodyStart::default();
+        let end = self.tokens.iter().position(|token| body_start.is_reached(token)).unwrap_or(self.tokens.len());
+        // The groups open at the start of the body are closed
+        self.tokens.truncate(end);
+        self.tokens.extend(iter::repeat(Token::ClosingBracket).take(body_start.depth));
+        self.parsed_item = vec![false; self.tokens.len()];
+        self.check_document_validity()?;
+        self.check_limits()?;
+        return self.parse_header();
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "parse", level = "debug", skip_all, fields(tokens = self.tokens.len())))]
     pub fn parse(&mut self) -> Result<RtfDocument, ParserError> {
+        self.warnings.clear();
+        if self.options.fragment {
+            self.wrap_fragment();
+        } else if self.options.recover_unclosed_groups {
+            self.close_unclosed_groups();
+        }
         self.check_document_validity()?;
-        let mut document = RtfDocument::default(); // Init empty document
-                                                   // Traverse the document and consume the header groups (FontTable, StyleSheet, etc ...)
-        document.header = self.parse_header()?;
+        self.check_limits()?;
+        // Traverse the document and consume the header groups (FontTable, StyleSheet, etc ...)
+        let mut document = RtfDocument {
+            header: self.parse_header()?,
+            ..RtfDocument::default()
+        };
         // Init the state of the docuement. the stack is used to keep track of the different scope changes.
-        let mut state_stack: Vec<ParserState> = vec![ParserState::default()];
+        let defaults = document.header.defaults;
+        let mut state_stack: Vec<ParserState> = vec![ParserState {
+            painter: Painter::with_defaults(&defaults),
+            paragraph: Paragraph::with_defaults(&defaults),
+            ..ParserState::default()
+        }];
+        let mut document_state = DocumentState {
+            max_text_length: self.options.max_text_length,
+            ..DocumentState::default()
+        };
         // Parse the body
         let len = self.tokens.len();
         let mut i = 0;
@@ -169,130 +657,815 @@ impl<'a> Parser<'a> {
 
             match token {
                 Token::OpeningBracket => {
+                    if let Some(end) = self.handle_destination(i, &mut state_stack, &mut document_state, &mut document)? {
+                        // The whole group is skipped
+                        i = end + 1;
+                        continue;
+                    }
                     if let Some(last_state) = state_stack.last() {
                         state_stack.push(last_state.clone()); // Inherit from the last state properties
                     } else {
                         state_stack.push(ParserState::default());
                     }
+                    document_state.unicode_decoder.begin_group();
                 }
                 Token::ClosingBracket => {
-                    let state = state_stack.pop();
-                    if state.is_none() {
+                    let Some(state) = state_stack.pop() else {
                         return Err(ParserError::MalformedPainterStack);
+                    };
+                    // The label belongs to the whole paragraph, which goes on in the enclosing group
+                    if let Some(parent_state) = state_stack.last_mut().filter(|_| document_state.paragraph_started) {
+                        parent_state.paragraph.list_label = state.paragraph.list_label;
+                    }
+                    document_state.unicode_decoder.end_group();
+                    let depth = state_stack.len();
+                    for open_field in document_state.open_fields.iter_mut() {
+                        // End of the \fldrslt group : the result of the field is complete
+                        if open_field.result_depth.is_some_and(|result_depth| depth < result_depth) {
+                            open_field.result_depth = None;
+                            document.fields[open_field.index].end = document_state.visible_length;
+                        }
+                    }
+                    while document_state.open_fields.last().is_some_and(|open_field| depth < open_field.depth) {
+                        document_state.open_fields.pop();
+                    }
+                    if let Some(open_shape) = document_state.open_shape.as_mut() {
+                        if open_shape.text_depth.is_some_and(|text_depth| depth < text_depth) {
+                            open_shape.text_depth = None;
+                        }
+                        if depth < open_shape.depth {
+                            document_state.open_shape = None;
+                        }
                     }
                 }
                 Token::ControlSymbol((control_word, property)) => {
                     let Some(current_state) = state_stack.last_mut() else {
                         return Err(ParserError::MalformedPainterStack);
                     };
+                    if control_word.parameter_kind().is_some_and(|kind| !kind.accepts(property)) {
+                        add_warning(&mut self.warnings, ParserWarning::InvalidParameter(control_word.name().to_string(), *property));
+                    }
                     let current_painter = &mut current_state.painter;
                     let paragraph = &mut current_state.paragraph;
                     #[rustfmt::skip]  // For now, rustfmt does not support this kind of alignement
                     match control_word {
-                        ControlWord::ColorNumber        => current_painter.color_ref = property.get_value_as::<ColorRef>()?,
-                        ControlWord::FontNumber         => current_painter.font_ref = property.get_value_as::<FontRef>()?,
-                        ControlWord::FontSize           => current_painter.font_size = property.get_value_as::<u16>()?,
-                        ControlWord::Bold               => current_painter.bold = property.as_bool(),
-                        ControlWord::Italic             => current_painter.italic = property.as_bool(),
-                        ControlWord::Underline          => current_painter.underline = property.as_bool(),
-                        ControlWord::UnderlineNone      => current_painter.underline = false,
-                        ControlWord::Superscript        => current_painter.superscript = property.as_bool(),
-                        ControlWord::Subscript          => current_painter.subscript = property.as_bool(),
-                        ControlWord::Smallcaps          => current_painter.smallcaps = property.as_bool(),
-                        ControlWord::Strikethrough      => current_painter.strike = property.as_bool(),
+                        ControlWord::ColorNumber        => {
+                            current_painter.color_ref = property.get_value_as::<ColorRef>()?;
+                            // \cf0 is the automatic color
+                            if current_painter.color_ref != 0 && document.header.get_color(&current_painter.color_ref).is_none() {
+                                add_warning(&mut self.warnings, ParserWarning::InvalidColorRef(current_painter.color_ref));
+                                if self.options.clamp_invalid_refs {
+                                    current_painter.color_ref = 0;
+                                }
+                            }
+                        }
+                        ControlWord::FontNumber         => {
+                            current_painter.font_ref = property.get_value_as::<FontRef>()?;
+                            if !document.header.font_table.contains_key(&current_painter.font_ref) {
+                                add_warning(&mut self.warnings, ParserWarning::InvalidFontRef(current_painter.font_ref));
+                                if self.options.clamp_invalid_refs {
+                                    current_painter.font_ref = defaults.font_ref;
+                                }
+                            }
+                        }
+                        ControlWord::FontSize           => if let Some(font_size) = property.get_half_points() {
+                            current_painter.font_size = font_size;
+                        },
+                        ControlWord::Bold
+                            | ControlWord::Italic
+                            | ControlWord::Superscript
+                            | ControlWord::Subscript
+                            | ControlWord::NoSuperSub
+                            | ControlWord::Smallcaps
+                            | ControlWord::Strikethrough
+                            | ControlWord::Outline
+                            | ControlWord::Shadow
+                            | ControlWord::Caps
+                            | ControlWord::Hidden
+                            | ControlWord::Deleted    => {
+                            current_painter.apply_toggle(control_word, property);
+                        }
+                        ControlWord::Underline
+                            | ControlWord::UnderlineDouble
+                            | ControlWord::UnderlineWord
+                            | ControlWord::UnderlineDotted
+                            | ControlWord::UnderlineDash
+                            | ControlWord::UnderlineDashDot
+                            | ControlWord::UnderlineDashDotDot
+                            | ControlWord::UnderlineLongDash
+                            | ControlWord::UnderlineThick
+                            | ControlWord::UnderlineWave
+                            | ControlWord::UnderlineDoubleWave => current_painter.underline = Underline::from(control_word, property),
+                        ControlWord::UnderlineNone      => current_painter.underline = Underline::None,
+                        ControlWord::UnderlineColor     => {
+                            current_painter.underline_color = property.get_value_as::<ColorRef>()?;
+                            if current_painter.underline_color != 0 && document.header.get_color(&current_painter.underline_color).is_none() {
+                                add_warning(&mut self.warnings, ParserWarning::InvalidColorRef(current_painter.underline_color));
+                                if self.options.clamp_invalid_refs {
+                                    current_painter.underline_color = 0;
+                                }
+                            }
+                        }
+                        ControlWord::BaselineUp         => current_painter.baseline_offset = property.get_value_or(6),
+                        ControlWord::BaselineDown       => current_painter.baseline_offset = -property.get_value_or(6),
+                        ControlWord::Expand             => current_painter.character_spacing = Twips(property.get_value().saturating_mul(5)), // 1 quarter point = 5 twips
+                        ControlWord::ExpandTwips        => current_painter.character_spacing = Twips(property.get_value()),
+                        ControlWord::Language           => current_painter.language = property.get_value_as::<u16>().ok(),
+                        ControlWord::EastAsianLanguage  => current_painter.east_asian_language = property.get_value_as::<u16>().ok(),
                         // Paragraph
-                        ControlWord::Pard               => *paragraph = Paragraph::default(), // Reset the par
-                        ControlWord::Plain              => *current_painter = Painter::default(), // Reset the painter
-                        ControlWord::ParDefTab          => paragraph.tab_width = property.get_value(),
+                        ControlWord::Pard               => {
+                            *paragraph = Paragraph::with_defaults(&defaults); // Reset the par
+                            current_state.table_level = 0;
+                        }
+                        ControlWord::Plain              => *current_painter = Painter::with_defaults(&defaults), // Reset the painter
+                        ControlWord::ParDefTab          => paragraph.tab_width = property.get_twips(),
+                        ControlWord::ParStyle           => {
+                            paragraph.style = property.get_value_as::<StyleRef>()?;
+                            // \s0 is the Normal style, even without stylesheet
+                            if paragraph.style != 0 && !document.header.stylesheet.contains_key(&paragraph.style) {
+                                add_warning(&mut self.warnings, ParserWarning::InvalidStyleRef(paragraph.style));
+                                if self.options.clamp_invalid_refs {
+                                    paragraph.style = 0;
+                                }
+                            }
+                        }
+                        ControlWord::CharStyle          => {
+                            let style_ref = property.get_value_as::<StyleRef>()?;
+                            current_painter.char_style_ref = Some(style_ref);
+                            if !document.header.character_styles.contains_key(&style_ref) {
+                                add_warning(&mut self.warnings, ParserWarning::InvalidStyleRef(style_ref));
+                                if self.options.clamp_invalid_refs {
+                                    current_painter.char_style_ref = None;
+                                }
+                            }
+                        }
                         ControlWord::LeftAligned
                             | ControlWord::RightAligned
                             | ControlWord::Center
                             | ControlWord::Justify      => paragraph.alignment = Alignment::from(control_word),
-                        ControlWord::SpaceBefore        => paragraph.spacing.before = property.get_value(),
-                        ControlWord::SpaceAfter         => paragraph.spacing.after = property.get_value(),
+                        // A negative first line indent is a hanging indent
+                        ControlWord::FirstLineIdent     => paragraph.indent.first_line = property.get_twips(),
+                        // \lin and \rin are the leading and trailing indents, the same as \li and \ri for a left to right paragraph
+                        ControlWord::LeftIndent
+                            | ControlWord::LeftIndentLogical => paragraph.indent.left = property.get_twips(),
+                        ControlWord::RightIndent
+                            | ControlWord::RightIndentLogical => paragraph.indent.right = property.get_twips(),
+                        ControlWord::SpaceBefore        => paragraph.spacing.before = property.get_twips(),
+                        ControlWord::SpaceAfter         => paragraph.spacing.after = property.get_twips(),
                         ControlWord::SpaceBetweenLine   => paragraph.spacing.between_line = SpaceBetweenLine::from(property.get_value()),
                         ControlWord::SpaceLineMul       => paragraph.spacing.line_multiplier = property.get_value(),
-                        ControlWord::UnicodeIgnoreCount => current_state.unicode_ignore_count = property.get_value(),
-                        ControlWord::Unicode            => {
-                            let mut unicodes = Vec::with_capacity(current_state.unicode_ignore_count as usize + 1); // try to avoid realocation due to fallback unicodes
-                            if let Ok(unicode) = property.get_unicode_value() {
-                                unicodes.push(unicode);
+                        ControlWord::TabRight
+                            | ControlWord::TabCenter
+                            | ControlWord::TabDecimal   => current_state.pending_tab_stop.kind = TabKind::from(control_word),
+                        ControlWord::TabLeaderDot
+                            | ControlWord::TabLeaderHyphen
+                            | ControlWord::TabLeaderUnderline
+                            | ControlWord::TabLeaderThick
+                            | ControlWord::TabLeaderEqual => current_state.pending_tab_stop.leader = TabLeader::from(control_word),
+                        ControlWord::TabPosition
+                            | ControlWord::TabBar       => {
+                            let mut tab_stop = mem::take(&mut current_state.pending_tab_stop);
+                            tab_stop.position = property.get_twips();
+                            if *control_word == ControlWord::TabBar {
+                                tab_stop.kind = TabKind::Bar;
                             }
-                            // Get the following unicode in case of compounds characters
-                            while i + 1 < len {
-                                // We should not check if the tokens has already been parsed, because we are looking for the following token in the document
-                                if let Token::ControlSymbol((ControlWord::Unicode, property)) = &self.tokens[i + 1] {
-                                    if let Ok(unicode) = property.get_unicode_value() {
-                                        unicodes.push(unicode);
-                                    }
-                                    i += 1;
-                                } else {
-                                    break;
+                            paragraph.tab_stops.push(tab_stop);
+                        }
+                        ControlWord::FieldResult        => {
+                            let depth = state_stack.len();
+                            if let Some(open_field) = document_state.open_fields.last_mut() {
+                                open_field.result_depth = Some(depth);
+                            }
+                        }
+                        // \fldinst is not always written as an ignorable destination
+                        ControlWord::FieldInstruction   => {
+                            let (text, form_field) = self.read_field_instruction(&mut i);
+                            let depth = state_stack.len();
+                            Self::parse_body_destination(control_word, text, depth, &mut document_state, &mut document, &mut self.warnings);
+                            Self::set_form_field(form_field, &mut document);
+                        }
+                        ControlWord::Picture            => document.images.push(self.read_picture(&mut i, document_state.visible_length)?),
+                        ControlWord::NonShapePicture    => self.skip_group(&mut i), // Fallback of a \shppict picture
+                        ControlWord::NoNestedTables     => self.skip_group(&mut i), // Fallback of the nested tables, already parsed
+                        ControlWord::Shape
+                            | ControlWord::DrawingObject => {
+                            document.shapes.push(Shape { position: document_state.visible_length, ..Shape::default() });
+                            document_state.open_shape = Some(OpenShape { depth: state_stack.len(), text_depth: None });
+                        }
+                        ControlWord::ShapeResult        => self.skip_group(&mut i), // Fallback of the shape, as a drawing object or a picture
+                        ControlWord::ShapeProperty      => {
+                            let property = self.read_shape_property(&mut i);
+                            if let Some(shape) = document.shapes.last_mut().filter(|_| document_state.open_shape.is_some()) {
+                                if property.name == "shapeType" {
+                                    shape.shape_type = property.value.trim().parse().unwrap_or_default();
                                 }
+                                shape.properties.push(property);
                             }
-                            if unicodes.len() > 0 {
-                                // Handle the fallback unicode (\uc2 \u0000 'FA 'FB)
-                                let mut ignore_mask = vec![true; unicodes.len()];
-                                let mut ignore_counter = 0;
-                                for i in 1..unicodes.len() {
-                                    if unicodes[i] <= 255 && ignore_counter < current_state.unicode_ignore_count {
-                                        ignore_counter += 1;
-                                        ignore_mask[i] = false;
-                                    } else {
-                                        ignore_counter = 0;
-                                    }
+                        }
+                        ControlWord::ShapeText
+                            | ControlWord::DrawingTextBoxText => {
+                            let depth = state_stack.len();
+                            if let Some(open_shape) = document_state.open_shape.as_mut() {
+                                open_shape.text_depth = Some(depth);
+                            }
+                        }
+                        control_word @ (ControlWord::ShapeLeft
+                            | ControlWord::ShapeTop
+                            | ControlWord::ShapeRight
+                            | ControlWord::ShapeBottom
+                            | ControlWord::DrawingX
+                            | ControlWord::DrawingY
+                            | ControlWord::DrawingWidth
+                            | ControlWord::DrawingHeight
+                            | ControlWord::DrawingLine
+                            | ControlWord::DrawingRectangle
+                            | ControlWord::DrawingEllipse
+                            | ControlWord::DrawingTextBox) => {
+                            if let Some(shape) = document.shapes.last_mut().filter(|_| document_state.open_shape.is_some()) {
+                                let value = Twips(property.get_value());
+                                #[rustfmt::skip]
+                                match control_word {
+                                    ControlWord::ShapeLeft | ControlWord::DrawingX   => shape.left = value,
+                                    ControlWord::ShapeTop | ControlWord::DrawingY    => shape.top = value,
+                                    ControlWord::ShapeRight                          => shape.right = value,
+                                    ControlWord::ShapeBottom                         => shape.bottom = value,
+                                    // The size of a drawing object comes after its position
+                                    ControlWord::DrawingWidth                        => shape.right = shape.left + value,
+                                    ControlWord::DrawingHeight                       => shape.bottom = shape.top + value,
+                                    _                                                => shape.shape_type = Shape::drawing_object_type(control_word).unwrap_or_default(),
+                                };
+                            }
+                        }
+                        control_word if ShapeAnchor::from(control_word).is_some() => {
+                            if let (Some(shape), Some((horizontal, anchor))) = (document.shapes.last_mut().filter(|_| document_state.open_shape.is_some()), ShapeAnchor::from(control_word)) {
+                                if horizontal {
+                                    shape.horizontal_anchor = anchor;
+                                } else {
+                                    shape.vertical_anchor = anchor;
                                 }
-                                let mut ignore_mask_iter = ignore_mask.iter();
-                                unicodes.retain(|_| *ignore_mask_iter.next().unwrap());
-                                // Convert the unicode to string
-                                let str = String::from_utf16(unicodes.as_slice()).unwrap();
-                                Self::add_text_to_document(&str, &state_stack, &mut document)?;
+                            }
+                        }
+                        // The Unicode version of the destination (\ud) is preferred to the ANSI one, which is the first group
+                        ControlWord::UnicodePair => {
+                            // Line breaks and whitespaces may separate \upr from its group
+                            let group_index = self.tokens[i + 1..]
+                                .iter()
+                                .position(|token| !matches!(token, Token::CRLF) && !matches!(token, Token::PlainText(text) if text.trim().is_empty()))
+                                .map(|offset| i + 1 + offset);
+                            if let Some(group_index) = group_index.filter(|index| self.tokens[*index] == Token::OpeningBracket) {
+                                i = group_index;
+                                self.skip_group(&mut i);
+                                i += 1; // The closing bracket of the skipped group
+                            }
+                        }
+                        ControlWord::Par                => Self::end_paragraph(&mut document_state, &mut document),
+                        ControlWord::PageBreakBefore    => paragraph.page_break_before = property.as_bool(),
+                        ControlWord::KeepTogether       => paragraph.keep_together = property.as_bool(),
+                        ControlWord::KeepWithNext       => paragraph.keep_with_next = property.as_bool(),
+                        ControlWord::WidowControl       => paragraph.widow_control = true,
+                        ControlWord::NoWidowControl     => paragraph.widow_control = false,
+                        ControlWord::OutlineLevel       => paragraph.outline_level = property.get_value_as::<u8>().ok().filter(|level| *level <= 8),
+                        control_word if Frame::is_frame_control_word(control_word) => paragraph.frame.get_or_insert_with(Frame::default).apply(control_word, property),
+                        ControlWord::Page
+                            | ControlWord::SoftPage     => document.page_breaks.push(PageBreak {
+                            position: document_state.visible_length,
+                            soft: *control_word == ControlWord::SoftPage,
+                        }),
+                        ControlWord::ListOverrideRef    => paragraph.list_override = property.get_value_as::<ListOverrideRef>()?,
+                        ControlWord::ListLevelIndex     => paragraph.list_level = property.get_value().clamp(0, LIST_LEVEL_COUNT as i32 - 1) as u8,
+                        ControlWord::InTable            => current_state.table_level = current_state.table_level.max(1),
+                        ControlWord::TableNestingLevel  => {
+                            // A negative level (ex: \itap-1) is clamped to 0 : the paragraph is not in a table
+                            current_state.table_level = property.get_value_as::<u16>().unwrap_or_else(|_| {
+                                add_warning(&mut self.warnings, ParserWarning::InvalidParameter(control_word.name().to_string(), *property));
+                                property.get_value().clamp(0, u16::MAX as i32) as u16
+                            });
+                        }
+                        control_word if TableBuilder::is_table_control_word(control_word) => {
+                            let (level, position) = (current_state.table_level as usize, document_state.visible_length);
+                            document_state.table_builder.apply(control_word, property, level, position, &mut document.tables);
+                            let separator = match control_word {
+                                ControlWord::TableCell | ControlWord::TableNestedCell => self.options.cell_separator,
+                                ControlWord::TableRow | ControlWord::TableNestedRow => self.options.row_separator,
+                                _ => None,
+                            };
+                            if let Some(separator) = separator {
+                                Self::add_separator_to_document(separator, &state_stack, &mut document_state, &mut document)?;
+                            }
+                        }
+                        ControlWord::UnicodeIgnoreCount => current_state.unicode_ignore_count = property.get_value(),
+                        ControlWord::Unicode            => {
+                            if let Some(char) = document_state.unicode_decoder.decode(property, current_state.unicode_ignore_count)? {
+                                Self::add_text_to_document(char.encode_utf8(&mut [0; 4]), self.get_source_span(i), &mut state_stack, &mut document_state, &mut document)?;
+                            }
+                        }
+                        ControlWord::HexByte            => {
+                            let byte = property.get_value_as::<u8>()?;
+                            let is_fallback = document_state.unicode_decoder.skip_char()?;
+                            let codepage = Self::get_codepage(current_painter.font_ref, &document.header);
+                            let char = if is_fallback { None } else { document_state.byte_decoder.decode(codepage, byte) };
+                            if let Some(char) = char {
+                                Self::add_text_to_document(char.encode_utf8(&mut [0; 4]), self.get_source_span(i), &mut state_stack, &mut document_state, &mut document)?;
+                            }
+                        }
+                        control_word if control_word.get_text().is_some() => {
+                            // The char of a control word is one char of the fallback of a \uN
+                            let is_fallback = document_state.unicode_decoder.skip_char()?;
+                            if let Some(text) = control_word.get_text().filter(|_| !is_fallback) {
+                                Self::add_text_to_document(text, self.get_source_span(i), &mut state_stack, &mut document_state, &mut document)?;
+                            }
+                        }
+                        ControlWord::PageNumber
+                            | ControlWord::CurrentDate
+                            | ControlWord::LongDate
+                            | ControlWord::AbbreviatedDate
+                            | ControlWord::CurrentTime if current_painter.is_visible() && !document_state.is_in_text_box() => {
+                            // A placeholder without text, the value is only known when the document is displayed
+                            document.fields.push(Field::new(control_word.name().to_string(), document_state.visible_length));
+                        }
+                        ControlWord::Unknown(name)      => {
+                            let action = match self.unknown_control_word_handler.as_mut() {
+                                Some(handler) => handler(name, *property, current_painter, paragraph),
+                                None => ControlWordAction::Ignored,
+                            };
+                            match action {
+                                ControlWordAction::Ignored => add_warning(&mut self.warnings, ParserWarning::UnknownControlWord(name.to_string())),
+                                ControlWordAction::Handled => {}
+                                ControlWordAction::InsertText(text) => Self::add_text_to_document(&text, self.get_source_span(i), &mut state_stack, &mut document_state, &mut document)?,
                             }
                         }
                         // Others tokens
                         _ => {}
                     };
                 }
-                Token::PlainText(text) => Self::add_text_to_document(*text, &state_stack, &mut document)?,
-                Token::CRLF => Self::add_text_to_document("\n", &state_stack, &mut document)?,
+                Token::PlainText(text) => {
+                    let codepage = Self::get_codepage(state_stack.last().map_or(0, |state| state.painter.font_ref), &document.header);
+                    let (char, text) = document_state.byte_decoder.decode_text(codepage, text);
+                    if let Some(char) = char {
+                        Self::add_text_to_document(char.encode_utf8(&mut [0; 4]), self.get_source_span(i), &mut state_stack, &mut document_state, &mut document)?;
+                    }
+                    let text = document_state.unicode_decoder.skip_fallback(text)?;
+                    // The fallback skipped is at the start of the text
+                    let source_span = self.get_source_span(i).map(|span| SourceSpan { start: span.end - text.len(), ..span });
+                    Self::add_text_to_document(text, source_span, &mut state_stack, &mut document_state, &mut document)?;
+                }
+                Token::CRLF => {
+                    Self::add_text_to_document("\n", self.get_source_span(i), &mut state_stack, &mut document_state, &mut document)?;
+                    document_state.paragraph_started = false; // An escaped line return is a \par, ending the paragraph with its line break
+                }
                 Token::IgnorableDestination => {
-                    return Err(ParserError::IgnorableDestinationParsingError);
+                    // Only the destinations depending on their position in the body are left by parse_header,
+                    // and the ones whose \* doesn't follow a bracket
+                    let Some(Token::ControlSymbol((destination, _))) = self.tokens.get(i + 1) else {
+                        // A \* without destination : the rest of the group is skipped
+                        self.read_destination_text(&mut i);
+                        add_warning(&mut self.warnings, ParserWarning::SkippedDestination(r"\*".to_string()));
+                        i += 1;
+                        continue;
+                    };
+                    // The nested table properties, the pictures, the Unicode destinations and the shapes are parsed as the rest of the body
+                    if !matches!(
+                        destination,
+                        ControlWord::NestedTableProperties | ControlWord::ShapePicture | ControlWord::UnicodeDestination | ControlWord::ShapeInstruction | ControlWord::DrawingObject
+                    ) {
+                        i += 1;
+                        let (text, form_field) = match destination {
+                            ControlWord::FieldInstruction => self.read_field_instruction(&mut i),
+                            _ => (self.read_destination_text(&mut i), None),
+                        };
+                        Self::parse_body_destination(destination, text, state_stack.len(), &mut document_state, &mut document, &mut self.warnings);
+                        Self::set_form_field(form_field, &mut document);
+                    }
                 }
+                Token::Binary(_) => {} // Binary data outside of a picture
                 Token::Empty => return Err(ParserError::ParseEmptyToken),
             };
             i += 1;
         }
+        document_state.table_builder.finish(document_state.visible_length, &mut document.tables);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            style_blocks = document.body.len(),
+            text_length = document_state.text_length,
+            tables = document.tables.len(),
+            images = document.images.len(),
+            fields = document.fields.len(),
+            warnings = self.warnings.len(),
+            "body parsed"
+        );
         return Ok(document);
     }
 
-    fn add_text_to_document(text: &str, state_stack: &Vec<ParserState>, document: &mut RtfDocument) -> Result<(), ParserError> {
+    // Read the text of the destination whose control word is at index until the end of its group
+    // The index is moved to the token before the closing bracket, which is handled by the next iteration of the parser
+    fn read_destination_text(&self, index: &mut usize) -> String {
+        let mut text = String::new();
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(*index + 1) {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => break,
+                Token::ClosingBracket => depth -= 1,
+                Token::PlainText(content) => text.push_str(content),
+                _ => {}
+            }
+            *index += 1;
+        }
+        return text;
+    }
+
+    // Read the text of the \fldinst destination whose control word is at index, and its form field
+    // The nested ignorable destinations (ex: \formfield or \datafield) are not part of the instruction
+    fn read_field_instruction(&self, index: &mut usize) -> (String, Option<FormField>) {
+        let mut text = String::new();
+        let mut form_field = None;
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(*index + 1) {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => break,
+                Token::ClosingBracket => depth -= 1,
+                Token::PlainText(content) => text.push_str(content),
+                Token::IgnorableDestination => {
+                    *index += 1;
+                    if let Some(Token::ControlSymbol((ControlWord::FormField, _))) = self.tokens.get(*index + 1) {
+                        *index += 1;
+                        form_field = Some(self.read_form_field(index));
+                    } else {
+                        self.read_destination_text(index);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            *index += 1;
+        }
+        return (text, form_field);
+    }
+
+    // Read the form field whose \formfield control word is at index until the end of its group
+    // The index is moved to the token before the closing bracket
+    fn read_form_field(&self, index: &mut usize) -> FormField {
+        let mut form_field = FormField::default();
+        let mut depth = 0;
+        // The destination of the text (\ffname, \ffdeftext or \ffl) with the depth of its group
+        let mut text_destination: Option<(&ControlWord, usize)> = None;
+        while let Some(token) = self.tokens.get(*index + 1) {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => break,
+                Token::ClosingBracket => {
+                    if text_destination.is_some_and(|(_, destination_depth)| destination_depth == depth) {
+                        text_destination = None;
+                    }
+                    depth -= 1;
+                }
+                Token::ControlSymbol((control_word, property)) => match control_word {
+                    ControlWord::FormFieldType => form_field.form_field_type = FormFieldType::from(property.get_value()),
+                    ControlWord::FormFieldResult => form_field.result = property.get_value_as::<u16>().ok().filter(|result| *result != FormField::NO_RESULT),
+                    ControlWord::FormFieldDefaultResult => form_field.default_result = property.get_value_as::<u16>().unwrap_or_default(),
+                    ControlWord::FormFieldMaxLength => form_field.max_length = property.get_value_as::<u16>().unwrap_or_default(),
+                    ControlWord::FormFieldName | ControlWord::FormFieldDefaultText | ControlWord::FormFieldListItem => {
+                        if *control_word == ControlWord::FormFieldListItem {
+                            form_field.items.push(String::new());
+                        }
+                        text_destination = Some((control_word, depth));
+                    }
+                    _ => {}
+                },
+                Token::PlainText(text) => match text_destination {
+                    Some((ControlWord::FormFieldName, _)) => form_field.name.push_str(text),
+                    Some((ControlWord::FormFieldDefaultText, _)) => form_field.default_text.push_str(text),
+                    Some((ControlWord::FormFieldListItem, _)) => form_field.items.last_mut().into_iter().for_each(|item| item.push_str(text)),
+                    _ => {}
+                },
+                _ => {}
+            }
+            *index += 1;
+        }
+        return form_field;
+    }
+
+    // Read the picture whose \pict control word is at index until the end of its group
+    // The index is moved to the token before the closing bracket, which is handled by the next iteration of the parser
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), fields(size = tracing::field::Empty)))]
+    fn read_picture(&self, index: &mut usize, position: usize) -> Result<Image, ParserError> {
+        let mut image = Image { position, ..Image::default() };
+        let mut depth = 0;
+        let mut high_nibble: Option<u8> = None;
+        while let Some(token) = self.tokens.get(*index + 1) {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => break,
+                Token::ClosingBracket => depth -= 1,
+                Token::ControlSymbol((control_word, property)) => match control_word {
+                    ControlWord::PictureWidth => image.width = property.get_value(),
+                    ControlWord::PictureHeight => image.height = property.get_value(),
+                    ControlWord::PictureGoalWidth => image.goal_width = property.get_twips(),
+                    ControlWord::PictureGoalHeight => image.goal_height = property.get_twips(),
+                    ControlWord::PictureScaleX => image.scale_x = property.get_value(),
+                    ControlWord::PictureScaleY => image.scale_y = property.get_value(),
+                    ControlWord::PictureCropLeft => image.crop.left = property.get_twips(),
+                    ControlWord::PictureCropRight => image.crop.right = property.get_twips(),
+                    ControlWord::PictureCropTop => image.crop.top = property.get_twips(),
+                    ControlWord::PictureCropBottom => image.crop.bottom = property.get_twips(),
+                    control_word => {
+                        if let Some(format) = ImageFormat::from(control_word) {
+                            image.format = format;
+                        }
+                    }
+                },
+                // The data is written in hexadecimal, possibly split across several lines
+                Token::PlainText(hex) if depth == 0 => {
+                    for digit in hex.chars().filter_map(|c| c.to_digit(16)) {
+                        match high_nibble.take() {
+                            Some(high) => image.data.push(high << 4 | digit as u8),
+                            None => high_nibble = Some(digit as u8),
+                        }
+                    }
+                }
+                Token::Binary(data) if depth == 0 => image.data.extend_from_slice(data),
+                _ => {}
+            }
+            if image.data.len() > self.options.max_image_size {
+                return Err(ParserError::LimitExceeded(format!("picture larger than {} bytes", self.options.max_image_size)));
+            }
+            *index += 1;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("size", image.data.len());
+        return Ok(image);
+    }
+
+    // Read the property ({\sp{\sn name}{\sv value}}) whose \sp control word is at index until the end of its group
+    // The index is moved to the token before the closing bracket, which is handled by the next iteration of the parser
+    fn read_shape_property(&self, index: &mut usize) -> ShapeProperty {
+        let mut property = ShapeProperty::default();
+        let mut depth = 0;
+        // The \sn or \sv group being read
+        let mut destination: Option<&ControlWord> = None;
+        while let Some(token) = self.tokens.get(*index + 1) {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => break,
+                Token::ClosingBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        destination = None;
+                    }
+                }
+                Token::ControlSymbol((control_word @ (ControlWord::ShapePropertyName | ControlWord::ShapePropertyValue), _)) if depth == 1 => destination = Some(control_word),
+                // The value may be a group, like a picture, whose text is skipped
+                Token::PlainText(text) if depth == 1 => match destination {
+                    Some(ControlWord::ShapePropertyName) => property.name.push_str(text),
+                    Some(ControlWord::ShapePropertyValue) => property.value.push_str(text),
+                    _ => {}
+                },
+                _ => {}
+            }
+            *index += 1;
+        }
+        return property;
+    }
+
+    // Skip the group of the control word at index, the index is moved to the token before the closing bracket
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn skip_group(&self, index: &mut usize) {
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(*index + 1) {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket if depth == 0 => break,
+                Token::ClosingBracket => depth -= 1,
+                _ => {}
+            }
+            *index += 1;
+        }
+    }
+
+    // Call the handler of the destination group starting at index, if it has one
+    // Returns the index of the closing bracket of the group if it is skipped
+    fn handle_destination(&mut self, index: usize, state_stack: &mut [ParserState], document_state: &mut DocumentState, document: &mut RtfDocument) -> Result<Option<usize>, ParserError> {
+        if self.destination_handlers.is_empty() {
+            return Ok(None);
+        }
+        let Some(name) = self.tokens.get(index + 1..).and_then(|tokens| {
+            tokens.iter().take(2).find_map(|token| match token {
+                Token::ControlSymbol((control_word, _)) => Some(control_word.name()),
+                _ => None,
+            })
+        }) else {
+            return Ok(None);
+        };
+        // Only the groups having a handler are scanned to their end
+        if !self.destination_handlers.contains_key(name) {
+            return Ok(None);
+        }
+        let mut end = index;
+        self.skip_group(&mut end);
+        // The closing bracket
+        end += 1;
+        // An ignorable destination left for its handler is skipped as it would have been by parse_header
+        let is_skipped = self.tokens.get(index + 1) == Some(&Token::IgnorableDestination) && !self.is_position_dependent(index);
+        let Some(handler) = self.destination_handlers.get_mut(name) else {
+            return Ok(None);
+        };
+        match handler(&self.tokens[index..(end + 1).min(self.tokens.len())]) {
+            DestinationAction::Ignored if is_skipped => {
+                add_warning(&mut self.warnings, ParserWarning::SkippedDestination(name.to_string()));
+            }
+            DestinationAction::Ignored => return Ok(None),
+            DestinationAction::Handled => {}
+            DestinationAction::InsertText(text) => Self::add_text_to_document(&text, None, state_stack, document_state, document)?,
+            DestinationAction::Attach(value) => document.custom_destinations.push(CustomDestination {
+                name: name.to_string(),
+                value,
+                position: document_state.visible_length,
+            }),
+        }
+        return Ok(Some(end));
+    }
+
+    // The depth is the one of the destination group
+    // The unknown destinations are skipped, wherever they are
+    fn parse_body_destination(destination: &ControlWord, text: String, depth: usize, document_state: &mut DocumentState, document: &mut RtfDocument, warnings: &mut Vec<ParserWarning>) {
+        let position = document_state.visible_length;
+        match destination {
+            ControlWord::BookmarkStart => document.bookmarks.push(Bookmark {
+                name: text.trim().to_string(),
+                start: position,
+                end: None,
+            }),
+            ControlWord::BookmarkEnd => {
+                let name = text.trim();
+                if let Some(bookmark) = document.bookmarks.iter_mut().rev().find(|bookmark| bookmark.name == name && bookmark.end.is_none()) {
+                    bookmark.end = Some(position);
+                }
+            }
+            ControlWord::FieldInstruction => {
+                document_state.open_fields.push(OpenField {
+                    index: document.fields.len(),
+                    depth: depth.saturating_sub(1),
+                    result_depth: None,
+                });
+                document.fields.push(Field::new(text, position));
+            }
+            _ => add_warning(warnings, ParserWarning::SkippedDestination(destination.name().to_string())),
+        }
+    }
+
+    // The form field belongs to the field just opened by its \fldinst destination
+    fn set_form_field(form_field: Option<FormField>, document: &mut RtfDocument) {
+        if let (Some(form_field), Some(field)) = (form_field, document.fields.last_mut()) {
+            field.form_field = Some(form_field);
+        }
+    }
+
+    // The last block ends the paragraph if it has some text, the next text starting a new block
+    fn end_paragraph(document_state: &mut DocumentState, document: &mut RtfDocument) {
+        if document_state.is_in_text_box() {
+            if let Some(style_block) = document.shapes.last_mut().and_then(|shape| shape.text_box.last_mut()) {
+                style_block.paragraph_end = true;
+            }
+            return;
+        }
+        if document_state.paragraph_started {
+            if let Some(style_block) = document.body.last_mut() {
+                style_block.paragraph_end = true;
+            }
+        }
+        document_state.paragraph_started = false;
+    }
+
+    fn add_text_to_document(text: &str, source_span: Option<SourceSpan>, state_stack: &mut [ParserState], document_state: &mut DocumentState, document: &mut RtfDocument) -> Result<(), ParserError> {
+        // Ex: a text made only of the fallback of a unicode char
+        if text.is_empty() {
+            return Ok(());
+        }
+        document_state.text_length += text.len();
+        if document_state.text_length > document_state.max_text_length {
+            return Err(ParserError::LimitExceeded(format!("text longer than {} bytes", document_state.max_text_length)));
+        }
+        if document_state.is_in_text_box() {
+            let (Some(current_state), Some(shape)) = (state_stack.last(), document.shapes.last_mut()) else {
+                return Err(ParserError::MalformedPainterStack);
+            };
+            Self::push_style_block(&mut shape.text_box, text, source_span, &current_state.painter, &current_state.paragraph);
+            return Ok(());
+        }
+        if !document_state.paragraph_started {
+            // First text of the paragraph : compute its list label
+            document_state.paragraph_started = true;
+            let Some(current_state) = state_stack.last_mut() else {
+                return Err(ParserError::MalformedPainterStack);
+            };
+            let paragraph = &mut current_state.paragraph;
+            paragraph.list_label = if paragraph.list_override > 0 {
+                document_state.list_numbering.next_label(&document.header, paragraph.list_override, paragraph.list_level)
+            } else {
+                String::new()
+            };
+        }
         let Some(current_state) = state_stack.last() else {
             return Err(ParserError::MalformedPainterStack);
         };
         let current_painter = &current_state.painter;
         let paragraph = &current_state.paragraph;
-        let last_style_group = document.body.last_mut();
+        let position = document_state.visible_length;
+        if current_painter.is_visible() {
+            document_state.visible_length += text.chars().count();
+            // The text is part of the result of the fields being computed
+            for open_field in document_state.open_fields.iter().filter(|open_field| open_field.result_depth.is_some()) {
+                document.fields[open_field.index].result.push_str(text);
+            }
+        }
+        document_state.table_builder.add_text(current_state.table_level as usize, text, position, &mut document.tables);
+        Self::push_style_block(&mut document.body, text, source_span, current_painter, paragraph);
+        return Ok(());
+    }
+
+    // The code page of the bytes written as \'hh : the one of the character set of the font, or else the one of the document
+    pub(crate) fn get_codepage(font_ref: FontRef, header: &RtfHeader) -> u16 {
+        return header
+            .font_table
+            .get(&font_ref)
+            .and_then(|font| codepage::from_charset(font.character_set))
+            .filter(|codepage| codepage::is_supported(*codepage))
+            .unwrap_or(header.character_set.codepage());
+    }
+
+    // Add a separator of the cells or the rows of a table to the body, outside of the text of the cells
+    fn add_separator_to_document(separator: char, state_stack: &[ParserState], document_state: &mut DocumentState, document: &mut RtfDocument) -> Result<(), ParserError> {
+        let Some(current_state) = state_stack.last() else {
+            return Err(ParserError::MalformedPainterStack);
+        };
+        let mut buffer = [0; 4];
+        let text: &str = separator.encode_utf8(&mut buffer);
+        document_state.text_length += text.len();
+        if document_state.text_length > document_state.max_text_length {
+            return Err(ParserError::LimitExceeded(format!("text longer than {} bytes", document_state.max_text_length)));
+        }
+        if document_state.is_in_text_box() {
+            if let Some(shape) = document.shapes.last_mut() {
+                Self::push_style_block(&mut shape.text_box, text, None, &current_state.painter, &current_state.paragraph);
+            }
+            return Ok(());
+        }
+        if current_state.painter.is_visible() {
+            document_state.visible_length += 1;
+        }
+        Self::push_style_block(&mut document.body, text, None, &current_state.painter, &current_state.paragraph);
+        return Ok(());
+    }
+
+    // Add the text to the last block of the body, or to a new one if its style is different
+    fn push_style_block(body: &mut Vec<StyleBlock>, text: &str, source_span: Option<SourceSpan>, current_painter: &Painter, paragraph: &Paragraph) {
+        let last_style_group = body.last_mut();
         // If the painter is the same as the previous one, merge the two block.
         if let Some(group) = last_style_group {
-            if group.painter.eq(current_painter) && group.paragraph.eq(&paragraph) {
+            if group.painter.eq(current_painter) && group.paragraph.eq(paragraph) && !group.paragraph_end {
+                if let Some(source) = source_span {
+                    let text_span = SourceSpan {
+                        start: group.text.len(),
+                        end: group.text.len() + text.len(),
+                    };
+                    let is_literal = |mapping: &SourceMapping| mapping.text.end - mapping.text.start == mapping.source.end - mapping.source.start;
+                    let mapping = SourceMapping { text: text_span, source };
+                    match group.source_spans.last_mut() {
+                        // Extend the previous mapping if both are text copied as is from contiguous parts of the source
+                        Some(last) if last.source.end == source.start && is_literal(last) && is_literal(&mapping) => {
+                            last.text.end = text_span.end;
+                            last.source.end = source.end;
+                        }
+                        _ => group.source_spans.push(mapping),
+                    }
+                }
                 group.text.push_str(text);
-                return Ok(());
+                return;
             }
         }
         // Else, push another StyleBlock on the stack with its own painter
-        document.body.push(StyleBlock {
+        body.push(StyleBlock {
             painter: current_painter.clone(),
             paragraph: paragraph.clone(),
             text: String::from(text),
+            paragraph_end: false,
+            source_spans: source_span
+                .map(|source| SourceMapping {
+                    text: SourceSpan { start: 0, end: text.len() },
+                    source,
+                })
+                .into_iter()
+                .collect(),
         });
-        return Ok(());
     }
 
-    fn get_token_at(&'a self, index: usize) -> Option<&'a Token<'a>> {
+    fn get_token_at(&self, index: usize) -> Option<&Token<'a>> {
         return self.tokens.get(index);
     }
 
     // Get a view of the next token after cursor
+    #[allow(dead_code)]
     fn get_next_token(&'a self) -> Option<&'a Token<'a>> {
         return self.get_token_at(self.cursor);
     }
@@ -345,13 +1518,49 @@ impl<'a> Parser<'a> {
     }
 
     // Consume all the tokens inside a group ({ ... }) and returns the includes ones
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self), fields(start = self.cursor)))]
     fn consume_group(&mut self) -> Vec<Token<'a>> {
         // TODO: check the the token at cursor is indeed an OpeningBracket
         self.consume_token_at(self.cursor); // Consume the opening bracket
-        return self.consume_tokens_until_matching_bracket();
+        let tokens = self.consume_tokens_until_matching_bracket();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(tokens = tokens.len(), "group consumed");
+        return tokens;
+    }
+
+    // Get the destination of the group starting at index : its first control word
+    fn get_destination(&self, index: usize) -> Option<&ControlWord<'a>> {
+        return self.tokens[index..].iter().find_map(|token| match token {
+            Token::ControlSymbol((control_word, _)) => Some(control_word),
+            _ => None,
+        });
+    }
+
+    // Check if the group starting at index is a destination left for the body : a destination with a handler, or a position dependent one
+    fn is_body_destination(&self, index: usize) -> bool {
+        let has_handler = self.get_destination(index).is_some_and(|destination| self.destination_handlers.contains_key(destination.name()));
+        return has_handler || self.is_position_dependent(index);
+    }
+
+    // Check if the group starting at index is a destination that depends on its position in the body (bookmarks, fields, nested tables, pictures, unicode text, shapes)
+    fn is_position_dependent(&self, index: usize) -> bool {
+        return matches!(
+            self.get_destination(index),
+            Some(
+                ControlWord::BookmarkStart
+                    | ControlWord::BookmarkEnd
+                    | ControlWord::FieldInstruction
+                    | ControlWord::NestedTableProperties
+                    | ControlWord::ShapePicture
+                    | ControlWord::UnicodeDestination
+                    | ControlWord::ShapeInstruction
+                    | ControlWord::DrawingObject
+            )
+        );
     }
 
     // Consume all tokens until the header is read
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     fn parse_header(&mut self) -> Result<RtfHeader, ParserError> {
         self.cursor = 0; // Reset the cursor
         let mut header = RtfHeader::default();
@@ -368,9 +1577,16 @@ impl<'a> Parser<'a> {
                 }
             }
             match (token, next_token) {
-                (Token::OpeningBracket, Token::IgnorableDestination) => {
+                // A form field is read with its \fldinst destination, its nested destinations included
+                (Token::OpeningBracket, Token::IgnorableDestination) if self.get_destination(self.cursor) == Some(&ControlWord::FormField) => {
+                    let mut index = self.cursor;
+                    self.skip_group(&mut index);
+                    self.cursor = index + 2; // After the closing bracket
+                }
+                // Destinations that depend on their position in the body are left for the body parsing
+                (Token::OpeningBracket, Token::IgnorableDestination) if !self.is_body_destination(self.cursor) => {
                     let ignore_group_tokens = self.consume_group();
-                    Self::parse_ignore_groups(&ignore_group_tokens);
+                    Self::parse_ignore_groups(&ignore_group_tokens, &mut header, &mut self.warnings, self.options.capture_blobs)?;
                 }
                 (Token::OpeningBracket, header_control_word!(FontTable, None)) => {
                     let font_table_tokens = self.consume_group();
@@ -380,98 +1596,482 @@ impl<'a> Parser<'a> {
                     let color_table_tokens = self.consume_group();
                     header.color_table = Self::parse_color_table(&color_table_tokens)?;
                 }
+                (Token::OpeningBracket, header_control_word!(ListOverrideTable, None)) => {
+                    let list_override_table_tokens = self.consume_group();
+                    header.list_override_table = Self::parse_list_override_table(&list_override_table_tokens)?;
+                }
                 (Token::OpeningBracket, header_control_word!(StyleSheet, None)) => {
                     let stylesheet_tokens = self.consume_group();
-                    header.stylesheet = Self::parse_stylesheet(&stylesheet_tokens)?;
+                    (header.stylesheet, header.character_styles) = Self::parse_stylesheet(&stylesheet_tokens)?;
+                }
+                // Check and consume token
+                (token, _) => {
+                    if let Some(charset) = CharacterSet::from(token) {
+                        header.character_set = charset;
+                    }
+                    if let Some(encapsulation) = EncapsulatedFormat::from(token) {
+                        header.encapsulation = Some(encapsulation);
+                    }
+                    if let Token::ControlSymbol((control_word, property)) = token {
+                        header.defaults.apply(control_word, property);
+                    }
+                    if let Token::ControlSymbol((ControlWord::AnsiCodePage, Property::Value(codepage))) = *token {
+                        if !u16::try_from(codepage).is_ok_and(codepage::is_supported) {
+                            add_warning(&mut self.warnings, ParserWarning::CodepageFallback(codepage));
+                        }
+                    }
+                    self.cursor += 1;
+                }
+            }
+        }
+        if let (Some(codepage), CharacterSet::Ansi) = (self.options.default_codepage, &header.character_set) {
+            header.character_set = CharacterSet::Ansicpg(codepage);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            fonts = header.font_table.len(),
+            colors = header.color_table.len(),
+            styles = header.stylesheet.len(),
+            lists = header.list_table.len(),
+            "header parsed"
+        );
+        return Ok(header);
+    }
+
+    pub(crate) fn parse_font_table(font_tables_tokens: &Vec<Token<'a>>) -> Result<FontTable, ParserError> {
+        let Some(font_table_first_token) = font_tables_tokens.first() else {
+            return Err(ParserError::NoMoreToken);
+        };
+        if font_table_first_token != header_control_word!(FontTable, None) {
+            return Err(ParserError::InvalidToken(format!("{:?} is not a FontTable token", font_table_first_token)));
+        }
+        // The entries are flat (\f0\fswiss Arial;\f1 ...) or grouped ({\f0\fswiss Arial;}), an entry ending with its ';' or its group
+        let mut table = BTreeMap::new();
+        let mut current_font: Option<(FontRef, Font)> = None;
+        let mut groups: Vec<FontGroup> = vec![];
+        let mut high_nibble: Option<u8> = None;
+        for (index, token) in font_tables_tokens.iter().enumerate() {
+            let group = groups.last().copied();
+            match token {
+                Token::OpeningBracket => {
+                    #[rustfmt::skip]
+                    let new_group = match (font_tables_tokens.get(index + 1), font_tables_tokens.get(index + 2)) {
+                        _ if group == Some(FontGroup::Skipped)                                              => FontGroup::Skipped,
+                        (Some(Token::IgnorableDestination), Some(Token::ControlSymbol((control_word, _)))) => match control_word {
+                            ControlWord::FontAlternate => FontGroup::Alternate,
+                            ControlWord::FontEmbedded  => FontGroup::Embedded,
+                            ControlWord::FontFile      => FontGroup::EmbeddedFile,
+                            // The other destinations of a font (\*\panose, \*\fname, ...) are not text of its name
+                            _                          => FontGroup::Skipped,
+                        },
+                        (Some(Token::IgnorableDestination), _)                                              => FontGroup::Skipped,
+                        _                                                                                   => group.unwrap_or(FontGroup::Entry),
+                    };
+                    if new_group == FontGroup::Embedded {
+                        if let Some((_, font)) = current_font.as_mut() {
+                            font.embedded = Some(EmbeddedFont::default());
+                        }
+                        high_nibble = None;
+                    }
+                    groups.push(new_group);
+                }
+                Token::ClosingBracket => {
+                    // The end of a grouped entry without ';'
+                    let entry_end = groups.pop() == Some(FontGroup::Entry) && groups.is_empty();
+                    if let Some((font_ref, font)) = current_font.take_if(|_| entry_end) {
+                        table.insert(font_ref, font);
+                    }
+                }
+                _ if group == Some(FontGroup::Skipped) => {}
+                Token::ControlSymbol((ControlWord::FontNumber, property)) => {
+                    let Property::Value(font_ref) = property else {
+                        return Err(ParserError::InvalidFontIdentifier(*property));
+                    };
+                    // The previous entry ends without ';'
+                    if let Some((font_ref, font)) = current_font.take() {
+                        table.insert(font_ref, font);
+                    }
+                    current_font = Some((*font_ref as FontRef, Font::default()));
+                }
+                Token::ControlSymbol((control_word, property)) => {
+                    let Some((_, font)) = current_font.as_mut() else {
+                        continue;
+                    };
+                    #[rustfmt::skip]
+                    match control_word {
+                        ControlWord::FontCharset      => font.character_set = property.get_value_as::<u8>().unwrap_or_default(),
+                        ControlWord::FontBias         => font.east_asian_bias = property.get_value() == 1,
+                        ControlWord::FontAlternate    => font.alternate_name = Some(String::new()),
+                        ControlWord::FontTypeTrueType => {
+                            if let Some(embedded) = font.embedded.as_mut() {
+                                embedded.font_type = EmbeddedFontType::TrueType;
+                            }
+                        }
+                        ControlWord::Unknown(name)    => {
+                            if let Some(font_family) = FontFamily::from(name) {
+                                font.font_family = font_family;
+                            }
+                        }
+                        _                             => {}
+                    };
+                }
+                Token::PlainText(text) => {
+                    let Some((font_ref, font)) = current_font.as_mut() else {
+                        continue;
+                    };
+                    match group {
+                        Some(FontGroup::Alternate) => {
+                            if let Some(alternate_name) = font.alternate_name.as_mut() {
+                                alternate_name.push_str(text.trim_end_matches(';'));
+                            }
+                        }
+                        Some(FontGroup::EmbeddedFile) => {
+                            if let Some(embedded) = font.embedded.as_mut() {
+                                embedded.file_name.push_str(text.trim_end_matches(';'));
+                            }
+                        }
+                        // The data is written in hexadecimal, possibly split across several lines
+                        Some(FontGroup::Embedded) => {
+                            if let Some(embedded) = font.embedded.as_mut() {
+                                for digit in text.chars().filter_map(|c| c.to_digit(16)) {
+                                    match high_nibble.take() {
+                                        Some(high) => embedded.data.push(high << 4 | digit as u8),
+                                        None => high_nibble = Some(digit as u8),
+                                    }
+                                }
+                            }
+                        }
+                        // The name can be split by the \falt group (ex: "Liberation Serif{\*\falt Times New Roman};")
+                        _ => match text.split_once(';') {
+                            Some((name, _)) => {
+                                font.name.push_str(name);
+                                table.insert(*font_ref, core::mem::take(font));
+                                current_font = None;
+                            }
+                            None => font.name.push_str(text),
+                        },
+                    }
+                }
+                Token::Binary(data) if group == Some(FontGroup::Embedded) => {
+                    if let Some(embedded) = current_font.as_mut().and_then(|(_, font)| font.embedded.as_mut()) {
+                        embedded.data.extend_from_slice(data);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some((font_ref, font)) = current_font {
+            table.insert(font_ref, font);
+        }
+        return Ok(table);
+    }
+
+    fn parse_color_table(color_table_tokens: &Vec<Token<'a>>) -> Result<ColorTable, ParserError> {
+        let Some(color_table_first_token) = color_table_tokens.first() else {
+            return Err(ParserError::NoMoreToken);
+        };
+        if color_table_first_token != header_control_word!(ColorTable, None) {
+            return Err(ParserError::InvalidToken(format!("ParserError: {:?} is not a ColorTable token", color_table_first_token)));
+        }
+        // Each entry ends with a ';', the empty one being the automatic color (usually the first one, \cf0)
+        // Some generators omit a component (black is \red0;) or the ';' between two entries, or the ';' of the last one
+        let mut table = BTreeMap::new();
+        let mut current_key: ColorRef = 0;
+        let mut current_color: [Option<u8>; 3] = [None; 3];
+        let mut end_entry = |current_color: &mut [Option<u8>; 3], current_key: &mut ColorRef| {
+            if current_color.iter().any(Option::is_some) {
+                let [red, green, blue] = current_color.map(Option::unwrap_or_default);
+                table.insert(*current_key, Color::from_rgb(red, green, blue));
+            }
+            *current_color = [None; 3];
+            *current_key = current_key.saturating_add(1);
+        };
+        for token in color_table_tokens.iter() {
+            match token {
+                Token::ControlSymbol((control_word, property)) => {
+                    let component = match control_word {
+                        ControlWord::ColorRed => 0,
+                        ControlWord::ColorGreen => 1,
+                        ControlWord::ColorBlue => 2,
+                        _ => continue,
+                    };
+                    // A component defined twice starts the next entry
+                    if current_color[component].is_some() {
+                        end_entry(&mut current_color, &mut current_key);
+                    }
+                    current_color[component] = Some(property.get_value().clamp(0, 255) as u8);
+                }
+                Token::PlainText(text) => {
+                    for _ in text.chars().filter(|c| *c == ';') {
+                        end_entry(&mut current_color, &mut current_key);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if current_color.iter().any(Option::is_some) {
+            end_entry(&mut current_color, &mut current_key);
+        }
+        return Ok(table);
+    }
+
+    // Only the names of the paragraph (\s) and character (\*\cs) styles are read, the section (\ds) and table (\ts) styles are skipped
+    fn parse_stylesheet(stylesheet_tokens: &[Token<'a>]) -> Result<(StyleSheet, StyleSheet), ParserError> {
+        let mut stylesheet = StyleSheet::new();
+        let mut character_styles = StyleSheet::new();
+        let mut depth: usize = 0;
+        // The reference and the name of the style of the current group, None if it is neither a paragraph nor a character style
+        let mut current_style: Option<(StyleRef, String)> = None;
+        let mut is_character_style = false;
+        let mut additive = false;
+        // The group is an ignorable destination (\*), only kept if it is a character style
+        let mut is_ignorable = false;
+        for token in stylesheet_tokens.iter() {
+            match token {
+                Token::OpeningBracket => {
+                    depth += 1;
+                    if depth == 1 {
+                        // A style without \sN is the default one
+                        current_style = Some((0, String::new()));
+                        is_character_style = false;
+                        additive = false;
+                        is_ignorable = false;
+                    }
+                }
+                Token::ClosingBracket => {
+                    if depth == 1 && !is_ignorable {
+                        if let Some((style_ref, name)) = current_style.take() {
+                            let name = name.trim().trim_end_matches(';').trim_end();
+                            if !name.is_empty() {
+                                let style = Style {
+                                    name: name.to_string(),
+                                    additive,
+                                    ..Style::default()
+                                };
+                                if is_character_style {
+                                    character_styles.insert(style_ref, style);
+                                } else {
+                                    stylesheet.insert(style_ref, style);
+                                }
+                            }
+                        }
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                Token::IgnorableDestination if depth == 1 => is_ignorable = true,
+                Token::ControlSymbol((ControlWord::ParStyle, property)) if depth == 1 => {
+                    if let Some((style_ref, _)) = current_style.as_mut() {
+                        *style_ref = property.get_value_as::<StyleRef>()?;
+                    }
+                }
+                Token::ControlSymbol((ControlWord::CharStyle, property)) if depth == 1 => {
+                    if let Some((style_ref, _)) = current_style.as_mut() {
+                        *style_ref = property.get_value_as::<StyleRef>()?;
+                        is_character_style = true;
+                        is_ignorable = false;
+                    }
                 }
-                // Check and consume token
-                (token, _) => {
-                    if let Some(charset) = CharacterSet::from(token) {
-                        header.character_set = charset;
+                Token::ControlSymbol((ControlWord::Additive, _)) if depth == 1 => additive = true,
+                Token::ControlSymbol((ControlWord::Unknown(r"\ds" | r"\ts" | r"\tsrowd"), _)) if depth == 1 => current_style = None,
+                Token::PlainText(text) if depth == 1 => {
+                    if let Some((_, name)) = current_style.as_mut() {
+                        name.push_str(text);
                     }
-                    self.cursor += 1;
                 }
+                _ => {}
             }
         }
-        return Ok(header);
+        return Ok((stylesheet, character_styles));
     }
 
-    fn parse_font_table(font_tables_tokens: &Vec<Token<'a>>) -> Result<FontTable, ParserError> {
-        let Some(font_table_first_token) = font_tables_tokens.get(0) else {
-            return Err(ParserError::NoMoreToken);
+    fn parse_expanded_color_table(expanded_color_table_tokens: &[Token<'a>]) -> Result<ExpandedColorTable, ParserError> {
+        let mut table = BTreeMap::new();
+        let mut current_key: ColorRef = 0;
+        let mut current_color: Option<(ColorSpace, Vec<i32>)> = None;
+        // Each ';' ends an entry, the first one being the automatic color
+        let mut insert_color = |current_color: &mut Option<(ColorSpace, Vec<i32>)>, current_key: &mut ColorRef| {
+            if let Some((color_space, components)) = current_color.take() {
+                table.insert(*current_key, ExpandedColor::from_components(color_space, &components));
+                *current_key += 1;
+            }
         };
-        if font_table_first_token != header_control_word!(FontTable, None) {
-            return Err(ParserError::InvalidToken(format!("{:?} is not a FontTable token", font_table_first_token)));
-        }
-        let mut table = HashMap::new();
-        let mut current_key = 0;
-        let mut current_font = Font::default();
-        for token in font_tables_tokens.iter() {
+        for token in expanded_color_table_tokens.iter() {
             match token {
-                Token::ControlSymbol((control_word, property)) => match control_word {
-                    ControlWord::FontNumber => {
-                        // Insert previous font
-                        table.insert(current_key, current_font.clone());
-                        if let Property::Value(key) = property {
-                            current_key = *key as FontRef;
+                Token::ControlSymbol((ControlWord::ColorComponent, property)) => {
+                    if let Some((_, components)) = current_color.as_mut() {
+                        components.push(property.get_value());
+                    }
+                }
+                Token::ControlSymbol((control_word, _)) => {
+                    if let Some(color_space) = ColorSpace::from(control_word) {
+                        insert_color(&mut current_color, &mut current_key);
+                        current_color = Some((color_space, vec![]));
+                    }
+                }
+                Token::PlainText(text) => {
+                    for _ in text.matches(';') {
+                        if current_color.is_some() {
+                            insert_color(&mut current_color, &mut current_key);
                         } else {
-                            return Err(ParserError::InvalidFontIdentifier(*property));
+                            current_key += 1;
+                        }
+                    }
+                }
+                Token::ClosingBracket => insert_color(&mut current_color, &mut current_key),
+                _ => {}
+            }
+        }
+        return Ok(table);
+    }
+
+    fn parse_list_table(list_table_tokens: &[Token<'a>]) -> Result<ListTable, ParserError> {
+        let mut table = BTreeMap::new();
+        let mut current_list: Option<List> = None;
+        // Group depth of the current list and of the current level text
+        let mut depth = 0;
+        let mut list_depth = 0;
+        let mut level_text: Option<(usize, Vec<u16>)> = None;
+        for token in list_table_tokens.iter() {
+            match token {
+                Token::OpeningBracket => depth += 1,
+                Token::ClosingBracket => {
+                    if let Some((text_depth, text)) = &level_text {
+                        if *text_depth == depth {
+                            // The first char of the level text is its length
+                            let length = text.first().map_or(0, |length| *length as usize);
+                            let template = text.get(1..(length + 1).min(text.len())).unwrap_or_default();
+                            if let Some(level) = current_list.as_mut().and_then(|list| list.levels.last_mut()) {
+                                level.text = String::from_utf16_lossy(template);
+                            }
+                            level_text = None;
                         }
                     }
-                    ControlWord::Unknown(name) => {
-                        if let Some(font_family) = FontFamily::from(name) {
-                            current_font.font_family = font_family;
+                    if depth == list_depth {
+                        if let Some(list) = current_list.take() {
+                            table.insert(list.id, list);
                         }
                     }
-                    _ => {}
-                },
-                Token::PlainText(name) => {
-                    current_font.name = name.trim_end_matches(';').to_string();
+                    depth = depth.saturating_sub(1);
+                }
+                Token::ControlSymbol((control_word, property)) => {
+                    let current_level = current_list.as_mut().and_then(|list| list.levels.last_mut());
+                    match (control_word, current_level) {
+                        (ControlWord::List, _) => {
+                            current_list = Some(List::default());
+                            list_depth = depth;
+                        }
+                        (ControlWord::ListId, _) => {
+                            if let Some(list) = current_list.as_mut() {
+                                list.id = property.get_value();
+                            }
+                        }
+                        (ControlWord::ListLevel, _) => {
+                            if let Some(list) = current_list.as_mut() {
+                                list.levels.push(ListLevel { start_at: 1, ..ListLevel::default() });
+                            }
+                        }
+                        (ControlWord::LevelNumberFormat, Some(level)) => level.number_format = property.get_value().into(),
+                        (ControlWord::LevelStartAt, Some(level)) => level.start_at = property.get_value(),
+                        (ControlWord::LevelText, _) => level_text = Some((depth, vec![])),
+                        (ControlWord::Unicode | ControlWord::HexByte, _) => {
+                            if let Some((_, text)) = level_text.as_mut() {
+                                text.push(property.get_unicode_value()?);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Token::PlainText(content) => {
+                    if let Some((_, text)) = level_text.as_mut() {
+                        text.extend(content.encode_utf16());
+                    }
                 }
-                Token::ClosingBracket => {
-                    table.insert(current_key, current_font.clone());
-                } // Insert previous font
                 _ => {}
             }
         }
         return Ok(table);
     }
 
-    fn parse_color_table(color_table_tokens: &Vec<Token<'a>>) -> Result<ColorTable, ParserError> {
-        let Some(color_table_first_token) = color_table_tokens.get(0) else {
-            return Err(ParserError::NoMoreToken);
-        };
-        if color_table_first_token != header_control_word!(ColorTable, None) {
-            return Err(ParserError::InvalidToken(format!("ParserError: {:?} is not a ColorTable token", color_table_first_token)));
-        }
-        let mut table = HashMap::new();
-        let mut current_key = 1;
-        let mut current_color = Color::default();
-        for token in color_table_tokens.iter() {
-            match token {
-                Token::ControlSymbol((control_word, property)) => match control_word {
-                    ControlWord::ColorRed => current_color.red = property.get_value_as::<u8>()?,
-                    ControlWord::ColorGreen => current_color.green = property.get_value_as::<u8>()?,
-                    ControlWord::ColorBlue => {
-                        current_color.blue = property.get_value_as::<u8>()?;
-                        table.insert(current_key, current_color.clone());
-                        current_key += 1;
+    fn parse_list_override_table(list_override_table_tokens: &[Token<'a>]) -> Result<ListOverrideTable, ParserError> {
+        let mut table = BTreeMap::new();
+        let mut current_override = ListOverride::default();
+        for token in list_override_table_tokens.iter() {
+            if let Token::ControlSymbol((control_word, property)) = token {
+                match control_word {
+                    ControlWord::ListOverride => current_override = ListOverride::default(),
+                    ControlWord::ListId => current_override.list_id = property.get_value(),
+                    ControlWord::ListOverrideLevel => current_override.start_at.push(None),
+                    ControlWord::LevelStartAt => {
+                        if let Some(start_at) = current_override.start_at.last_mut() {
+                            *start_at = Some(property.get_value());
+                        }
+                    }
+                    // The reference ends the override definition
+                    ControlWord::ListOverrideRef => {
+                        table.insert(property.get_value_as::<ListOverrideRef>()?, current_override.clone());
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
         return Ok(table);
     }
 
-    fn parse_stylesheet(_stylesheet_tokens: &Vec<Token<'a>>) -> Result<StyleSheet, ParserError> {
-        // TODO : parse the stylesheet
-        return Ok(StyleSheet::from([]));
+    // Decode the hexadecimal data of a blob, read as binary by the lexer (or as text if its group is split between two chunks)
+    fn decode_blob(tokens: &[Token<'a>]) -> Vec<u8> {
+        let mut data = vec![];
+        let mut high_nibble: Option<u8> = None;
+        for token in tokens {
+            let digits: &[u8] = match token {
+                Token::Binary(data) => data,
+                Token::PlainText(text) => text.as_bytes(),
+                _ => continue,
+            };
+            for digit in digits.iter().filter_map(|c| (*c as char).to_digit(16)) {
+                match high_nibble.take() {
+                    Some(high) => data.push(high << 4 | digit as u8),
+                    None => high_nibble = Some(digit as u8),
+                }
+            }
+        }
+        return data;
+    }
+
+    // The text of the group, without its trailing ';' (ex: {\*\generator Microsoft Word 11.0.8134;})
+    fn parse_generator(generator_tokens: &[Token<'a>]) -> Option<String> {
+        let text: String = generator_tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::PlainText(text) => Some(*text),
+                _ => None,
+            })
+            .collect();
+        let generator = text.trim().trim_end_matches(';').trim_end();
+        return (!generator.is_empty()).then(|| generator.to_string());
     }
 
-    fn parse_ignore_groups(_tokens: &Vec<Token<'a>>) {
-        // Do nothing for now
+    fn parse_ignore_groups(tokens: &[Token<'a>], header: &mut RtfHeader, warnings: &mut Vec<ParserWarning>, capture_blobs: bool) -> Result<(), ParserError> {
+        // The destination is the first control word of the group
+        let destination = tokens.iter().find_map(|token| match token {
+            Token::ControlSymbol((control_word, _)) => Some(control_word),
+            _ => None,
+        });
+        if let Some((_, parse_destination)) = HEADER_DESTINATIONS.iter().find(|(control_word, _)| Some(control_word) == destination) {
+            return parse_destination(tokens, header);
+        }
+        match destination {
+            Some(destination @ (ControlWord::ThemeData | ControlWord::ColorSchemeMapping | ControlWord::DataStore)) if capture_blobs => {
+                header.blobs.insert(destination.name().to_string(), Self::decode_blob(tokens));
+            }
+            // The content of a protected document can't be trusted to be readable
+            Some(ControlWord::Password | ControlWord::PasswordHash) => return Err(ParserError::EncryptedDocument),
+            // The blobs are skipped without warning
+            Some(ControlWord::ThemeData | ControlWord::ColorSchemeMapping | ControlWord::DataStore) => {}
+            // The other ignorable destinations are skipped
+            Some(destination) => add_warning(warnings, ParserWarning::SkippedDestination(destination.name().to_string())),
+            None => {}
+        }
+        return Ok(());
     }
 }
 
@@ -480,9 +2080,11 @@ pub mod tests {
     use super::*;
     use crate::header::CharacterSet::*;
     use crate::header::FontFamily::*;
-    use crate::header::RtfHeader;
+    use crate::header::{RtfHeader, ViewKind};
     use crate::include_test_file;
-    use crate::lexer::Lexer;
+    use crate::lexer::{Lexer, LexerError};
+    use crate::paragraph::{DropCap, FramePosition, HorizontalAnchor, Indentation, VerticalAnchor};
+    use crate::units::Twips;
 
     #[test]
     fn parser_header() {
@@ -497,7 +2099,8 @@ pub mod tests {
                     Font {
                         name: "Helvetica".into(),
                         character_set: 0,
-                        font_family: Swiss
+                        font_family: Swiss,
+                        ..Font::default()
                     }
                 )]),
                 ..RtfHeader::default()
@@ -508,21 +2111,151 @@ pub mod tests {
             [
                 StyleBlock {
                     painter: Painter::default(),
-                    paragraph: Default::default(),
+                    paragraph: Paragraph::with_defaults(&DocumentDefaults::default()),
                     text: "Voici du texte en ".into(),
+                    ..StyleBlock::default()
                 },
                 StyleBlock {
                     painter: Painter { bold: true, ..Painter::default() },
-                    paragraph: Default::default(),
+                    paragraph: Paragraph::with_defaults(&DocumentDefaults::default()),
                     text: "gras".into(),
+                    ..StyleBlock::default()
                 },
                 StyleBlock {
                     painter: Painter::default(),
-                    paragraph: Default::default(),
+                    paragraph: Paragraph::with_defaults(&DocumentDefaults::default()),
                     text: ".".into(),
+                    paragraph_end: true,
+                    ..StyleBlock::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_source_spans() {
+        let rtf = r"{\rtf1\ansi Voici du {\b gras}, \{du texte\} et b\'eate\tab\u233?.\
+}";
+        let (tokens, spans) = Lexer::scan_with_spans(rtf).unwrap();
+        let document = Parser::new_with_spans(tokens, spans).parse().unwrap();
+        fn mappings<'a>(block: &'a StyleBlock, rtf: &'a str) -> Vec<(&'a str, &'a str)> {
+            return block
+                .source_spans
+                .iter()
+                .map(|mapping| (&block.text[mapping.text.start..mapping.text.end], &rtf[mapping.source.start..mapping.source.end]))
+                .collect();
+        }
+        assert_eq!(mappings(&document.body[0], rtf), [("Voici du ", "Voici du ")]);
+        assert_eq!(mappings(&document.body[1], rtf), [("gras", "gras")]);
+        assert_eq!(
+            mappings(&document.body[2], rtf),
+            [
+                (", ", ", "),
+                ("{du texte", "{du texte"),
+                ("} et b", "} et b"),
+                ("ê", r"\'ea"),
+                ("te", "te"),
+                ("\t", r"\tab"),
+                ("é", r"\u233"),
+                (".", "."),
+                ("\n", "\\\n")
+            ]
+        );
+        // Without the spans, no mapping is recorded
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        assert!(document.body.iter().all(|block| block.source_spans.is_empty()));
+    }
+
+    #[test]
+    fn parse_bookmarks() {
+        let rtf = r"{\rtf1\ansi{\*\bkmkstart intro}Hello {\*\bkmkstart name}{\b world}{\*\bkmkend name}!{\*\bkmkstart open}}";
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        assert_eq!(document.get_text(), "Hello world!");
+        assert_eq!(
+            document.bookmarks,
+            vec![
+                Bookmark {
+                    name: "intro".into(),
+                    start: 0,
+                    end: None
+                },
+                Bookmark {
+                    name: "name".into(),
+                    start: 6,
+                    end: Some(11)
+                },
+                Bookmark {
+                    name: "open".into(),
+                    start: 12,
+                    end: None
                 },
             ]
         );
+        // The hidden text is not counted
+        let rtf = r"{\rtf1\ansi abc{\v hidden }{\*\bkmkstart b}def}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.bookmarks[0].start, 3);
+    }
+
+    #[test]
+    fn parse_page_breaks() {
+        let rtf = r"{\rtf1\ansi First page\page Second\softpage  page\par\pard\pagebb Third page\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.page_breaks, [PageBreak { position: 10, soft: false }, PageBreak { position: 16, soft: true }]);
+        assert!(!document.body[0].paragraph.page_break_before);
+        assert_eq!(document.body.last().unwrap().text, "Third page");
+        assert!(document.body.last().unwrap().paragraph.page_break_before);
+    }
+
+    #[test]
+    fn parse_pagination_properties() {
+        let rtf = r"{\rtf1\ansi\outlinelevel0\keepn\widctlpar Title\par\pard\keep\outlinelevel9 Body\par\pard\widctlpar\nowidctlpar End\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let paragraphs: Vec<&Paragraph> = document.body.iter().map(|style_block| &style_block.paragraph).collect();
+        assert_eq!(paragraphs[0].outline_level, Some(0));
+        assert!(paragraphs[0].keep_with_next && !paragraphs[0].keep_together && paragraphs[0].widow_control);
+        // The levels above 8 are not valid
+        assert_eq!(paragraphs[1].outline_level, None);
+        assert!(paragraphs[1].keep_together && !paragraphs[1].keep_with_next && !paragraphs[1].widow_control);
+        assert!(!paragraphs[2].widow_control);
+    }
+
+    #[test]
+    fn parse_frames() {
+        let rtf = r"{\rtf1\ansi\pard\dropcapli3\dropcapt1\absw0\absh-990\phpg\pvpara\posx1440\posyt\dxfrtext144 D\par
+            \pard\absw2880\phmrg\pvpg\posxc\posnegy-120\dfrmtxtx180\nowrap Sidebar\par\pard Body\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let frames: Vec<Option<Frame>> = document.body.iter().map(|style_block| style_block.paragraph.frame).collect();
+        assert_eq!(
+            frames,
+            [
+                Some(Frame {
+                    height: Twips(-990),
+                    horizontal_anchor: HorizontalAnchor::Page,
+                    vertical_anchor: VerticalAnchor::Paragraph,
+                    horizontal_position: FramePosition::Offset(Twips(1440)),
+                    vertical_position: FramePosition::Start,
+                    horizontal_distance: Twips(144),
+                    vertical_distance: Twips(144),
+                    drop_cap: DropCap::InText,
+                    drop_cap_lines: 3,
+                    ..Frame::default()
+                }),
+                Some(Frame {
+                    width: Twips(2880),
+                    horizontal_anchor: HorizontalAnchor::Margin,
+                    vertical_anchor: VerticalAnchor::Page,
+                    horizontal_position: FramePosition::Center,
+                    vertical_position: FramePosition::Offset(Twips(-120)),
+                    horizontal_distance: Twips(180),
+                    no_wrap: true,
+                    ..Frame::default()
+                }),
+                None,
+            ]
+        );
     }
 
     #[test]
@@ -550,7 +2283,7 @@ pub mod tests {
         assert_eq!(
             doc.header,
             RtfHeader {
-                character_set: Ansi,
+                character_set: Ansicpg(1252),
                 font_table: FontTable::from([
                     (
                         0,
@@ -558,6 +2291,7 @@ pub mod tests {
                             name: "Helvetica".into(),
                             character_set: 0,
                             font_family: Swiss,
+                            ..Font::default()
                         }
                     ),
                     (
@@ -566,10 +2300,11 @@ pub mod tests {
                             name: "Helvetica-Bold".into(),
                             character_set: 0,
                             font_family: Swiss,
+                            ..Font::default()
                         }
                     )
                 ]),
-                color_table: ColorTable::from([(1, Color { red: 255, green: 255, blue: 255 }),]),
+                color_table: ColorTable::from([(1, Color::from_rgb(255, 255, 255)),]),
                 ..RtfHeader::default()
             }
         );
@@ -607,12 +2342,103 @@ pub mod tests {
             document.body,
             vec![StyleBlock {
                 painter: Painter { font_size: 24, ..Painter::default() },
-                paragraph: Default::default(),
+                paragraph: Paragraph {
+                    tab_stops: [566, 1133, 1700, 2267, 2834, 3401, 3968, 4535, 5102, 5669, 6236, 6803]
+                        .map(|position| TabStop {
+                            position: Twips(position),
+                            ..TabStop::default()
+                        })
+                        .to_vec(),
+                    ..Paragraph::with_defaults(&DocumentDefaults::default())
+                },
                 text: "\nEmpty start\n\nList test : \n - item 1\n - item 2\n - item 3\n - item 4".into(),
+                ..StyleBlock::default()
             },]
         );
     }
 
+    #[test]
+    fn parse_document_defaults() {
+        let rtf = r"{\rtf1\ansi\deff1\deflang1036\deflangfe2052\deftab708\viewkind4{\fonttbl\f0\fswiss Helvetica;\f1\froman Times;}
+First\par\pard\f0\fs20 Second\plain Third\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.header.defaults,
+            DocumentDefaults {
+                font_ref: 1,
+                language: 1036,
+                east_asian_language: 2052,
+                tab_width: Twips(708),
+                view_kind: ViewKind::Normal,
+            }
+        );
+        // The paragraphs and the plain text fall back to the document defaults
+        assert!(document.body.iter().all(|style_block| style_block.paragraph.tab_width == Twips(708)));
+        assert_eq!(document.body.iter().map(|style_block| style_block.painter.font_ref).collect::<Vec<_>>(), [1, 0, 1]);
+        assert_eq!(RtfDocument::try_from(r"{\rtf1\ansi Text}").unwrap().body[0].paragraph.tab_width, Twips(720));
+    }
+
+    #[test]
+    fn parse_run_languages() {
+        let rtf = r"{\rtf1\ansi\deflang1033 English {\lang1036 Français }{\lang1031\langfe1041 Deutsch}\plain\lang1033 Reset}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document
+                .body
+                .iter()
+                .map(|style_block| (style_block.painter.language, style_block.painter.east_asian_language))
+                .collect::<Vec<_>>(),
+            [(None, None), (Some(1036), None), (Some(1031), Some(1041)), (Some(1033), None)]
+        );
+    }
+
+    #[test]
+    fn parse_toggles() {
+        let rtf = r"{\rtf1\ansi 1\super st\super0  2\super nd\nosupersub  H\sub 2\super +\sub0 O \b2 bold\b0  plain}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document
+                .body
+                .iter()
+                .map(|style_block| (style_block.text.as_str(), style_block.painter.superscript, style_block.painter.subscript, style_block.painter.bold))
+                .collect::<Vec<_>>(),
+            [
+                ("1", false, false, false),
+                ("st", true, false, false),
+                (" 2", false, false, false),
+                ("nd", true, false, false),
+                (" H", false, false, false),
+                ("2", false, true, false),
+                // \sub0 doesn't turn the superscript off
+                ("+O ", true, false, false),
+                ("bold", true, false, true),
+                (" plain", true, false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_indentation() {
+        let rtf = r"{\rtf1\ansi\pard\li720\ri360\fi-360 Hanging\par\pard\lin1440\rin720\fi720 Logical\par\pard Reset\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.body.iter().map(|style_block| style_block.paragraph.indent).collect::<Vec<_>>(),
+            [
+                Indentation {
+                    left: Twips(720),
+                    right: Twips(360),
+                    first_line: Twips(-360)
+                },
+                Indentation {
+                    left: Twips(1440),
+                    right: Twips(720),
+                    first_line: Twips(720)
+                },
+                Indentation::default(),
+            ]
+        );
+    }
+
     #[test]
     fn parse_image_data() {
         // Try to parse without error
@@ -621,6 +2447,58 @@ pub mod tests {
         let _document = Parser::new(tokens).parse();
     }
 
+    #[test]
+    fn parse_font_alternate() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0\froman Liberation Serif{\*\falt Times New Roman};}{\f1\fswiss Arial;}}\f0 text}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let fonts = &document.header.font_table;
+        assert_eq!(fonts[&0].name, "Liberation Serif");
+        assert_eq!(fonts[&0].alternate_name.as_deref(), Some("Times New Roman"));
+        assert_eq!(fonts[&1].name, "Arial");
+        assert_eq!(fonts[&1].alternate_name, None);
+    }
+
+    #[test]
+    fn parse_grouped_and_flat_font_tables() {
+        let flat = r"{\rtf1\ansi{\fonttbl\f1\froman\fcharset0 Times;\f2\fcharset128\fbias1 MS Mincho;\f3 Courier}\f1 text}";
+        let grouped = r"{\rtf1\ansi{\fonttbl{\f1\froman\fcharset0 Times;}{\f2\fcharset128\fbias1 MS Mincho;}{\f3 Courier}}\f1 text}";
+        let fonts = RtfDocument::try_from(flat).unwrap().header.font_table;
+        assert_eq!(fonts, RtfDocument::try_from(grouped).unwrap().header.font_table);
+        // No font 0, and the family of a font doesn't leak to the next one
+        assert_eq!(fonts.keys().collect::<Vec<_>>(), [&1, &2, &3]);
+        assert_eq!((fonts[&1].name.as_str(), &fonts[&1].font_family, fonts[&1].east_asian_bias), ("Times", &Roman, false));
+        assert_eq!((fonts[&2].name.as_str(), &fonts[&2].font_family, fonts[&2].east_asian_bias), ("MS Mincho", &Nil, true));
+        assert_eq!(fonts[&3].name, "Courier");
+    }
+
+    #[test]
+    fn parse_embedded_fonts() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0\fswiss{\*\panose 020b0604020202020204}Arial;}
+{\f1\fnil{\*\fontemb\fttruetype{\*\fontfile\cpg1252 C:\\Fonts\\custom.ttf}00010000
+0004}Custom{\*\falt Arial};}{\f2 Linked{\*\fontemb{\*\fontfile linked.ttf}};}}\f1 text}";
+        let fonts = RtfDocument::try_from(rtf).unwrap().header.font_table;
+        assert_eq!(fonts[&0].name, "Arial");
+        assert_eq!(fonts[&0].embedded, None);
+        assert_eq!(fonts[&1].name, "Custom");
+        assert_eq!(fonts[&1].alternate_name.as_deref(), Some("Arial"));
+        assert_eq!(
+            fonts[&1].embedded,
+            Some(EmbeddedFont {
+                font_type: EmbeddedFontType::TrueType,
+                file_name: r"C:\Fonts\custom.ttf".into(),
+                data: vec![0, 1, 0, 0, 0, 4],
+            })
+        );
+        assert_eq!(fonts[&2].name, "Linked");
+        assert_eq!(
+            fonts[&2].embedded,
+            Some(EmbeddedFont {
+                file_name: "linked.ttf".into(),
+                ..EmbeddedFont::default()
+            })
+        );
+    }
+
     #[test]
     fn parse_header_and_body() {
         let rtf = r#"{\rtf1\ansi\ansicpg1252\cocoartf2639
@@ -664,6 +2542,40 @@ pub mod tests {
         assert_eq!(document.body[2].paragraph.alignment, Alignment::LeftAligned);
     }
 
+    #[test]
+    fn parse_tab_stops() {
+        let rtf = r#"{\rtf1\ansi\pard\tx720\tqr\tldot\tx1440\tqc\tx2160\tb2880 Tabbed\tab text\par
+            \pard No tabs}"#;
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        assert_eq!(document.body[0].text, "Tabbed\ttext");
+        assert_eq!(
+            document.body[0].paragraph.tab_stops,
+            vec![
+                TabStop {
+                    position: Twips(720),
+                    ..TabStop::default()
+                },
+                TabStop {
+                    position: Twips(1440),
+                    kind: TabKind::Right,
+                    leader: TabLeader::Dot
+                },
+                TabStop {
+                    position: Twips(2160),
+                    kind: TabKind::Center,
+                    ..TabStop::default()
+                },
+                TabStop {
+                    position: Twips(2880),
+                    kind: TabKind::Bar,
+                    ..TabStop::default()
+                },
+            ]
+        );
+        assert_eq!(document.body[1].paragraph.tab_stops, vec![]);
+    }
+
     #[test]
     fn should_parse_escaped_char() {
         let rtf = r"{\rtf1\ansi\deff0 {\fonttbl {\f0 Times;}}je suis une b\'eate}";
@@ -672,6 +2584,16 @@ pub mod tests {
         assert_eq!(document.body[0].text, "je suis une bête");
     }
 
+    #[test]
+    fn font_size_in_points() {
+        let rtf = r"{\rtf1 Default size {\fs21 Small size}}";
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        assert_eq!(document.body[0].painter.font_size_pt(), 12.0);
+        assert_eq!(document.body[1].painter.font_size, 21);
+        assert_eq!(document.body[1].painter.font_size_pt(), 10.5);
+    }
+
     #[test]
     fn parse_plain_directive() {
         let rtf = r"{\rtf1{\fonttbl {\f0 Times;}}\f0\b\fs36\u\cf2\plain Plain text}";
@@ -691,7 +2613,77 @@ pub mod tests {
             \f1 \cf3 B}"#;
         let tokens = Lexer::scan(rtf).unwrap();
         let document = Parser::new(tokens).parse().unwrap();
-        assert_eq!(document.header.color_table.get(&document.body[0].painter.color_ref).unwrap(), &Color { red: 251, green: 2, blue: 7 });
+        assert_eq!(document.header.color_table.get(&document.body[0].painter.color_ref).unwrap(), &Color::from_rgb(251, 2, 7));
+    }
+
+    #[test]
+    fn parse_color_table_quirks() {
+        #[rustfmt::skip]
+        let color_tables: [(&str, &[(ColorRef, Color)]); 9] = [
+            // Word, TextEdit and WordPad start with the automatic color
+            (r"{\colortbl;\red0\green0\blue0;\red255\green0\blue0;}",               &[(1, Color::from_rgb(0, 0, 0)), (2, Color::from_rgb(255, 0, 0))]),
+            (r"{\colortbl;\red255\green255\blue255;\red251\green2\blue7;}",         &[(1, Color::from_rgb(255, 255, 255)), (2, Color::from_rgb(251, 2, 7))]),
+            (r"{\colortbl ;\red255\green0\blue0;\red0\green0\blue255;}",            &[(1, Color::from_rgb(255, 0, 0)), (2, Color::from_rgb(0, 0, 255))]),
+            // Without automatic color, \cf0 is the first one
+            (r"{\colortbl\red0\green0\blue0;\red255\green0\blue0;}",                &[(0, Color::from_rgb(0, 0, 0)), (1, Color::from_rgb(255, 0, 0))]),
+            // The missing components are 0
+            (r"{\colortbl;\red255;\green128\blue64;\blue255;}",                      &[(1, Color::from_rgb(255, 0, 0)), (2, Color::from_rgb(0, 128, 64)), (3, Color::from_rgb(0, 0, 255))]),
+            // Missing ';' at the end, or between two entries
+            (r"{\colortbl;\red255\green0\blue0;\red0\green255\blue0}",              &[(1, Color::from_rgb(255, 0, 0)), (2, Color::from_rgb(0, 255, 0))]),
+            (r"{\colortbl;\red255\green0\blue0\red0\green0\blue255;}",              &[(1, Color::from_rgb(255, 0, 0)), (2, Color::from_rgb(0, 0, 255))]),
+            // An empty entry still takes an index
+            (r"{\colortbl;;\red0\green0\blue255;}",                                &[(2, Color::from_rgb(0, 0, 255))]),
+            (r"{\colortbl;\red300\green-5\blue0;}",                                 &[(1, Color::from_rgb(255, 0, 0))]),
+        ];
+        for (color_table, expected) in color_tables {
+            let document = RtfDocument::try_from(format!(r"{{\rtf1\ansi{color_table}{{\cf1 A}}}}")).unwrap();
+            assert_eq!(document.header.color_table, expected.iter().cloned().collect::<ColorTable>(), "{color_table}");
+            assert_eq!(
+                document.header.get_color(&document.body[0].painter.color_ref),
+                expected.iter().find(|(color_ref, _)| *color_ref == 1).map(|(_, color)| color)
+            );
+        }
+    }
+
+    #[test]
+    fn parse_expanded_color_table() {
+        let rtf = r#"{\rtf1\ansi
+            {\colortbl;\red255\green255\blue255;\red251\green2\blue7;\red114\green44\blue253;\red128\green128\blue128;}
+            {\*\expandedcolortbl;;\cssrgb\c100000\c14913\c0;\cssrgb\c52799\c30710\c99498\c50000;\csgray\c50000;}
+            \cf2 A}"#;
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        let header = &document.header;
+        assert_eq!(header.expanded_color_table.len(), 3);
+        assert_eq!(header.get_color(&1).unwrap(), &Color::from_rgb(255, 255, 255));
+        assert_eq!(header.get_color(&2).unwrap(), &Color::from_rgb(255, 38, 0));
+        assert_eq!(
+            header.expanded_color_table.get(&3).unwrap(),
+            &ExpandedColor {
+                color_space: ColorSpace::Srgb,
+                color: Color::from_rgba(135, 78, 254, 128),
+            }
+        );
+        assert_eq!(header.expanded_color_table.get(&4).unwrap().color_space, ColorSpace::Gray);
+        assert_eq!(header.get_color(&4).unwrap(), &Color::from_rgb(128, 128, 128));
+    }
+
+    #[test]
+    fn parse_expanded_color_table_empty_entries() {
+        // An empty entry still takes an index
+        let rtf = r"{\rtf1\ansi{\*\expandedcolortbl;;\cssrgb\c3;;\cssrgb\c4;}A}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let mut keys: Vec<&ColorRef> = document.header.expanded_color_table.keys().collect();
+        keys.sort();
+        assert_eq!(keys, [&2, &4]);
+    }
+
+    #[test]
+    fn keep_semicolon_after_control_word() {
+        // Only a space delimits a control word, the ';' is text
+        let rtf = r"{\rtf1\ansi A\b0; B}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "A; B");
     }
 
     #[test]
@@ -708,8 +2700,20 @@ pub mod tests {
             \f0\fs24 \cf0 \ul \ulc0 a\ulnone A}"#;
         let tokens = Lexer::scan(rtf).unwrap();
         let document = Parser::new(tokens).parse().unwrap();
-        assert_eq!(&document.body[0].painter.underline, &true);
-        assert_eq!(&document.body[1].painter.underline, &false);
+        assert_eq!(&document.body[0].painter.underline, &Underline::Single);
+        assert_eq!(&document.body[1].painter.underline, &Underline::None);
+    }
+
+    #[test]
+    fn parse_underline_styles() {
+        let rtf = r#"{\rtf1\ansi{\colortbl;\red255\green0\blue0;}{\uldb\ulc1 double}{\uld dotted}{\ulw word}{\uldash0 none}}"#;
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        assert_eq!(document.body[0].painter.underline, Underline::Double);
+        assert_eq!(document.body[0].painter.underline_color, 1);
+        assert_eq!(document.body[1].painter.underline, Underline::Dotted);
+        assert_eq!(document.body[2].painter.underline, Underline::Word);
+        assert_eq!(document.body[3].painter.underline, Underline::None);
     }
 
     #[test]
@@ -730,7 +2734,8 @@ pub mod tests {
             \f0 a\u55357 \u56447 1 \u21834}"#;
         let tokens = Lexer::scan(rtf).unwrap();
         let document = Parser::new(tokens).parse().unwrap();
-        assert_eq!(&document.body[0].text, "a👿1 啊");
+        // The '1' is the \uc1 fallback of the low surrogate
+        assert_eq!(&document.body[0].text, "a👿 啊");
     }
 
     #[test]
@@ -752,6 +2757,19 @@ pub mod tests {
         assert_eq!(&document.body[4].text, "bêête");
     }
 
+    #[test]
+    fn parse_unicode_destination_pair() {
+        // The ANSI version (\upr) is discarded for the Unicode version (\ud)
+        let rtf = r#"{\rtf1\ansi Name : {\upr{\f0 \'93\'fa\'96\'7b}{\*\ud{\f0\uc0 \u26085\u26412}}}, end}"#;
+        let tokens = Lexer::scan(rtf).unwrap();
+        let document = Parser::new(tokens).parse().unwrap();
+        assert_eq!(document.get_text(), "Name : 日本, end");
+        // With a line break after \upr
+        let rtf = "{\\rtf1\\ansi Name : {\\upr\\\n {\\f0 \\'93\\'fa}\r\n{\\*\\ud{\\f0\\uc0 \\u26085}}}, end}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Name : 日, end");
+    }
+
     #[test]
     fn body_starts_with_a_group() {
         let rtf = r"{\rtf1\ansi\deff0{\fonttbl {\f0\fnil\fcharset0 Calibri;}{\f1\fnil\fcharset2 Symbol;}}{\colortbl ;}{\pard \u21435  \sb70\par}}";
@@ -759,6 +2777,26 @@ pub mod tests {
         let _document = Parser::new(tokens).parse().unwrap();
     }
 
+    #[test]
+    fn parse_character_properties() {
+        let rtf = r"{\rtf1 {\up Up}{\dn4 Down}{\expnd-4 Condensed}{\expndtw30 Expanded}{\outl\shad\caps Styled}{\v Hidden}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.body[0].painter.baseline_offset, 6);
+        assert_eq!(document.body[1].painter.baseline_offset, -4);
+        assert_eq!(document.body[2].painter.character_spacing, Twips(-20));
+        assert_eq!(document.body[3].painter.character_spacing, Twips(30));
+        assert_eq!(
+            document.body[4].painter,
+            Painter {
+                outline: true,
+                shadow: true,
+                caps: true,
+                ..Painter::default()
+            }
+        );
+        assert!(document.body[5].painter.hidden);
+    }
+
     #[test]
     fn rtf_different_semantic() {
         let rtf1 = r"{\rtf1 \b bold \i Bold Italic \i0 Bold again}";
@@ -770,4 +2808,269 @@ pub mod tests {
         assert_eq!(doc1.body, doc2.body);
         assert_eq!(doc3.body, doc2.body);
     }
+
+    #[test]
+    fn limit_resources() {
+        let rtf = r"{\rtf1 {{{deep}}} text {\pict\pngblip 0102030405}}";
+        let parse = |options: ParserOptions| Parser::new(Lexer::scan(rtf).unwrap()).with_options(options).parse();
+        assert!(parse(ParserOptions::default()).is_ok());
+        let limits = [
+            ParserOptions {
+                max_nesting_depth: 3,
+                ..ParserOptions::default()
+            },
+            ParserOptions {
+                max_tokens: 10,
+                ..ParserOptions::default()
+            },
+            ParserOptions {
+                max_text_length: 8,
+                ..ParserOptions::default()
+            },
+            ParserOptions {
+                max_image_size: 4,
+                ..ParserOptions::default()
+            },
+        ];
+        for options in limits {
+            assert!(matches!(parse(options), Err(ParserError::LimitExceeded(_))));
+        }
+        let options = ParserOptions {
+            max_nesting_depth: 4,
+            max_tokens: 16,
+            max_text_length: 10,
+            max_image_size: 5,
+            recover_unclosed_groups: false,
+            fragment: false,
+            capture_blobs: false,
+            cell_separator: None,
+            row_separator: None,
+            clamp_invalid_refs: false,
+            matching: crate::tokens::ControlWordMatching::Strict,
+            default_codepage: None,
+        };
+        assert!(parse(options).is_ok());
+        // The tokens and the nesting depth are already limited by the lexer
+        let hostile = format!("{{\\rtf1 {}deep{}}}", "{".repeat(100_000), "}".repeat(100_000));
+        let options = ParserOptions {
+            max_nesting_depth: 64,
+            ..ParserOptions::default()
+        };
+        assert!(matches!(Lexer::scan_with_options(&hostile, &options), Err(LexerError::LimitExceeded(_))));
+        assert!(RtfDocument::from_str_with_options(&hostile, options).is_err());
+        assert!(RtfDocument::from_str_with_options(rtf, options).is_ok());
+    }
+
+    #[test]
+    fn decode_bytes_with_codepage() {
+        let texts: Vec<String> = [r"\ansi", r"\ansi\ansicpg1251", r"\mac", r"\pc", r"\ansi\ansicpg1255"]
+            .iter()
+            .map(|charset| RtfDocument::try_from(format!(r"{{\rtf1{charset} \'80\'c0\'8e}}")).unwrap().get_text())
+            .collect();
+        assert_eq!(texts, ["€ÀŽ", "ЂАЋ", "Ä¿é", "Ç└Ä", "€ÀŽ"].map(String::from));
+        let document = RtfDocument::try_from(r"{\rtf1\mac text}").unwrap();
+        assert_eq!(document.header.character_set, Mac);
+        assert_eq!(document.header.character_set.codepage(), 10000);
+    }
+
+    #[test]
+    #[cfg(feature = "cjk")]
+    fn decode_double_byte_codepages() {
+        let rtf = r"{\rtf1\ansi\ansicpg932{\fonttbl{\f0\fnil\fcharset128 MS Mincho;}{\f1\fnil\fcharset134 SimSun;}{\f2\fswiss\fcharset0 Arial;}}
+            \f0 \'93\'fa\'96\'7b\'8c\'ea \'83e\'83\'58\'83g \'b1 {\uc2\u26085\'93\'fa}\par
+            \f1 \'d6\'d0\'ce\'c4\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "日本語 テスト ｱ 日中文");
+        let (_, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn parse_with_warnings() {
+        let rtf = r"{\rtf1\ansi\ansicpg1255{\fonttbl\f0\fswiss Helvetica;}{\colortbl;\red255\green0\blue0;}{\*\unknowndest data}
+            \f0\cf1 Text \f3\cf2 with \cf0 bad \f3 refs \vendorword1 and \vendorword2 unknown}";
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        assert_eq!(document.get_text(), "Text with bad refs and unknown");
+        assert_eq!(
+            warnings,
+            vec![
+                ParserWarning::CodepageFallback(1255),
+                ParserWarning::SkippedDestination(r"\unknowndest".into()),
+                ParserWarning::InvalidFontRef(3),
+                ParserWarning::InvalidColorRef(2),
+                ParserWarning::UnknownControlWord(r"\vendorword".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn warn_invalid_parameters() {
+        let rtf = r"{\rtf1\ansi \fs24 Big {\fs-10 same size}{\b2 bold}\par1 {\b0\ul1\up8\dn\li-720 valid}}";
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        // The invalid font size is ignored, so the text is in the same style block
+        assert_eq!(document.body[0].text, "Big same size");
+        assert!(document.body[1].painter.bold);
+        assert_eq!(
+            warnings,
+            vec![
+                ParserWarning::InvalidParameter(r"\fs".into(), Property::Value(-10)),
+                ParserWarning::InvalidParameter(r"\b".into(), Property::Value(2)),
+                ParserWarning::InvalidParameter(r"\par".into(), Property::Value(1)),
+            ]
+        );
+        // The nesting level is clamped
+        let rtf = r"{\rtf1\ansi \itap-1 Not in a table\par}";
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        assert_eq!(document.get_text(), "Not in a table");
+        assert!(document.tables.is_empty());
+        assert_eq!(warnings, vec![ParserWarning::InvalidParameter(r"\itap".into(), Property::Value(-1))]);
+    }
+
+    #[test]
+    fn capture_blobs() {
+        let rtf = r"{\rtf1\ansi{\*\themedata 504b
+            0304}{\*\colorschememapping 3c3f}{\*\datastore 0105}Text}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert!(document.header.blobs.is_empty());
+        assert_eq!(document.get_text(), "Text");
+        let options = ParserOptions {
+            capture_blobs: true,
+            ..ParserOptions::default()
+        };
+        let document = RtfDocument::from_str_with_options(rtf, options).unwrap();
+        assert_eq!(document.header.blobs[r"\themedata"], [0x50, 0x4b, 0x03, 0x04]);
+        assert_eq!(document.header.blobs[r"\colorschememapping"], b"<?");
+        assert_eq!(document.header.blobs[r"\datastore"], [0x01, 0x05]);
+        assert_eq!(document.get_text(), "Text");
+    }
+
+    #[test]
+    fn parse_encrypted_document() {
+        let rtf = r"{\rtf1\ansi{\*\password 0b5e2a7c}\u-3913?\u-3847?}";
+        let error = Parser::new(Lexer::scan(rtf).unwrap()).parse().unwrap_err();
+        assert!(matches!(error, ParserError::EncryptedDocument));
+        let rtf = r"{\rtf1\ansi{\*\passwordhash 0100000012}Text}";
+        assert!(matches!(Parser::new(Lexer::scan(rtf).unwrap()).parse(), Err(ParserError::EncryptedDocument)));
+    }
+
+    #[test]
+    fn parse_truncated_document() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}\f0 Partial {\b document";
+        assert!(matches!(Lexer::scan(rtf), Err(LexerError::InvalidLastChar)));
+        let options = ParserOptions {
+            recover_unclosed_groups: true,
+            ..ParserOptions::default()
+        };
+        let tokens = Lexer::scan_with_options(rtf, &options).unwrap();
+        assert!(Parser::new(tokens.clone()).parse().is_err());
+        let (document, warnings) = Parser::new(tokens).with_options(options).parse_with_warnings().unwrap();
+        assert_eq!(document.get_text(), "Partial document");
+        assert!(document.body[1].painter.bold);
+        assert_eq!(warnings, vec![ParserWarning::UnclosedGroups(2)]);
+        // A complete document is not changed
+        let (_, warnings) = Parser::new(Lexer::scan(r"{\rtf1 Complete}").unwrap()).with_options(options).parse_with_warnings().unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn handle_unknown_control_words() {
+        // The control words known by the parser, as \tab, are not given to the handler
+        let rtf = r"{\rtf1\ansi \vendorbold Bold \vendorplain text\vendorsymbol2 and\tab \vendorother end}";
+        let tokens = Lexer::scan(rtf).unwrap();
+        let (document, warnings) = Parser::new(tokens)
+            .on_unknown_control_word(|name, property, painter, _paragraph| match name {
+                r"\vendorbold" => {
+                    painter.bold = true;
+                    ControlWordAction::Handled
+                }
+                r"\vendorplain" => {
+                    *painter = Painter::default();
+                    ControlWordAction::Handled
+                }
+                r"\vendorsymbol" => ControlWordAction::InsertText("★".repeat(property.get_value() as usize)),
+                _ => ControlWordAction::Ignored,
+            })
+            .parse_with_warnings()
+            .unwrap();
+        assert_eq!(document.get_text(), "Bold text★★and\tend");
+        assert!(document.body[0].painter.bold);
+        assert!(!document.body[1].painter.bold);
+        assert_eq!(warnings, vec![ParserWarning::UnknownControlWord(r"\vendorother".into())]);
+    }
+
+    #[test]
+    fn handle_destinations() {
+        let rtf = r"{\rtf1\ansi{\*\vendormeta id-42}{\*\vendorignored data}Start {\*\vendorsymbol star} {\pict\pngblip 89504e47}{\*\bkmkstart mark}End{\*\bkmkend mark}}";
+        let mut picture_tokens = 0;
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap())
+            .on_destination(r"\vendormeta", |tokens| {
+                let text = tokens.iter().filter_map(|token| if let Token::PlainText(text) = token { Some(*text) } else { None }).collect();
+                DestinationAction::Attach(text)
+            })
+            .on_destination(r"\vendorsymbol", |_| DestinationAction::InsertText("★".into()))
+            .on_destination(r"\vendorignored", |_| DestinationAction::Ignored)
+            .on_destination(r"\bkmkstart", |_| DestinationAction::Ignored)
+            .on_destination(r"\pict", |tokens| {
+                picture_tokens = tokens.len();
+                DestinationAction::Handled
+            })
+            .parse_with_warnings()
+            .unwrap();
+        assert_eq!(document.get_text(), "Start ★End");
+        assert_eq!(
+            document.custom_destinations,
+            vec![CustomDestination {
+                name: r"\vendormeta".into(),
+                value: "id-42".into(),
+                position: 0,
+            }]
+        );
+        assert!(document.images.is_empty());
+        assert_eq!(picture_tokens, 5);
+        // An ignored destination is parsed as without handler
+        assert_eq!(document.bookmarks[0].start, 7);
+        assert_eq!(warnings, vec![ParserWarning::SkippedDestination(r"\vendorignored".into())]);
+    }
+
+    #[test]
+    fn parse_character_styles() {
+        let rtf = r"{\rtf1\ansi{\stylesheet{\s0 Normal;}{\*\cs10\additive Default Paragraph Font;}{\*\cs15\additive\ul\cf2 Hyperlink;}{\s15 Heading;}{\*\ds20 Section;}}
+\pard\s15 Title\par\pard\s0 See {\cs15\ul the link} and {\cs42 more}}";
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        let header = &document.header;
+        assert_eq!(header.stylesheet.keys().collect::<Vec<_>>(), [&0, &15]);
+        assert_eq!(header.character_styles.keys().collect::<Vec<_>>(), [&10, &15]);
+        assert_eq!(header.character_styles[&15].name, "Hyperlink");
+        assert!(header.character_styles[&15].additive && !header.stylesheet[&15].additive);
+        assert_eq!(header.get_character_style_by_name("hyperlink").map(|(style_ref, _)| style_ref), Some(15));
+        // The paragraph and character styles don't share their references
+        assert_eq!(
+            document.body.iter().map(|style_block| style_block.painter.char_style_ref).collect::<Vec<_>>(),
+            [None, None, Some(15), None, Some(42)]
+        );
+        assert_eq!(document.blocks_with_style("Hyperlink").map(|style_block| style_block.text.as_str()).collect::<Vec<_>>(), ["the link"]);
+        assert_eq!(warnings, vec![ParserWarning::InvalidStyleRef(42)]);
+        let options = ParserOptions {
+            clamp_invalid_refs: true,
+            ..ParserOptions::default()
+        };
+        let document = RtfDocument::from_str_with_options(rtf, options).unwrap();
+        assert_eq!(document.body.last().unwrap().painter.char_style_ref, None);
+    }
+
+    #[test]
+    fn parse_ignorable_destinations() {
+        let rtf = r"{\rtf1\ansi{\*\generator Microsoft Word 11.0.8134;}{\*\expandedcolortbl;;}Text {\b\*\vendorword skipped} end{\i\* skipped} more\par}";
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        assert_eq!(document.header.generator.as_deref(), Some("Microsoft Word 11.0.8134"));
+        // The unknown destinations are skipped, even when their \* doesn't follow a bracket
+        assert_eq!(document.get_text(), "Text  end more");
+        assert_eq!(
+            warnings,
+            vec![ParserWarning::SkippedDestination(r"\vendorword".into()), ParserWarning::SkippedDestination(r"\*".into())]
+        );
+        // A handler replaces the parsing of a known destination
+        let document = Parser::new(Lexer::scan(rtf).unwrap()).on_destination(r"\generator", |_| DestinationAction::Handled).parse().unwrap();
+        assert_eq!(document.header.generator, None);
+    }
 }
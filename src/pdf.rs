@@ -0,0 +1,429 @@
+/// Define the export of a document to PDF, with the standard Type 1 fonts so that no font has to be embedded
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::mem;
+
+use crate::document::RtfDocument;
+use crate::header::FontFamily;
+use crate::paragraph::{Alignment, Paragraph, SpaceBetweenLine};
+use crate::parser::{Painter, Underline};
+
+// US Letter page, in points
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 72.0;
+// Height of a line relative to the size of its font
+const LINE_HEIGHT_FACTOR: f32 = 1.2;
+// Number of spaces of a tab
+const TAB_SPACES: f32 = 4.0;
+// The line breaks inside of a paragraph (\line) separate the words like the spaces, and end their line
+const WHITESPACES: [char; 3] = [' ', '\t', '\n'];
+
+// The standard fonts, by family (Helvetica, Times, Courier) then by style (regular, bold, italic, bold italic)
+const FONT_NAMES: [&str; 12] = [
+    "Helvetica",
+    "Helvetica-Bold",
+    "Helvetica-Oblique",
+    "Helvetica-BoldOblique",
+    "Times-Roman",
+    "Times-Bold",
+    "Times-Italic",
+    "Times-BoldItalic",
+    "Courier",
+    "Courier-Bold",
+    "Courier-Oblique",
+    "Courier-BoldOblique",
+];
+// Widths of the ASCII chars from ' ' to '~' in thousandths of the font size, the other chars having the width of 'n'
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+#[rustfmt::skip]
+const TIMES_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TextStyle {
+    // Index in FONT_NAMES
+    font: usize,
+    size: f32,
+    color: (u8, u8, u8),
+    underline: bool,
+    strike: bool,
+}
+
+impl TextStyle {
+    // Width of the text in points, the bold and italic fonts being approximated with the widths of the regular ones
+    fn get_width(&self, text: &str) -> f32 {
+        let widths = match self.font / 4 {
+            0 => &HELVETICA_WIDTHS,
+            1 => &TIMES_WIDTHS,
+            _ => return text.chars().count() as f32 * 0.6 * self.size, // Courier is monospaced
+        };
+        let width: u32 = text
+            .chars()
+            .map(|c| match c {
+                ' '..='~' => widths[c as usize - ' ' as usize] as u32,
+                '\t' => widths[0] as u32 * TAB_SPACES as u32,
+                _ => widths['n' as usize - ' ' as usize] as u32,
+            })
+            .sum();
+        return width as f32 * self.size / 1000.0;
+    }
+}
+
+// A part of a word written with a single style
+struct Fragment<'a> {
+    text: &'a str,
+    style: TextStyle,
+}
+
+struct Word<'a> {
+    fragments: Vec<Fragment<'a>>,
+    width: f32,
+    // Width of the spaces between the previous word and this one
+    space_before: f32,
+    // Number of line breaks between the previous word and this one
+    line_breaks_before: usize,
+}
+
+struct Line<'a> {
+    words: Vec<Word<'a>>,
+    // Size of the largest font of the line
+    size: f32,
+}
+
+impl Line<'_> {
+    fn get_width(&self) -> f32 {
+        return self.words.iter().enumerate().map(|(index, word)| word.width + if index > 0 { word.space_before } else { 0.0 }).sum();
+    }
+}
+
+// The content streams of the pages, filled paragraph by paragraph
+struct PageWriter {
+    pages: Vec<String>,
+    // Top of the next line
+    y: f32,
+}
+
+impl PageWriter {
+    fn new_page(&mut self) {
+        self.pages.push(String::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn is_page_empty(&self) -> bool {
+        return self.y >= PAGE_HEIGHT - MARGIN;
+    }
+
+    fn write_fragment(&mut self, fragment: &Fragment, x: f32, baseline: f32) {
+        let style = &fragment.style;
+        let content = self.pages.last_mut().expect("A page is created before writing");
+        let (red, green, blue) = style.color;
+        let color = format!("{:.3} {:.3} {:.3} rg", red as f32 / 255.0, green as f32 / 255.0, blue as f32 / 255.0);
+        let _ = writeln!(
+            content,
+            "BT /F{} {:.2} Tf {color} {x:.2} {baseline:.2} Td ({}) Tj ET",
+            style.font + 1,
+            style.size,
+            encode_text(fragment.text)
+        );
+        let width = style.get_width(fragment.text);
+        // The lines are thin rectangles, below the baseline for the underline and at the middle of the lowercase letters for the strike
+        let thickness = style.size * 0.05;
+        for (enabled, offset) in [(style.underline, -style.size * 0.12), (style.strike, style.size * 0.25)] {
+            if enabled {
+                let _ = writeln!(content, "{color} {x:.2} {:.2} {width:.2} {thickness:.2} re f", baseline + offset);
+            }
+        }
+    }
+}
+
+impl RtfDocument {
+    /// Convert the document to the bytes of a single column PDF file, with the fonts, sizes, colors, alignment, indentation and spacing of the text.
+    /// The fonts are replaced by the standard Helvetica, Times or Courier fonts, and the chars outside of the Windows-1252 charset by '?'.
+    /// The tables, images and fields are exported as plain paragraphs, hidden text (\v) and deleted text (\deleted) are excluded.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut writer = PageWriter { pages: vec![], y: 0.0 };
+        writer.new_page();
+        for (paragraph, runs) in self.get_paragraph_runs(false) {
+            self.write_pdf_paragraph(&mut writer, paragraph, &runs);
+        }
+        return write_pdf(&writer.pages);
+    }
+
+    fn get_text_style(&self, painter: &Painter) -> TextStyle {
+        let family = match self.header.font_table.get(&painter.font_ref) {
+            Some(font) if font.font_family == FontFamily::Modern || font.name.contains("Courier") => 2,
+            Some(font) if font.font_family == FontFamily::Roman || font.name.contains("Times") => 1,
+            _ => 0,
+        };
+        let color = match self.header.get_color(&painter.color_ref).filter(|_| painter.color_ref != 0) {
+            Some(color) => (color.red, color.green, color.blue),
+            None => (0, 0, 0),
+        };
+        return TextStyle {
+            font: family * 4 + usize::from(painter.bold) + 2 * usize::from(painter.italic),
+            size: painter.font_size.max(1) as f32 / 2.0,
+            color,
+            underline: painter.underline != Underline::None,
+            strike: painter.strike,
+        };
+    }
+
+    fn write_pdf_paragraph(&self, writer: &mut PageWriter, paragraph: &Paragraph, runs: &[(&Painter, &str)]) {
+        let default_style = self.get_text_style(runs.first().map_or(&Painter::default(), |(painter, _)| painter));
+        let label = if paragraph.list_label.is_empty() { String::new() } else { paragraph.list_label.clone() + " " };
+        let styled_runs = [(default_style, label.as_str())]
+            .into_iter()
+            .chain(runs.iter().map(|(painter, text)| (self.get_text_style(painter), *text)));
+        let words = split_words(styled_runs);
+
+        let left = MARGIN + paragraph.indent.left.to_points();
+        let right = PAGE_WIDTH - MARGIN - paragraph.indent.right.to_points();
+        let first_left = left + paragraph.indent.first_line.to_points();
+        let lines = wrap_words(words, right - first_left, right - left, default_style.size);
+
+        if paragraph.page_break_before && !writer.is_page_empty() {
+            writer.new_page();
+        }
+        writer.y -= paragraph.spacing.before.to_points();
+        let line_count = lines.len();
+        for (index, line) in lines.iter().enumerate() {
+            let line_height = match paragraph.spacing.between_line {
+                SpaceBetweenLine::Value(height) => height.to_points().max(line.size * LINE_HEIGHT_FACTOR),
+                _ => line.size * LINE_HEIGHT_FACTOR,
+            };
+            if writer.y - line_height < MARGIN && !writer.is_page_empty() {
+                writer.new_page();
+            }
+            let baseline = writer.y - line.size;
+            writer.y -= line_height;
+            let line_left = if index == 0 { first_left } else { left };
+            let extra = (right - line_left - line.get_width()).max(0.0);
+            let gaps = line.words.len().saturating_sub(1);
+            #[rustfmt::skip]
+            let (mut x, gap) = match paragraph.alignment {
+                Alignment::LeftAligned                                      => (line_left, 0.0),
+                Alignment::RightAligned                                     => (line_left + extra, 0.0),
+                Alignment::Center                                           => (line_left + extra / 2.0, 0.0),
+                Alignment::Justify if index + 1 < line_count && gaps > 0    => (line_left, extra / gaps as f32),
+                Alignment::Justify                                          => (line_left, 0.0),
+            };
+            for (word_index, word) in line.words.iter().enumerate() {
+                if word_index > 0 {
+                    x += word.space_before + gap;
+                }
+                for fragment in &word.fragments {
+                    writer.write_fragment(fragment, x, baseline);
+                    x += fragment.style.get_width(fragment.text);
+                }
+            }
+        }
+        writer.y -= paragraph.spacing.after.to_points();
+    }
+}
+
+// Split the styled text in words, a word being able to have several styles
+fn split_words<'a>(runs: impl Iterator<Item = (TextStyle, &'a str)>) -> Vec<Word<'a>> {
+    let mut words: Vec<Word> = vec![];
+    // The spaces and the line breaks read since the last word
+    let mut space = 0.0;
+    let mut line_breaks = 0;
+    for (style, text) in runs {
+        let mut rest = text;
+        while !rest.is_empty() {
+            let spaces = rest.len() - rest.trim_start_matches(WHITESPACES).len();
+            if spaces > 0 {
+                let whitespaces = &rest[..spaces];
+                let breaks = whitespaces.matches('\n').count();
+                // Only the spaces after the last line break are at the start of the next line
+                let line_start = whitespaces.rfind('\n').map_or(0, |index| index + 1);
+                space = if breaks > 0 { 0.0 } else { space } + style.get_width(&whitespaces[line_start..]);
+                line_breaks += breaks;
+                rest = &rest[spaces..];
+                continue;
+            }
+            let length = rest.find(WHITESPACES).unwrap_or(rest.len());
+            let fragment = Fragment { text: &rest[..length], style };
+            let width = style.get_width(fragment.text);
+            match words.last_mut() {
+                // The word continues with the style of another run
+                Some(word) if space == 0.0 && line_breaks == 0 => {
+                    word.fragments.push(fragment);
+                    word.width += width;
+                }
+                _ => words.push(Word {
+                    fragments: vec![fragment],
+                    width,
+                    space_before: space,
+                    line_breaks_before: line_breaks,
+                }),
+            }
+            space = 0.0;
+            line_breaks = 0;
+            rest = &rest[length..];
+        }
+    }
+    return words;
+}
+
+// Fill the lines with the words, a word longer than the line being on its own line
+fn wrap_words(words: Vec<Word>, first_width: f32, width: f32, default_size: f32) -> Vec<Line> {
+    let mut lines: Vec<Line> = vec![];
+    let mut current = Line { words: vec![], size: 0.0 };
+    for word in words {
+        // Each line break ends a line, the ones which follow another make empty lines
+        for _ in 0..word.line_breaks_before {
+            if current.size == 0.0 {
+                current.size = default_size;
+            }
+            lines.push(mem::replace(&mut current, Line { words: vec![], size: 0.0 }));
+        }
+        let line_width = if lines.is_empty() { first_width } else { width };
+        if !current.words.is_empty() && current.get_width() + word.space_before + word.width > line_width {
+            lines.push(current);
+            current = Line { words: vec![], size: 0.0 };
+        }
+        current.size = word.fragments.iter().fold(current.size, |size, fragment| size.max(fragment.style.size));
+        current.words.push(word);
+    }
+    // An empty paragraph is an empty line
+    if current.size == 0.0 {
+        current.size = default_size;
+    }
+    lines.push(current);
+    return lines;
+}
+
+// Encode the text as a PDF string in the WinAnsiEncoding of the standard fonts
+fn encode_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        #[rustfmt::skip]
+        let byte = match c {
+            '(' | ')' | '\\'         => {
+                result.push('\\');
+                result.push(c);
+                continue;
+            }
+            '\t'                     => {
+                result.push_str(&" ".repeat(TAB_SPACES as usize));
+                continue;
+            }
+            ' '..='~'                => {
+                result.push(c);
+                continue;
+            }
+            '\u{A0}'..='\u{FF}'      => c as u32 as u8,
+            '€'                      => 0x80,
+            '…'                      => 0x85,
+            '‘'                      => 0x91,
+            '’'                      => 0x92,
+            '“'                      => 0x93,
+            '”'                      => 0x94,
+            '•'                      => 0x95,
+            '–'                      => 0x96,
+            '—'                      => 0x97,
+            '™'                      => 0x99,
+            _                        => b'?',
+        };
+        let _ = write!(result, "\\{byte:03o}");
+    }
+    return result;
+}
+
+// Write the PDF file : the catalog, the page tree, the fonts, then each page with its content stream
+fn write_pdf(pages: &[String]) -> Vec<u8> {
+    let font_count = FONT_NAMES.len();
+    let first_page = 4 + font_count;
+    let mut objects: Vec<String> = vec![];
+    objects.push(String::from("<< /Type /Catalog /Pages 2 0 R >>"));
+    let kids: Vec<String> = (0..pages.len()).map(|index| format!("{} 0 R", first_page + 2 * index)).collect();
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+    let fonts: Vec<String> = (0..font_count).map(|index| format!("/F{} {} 0 R", index + 1, 4 + index)).collect();
+    objects.push(format!("<< /Font << {} >> >>", fonts.join(" ")));
+    for name in FONT_NAMES {
+        objects.push(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>"));
+    }
+    for (index, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources 3 0 R /Contents {} 0 R >>",
+            first_page + 2 * index + 1
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{content}endstream", content.len()));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", index + 1);
+    }
+    let xref_offset = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{offset:010} 00000 n ");
+    }
+    let _ = write!(pdf, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n", objects.len() + 1);
+    return pdf.into_bytes();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_pdf() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\froman Times;}{\colortbl;\red255\green0\blue0;}\f0\fs32\qc Title (1)\par\pard\fs24 Some {\b\cf1 bold} te{\ul xt} \'e9\u8364?\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let pdf = String::from_utf8(document.to_pdf()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains(r"/F5 16.00 Tf 0.000 0.000 0.000 rg"));
+        assert!(pdf.contains(r"(Title) Tj"));
+        assert!(pdf.contains(r"(\(1\)) Tj"));
+        assert!(pdf.contains(r"/F6 12.00 Tf 1.000 0.000 0.000 rg"));
+        assert!(pdf.contains(r"(\351\200) Tj"));
+        // The underline of "xt", which is a fragment of the word "text"
+        assert_eq!(pdf.matches(" re f").count(), 1);
+        // The offsets of the xref table
+        let xref = &pdf[pdf.find("xref\n").unwrap()..];
+        let first_offset: usize = xref.lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[first_offset..].starts_with("1 0 obj"));
+    }
+
+    #[test]
+    fn export_pdf_line_breaks() {
+        let document = RtfDocument::try_from(r"{\rtf1\ansi first\line second\line\line third\par}").unwrap();
+        let pdf = String::from_utf8(document.to_pdf()).unwrap();
+        // The lines of 14.4 points of the paragraph, the second line break making an empty one
+        assert!(pdf.contains("72.00 708.00 Td (first) Tj"));
+        assert!(pdf.contains("72.00 693.60 Td (second) Tj"));
+        assert!(pdf.contains("72.00 664.80 Td (third) Tj"));
+    }
+
+    #[test]
+    fn export_pdf_pages() {
+        let rtf = format!(r"{{\rtf1\ansi {}\pard\pagebb Last\par}}", r"Line\par ".repeat(60));
+        let document = RtfDocument::try_from(rtf.as_str()).unwrap();
+        let pdf = String::from_utf8(document.to_pdf()).unwrap();
+        // 60 lines of 14.4 points need 2 pages, and the last paragraph starts on a new one
+        assert!(pdf.contains("/Count 3"));
+    }
+}
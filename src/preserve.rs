@@ -0,0 +1,285 @@
+/// Define the lossless tree of a document, to write it back byte-for-byte
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::ops::Range;
+use core::{mem, str};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::document::RtfDocument;
+use crate::error::RtfError;
+use crate::lexer::{Lexer, LexerError};
+use crate::parser::Parser;
+use crate::tokens::{ControlWord, ControlWordMatching, Token};
+
+/// A node of the source tree, holding the exact source of what it represents
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum SourceNode {
+    /// A group, its brackets excluded
+    Group(Vec<SourceNode>),
+    /// A control word with its parameter and its delimiting space if any (`\b0 `, `\par`, `\unknownword`)
+    ControlWord(String),
+    /// A control symbol or an escaped char (`\*`, `\~`, `\{`, `\'e9`)
+    ControlSymbol(String),
+    /// The text between the control words and the groups, whitespaces and line breaks included
+    Text(String),
+    /// The data following a \binN control word, which can be any bytes
+    Binary(Vec<u8>),
+}
+
+/// The ordered tree of all the tokens of a document, writing it back gives the exact source.
+/// The source is given as bytes : the data of \binN can be any bytes, while the rest of the document must be valid UTF-8.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SourceTree {
+    pub nodes: Vec<SourceNode>,
+}
+
+impl SourceTree {
+    pub fn from(src: &[u8]) -> Result<Self, LexerError> {
+        let (tokens, spans) = Lexer::scan_bytes_with_spans(src)?;
+        let mut stack: Vec<Vec<SourceNode>> = vec![vec![]];
+        // End of the source already in the tree
+        let mut cursor = 0;
+        for (token, span) in tokens.iter().zip(spans) {
+            let current_group = stack.last_mut().unwrap();
+            push_untokenized(current_group, get_source(src, cursor..span.start.max(cursor))?);
+            cursor = cursor.max(span.end);
+            // The binary data is the only part of the source which may not be UTF-8
+            if let Token::Binary(data) = token {
+                current_group.push(SourceNode::Binary(data.to_vec()));
+                continue;
+            }
+            let source = get_source(src, span.start..span.end)?;
+            match token {
+                Token::OpeningBracket => stack.push(vec![]),
+                Token::ClosingBracket => {
+                    let group = stack.pop().filter(|_| !stack.is_empty()).ok_or(LexerError::Error(String::from("Unexpected closing bracket")))?;
+                    stack.last_mut().unwrap().push(SourceNode::Group(group));
+                }
+                Token::ControlSymbol((control_word, _)) if control_word.is_symbol() => current_group.push(SourceNode::ControlSymbol(String::from(source))),
+                Token::IgnorableDestination | Token::CRLF => current_group.push(SourceNode::ControlSymbol(String::from(source))),
+                Token::ControlSymbol(_) => current_group.push(SourceNode::ControlWord(String::from(source))),
+                Token::PlainText(_) => {
+                    let mut text = source;
+                    // An escaped char : its backslash is left before the text by the lexer
+                    if current_group.last() == Some(&SourceNode::ControlSymbol(String::from("\\"))) {
+                        if let Some(char) = text.chars().next() {
+                            current_group.pop();
+                            current_group.push(SourceNode::ControlSymbol(format!("\\{char}")));
+                            text = &text[char.len_utf8()..];
+                        }
+                    }
+                    push_text(current_group, text);
+                }
+                _ => push_text(current_group, source),
+            }
+        }
+        if stack.len() > 1 {
+            return Err(LexerError::Error(format!("{} group(s) not closed", stack.len() - 1)));
+        }
+        let mut nodes = stack.pop().unwrap_or_default();
+        push_untokenized(&mut nodes, get_source(src, cursor..src.len().max(cursor))?);
+        return Ok(Self { nodes });
+    }
+
+    /// Write the tree back to RTF, as bytes for the binary data to be written as is
+    pub fn to_rtf(&self) -> Vec<u8> {
+        let mut result = vec![];
+        write_nodes(&mut result, &self.nodes);
+        return result;
+    }
+}
+
+// The derived drop would recurse in the nested groups : they are moved to a stack to be dropped one by one
+impl Drop for SourceTree {
+    fn drop(&mut self) {
+        let mut groups = vec![mem::take(&mut self.nodes)];
+        while let Some(mut nodes) = groups.pop() {
+            for node in &mut nodes {
+                if let SourceNode::Group(children) = node {
+                    groups.push(mem::take(children));
+                }
+            }
+        }
+    }
+}
+
+fn get_source(src: &[u8], range: Range<usize>) -> Result<&str, LexerError> {
+    let bytes = src.get(range).unwrap_or_default();
+    return str::from_utf8(bytes).map_err(|_| LexerError::Error(String::from("The document is not valid UTF-8 outside of its binary data")));
+}
+
+// Push the parts of the source the lexer has no token for : whitespaces, control symbols (\~) and \binN
+fn push_untokenized(nodes: &mut Vec<SourceNode>, mut source: &str) {
+    while !source.is_empty() {
+        let length = match source.strip_prefix('\\') {
+            Some(tail) if tail.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                let (_, tail) = ControlWord::split(source, ControlWordMatching::Strict);
+                let length = source.len() - tail.len();
+                nodes.push(SourceNode::ControlWord(String::from(&source[..length])));
+                length
+            }
+            Some(tail) => {
+                let length = 1 + tail.chars().next().map_or(0, char::len_utf8);
+                nodes.push(SourceNode::ControlSymbol(String::from(&source[..length])));
+                length
+            }
+            None => {
+                let length = source.find('\\').unwrap_or(source.len());
+                push_text(nodes, &source[..length]);
+                length
+            }
+        };
+        source = &source[length..];
+    }
+}
+
+// Push some text, merged with the previous text node
+fn push_text(nodes: &mut Vec<SourceNode>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match nodes.last_mut() {
+        Some(SourceNode::Text(previous)) => previous.push_str(text),
+        _ => nodes.push(SourceNode::Text(String::from(text))),
+    }
+}
+
+// Write the nodes without recursion, so that deeply nested groups can't overflow the stack
+fn write_nodes(result: &mut Vec<u8>, nodes: &[SourceNode]) {
+    // The nodes left to write in each open group
+    let mut stack = vec![nodes.iter()];
+    while let Some(group_nodes) = stack.last_mut() {
+        let Some(node) = group_nodes.next() else {
+            stack.pop();
+            if !stack.is_empty() {
+                result.push(b'}');
+            }
+            continue;
+        };
+        match node {
+            SourceNode::Group(children) => {
+                result.push(b'{');
+                stack.push(children.iter());
+            }
+            SourceNode::ControlWord(source) | SourceNode::ControlSymbol(source) | SourceNode::Text(source) => result.extend_from_slice(source.as_bytes()),
+            SourceNode::Binary(data) => result.extend_from_slice(data),
+        }
+    }
+}
+
+/// A document parsed in preserve mode : the semantic model along with the lossless tree of its source
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PreservedDocument {
+    pub document: RtfDocument,
+    pub source_tree: SourceTree,
+}
+
+impl PreservedDocument {
+    /// Parse the document given as bytes : the data of \binN can be any bytes, while the rest of the document must be valid UTF-8
+    pub fn parse(src: &[u8]) -> Result<Self, RtfError> {
+        let source_tree = SourceTree::from(src)?;
+        let (tokens, spans) = Lexer::scan_bytes_with_spans(src)?;
+        let document = Parser::new_with_spans(tokens, spans).parse()?;
+        return Ok(Self { document, source_tree });
+    }
+
+    /// Write the document back to RTF from its source tree : the output is the input as long as the tree is not modified
+    pub fn to_rtf(&self) -> Vec<u8> {
+        return self.source_tree.to_rtf();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::include_test_file;
+
+    #[test]
+    fn build_source_tree() {
+        let rtf = "{\\rtf1\\ansi {\\*\\unknown Te\\'e9xt}\\b0 bold\\~\\par\r\n}\n";
+        let tree = SourceTree::from(rtf.as_bytes()).unwrap();
+        assert_eq!(
+            tree.nodes,
+            vec![
+                SourceNode::Group(vec![
+                    SourceNode::ControlWord(String::from("\\rtf1")),
+                    SourceNode::ControlWord(String::from("\\ansi ")),
+                    SourceNode::Group(vec![
+                        SourceNode::ControlSymbol(String::from("\\*")),
+                        SourceNode::ControlWord(String::from("\\unknown ")),
+                        SourceNode::Text(String::from("Te")),
+                        SourceNode::ControlSymbol(String::from("\\'e9")),
+                        SourceNode::Text(String::from("xt")),
+                    ]),
+                    SourceNode::ControlWord(String::from("\\b0 ")),
+                    SourceNode::Text(String::from("bold")),
+                    SourceNode::ControlSymbol(String::from("\\~")),
+                    SourceNode::ControlWord(String::from("\\par")),
+                    SourceNode::Text(String::from("\r\n")),
+                ]),
+                SourceNode::Text(String::from("\n")),
+            ]
+        );
+        assert_eq!(tree.to_rtf(), rtf.as_bytes());
+        // The escaped chars are control symbols
+        let rtf = "{\\rtf1 a\\{b\\\\ c}";
+        let tree = SourceTree::from(rtf.as_bytes()).unwrap();
+        let SourceNode::Group(root) = &tree.nodes[0] else { panic!("The root should be a group") };
+        assert_eq!(
+            root[2..],
+            [
+                SourceNode::ControlSymbol(String::from("\\{")),
+                SourceNode::Text(String::from("b")),
+                SourceNode::ControlSymbol(String::from("\\\\")),
+                SourceNode::Text(String::from(" c")),
+            ]
+        );
+        assert_eq!(tree.to_rtf(), rtf.as_bytes());
+        assert!(SourceTree::from(b"{\\rtf1 {}").is_err());
+        assert!(SourceTree::from(b"{\\rtf1 }}").is_err());
+    }
+
+    #[test]
+    fn preserve_binary_data() {
+        let rtf = "{\\rtf1 {\\pict\\bin4 {\\}a}}";
+        let tree = SourceTree::from(rtf.as_bytes()).unwrap();
+        let SourceNode::Group(root) = &tree.nodes[0] else { panic!("The root should be a group") };
+        let SourceNode::Group(picture) = &root[1] else { panic!("The picture should be a group") };
+        assert_eq!(picture[2], SourceNode::Binary(b"{\\}a".to_vec()));
+        assert_eq!(tree.to_rtf(), rtf.as_bytes());
+    }
+
+    #[test]
+    fn preserve_binary_bytes() {
+        // The binary data of a picture is not UTF-8
+        let rtf = b"{\\rtf1 {\\pict\\bin3 \xff\xd8\x00}after}";
+        let preserved = PreservedDocument::parse(rtf).unwrap();
+        assert_eq!(preserved.to_rtf(), rtf);
+        assert_eq!(preserved.document.get_text(), "after");
+        assert!(SourceTree::from(b"{\\rtf1 \xff}").is_err());
+    }
+
+    #[test]
+    fn drop_deep_tree() {
+        let depth = 200_000;
+        let rtf = format!("{{\\rtf1 {}a{}}}", "{".repeat(depth), "}".repeat(depth));
+        let preserved = PreservedDocument::parse(rtf.as_bytes()).unwrap();
+        assert_eq!(preserved.to_rtf(), rtf.as_bytes());
+        drop(preserved);
+    }
+
+    #[test]
+    fn round_trip_test_files() {
+        for rtf in [include_test_file!("test-file.rtf"), include_test_file!("file-sample_500kB.rtf")] {
+            let preserved = PreservedDocument::parse(rtf.as_bytes()).unwrap();
+            assert_eq!(preserved.to_rtf(), rtf.as_bytes());
+            assert_eq!(preserved.document.get_text(), RtfDocument::try_from(rtf).unwrap().get_text());
+        }
+    }
+}
@@ -0,0 +1,147 @@
+/// Define the parsing of a document in steps, to report its progress and give the hand back to the caller between the steps
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
+
+use crate::document::RtfDocument;
+use crate::error::RtfError;
+use crate::lexer::ChunkScanner;
+use crate::parser::{Parser, ParserOptions};
+use crate::tokens::OwnedToken;
+
+/// Parse a document while reporting the progress to `callback(processed_bytes, total_bytes)`, called after each scanned chunk.
+/// It runs at once : to keep the UI responsive, call it from a Web Worker, or drive a `ProgressiveParser` from the main thread.
+/// An exception thrown by the callback stops the parsing.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn parse_rtf_with_progress(rtf: String, callback: &js_sys::Function) -> Result<RtfDocument, JsValue> {
+    let mut parser = ProgressiveParser::new(&rtf);
+    let total_bytes = JsValue::from(parser.total_bytes());
+    while !parser.is_scanned() {
+        let processed_bytes = parser.step()?;
+        callback.call2(&JsValue::NULL, &JsValue::from(processed_bytes), &total_bytes)?;
+    }
+    return Ok(parser.finish()?);
+}
+
+/// A parsing done in steps : each call to `step` scans a chunk of about `chunk_size` bytes, and `finish` parses the scanned tokens.
+/// Between the steps, the caller can report the progress or yield to the event loop, for the browser UI not to freeze on the documents of several MB.
+/// ex: in JS, `while (!parser.is_scanned()) { progress.value = parser.step(); await new Promise(requestAnimationFrame) }`
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct ProgressiveParser {
+    rtf: Vec<u8>,
+    options: ParserOptions,
+    chunk_size: usize,
+    scanner: ChunkScanner,
+    // The bytes before the position are scanned
+    position: usize,
+    scanned: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ProgressiveParser {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(rtf: &str) -> Self {
+        let options = ParserOptions::default();
+        return Self {
+            rtf: rtf.as_bytes().to_vec(),
+            options,
+            chunk_size: 1 << 18,
+            scanner: ChunkScanner::new(&options),
+            position: 0,
+            scanned: false,
+        };
+    }
+
+    pub fn with_options(self, options: ParserOptions) -> Self {
+        return Self {
+            options,
+            scanner: ChunkScanner::new(&options),
+            ..self
+        };
+    }
+
+    /// The number of bytes scanned by each step, 256 KiB by default
+    pub fn with_chunk_size(self, chunk_size: usize) -> Self {
+        return Self {
+            chunk_size: chunk_size.max(1),
+            ..self
+        };
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        return self.rtf.len();
+    }
+
+    pub fn processed_bytes(&self) -> usize {
+        return self.position;
+    }
+
+    /// Whether the whole document is scanned, the tokens being ready for `finish`
+    pub fn is_scanned(&self) -> bool {
+        return self.scanned;
+    }
+
+    /// Scan the next chunk of the document, and return the number of bytes processed so far
+    /// A chunk ends before a bracket : without bracket in `chunk_size` bytes (ex: the data of a picture), the chunk is extended.
+    pub fn step(&mut self) -> Result<usize, RtfError> {
+        let mut chunk_size = self.chunk_size;
+        while !self.scanned {
+            let end = self.position.saturating_add(chunk_size).min(self.rtf.len());
+            if end == self.rtf.len() {
+                self.scanner.scan_last_chunk(&self.rtf[self.position..])?;
+                self.position = end;
+                self.scanned = true;
+                break;
+            }
+            let scanned = self.scanner.scan_chunk(&self.rtf[self.position..end])?;
+            if scanned > 0 {
+                self.position += scanned;
+                break;
+            }
+            chunk_size = chunk_size.saturating_mul(2);
+        }
+        return Ok(self.position);
+    }
+
+    /// Scan the rest of the document and parse it
+    pub fn finish(mut self) -> Result<RtfDocument, RtfError> {
+        while !self.scanned {
+            self.step()?;
+        }
+        let tokens = self.scanner.tokens.iter().map(OwnedToken::as_token).collect();
+        return Ok(Parser::new(tokens).with_options(self.options).parse()?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::include_test_file;
+    use crate::lexer::LexerError;
+
+    #[test]
+    fn parse_in_steps() {
+        for rtf in [include_test_file!("file-sample_500kB.rtf"), include_test_file!("file-with-image.rtf"), "  {\\rtf1 a}"] {
+            let mut parser = ProgressiveParser::new(rtf).with_chunk_size(4096);
+            let mut progress = vec![];
+            while !parser.is_scanned() {
+                progress.push(parser.step().unwrap());
+            }
+            assert!(progress.windows(2).all(|steps| steps[0] < steps[1]));
+            assert_eq!(progress.last(), Some(&rtf.len()));
+            assert_eq!(parser.processed_bytes(), parser.total_bytes());
+            assert_eq!(parser.finish().unwrap(), RtfDocument::try_from(rtf).unwrap());
+        }
+        let options = ParserOptions {
+            max_tokens: 1000,
+            ..ParserOptions::default()
+        };
+        let parser = ProgressiveParser::new(include_test_file!("file-sample_500kB.rtf")).with_options(options);
+        assert!(matches!(parser.finish(), Err(RtfError::Lexer(LexerError::LimitExceeded(_)))));
+        assert!(matches!(ProgressiveParser::new(r"{\rtf1 {a} text").with_chunk_size(1).finish(), Err(RtfError::Lexer(_))));
+    }
+}
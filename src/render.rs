@@ -0,0 +1,224 @@
+/// Define the Renderer trait, to convert a document to any output format, and the driver walking the document
+use alloc::collections::BTreeSet;
+use alloc::vec;
+
+use crate::document::RtfDocument;
+use crate::image::Image;
+use crate::paragraph::Paragraph;
+use crate::parser::Painter;
+use crate::table::Table;
+
+/// An output format of the document, receiving its content in the order of the text by `RtfDocument::render`
+/// Only `text` is required, the other methods do nothing by default.
+pub trait Renderer {
+    /// Start a paragraph : a paragraph ends with a \par, a line break, a cell of a table (if not rendered by `table`) or when the paragraph properties change
+    fn begin_paragraph(&mut self, _paragraph: &Paragraph) {}
+
+    /// Add a run of text of the current paragraph, without line breaks
+    fn text(&mut self, text: &str, painter: &Painter);
+
+    fn end_paragraph(&mut self, _paragraph: &Paragraph) {}
+
+    /// Add a picture at its position in the text, between the runs of the current paragraph (or between two paragraphs)
+    fn image(&mut self, _image: &Image) {}
+
+    /// Called before the text of a top-level table, between two paragraphs.
+    /// Returns true if the table has been rendered : its text is then skipped, else it's rendered as plain paragraphs.
+    fn table(&mut self, _table: &Table) -> bool {
+        return false;
+    }
+}
+
+impl RtfDocument {
+    /// Render the document with a `Renderer`. Hidden text (\v) and deleted text (\deleted) are excluded.
+    pub fn render<R: Renderer>(&self, renderer: &mut R) {
+        let mut images = self.images.iter().peekable();
+        let mut tables = self.tables.iter().peekable();
+        let mut current_paragraph: Option<&Paragraph> = None;
+        // Ends of the cells of the tables rendered as plain paragraphs, each cell (and each row) ends its paragraph
+        let mut cell_ends = BTreeSet::new();
+        // Char offset in the visible text, and end of the text of the last table rendered by the renderer
+        let mut position = 0;
+        let mut skipped_until = 0;
+        for style_block in &self.body {
+            let mut text = if !style_block.painter.is_visible() { "" } else { style_block.text.as_str() };
+            loop {
+                while let Some(table) = tables.next_if(|table| table.start <= position) {
+                    if let Some(paragraph) = current_paragraph.take() {
+                        renderer.end_paragraph(paragraph);
+                    }
+                    if renderer.table(table) {
+                        skipped_until = skipped_until.max(table.end);
+                    } else {
+                        Self::add_cell_ends(table, &mut cell_ends);
+                    }
+                }
+                while cell_ends.first().is_some_and(|end| *end <= position) {
+                    cell_ends.pop_first();
+                    if let Some(paragraph) = current_paragraph.take() {
+                        renderer.end_paragraph(paragraph);
+                    }
+                }
+                while let Some(image) = images.next_if(|image| image.position <= position) {
+                    if position >= skipped_until {
+                        renderer.image(image);
+                    }
+                }
+                if text.is_empty() {
+                    break;
+                }
+                // The run goes up to the next line break, picture, table, end of a cell or end of a skipped table
+                let next_event = [
+                    images.peek().map(|image| image.position),
+                    tables.peek().map(|table| table.start),
+                    cell_ends.first().copied(),
+                    Some(skipped_until).filter(|end| *end > position),
+                ]
+                .into_iter()
+                .flatten()
+                .min();
+                let max_length = next_event.map_or(usize::MAX, |event| event - position);
+                let (mut length, mut run_end) = (0, 0);
+                for (index, c) in text.char_indices() {
+                    if length == max_length || c == '\n' {
+                        break;
+                    }
+                    length += 1;
+                    run_end = index + c.len_utf8();
+                }
+                let skipped = position < skipped_until;
+                if run_end > 0 {
+                    if !skipped {
+                        if current_paragraph != Some(&style_block.paragraph) {
+                            if let Some(paragraph) = current_paragraph {
+                                renderer.end_paragraph(paragraph);
+                            }
+                            renderer.begin_paragraph(&style_block.paragraph);
+                            current_paragraph = Some(&style_block.paragraph);
+                        }
+                        renderer.text(&text[..run_end], &style_block.painter);
+                    }
+                    position += length;
+                    text = &text[run_end..];
+                } else {
+                    // A line break ends the paragraph, or makes an empty one
+                    if !skipped {
+                        let paragraph = current_paragraph.take().unwrap_or_else(|| {
+                            renderer.begin_paragraph(&style_block.paragraph);
+                            &style_block.paragraph
+                        });
+                        renderer.end_paragraph(paragraph);
+                    }
+                    position += 1;
+                    text = &text[1..];
+                }
+            }
+            if style_block.paragraph_end {
+                if let Some(paragraph) = current_paragraph.take() {
+                    renderer.end_paragraph(paragraph);
+                }
+            }
+        }
+        if let Some(paragraph) = current_paragraph {
+            renderer.end_paragraph(paragraph);
+        }
+        // The pictures and the tables at the end of the document
+        for table in tables {
+            renderer.table(table);
+        }
+        for image in images {
+            renderer.image(image);
+        }
+    }
+
+    // Add the ends of the cells of the table and of the tables nested in them
+    fn add_cell_ends(table: &Table, cell_ends: &mut BTreeSet<usize>) {
+        let mut tables = vec![table];
+        while let Some(table) = tables.pop() {
+            for cell in table.rows.iter().flat_map(|row| &row.cells) {
+                cell_ends.insert(cell.end);
+                tables.extend(&cell.nested_tables);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    // Write the events received by the renderer
+    #[derive(Default)]
+    struct EventRenderer {
+        events: Vec<String>,
+        render_tables: bool,
+    }
+
+    impl Renderer for EventRenderer {
+        fn begin_paragraph(&mut self, _paragraph: &Paragraph) {
+            self.events.push(String::from("<p>"));
+        }
+
+        fn text(&mut self, text: &str, painter: &Painter) {
+            self.events.push(if painter.bold { format!("**{text}**") } else { String::from(text) });
+        }
+
+        fn end_paragraph(&mut self, _paragraph: &Paragraph) {
+            self.events.push(String::from("</p>"));
+        }
+
+        fn image(&mut self, image: &Image) {
+            self.events.push(format!("<img {}x{}>", image.width, image.height));
+        }
+
+        fn table(&mut self, table: &Table) -> bool {
+            self.events.push(format!("<table {}>", table.rows.len()));
+            return self.render_tables;
+        }
+    }
+
+    #[test]
+    fn render_document() {
+        let rtf = r"{\rtf1\ansi Some {\b bold}{\v hidden} text {\pict\pngblip\picw2\pich1 89504e} after\par
+            \trowd\cellx2000\cellx4000\pard\intbl A\cell B\cell\row
+            \pard Line 1\par Line 2\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let mut renderer = EventRenderer::default();
+        document.render(&mut renderer);
+        assert_eq!(
+            renderer.events,
+            [
+                "<p>",
+                "Some ",
+                "**bold**",
+                " text ",
+                "<img 2x1>",
+                " after",
+                "</p>",
+                "<table 1>",
+                "<p>",
+                "A",
+                "</p>",
+                "<p>",
+                "B",
+                "</p>",
+                "<p>",
+                "Line 1",
+                "</p>",
+                "<p>",
+                "Line 2",
+                "</p>"
+            ]
+        );
+        let mut renderer = EventRenderer {
+            render_tables: true,
+            ..EventRenderer::default()
+        };
+        document.render(&mut renderer);
+        assert_eq!(renderer.events[7..], ["<table 1>", "<p>", "Line 1", "</p>", "<p>", "Line 2", "</p>"]);
+    }
+}
@@ -0,0 +1,78 @@
+/// Define the runs of a document : the text of the body with its format, to be processed with iterators
+use crate::document::RtfDocument;
+use crate::header::{ColorRef, FontRef};
+use crate::paragraph::Paragraph;
+use crate::parser::{Painter, Underline};
+
+/// A text of the body with the painter and the paragraph of its style block
+pub type Run<'a> = (&'a str, &'a Painter, &'a Paragraph);
+
+impl RtfDocument {
+    /// Iterate over the runs of the body, in the order of the document. The empty blocks are skipped, the hidden text (\v) is kept.
+    /// ex: `document.runs().bold().with_font(1)` for the bold text written with the font 1
+    pub fn runs(&self) -> impl Iterator<Item = Run<'_>> {
+        return self
+            .body
+            .iter()
+            .filter(|style_block| !style_block.text.is_empty())
+            .map(|style_block| (style_block.text.as_str(), &style_block.painter, &style_block.paragraph));
+    }
+}
+
+/// Filters on the format of the runs, for any iterator of runs
+pub trait RunFilter<'a>: Iterator<Item = Run<'a>> + Sized {
+    fn bold(self) -> impl Iterator<Item = Run<'a>> {
+        return self.filter(|(_, painter, _)| painter.bold);
+    }
+
+    fn italic(self) -> impl Iterator<Item = Run<'a>> {
+        return self.filter(|(_, painter, _)| painter.italic);
+    }
+
+    fn underlined(self) -> impl Iterator<Item = Run<'a>> {
+        return self.filter(|(_, painter, _)| painter.underline != Underline::None);
+    }
+
+    /// Skip the hidden text (\v) and the deleted text (\deleted), as `get_text` does
+    fn visible(self) -> impl Iterator<Item = Run<'a>> {
+        return self.filter(|(_, painter, _)| painter.is_visible());
+    }
+
+    fn with_font(self, font_ref: FontRef) -> impl Iterator<Item = Run<'a>> {
+        return self.filter(move |(_, painter, _)| painter.font_ref == font_ref);
+    }
+
+    fn with_color(self, color_ref: ColorRef) -> impl Iterator<Item = Run<'a>> {
+        return self.filter(move |(_, painter, _)| painter.color_ref == color_ref);
+    }
+}
+
+impl<'a, I: Iterator<Item = Run<'a>>> RunFilter<'a> for I {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::paragraph::Alignment;
+
+    #[test]
+    fn filter_runs() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0\fswiss Helvetica;}{\f1\fmodern Courier;}}{\colortbl;\red255\green0\blue0;}
+            \f0 Some {\b bold} and {\i\cf1 red italic} text{\v  hidden}\par
+            \qc {\b\f1 Bold code}{\ul\f1  underlined}\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        fn texts<'a>(runs: impl Iterator<Item = Run<'a>>) -> Vec<&'a str> {
+            return runs.map(|(text, _, _)| text).collect();
+        }
+        assert_eq!(document.runs().count(), 8);
+        assert_eq!(document.runs().visible().map(|(text, _, _)| text).collect::<String>(), document.get_text());
+        assert_eq!(texts(document.runs().bold()), ["bold", "Bold code"]);
+        assert_eq!(texts(document.runs().bold().with_font(1)), ["Bold code"]);
+        assert_eq!(texts(document.runs().italic().with_color(1)), ["red italic"]);
+        assert_eq!(texts(document.runs().underlined()), [" underlined"]);
+        let centered = document.runs().filter(|(_, _, paragraph)| paragraph.alignment == Alignment::Center);
+        assert_eq!(texts(centered.with_font(1)), ["Bold code", " underlined"]);
+    }
+}
@@ -0,0 +1,287 @@
+/// Define the search API over the styled text of a document
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::document::RtfDocument;
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+/// A range of characters inside a StyleBlock of the document body
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TextSpan {
+    /// Index of the StyleBlock in the document body
+    pub block_index: usize,
+    /// Char (not byte) offset of the start of the span in the StyleBlock text
+    pub start: usize,
+    /// Char (not byte) offset of the end of the span in the StyleBlock text (exclusive)
+    pub end: usize,
+}
+
+/// How `RtfDocument::find_with_options` compares the pattern with the text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SearchOptions {
+    /// Match the text whatever its case, with the full case folding (ex: "STRASSE" matches "Straße")
+    pub ignore_case: bool,
+    /// Match the text whatever its accents and other combining marks (ex: "bete" matches "bête" and "be\u{302}te")
+    #[cfg(feature = "unicode-normalization")]
+    pub ignore_diacritics: bool,
+    /// Fold the case as in Turkish and Azeri, where the lowercase of I is ı and the lowercase of İ is i
+    pub turkic_case: bool,
+}
+
+impl SearchOptions {
+    /// Fold the text as defined by the options, to compare it with another folded text
+    pub fn fold(&self, text: &str) -> String {
+        return self.fold_with_origins(text).0;
+    }
+
+    // Fold the text, and return the (folded offset, original offset) of each char of the text, followed by the end of both texts.
+    // The chars folded to nothing (ex: the combining marks without diacritics) have the offset of the next one.
+    fn fold_with_origins(&self, text: &str) -> (String, Vec<(usize, usize)>) {
+        let mut folded = String::with_capacity(text.len());
+        let mut origins = Vec::with_capacity(text.len() + 1);
+        let mut chars = vec![];
+        for (index, c) in text.char_indices() {
+            origins.push((folded.len(), index));
+            chars.clear();
+            #[cfg(feature = "unicode-normalization")]
+            if self.ignore_diacritics {
+                unicode_normalization::char::decompose_canonical(c, |c| {
+                    if unicode_normalization::char::canonical_combining_class(c) == 0 {
+                        chars.push(c);
+                    }
+                });
+            } else {
+                chars.push(c);
+            }
+            #[cfg(not(feature = "unicode-normalization"))]
+            chars.push(c);
+            for c in chars.iter() {
+                if self.ignore_case {
+                    self.fold_case(*c, &mut folded);
+                } else {
+                    folded.push(*c);
+                }
+            }
+        }
+        origins.push((folded.len(), text.len()));
+        return (folded, origins);
+    }
+
+    fn fold_case(&self, c: char, folded: &mut String) {
+        #[rustfmt::skip]
+        match c {
+            'I' if self.turkic_case       => folded.push('ı'),
+            '\u{130}' if self.turkic_case => folded.push('i'),
+            'ß' | 'ẞ'                     => folded.push_str("ss"),
+            'ς'                           => folded.push('σ'),
+            _                             => folded.extend(c.to_lowercase()),
+        };
+    }
+}
+
+impl RtfDocument {
+    /// Find all the non-overlapping occurrences of `pattern` in the text of the document.
+    /// An occurrence spanning several StyleBlocks is split in one TextSpan per block.
+    pub fn find(&self, pattern: &str) -> Vec<TextSpan> {
+        if pattern.is_empty() {
+            return vec![];
+        }
+        return self.find_with(|text| text.match_indices(pattern).map(|(index, matched)| index..index + matched.len()).collect());
+    }
+
+    /// Find all the non-overlapping occurrences of `pattern`, compared with the text as defined by the options (ex: case insensitive).
+    /// The spans cover the whole chars of the text, with their combining marks.
+    pub fn find_with_options(&self, pattern: &str, options: &SearchOptions) -> Vec<TextSpan> {
+        let pattern = options.fold(pattern);
+        if pattern.is_empty() {
+            return vec![];
+        }
+        return self.find_with(|text| {
+            let (folded, origins) = options.fold_with_origins(text);
+            // The start is in the char whose folding contains it, the end after the char whose folding contains the byte before it
+            let get_start = |index: usize| origins[origins.partition_point(|(folded_index, _)| *folded_index <= index) - 1].1;
+            let get_end = |index: usize| {
+                let mut origin = origins.partition_point(|(folded_index, _)| *folded_index < index);
+                // The chars folded to nothing after the match are part of it
+                while origin + 1 < origins.len() && origins[origin + 1].0 == origins[origin].0 {
+                    origin += 1;
+                }
+                return origins[origin].1;
+            };
+            return folded.match_indices(&pattern).map(|(index, matched)| get_start(index)..get_end(index + matched.len())).collect();
+        });
+    }
+
+    /// Find all the non-overlapping matches of the regular expression `pattern` (syntax of the `regex` crate) in the text of the document, the empty matches excluded
+    #[cfg(feature = "regex")]
+    pub fn find_regex(&self, pattern: &str) -> Result<Vec<TextSpan>, regex::Error> {
+        let regex = Regex::new(pattern)?;
+        return Ok(self.find_with(|text| regex.find_iter(text).map(|found| found.range()).filter(|range| !range.is_empty()).collect()));
+    }
+
+    /// Find the occurrences returned by a custom matcher, to use a regex for instance.
+    /// The matcher receives the text of the document (hidden and deleted text excluded) and returns the byte ranges of its matches.
+    pub fn find_with<F: FnOnce(&str) -> Vec<Range<usize>>>(&self, matcher: F) -> Vec<TextSpan> {
+        // Concatenate the text and keep track of the byte offset of each block
+        let mut text = String::new();
+        let mut block_offsets = Vec::with_capacity(self.body.len());
+        for (block_index, style_block) in self.body.iter().enumerate() {
+            if !style_block.painter.is_visible() {
+                continue;
+            }
+            block_offsets.push((block_index, text.len()));
+            text.push_str(&style_block.text);
+        }
+
+        let mut spans = vec![];
+        for range in matcher(&text) {
+            for (block_index, block_offset) in block_offsets.iter() {
+                let block_text = &self.body[*block_index].text;
+                let block_end = block_offset + block_text.len();
+                if range.start >= block_end || range.end <= *block_offset || block_text.is_empty() {
+                    continue;
+                }
+                let start = range.start.saturating_sub(*block_offset);
+                let end = range.end.min(block_end) - block_offset;
+                let (Some(before), Some(matched)) = (block_text.get(..start), block_text.get(start..end)) else {
+                    continue; // The matcher returned a range that is not on a char boundary
+                };
+                let start = before.chars().count();
+                spans.push(TextSpan {
+                    block_index: *block_index,
+                    start,
+                    end: start + matched.chars().count(),
+                });
+            }
+        }
+        return spans;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_in_single_block() {
+        let rtf = r"{\rtf1\ansi Voici du texte, encore du texte}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.find("texte"),
+            vec![TextSpan { block_index: 0, start: 9, end: 14 }, TextSpan { block_index: 0, start: 26, end: 31 },]
+        );
+        assert_eq!(document.find("absent"), vec![]);
+    }
+
+    #[test]
+    fn find_across_blocks() {
+        let rtf = r"{\rtf1\ansi Voici du texte en {\b gras}.}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.find("en gr"),
+            vec![TextSpan { block_index: 0, start: 15, end: 18 }, TextSpan { block_index: 1, start: 0, end: 2 },]
+        );
+    }
+
+    #[test]
+    fn find_with_char_offsets() {
+        let rtf = r"{\rtf1\ansi je suis une b\'eate b\'eate}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.find("bête")[1], TextSpan { block_index: 0, start: 17, end: 21 });
+    }
+
+    #[test]
+    fn find_with_custom_matcher() {
+        let rtf = r"{\rtf1\ansi 1 apple, 22 pears}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let digits = |text: &str| {
+            let mut ranges: Vec<Range<usize>> = vec![];
+            for (index, c) in text.char_indices() {
+                match ranges.last_mut() {
+                    Some(range) if c.is_ascii_digit() && range.end == index => range.end += 1,
+                    _ if c.is_ascii_digit() => ranges.push(index..index + 1),
+                    _ => {}
+                }
+            }
+            ranges
+        };
+        assert_eq!(
+            document.find_with(digits),
+            vec![TextSpan { block_index: 0, start: 0, end: 1 }, TextSpan { block_index: 0, start: 9, end: 11 },]
+        );
+    }
+
+    #[test]
+    fn find_case_insensitive() {
+        let rtf = r"{\rtf1\ansi Une B\'caTE, une b\'eate et une be\u770 ?te. {\b Stra\'dfe} STRASSE strasse}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let options = SearchOptions {
+            ignore_case: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(document.find_with_options("bête", &options).len(), 2);
+        assert_eq!(document.find_with_options("strasse", &options).len(), 3);
+        assert_eq!(document.find_with_options("straß", &options)[0], TextSpan { block_index: 1, start: 0, end: 5 });
+        // Turkish
+        let document = RtfDocument::try_from(r"{\rtf1\ansi ISPARTA \u304 ?ZM\u304 ?R}").unwrap();
+        // ignore_diacritics is the only other field, with the unicode-normalization feature
+        #[allow(clippy::needless_update)]
+        let options = SearchOptions {
+            ignore_case: true,
+            turkic_case: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(document.find_with_options("ısparta", &options).len(), 1);
+        assert_eq!(document.find_with_options("izmir", &options).len(), 1);
+        assert_eq!(document.find_with_options("isparta", &options).len(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-normalization")]
+    fn find_diacritic_insensitive() {
+        let rtf = r"{\rtf1\ansi Une B\'caTE, une b\'eate et une be\u770 ?te.}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.find("bete"), vec![]);
+        let options = SearchOptions {
+            ignore_diacritics: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(
+            document.find_with_options("bete", &options),
+            vec![TextSpan { block_index: 0, start: 14, end: 18 }, TextSpan { block_index: 0, start: 26, end: 31 }]
+        );
+        let options = SearchOptions {
+            ignore_case: true,
+            ignore_diacritics: true,
+            ..SearchOptions::default()
+        };
+        // The combining mark is in the span of the decomposed occurrence
+        assert_eq!(
+            document.find_with_options("BETE", &options),
+            vec![TextSpan { block_index: 0, start: 4, end: 8 }, TextSpan { block_index: 0, start: 14, end: 18 }, TextSpan { block_index: 0, start: 26, end: 31 }]
+        );
+        assert_eq!(document.find_with_options("\u{301}", &options), vec![]);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn find_with_regex() {
+        let rtf = r"{\rtf1\ansi Prix : 12 euros, {\b 3}0 centimes}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(
+            document.find_regex(r"\d+").unwrap(),
+            vec![TextSpan { block_index: 0, start: 7, end: 9 }, TextSpan { block_index: 1, start: 0, end: 1 }, TextSpan { block_index: 2, start: 0, end: 1 },]
+        );
+        assert_eq!(document.find_regex(r"(?i:EUROS)").unwrap(), vec![TextSpan { block_index: 0, start: 10, end: 15 }]);
+        assert!(document.find_regex("(unclosed").is_err());
+    }
+}
@@ -0,0 +1,191 @@
+/// Define the shapes ({\shp ...}) and the drawing objects of the older versions of Word ({\*\do ...}) of a document
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::document::{TextOptions, TextWriter};
+use crate::parser::StyleBlock;
+use crate::tokens::ControlWord;
+use crate::units::Twips;
+
+/// A shape anchored in the body. Its text box is not part of the body, see `TextOptions::include_text_boxes`.
+/// The position is a char offset in the visible text of the body (see `RtfDocument::get_text`)
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Shape {
+    /// The shapeType property (ex: 1 for a rectangle, 202 for a text box), 0 if unknown
+    pub shape_type: i32,
+    /// Bounds of the shape relative to its anchor (\shpleft, \shptop, \shpright, \shpbottom)
+    pub left: Twips,
+    pub top: Twips,
+    pub right: Twips,
+    pub bottom: Twips,
+    /// What the bounds are relative to (\shpbxpage, \shpbypara, ...)
+    pub horizontal_anchor: ShapeAnchor,
+    pub vertical_anchor: ShapeAnchor,
+    /// The properties of the shape ({\sp{\sn name}{\sv value}}), in the order of the document
+    pub properties: Vec<ShapeProperty>,
+    /// The text of the text box (\shptxt or \dptxbxtext), with its own style blocks
+    pub text_box: Vec<StyleBlock>,
+    pub position: usize,
+}
+
+/// What the position of a shape is relative to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum ShapeAnchor {
+    Page,
+    Margin,
+    /// The column horizontally, the paragraph vertically
+    #[default]
+    Text,
+}
+
+impl ShapeAnchor {
+    /// Get the anchor defined by the control word, and whether it's the horizontal one
+    pub fn from(control_word: &ControlWord) -> Option<(bool, Self)> {
+        #[rustfmt::skip]
+        return match control_word {
+            ControlWord::ShapeAnchorXPage | ControlWord::DrawingAnchorXPage                 => Some((true, ShapeAnchor::Page)),
+            ControlWord::ShapeAnchorXMargin | ControlWord::DrawingAnchorXMargin             => Some((true, ShapeAnchor::Margin)),
+            ControlWord::ShapeAnchorXColumn | ControlWord::DrawingAnchorXColumn             => Some((true, ShapeAnchor::Text)),
+            ControlWord::ShapeAnchorYPage | ControlWord::DrawingAnchorYPage                 => Some((false, ShapeAnchor::Page)),
+            ControlWord::ShapeAnchorYMargin | ControlWord::DrawingAnchorYMargin             => Some((false, ShapeAnchor::Margin)),
+            ControlWord::ShapeAnchorYParagraph | ControlWord::DrawingAnchorYParagraph       => Some((false, ShapeAnchor::Text)),
+            _                                                                               => None,
+        };
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ShapeProperty {
+    pub name: String,
+    pub value: String,
+}
+
+impl Shape {
+    pub const RECTANGLE: i32 = 1;
+    pub const ELLIPSE: i32 = 3;
+    pub const LINE: i32 = 20;
+    pub const TEXT_BOX: i32 = 202;
+
+    pub fn width(&self) -> Twips {
+        return self.right - self.left;
+    }
+
+    pub fn height(&self) -> Twips {
+        return self.bottom - self.top;
+    }
+
+    /// Get the text of the text box, with the breaks and the whitespaces written as defined by the options
+    pub fn get_text_with_options(&self, options: &TextOptions) -> String {
+        let mut writer = TextWriter::new(options);
+        writer.write(&self.text_box, &[], &[]);
+        return writer.finish();
+    }
+
+    /// Get the value of a property by name (ex: "fillColor")
+    pub fn get_property(&self, name: &str) -> Option<&str> {
+        return self.properties.iter().find(|property| property.name == name).map(|property| property.value.as_str());
+    }
+
+    // The shape type of a drawing object, which is declared by its control word
+    pub(crate) fn drawing_object_type(control_word: &ControlWord) -> Option<i32> {
+        #[rustfmt::skip]
+        return match control_word {
+            ControlWord::DrawingRectangle   => Some(Shape::RECTANGLE),
+            ControlWord::DrawingEllipse     => Some(Shape::ELLIPSE),
+            ControlWord::DrawingLine        => Some(Shape::LINE),
+            ControlWord::DrawingTextBox     => Some(Shape::TEXT_BOX),
+            _                               => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::document::RtfDocument;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    #[test]
+    fn parse_shapes() {
+        let rtf = r"{\rtf1\ansi Before {\shp{\*\shpinst\shpleft100\shptop200\shpright3100\shpbottom1200\shpbxmargin\shpbypage{\sp{\sn shapeType}{\sv 202}}{\sp{\sn fFilled}{\sv 0}}
+            {\shptxt \pard Text in {\b the} box\par Caf\'e9\par}}{\shprslt{\*\do\dobxcolumn\dptxbx{\dptxbxtext\pard Text in the box\par}}}} after\par
+            {\*\do\dobxpage\dprect\dpx10\dpy20\dpxsize500\dpysize300}End}";
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).parse_with_warnings().unwrap();
+        assert_eq!(document.get_text(), "Before  afterEnd");
+        assert_eq!(warnings, []);
+        assert_eq!(
+            document.shapes,
+            vec![
+                Shape {
+                    shape_type: Shape::TEXT_BOX,
+                    left: Twips(100),
+                    top: Twips(200),
+                    right: Twips(3100),
+                    bottom: Twips(1200),
+                    properties: vec![
+                        ShapeProperty {
+                            name: "shapeType".into(),
+                            value: "202".into()
+                        },
+                        ShapeProperty {
+                            name: "fFilled".into(),
+                            value: "0".into()
+                        },
+                    ],
+                    horizontal_anchor: ShapeAnchor::Margin,
+                    vertical_anchor: ShapeAnchor::Page,
+                    text_box: document.shapes[0].text_box.clone(),
+                    position: 7,
+                },
+                Shape {
+                    shape_type: Shape::RECTANGLE,
+                    left: Twips(10),
+                    top: Twips(20),
+                    right: Twips(510),
+                    bottom: Twips(320),
+                    horizontal_anchor: ShapeAnchor::Page,
+                    properties: vec![],
+                    position: 13,
+                    ..Shape::default()
+                },
+            ]
+        );
+        assert_eq!((document.shapes[0].width(), document.shapes[0].height()), (Twips(3000), Twips(1000)));
+        assert_eq!(document.shapes[0].get_property("fFilled"), Some("0"));
+        assert_eq!(RtfDocument::try_from(rtf).unwrap(), document);
+    }
+
+    #[test]
+    fn extract_text_boxes() {
+        let rtf = r"{\rtf1\ansi Before {\shp{\*\shpinst\shpleft100\shpright3100{\sp{\sn shapeType}{\sv 202}}
+            {\shptxt \pard Text in {\b the} box\par Caf\'e9\par}}{\shprslt{\*\do\dobxcolumn\dptxbx{\dptxbxtext\pard Text in the box\par}}}} after\par
+            {\shp{\*\shpinst{\sp{\sn shapeType}{\sv 1}}}}End}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.text_boxes().count(), 1);
+        let text_box = &document.shapes[0].text_box;
+        assert_eq!(text_box.iter().map(|style_block| style_block.text.as_str()).collect::<Vec<_>>(), ["Text in ", "the", " box", "Café"]);
+        assert!(text_box[1].painter.bold);
+        assert!(text_box[2].paragraph_end && text_box[3].paragraph_end);
+        let options = TextOptions {
+            paragraph_break: "\n".into(),
+            ..TextOptions::default()
+        };
+        assert_eq!(document.shapes[0].get_text_with_options(&options), "Text in the box\nCafé\n");
+        assert_eq!(document.get_text_with_options(&options), "Before  after\nEnd");
+        let options = TextOptions { include_text_boxes: true, ..options };
+        assert_eq!(document.get_text_with_options(&options), "Before Text in the box\nCafé\n after\nEnd");
+    }
+}
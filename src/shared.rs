@@ -0,0 +1,139 @@
+/// Define the sharing of a parsed document between threads : all the types of the model are `Send + Sync`
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Deref, Range};
+
+use crate::document::{RtfDocument, TextOptions, TextWriter};
+use crate::parser::StyleBlock;
+
+/// A document shared between threads without copy, ex: to render it on a thread pool
+pub type SharedDocument = Arc<RtfDocument>;
+
+/// A range of style blocks of a shared document, made of whole paragraphs.
+/// It holds a reference to the document, so it can be moved to a thread that outlives the caller (ex: `std::thread::spawn`).
+#[derive(Debug, Clone)]
+pub struct SharedBlocks {
+    document: SharedDocument,
+    range: Range<usize>,
+}
+
+impl SharedBlocks {
+    pub fn document(&self) -> &RtfDocument {
+        return &self.document;
+    }
+
+    /// The indexes of the style blocks in the body of the document
+    pub fn range(&self) -> Range<usize> {
+        return self.range.clone();
+    }
+
+    pub fn style_blocks(&self) -> &[StyleBlock] {
+        return &self.document.body[self.range.clone()];
+    }
+
+    /// The raw text of the style blocks, hidden and deleted text excluded : the texts of all the parts make the text of the document (see `get_text`)
+    pub fn get_text(&self) -> String {
+        let options = TextOptions::default();
+        let mut writer = TextWriter::new(&options);
+        writer.write(self.style_blocks(), &[], &[]);
+        return writer.finish();
+    }
+}
+
+impl Deref for SharedBlocks {
+    type Target = [StyleBlock];
+
+    fn deref(&self) -> &Self::Target {
+        return self.style_blocks();
+    }
+}
+
+impl RtfDocument {
+    pub fn into_shared(self) -> SharedDocument {
+        return Arc::new(self);
+    }
+
+    /// Split the body of a shared document in at most `count` parts of whole paragraphs, of about the same text length,
+    /// to process them on several threads. A paragraph longer than a part is not split.
+    pub fn split_shared(document: &SharedDocument, count: usize) -> Vec<SharedBlocks> {
+        let body = &document.body;
+        let text_length: usize = body.iter().map(|style_block| style_block.text.len()).sum();
+        let count = count.max(1);
+        let part_length = text_length.div_ceil(count).max(1);
+        let mut parts = vec![];
+        let mut start = 0;
+        let mut length = 0;
+        for (index, style_block) in body.iter().enumerate() {
+            length += style_block.text.len();
+            // The last part takes the rest of the body
+            if style_block.paragraph_end && length >= part_length && parts.len() + 1 < count {
+                parts.push(SharedBlocks {
+                    document: document.clone(),
+                    range: start..index + 1,
+                });
+                start = index + 1;
+                length = 0;
+            }
+        }
+        if start < body.len() {
+            parts.push(SharedBlocks {
+                document: document.clone(),
+                range: start..body.len(),
+            });
+        }
+        return parts;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::include_test_file;
+
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    #[test]
+    fn model_is_send_sync() {
+        assert_send_sync::<crate::RtfDocument>();
+        assert_send_sync::<crate::RtfHeader>();
+        assert_send_sync::<crate::StyleBlock>();
+        assert_send_sync::<crate::Painter>();
+        assert_send_sync::<crate::Paragraph>();
+        assert_send_sync::<crate::Table>();
+        assert_send_sync::<crate::Image>();
+        assert_send_sync::<crate::Field>();
+        assert_send_sync::<crate::Shape>();
+        assert_send_sync::<crate::List>();
+        assert_send_sync::<crate::Bookmark>();
+        assert_send_sync::<crate::Section>();
+        assert_send_sync::<crate::RtfFragment>();
+        assert_send_sync::<crate::PreservedDocument>();
+        assert_send_sync::<crate::DocumentChange>();
+        assert_send_sync::<crate::ParserOptions>();
+        assert_send_sync::<crate::ParserWarning>();
+        assert_send_sync::<crate::OwnedToken>();
+        assert_send_sync::<crate::RtfError>();
+        assert_send_sync::<SharedDocument>();
+        assert_send_sync::<SharedBlocks>();
+    }
+
+    #[test]
+    fn split_shared_document() {
+        let document = RtfDocument::try_from(include_test_file!("file-sample_500kB.rtf")).unwrap().into_shared();
+        let parts = RtfDocument::split_shared(&document, 4);
+        assert!(parts.len() > 1 && parts.len() <= 4);
+        assert!(parts.windows(2).all(|parts| parts[0].range().end == parts[1].range().start));
+        assert!(parts.iter().all(|part| part.last().is_some_and(|style_block| style_block.paragraph_end)));
+        // The parts are processed on other threads, without borrowing the document
+        let handles: Vec<_> = parts.into_iter().map(|part| thread::spawn(move || part.get_text())).collect();
+        let text: String = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert_eq!(text, document.get_text());
+        let empty = RtfDocument::default().into_shared();
+        assert!(RtfDocument::split_shared(&empty, 4).is_empty());
+        assert_eq!(RtfDocument::split_shared(&RtfDocument::try_from(r"{\rtf1 a}").unwrap().into_shared(), 0)[0].get_text(), "a");
+    }
+}
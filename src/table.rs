@@ -0,0 +1,484 @@
+/// Define the table related structs and enums
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::header::ColorRef;
+use crate::tokens::{ControlWord, Property};
+use crate::units::Twips;
+
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Table {
+    pub rows: Vec<TableRow>,
+    /// 1 for a top-level table, 2 for a table nested in a cell of a top-level table, etc ...
+    pub nesting_level: u16,
+    /// Char offsets of the text of the table in the visible text of the body (see `RtfDocument::get_text`)
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct TableRow {
+    pub cells: Vec<TableCell>,
+    /// Height of the row in twips (\trrhN) : at least N if positive, exactly |N| if negative, auto if 0
+    pub height: Twips,
+    pub alignment: RowAlignment,
+    /// Position of the leftmost edge of the row (\trleftN)
+    pub left: Twips,
+    /// Half the space between the cells (\trgaphN)
+    pub gap: Twips,
+    /// The row is repeated at the top of each page (\trhdr)
+    pub header: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct TableCell {
+    pub text: String,
+    /// Position of the right edge of the cell (\cellxN)
+    pub right_boundary: Twips,
+    pub merge: CellMerge,
+    pub vertical_merge: CellMerge,
+    pub vertical_alignment: CellVerticalAlignment,
+    pub borders: CellBorders,
+    /// Shading of the background, in hundredths of a percent (\clshdngN)
+    pub shading: i32,
+    pub background_color: ColorRef,
+    /// Tables nested in the cell
+    pub nested_tables: Vec<Table>,
+    /// Char offset of the end of the text of the cell (\cell) in the visible text of the body
+    pub end: usize,
+}
+
+/// Merge of a cell with its neighbours, horizontally (\clmgf, \clmrg) or vertically (\clvmgf, \clvmrg)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CellMerge {
+    #[default]
+    None,
+    First,  // First cell of the merged range
+    Merged, // Merged with the previous cell
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum RowAlignment {
+    #[default]
+    Left, // \trql
+    Right,  // \trqr
+    Center, // \trqc
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CellVerticalAlignment {
+    #[default]
+    Top, // \clvertalt
+    Center, // \clvertalc
+    Bottom, // \clvertalb
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CellBorders {
+    pub top: Border,
+    pub left: Border,
+    pub bottom: Border,
+    pub right: Border,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Border {
+    pub style: BorderStyle,
+    /// Width of the border (\brdrwN)
+    pub width: Twips,
+    pub color: ColorRef,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum BorderStyle {
+    #[default]
+    None, // \brdrnone
+    Single, // \brdrs
+    Double, // \brdrdb
+    Thick,  // \brdrth
+    Dotted, // \brdrdot
+    Dashed, // \brdrdash
+}
+
+impl BorderStyle {
+    pub fn from(control_word: &ControlWord) -> Option<Self> {
+        #[rustfmt::skip]
+        return match control_word {
+            ControlWord::BorderNone   => Some(BorderStyle::None),
+            ControlWord::BorderSingle => Some(BorderStyle::Single),
+            ControlWord::BorderDouble => Some(BorderStyle::Double),
+            ControlWord::BorderThick  => Some(BorderStyle::Thick),
+            ControlWord::BorderDotted => Some(BorderStyle::Dotted),
+            ControlWord::BorderDashed => Some(BorderStyle::Dashed),
+            _                         => None,
+        };
+    }
+}
+
+/// Build the tables while the body is parsed
+/// The row and cell properties can be defined before or after the content of the row (Word writes the nested rows properties after their content),
+/// so they are merged with the content when the row ends.
+#[derive(Debug, Default)]
+pub(crate) struct TableBuilder {
+    // One entry per nesting level, the index 0 being the top-level table
+    levels: Vec<TableLevel>,
+    // Level of the row being defined by \trowd
+    definition_level: usize,
+    // Border targeted by the border properties (\brdrs, \brdrwN, ...)
+    current_border: Option<BorderSide>,
+    // Length of the visible text of the body when the last text or control word was added
+    position: usize,
+}
+
+#[derive(Debug, Default)]
+struct TableLevel {
+    table: Table,
+    row_definition: TableRow,
+    cell_definition: TableCell,
+    cells: Vec<TableCell>,
+    cell_text: String,
+    cell_nested_tables: Vec<Table>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BorderSide {
+    Top,
+    Left,
+    Bottom,
+    Right,
+}
+
+impl TableBuilder {
+    pub fn is_table_control_word(control_word: &ControlWord) -> bool {
+        return matches!(
+            control_word,
+            ControlWord::TableRowDefaults
+                | ControlWord::TableCell
+                | ControlWord::TableNestedCell
+                | ControlWord::TableRow
+                | ControlWord::TableNestedRow
+                | ControlWord::RowHeight
+                | ControlWord::RowLeft
+                | ControlWord::RowGap
+                | ControlWord::RowHeader
+                | ControlWord::RowLeftAligned
+                | ControlWord::RowRightAligned
+                | ControlWord::RowCenter
+                | ControlWord::CellMergeFirst
+                | ControlWord::CellMerge
+                | ControlWord::CellVerticalMergeFirst
+                | ControlWord::CellVerticalMerge
+                | ControlWord::CellVerticalTop
+                | ControlWord::CellVerticalCenter
+                | ControlWord::CellVerticalBottom
+                | ControlWord::CellShading
+                | ControlWord::CellBackgroundColor
+                | ControlWord::CellBorderTop
+                | ControlWord::CellBorderLeft
+                | ControlWord::CellBorderBottom
+                | ControlWord::CellBorderRight
+                | ControlWord::CellRightBoundary
+                | ControlWord::BorderWidth
+                | ControlWord::BorderColor
+                | ControlWord::BorderNone
+                | ControlWord::BorderSingle
+                | ControlWord::BorderDouble
+                | ControlWord::BorderThick
+                | ControlWord::BorderDotted
+                | ControlWord::BorderDashed
+        );
+    }
+
+    fn get_level(&mut self, level: usize) -> &mut TableLevel {
+        let level = level.max(1);
+        if self.levels.len() < level {
+            let start = self.position;
+            self.levels.resize_with(level, || TableLevel {
+                table: Table { start, ..Table::default() },
+                ..TableLevel::default()
+            });
+        }
+        return &mut self.levels[level - 1];
+    }
+
+    /// Add the text of a paragraph at the given nesting level (0 if it's not in a table), `position` being its offset in the visible text
+    pub fn add_text(&mut self, level: usize, text: &str, position: usize, tables: &mut Vec<Table>) {
+        self.position = position;
+        if level == 0 {
+            // The text is not in a table anymore
+            self.close_tables(0, tables);
+            return;
+        }
+        // Text of an outer cell ends the tables nested in it
+        self.close_tables(level, tables);
+        self.get_level(level).cell_text.push_str(text);
+    }
+
+    /// Apply a table control word, `level` being the nesting level of the current paragraph
+    pub fn apply(&mut self, control_word: &ControlWord, property: &Property, level: usize, position: usize, tables: &mut Vec<Table>) {
+        self.position = position;
+        match control_word {
+            ControlWord::TableRowDefaults => {
+                self.definition_level = level.max(1);
+                self.current_border = None;
+                let table_level = self.get_level(level);
+                table_level.row_definition = TableRow::default();
+                table_level.cell_definition = TableCell::default();
+            }
+            ControlWord::TableCell => self.end_cell(level.max(1), tables),
+            ControlWord::TableNestedCell => self.end_cell(level, tables),
+            ControlWord::TableRow => self.end_row(1, tables),
+            ControlWord::TableNestedRow => self.end_row(level, tables),
+            _ => self.apply_definition(control_word, property),
+        }
+    }
+
+    // Apply a row or cell property to the row being defined
+    fn apply_definition(&mut self, control_word: &ControlWord, property: &Property) {
+        let current_border = self.current_border;
+        let level = self.get_level(self.definition_level);
+        let row = &mut level.row_definition;
+        let cell = &mut level.cell_definition;
+        let border = current_border.map(|side| match side {
+            BorderSide::Top => &mut cell.borders.top,
+            BorderSide::Left => &mut cell.borders.left,
+            BorderSide::Bottom => &mut cell.borders.bottom,
+            BorderSide::Right => &mut cell.borders.right,
+        });
+        #[rustfmt::skip]
+        match control_word {
+            ControlWord::RowHeight              => row.height = Twips(property.get_value()),
+            ControlWord::RowLeft                => row.left = Twips(property.get_value()),
+            ControlWord::RowGap                 => row.gap = Twips(property.get_value()),
+            ControlWord::RowHeader              => row.header = property.as_bool(),
+            ControlWord::RowLeftAligned         => row.alignment = RowAlignment::Left,
+            ControlWord::RowRightAligned        => row.alignment = RowAlignment::Right,
+            ControlWord::RowCenter              => row.alignment = RowAlignment::Center,
+            ControlWord::CellMergeFirst         => cell.merge = CellMerge::First,
+            ControlWord::CellMerge              => cell.merge = CellMerge::Merged,
+            ControlWord::CellVerticalMergeFirst => cell.vertical_merge = CellMerge::First,
+            ControlWord::CellVerticalMerge      => cell.vertical_merge = CellMerge::Merged,
+            ControlWord::CellVerticalTop        => cell.vertical_alignment = CellVerticalAlignment::Top,
+            ControlWord::CellVerticalCenter     => cell.vertical_alignment = CellVerticalAlignment::Center,
+            ControlWord::CellVerticalBottom     => cell.vertical_alignment = CellVerticalAlignment::Bottom,
+            ControlWord::CellShading            => cell.shading = property.get_value(),
+            ControlWord::CellBackgroundColor    => cell.background_color = property.get_value_as::<ColorRef>().unwrap_or_default(),
+            ControlWord::CellBorderTop          => self.current_border = Some(BorderSide::Top),
+            ControlWord::CellBorderLeft         => self.current_border = Some(BorderSide::Left),
+            ControlWord::CellBorderBottom       => self.current_border = Some(BorderSide::Bottom),
+            ControlWord::CellBorderRight        => self.current_border = Some(BorderSide::Right),
+            ControlWord::BorderWidth            => if let Some(border) = border { border.width = Twips(property.get_value()) },
+            ControlWord::BorderColor            => if let Some(border) = border { border.color = property.get_value_as::<ColorRef>().unwrap_or_default() },
+            ControlWord::CellRightBoundary      => {
+                let mut cell = mem::take(cell);
+                cell.right_boundary = Twips(property.get_value());
+                row.cells.push(cell);
+                self.current_border = None;
+            }
+            control_word => {
+                if let (Some(style), Some(border)) = (BorderStyle::from(control_word), border) {
+                    border.style = style;
+                }
+            }
+        };
+    }
+
+    fn end_cell(&mut self, level: usize, tables: &mut Vec<Table>) {
+        self.close_tables(level, tables);
+        let end = self.position;
+        let table_level = self.get_level(level);
+        let cell = TableCell {
+            text: mem::take(&mut table_level.cell_text),
+            nested_tables: mem::take(&mut table_level.cell_nested_tables),
+            end,
+            ..TableCell::default()
+        };
+        table_level.cells.push(cell);
+    }
+
+    fn end_row(&mut self, level: usize, tables: &mut Vec<Table>) {
+        self.close_tables(level, tables);
+        let table_level = self.get_level(level);
+        // Merge the content of the cells with their definition
+        let mut row = table_level.row_definition.clone();
+        let cells = mem::take(&mut table_level.cells);
+        row.cells = cells
+            .into_iter()
+            .enumerate()
+            .map(|(index, cell)| TableCell {
+                text: cell.text,
+                nested_tables: cell.nested_tables,
+                end: cell.end,
+                ..row.cells.get(index).cloned().unwrap_or_default()
+            })
+            .collect();
+        table_level.table.nesting_level = level.max(1) as u16;
+        table_level.table.rows.push(row);
+    }
+
+    // Close the tables nested deeper than level, and push them in their parent cell (or in tables for top-level ones)
+    fn close_tables(&mut self, level: usize, tables: &mut Vec<Table>) {
+        while self.levels.len() > level {
+            let Some(mut table_level) = self.levels.pop() else {
+                break;
+            };
+            table_level.table.end = self.position;
+            if table_level.table.rows.is_empty() {
+                continue;
+            }
+            match self.levels.last_mut() {
+                Some(parent) => parent.cell_nested_tables.push(table_level.table),
+                None => tables.push(table_level.table),
+            }
+        }
+    }
+
+    /// Close all the tables at the end of the document
+    pub fn finish(&mut self, position: usize, tables: &mut Vec<Table>) {
+        self.position = position;
+        self.close_tables(0, tables);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::RtfDocument;
+    use crate::parser::ParserOptions;
+
+    #[test]
+    fn parse_table_with_merged_cells() {
+        let rtf = r"{\rtf1\ansi
+            {\colortbl;\red0\green0\blue0;\red200\green200\blue200;}
+            \trowd\trgaph108\trleft-108\trrh400\trqc\trhdr\clmgf\clbrdrt\brdrdb\brdrw10\brdrcf1\clcbpat2\clshdng2000\cellx3000\clmrg\cellx6000
+            \pard\intbl Header\cell\cell\row
+            \trowd\clvmgf\clvertalc\cellx3000\cellx6000
+            \pard\intbl A\cell B\cell\row
+            \trowd\clvmrg\cellx3000\cellx6000
+            \pard\intbl\cell C\cell\row
+            \pard After the table}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.tables.len(), 1);
+        let table = &document.tables[0];
+        assert_eq!(table.nesting_level, 1);
+        assert_eq!((table.start, table.end), (0, 9));
+        assert_eq!(table.rows.len(), 3);
+        let header = &table.rows[0];
+        assert_eq!(
+            (header.height, header.alignment, header.left, header.gap, header.header),
+            (Twips(400), RowAlignment::Center, Twips(-108), Twips(108), true)
+        );
+        assert_eq!(
+            header.cells[0],
+            TableCell {
+                text: "Header".into(),
+                right_boundary: Twips(3000),
+                merge: CellMerge::First,
+                borders: CellBorders {
+                    top: Border {
+                        style: BorderStyle::Double,
+                        width: Twips(10),
+                        color: 1
+                    },
+                    ..CellBorders::default()
+                },
+                shading: 2000,
+                background_color: 2,
+                end: 6,
+                ..TableCell::default()
+            }
+        );
+        assert_eq!(header.cells[1].merge, CellMerge::Merged);
+        assert_eq!(table.rows.iter().flat_map(|row| row.cells.iter().map(|cell| cell.end)).collect::<Vec<_>>(), [6, 6, 7, 8, 8, 9]);
+        assert_eq!(table.rows[1].cells[0].vertical_merge, CellMerge::First);
+        assert_eq!(table.rows[1].cells[0].vertical_alignment, CellVerticalAlignment::Center);
+        assert_eq!(table.rows[1].cells[1].text, "B");
+        assert_eq!(table.rows[2].cells[0].vertical_merge, CellMerge::Merged);
+        assert_eq!(table.rows[2].cells[1].text, "C");
+        assert_eq!(table.rows[2].cells[1].right_boundary, Twips(6000));
+    }
+
+    #[test]
+    fn parse_nested_table() {
+        let rtf = r"{\rtf1\ansi
+            \trowd\cellx4000\cellx8000
+            \pard\intbl Outer\par
+            \pard\intbl\itap2 Inner 1\nestcell Inner 2\nestcell
+            {\*\nesttableprops\trowd\clmgf\cellx2000\cellx4000\nestrow}{\nonesttables\par}
+            \pard\intbl\itap1\cell Second\cell\row
+            \pard Text}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.tables.len(), 1);
+        let outer_cells = &document.tables[0].rows[0].cells;
+        assert_eq!(outer_cells.len(), 2);
+        assert_eq!(outer_cells[0].text, "Outer");
+        assert_eq!(outer_cells[0].right_boundary, Twips(4000));
+        assert_eq!(outer_cells[1].text, "Second");
+        let nested = &outer_cells[0].nested_tables;
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].nesting_level, 2);
+        assert_eq!(nested[0].rows[0].cells.len(), 2);
+        assert_eq!(nested[0].rows[0].cells[0].text, "Inner 1");
+        assert_eq!(nested[0].rows[0].cells[0].merge, CellMerge::First);
+        assert_eq!(nested[0].rows[0].cells[1].text, "Inner 2");
+        assert_eq!(nested[0].rows[0].cells[1].right_boundary, Twips(4000));
+    }
+
+    #[test]
+    fn parse_nested_table_fallback() {
+        // \cell at the level given by \itap, and the text of \nonesttables only for the readers not supporting nested tables
+        let rtf = r"{\rtf1\ansi
+            \trowd\cellx8000
+            \pard\intbl\itap2 Inner 1\cell Inner 2\cell
+            {\*\nesttableprops\trowd\cellx2000\cellx4000\nestrow}{\nonesttables Inner 1 Inner 2\par}
+            \pard\intbl\itap1\cell\row}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Inner 1Inner 2");
+        let nested = &document.tables[0].rows[0].cells[0].nested_tables;
+        assert_eq!(nested[0].rows[0].cells.len(), 2);
+        assert_eq!(nested[0].rows[0].cells[1].text, "Inner 2");
+    }
+
+    #[test]
+    fn separate_cells_in_text() {
+        let rtf = r"{\rtf1\ansi Before\par
+            \trowd\cellx2000\cellx4000\pard\intbl Name\cell Age\cell\row
+            \trowd\cellx2000\cellx4000\pard\intbl Alice\cell 42\cell\row
+            \pard After}";
+        assert_eq!(RtfDocument::try_from(rtf).unwrap().get_text(), "BeforeNameAgeAlice42After");
+        let options = ParserOptions {
+            cell_separator: Some('\t'),
+            row_separator: Some('\n'),
+            ..ParserOptions::default()
+        };
+        let document = RtfDocument::from_str_with_options(rtf, options).unwrap();
+        assert_eq!(document.get_text(), "BeforeName\tAge\t\nAlice\t42\t\nAfter");
+        let table = &document.tables[0];
+        assert_eq!(table.rows[1].cells.iter().map(|cell| cell.text.as_str()).collect::<Vec<_>>(), ["Alice", "42"]);
+        assert_eq!((table.start, table.end), (6, 26));
+    }
+}
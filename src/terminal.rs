@@ -0,0 +1,112 @@
+/// Define the rendering of a document with ANSI escape codes, to preview it in a terminal
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::document::RtfDocument;
+use crate::parser::{Painter, StyleBlock, Underline};
+
+const RESET: &str = "\x1b[0m";
+
+impl RtfDocument {
+    /// Render the document with ANSI escape codes for the bold, italic, underline, strike and the colors of the color table.
+    /// Hidden text (\v) and the control chars other than the line breaks and the tabs are excluded. Each paragraph (\par) ends with a line break.
+    pub fn to_ansi(&self) -> String {
+        let mut result = String::new();
+        for style_block in &self.body {
+            if style_block.painter.is_visible() {
+                self.write_ansi(&mut result, style_block);
+            }
+            if style_block.paragraph_end {
+                result.push('\n');
+            }
+        }
+        return result;
+    }
+
+    fn write_ansi(&self, result: &mut String, style_block: &StyleBlock) {
+        let text = strip_control_chars(&style_block.text);
+        let codes = self.get_ansi_codes(&style_block.painter);
+        if codes.is_empty() {
+            result.push_str(&text);
+            return;
+        }
+        let escape = format!("\x1b[{}m", codes.join(";"));
+        // Reset the style at the end of each line, so the lines can be displayed independently (less -R, pagers, ...)
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                result.push('\n');
+            }
+            if !line.is_empty() {
+                result.push_str(&escape);
+                result.push_str(line);
+                result.push_str(RESET);
+            }
+        }
+    }
+
+    fn get_ansi_codes(&self, painter: &Painter) -> Vec<String> {
+        let mut codes: Vec<String> = vec![];
+        if painter.bold {
+            codes.push(String::from("1"));
+        }
+        if painter.italic {
+            codes.push(String::from("3"));
+        }
+        #[rustfmt::skip]
+        match painter.underline {
+            Underline::None                           => {}
+            Underline::Double | Underline::DoubleWave => codes.push(String::from("21")),
+            _                                         => codes.push(String::from("4")),
+        };
+        if painter.strike {
+            codes.push(String::from("9"));
+        }
+        // \cf0 is the automatic color : the default color of the terminal
+        if painter.color_ref != 0 {
+            if let Some(color) = self.header.get_color(&painter.color_ref) {
+                codes.push(format!("38;2;{};{};{}", color.red, color.green, color.blue));
+            }
+        }
+        return codes;
+    }
+}
+
+// The document could otherwise write its own escape sequences to the terminal (ex: \'1b)
+fn strip_control_chars(text: &str) -> String {
+    return text.chars().filter(|c| !c.is_control() || *c == '\n' || *c == '\t').collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_ansi() {
+        let rtf = r"{\rtf1\ansi{\colortbl;\red255\green0\blue0;}Plain {\b bold}, {\i\ul italic} and {\cf1\strike red}{\v hidden}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_ansi(), "Plain \x1b[1mbold\x1b[0m, \x1b[3;4mitalic\x1b[0m and \x1b[9;38;2;255;0;0mred\x1b[0m");
+    }
+
+    #[test]
+    fn render_ansi_reset_each_line() {
+        let rtf = "{\\rtf1\\ansi {\\b first\\\nsecond}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_ansi(), "\x1b[1mfirst\x1b[0m\n\x1b[1msecond\x1b[0m");
+    }
+
+    #[test]
+    fn render_ansi_paragraphs() {
+        let rtf = r"{\rtf1\ansi{\colortbl;\red255\green0\blue0;}First paragraph\par {\cf1 Second} paragraph\par {\v Hidden\par}Third\par}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_ansi(), "First paragraph\n\x1b[38;2;255;0;0mSecond\x1b[0m paragraph\n\nThird\n");
+    }
+
+    #[test]
+    fn render_ansi_strip_control_chars() {
+        let rtf = r"{\rtf1\ansi A\'1b[31m\tab B{\b \u7?C\u155?}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.to_ansi(), "A[31m\tB\x1b[1mC\x1b[0m");
+    }
+}
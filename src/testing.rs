@@ -0,0 +1,201 @@
+/// Define the helpers to write the tests of the parsing tersely : fixture builders of the style blocks and assertions with readable diffs
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::document::RtfDocument;
+use crate::header::{ColorRef, DocumentDefaults, FontRef};
+use crate::paragraph::{Alignment, Paragraph};
+use crate::parser::{Painter, StyleBlock, Underline};
+
+/// Parse a document, panicking with the error if it's invalid
+pub fn parse(rtf: &str) -> RtfDocument {
+    return RtfDocument::try_from(rtf).unwrap_or_else(|error| panic!("Failed to parse the document : {error}"));
+}
+
+/// Start a fixture of style block with the text, formatted as the text of a document without formatting
+/// ex: `block("Title").bold().font_size(32).paragraph_end()`
+pub fn block(text: &str) -> BlockBuilder {
+    return BlockBuilder {
+        style_block: StyleBlock {
+            painter: Painter::default(),
+            paragraph: Paragraph::with_defaults(&DocumentDefaults::default()),
+            text: text.to_string(),
+            ..StyleBlock::default()
+        },
+    };
+}
+
+/// A fixture of style block, built by `block`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockBuilder {
+    style_block: StyleBlock,
+}
+
+impl BlockBuilder {
+    pub fn painter(self, painter: Painter) -> Self {
+        return Self {
+            style_block: StyleBlock { painter, ..self.style_block },
+        };
+    }
+
+    pub fn paragraph(self, paragraph: Paragraph) -> Self {
+        return Self {
+            style_block: StyleBlock { paragraph, ..self.style_block },
+        };
+    }
+
+    /// The block is the last one of its paragraph (\par)
+    pub fn paragraph_end(mut self) -> Self {
+        self.style_block.paragraph_end = true;
+        return self;
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.style_block.painter.bold = true;
+        return self;
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.style_block.painter.italic = true;
+        return self;
+    }
+
+    pub fn underline(mut self, underline: Underline) -> Self {
+        self.style_block.painter.underline = underline;
+        return self;
+    }
+
+    pub fn hidden(mut self) -> Self {
+        self.style_block.painter.hidden = true;
+        return self;
+    }
+
+    pub fn font(mut self, font_ref: FontRef) -> Self {
+        self.style_block.painter.font_ref = font_ref;
+        return self;
+    }
+
+    /// Font size in half-points (\fsN)
+    pub fn font_size(mut self, font_size: u16) -> Self {
+        self.style_block.painter.font_size = font_size;
+        return self;
+    }
+
+    pub fn color(mut self, color_ref: ColorRef) -> Self {
+        self.style_block.painter.color_ref = color_ref;
+        return self;
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.style_block.paragraph.alignment = alignment;
+        return self;
+    }
+
+    pub fn build(self) -> StyleBlock {
+        return self.style_block;
+    }
+}
+
+impl From<BlockBuilder> for StyleBlock {
+    fn from(builder: BlockBuilder) -> Self {
+        return builder.build();
+    }
+}
+
+/// Compare the text of the document (see `get_text`) to the expected one.
+/// The error shows both texts escaped, from the first different char.
+pub fn check_text(document: &RtfDocument, expected: &str) -> Result<(), String> {
+    let text = document.get_text();
+    if text == expected {
+        return Ok(());
+    }
+    let offset = text.chars().zip(expected.chars()).take_while(|(c, expected_c)| c == expected_c).count();
+    let rest = |text: &str| text.chars().skip(offset).collect::<String>();
+    return Err(format!(
+        "The text differs from char {offset}\n  expected: {:?}\n    actual: {:?}\n  expected from there: {:?}\n    actual from there: {:?}",
+        expected,
+        text,
+        rest(expected),
+        rest(&text)
+    ));
+}
+
+/// Compare style blocks to the expected ones.
+/// The error lists the fields of the first different block whose values differ, instead of the whole blocks.
+pub fn check_blocks(blocks: &[StyleBlock], expected: &[StyleBlock]) -> Result<(), String> {
+    if blocks == expected {
+        return Ok(());
+    }
+    let mut message = String::new();
+    if blocks.len() != expected.len() {
+        let _ = writeln!(message, "Expected {} blocks, found {}", expected.len(), blocks.len());
+    }
+    let _ = writeln!(message, "  expected texts: {:?}\n    actual texts: {:?}", get_texts(expected), get_texts(blocks));
+    if let Some(index) = blocks.iter().zip(expected).position(|(style_block, expected_block)| style_block != expected_block) {
+        let _ = writeln!(message, "Block {index} differs :");
+        // The pretty debug outputs of two style blocks have the same lines, except the ones of the lists of different lengths
+        let block_lines = format!("{:#?}", blocks[index]);
+        let expected_lines = format!("{:#?}", expected[index]);
+        for (line, expected_line) in block_lines.lines().zip(expected_lines.lines()).filter(|(line, expected_line)| line != expected_line) {
+            let _ = writeln!(message, "  - {}\n  + {}", expected_line.trim(), line.trim());
+        }
+    }
+    return Err(message);
+}
+
+fn get_texts(blocks: &[StyleBlock]) -> Vec<&str> {
+    return blocks.iter().map(|style_block| style_block.text.as_str()).collect();
+}
+
+/// Assert that the text of a document (see `get_text`) equals the expected one, showing where they differ
+/// ex: `assert_doc_text!(document, "Some bold text")`
+#[macro_export]
+macro_rules! assert_doc_text {
+    ($document:expr, $expected:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_text(&$document, $expected) {
+            panic!("{}", message);
+        }
+    };
+}
+
+/// Assert that style blocks equal the expected ones, showing the fields of the first different block
+/// ex: `assert_blocks_eq!(document.body, [block("Some "), block("bold").bold()])`
+#[macro_export]
+macro_rules! assert_blocks_eq {
+    ($blocks:expr, [$($expected:expr),* $(,)?] $(,)?) => {
+        let expected: ::std::vec::Vec<$crate::StyleBlock> = ::std::vec![$($crate::StyleBlock::from($expected)),*];
+        if let Err(message) = $crate::testing::check_blocks(&$blocks, &expected) {
+            panic!("{}", message);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_expected_blocks() {
+        let document = parse(r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;\f1\froman Times;}\qc Some {\b\fs32 bold}\par\pard\f1 End}");
+        assert_doc_text!(document, "Some boldEnd");
+        assert_blocks_eq!(
+            document.body,
+            [block("Some ").alignment(Alignment::Center), block("bold").alignment(Alignment::Center).bold().font_size(32).paragraph_end(), block("End").font(1),]
+        );
+    }
+
+    #[test]
+    fn show_differences() {
+        let document = parse(r"{\rtf1\ansi Some {\i text}}");
+        let message = check_text(&document, "Some test").unwrap_err();
+        assert!(message.starts_with("The text differs from char 7\n"));
+        assert!(message.contains(r#"expected from there: "st""#) && message.contains(r#"actual from there: "xt""#));
+        let message = check_blocks(&document.body, &[block("Some ").build(), block("text").bold().build()]).unwrap_err();
+        assert!(message.starts_with("  expected texts: [\"Some \", \"text\"]\n"));
+        assert!(message.contains("Block 1 differs :\n  - bold: true,\n  + bold: false,\n  - italic: false,\n  + italic: true,\n"));
+        let message = check_blocks(&document.body, &[block("Some ").build()]).unwrap_err();
+        assert!(message.starts_with("Expected 1 blocks, found 2\n"));
+    }
+}
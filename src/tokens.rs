@@ -1,9 +1,18 @@
-use std::any::type_name;
-use std::convert::TryFrom;
-use std::fmt;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::convert::TryFrom;
+use core::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+use crate::error::ParserError;
 use crate::lexer::LexerError;
-use crate::parser::ParserError;
+use crate::units::Twips;
 
 /// Parser representation of an RTF token
 #[allow(dead_code)]
@@ -15,7 +24,8 @@ pub enum Token<'a> {
     CRLF,                 // Line-return \n
     IgnorableDestination, // \*\ <destination-name>
     ControlSymbol(ControlSymbol<'a>),
-    Empty, // Used by the parser for optimization
+    Binary(&'a [u8]), // The N bytes following \binN
+    Empty,            // Used by the parser for optimization
 }
 
 #[allow(dead_code)]
@@ -29,18 +39,115 @@ impl<'a> fmt::Debug for Token<'a> {
             Token::CRLF                   => write!(f, "CRLF"),
             Token::IgnorableDestination   => write!(f, "IgnorableDestination"),
             Token::ControlSymbol(symbol)  => write!(f, "ControlSymbol : {:?}", symbol),
+            Token::Binary(data)           => write!(f, "Binary : {} bytes", data.len()),
             Token::Empty                  => write!(f, "Empty"),
         };
     }
 }
 
+/// Write the token as RTF, the control words without their delimiting space (see `tokens_to_string` to write a token stream).
+/// The bytes of the binary data (\binN) which are not valid UTF-8 are escaped as \'hh : `tokens_to_bytes` writes the exact bytes.
+impl<'a> fmt::Display for Token<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Token::PlainText(text) => {
+                for c in text.chars() {
+                    if matches!(c, '\\' | '{' | '}') {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{c}")?;
+                }
+                Ok(())
+            }
+            Token::OpeningBracket => f.write_str("{"),
+            Token::ClosingBracket => f.write_str("}"),
+            Token::CRLF => f.write_str("\\\n"),
+            Token::IgnorableDestination => f.write_str(r"\*"),
+            Token::ControlSymbol((ControlWord::HexByte, property)) => write!(f, r"\'{:02x}", property.get_value() as u8),
+            Token::ControlSymbol((control_word, property)) => {
+                f.write_str(control_word.name())?;
+                #[rustfmt::skip]
+                return match property {
+                    Property::On         => f.write_str("1"),
+                    Property::Off        => f.write_str("0"),
+                    Property::Value(val) => write!(f, "{val}"),
+                    Property::None       => Ok(()),
+                };
+            }
+            Token::Binary(data) => {
+                write!(f, r"\bin{} ", data.len())?;
+                write_escaped_bytes(f, data)
+            }
+            Token::Empty => Ok(()),
+        };
+    }
+}
+
+// Write the valid UTF-8 of the bytes as is, and the other bytes as \'hh
+pub(crate) fn write_escaped_bytes(f: &mut impl fmt::Write, bytes: &[u8]) -> fmt::Result {
+    for chunk in bytes.utf8_chunks() {
+        f.write_str(chunk.valid())?;
+        for byte in chunk.invalid() {
+            write!(f, r"\'{byte:02x}")?;
+        }
+    }
+    return Ok(());
+}
+
+/// Owned version of a Token, that doesn't borrow the RTF source (ex: produced by `Lexer::scan_reader`)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OwnedToken {
+    PlainText(String),
+    OpeningBracket,
+    ClosingBracket,
+    CRLF,
+    IgnorableDestination,
+    /// The name of the control word (ex: \b) and its property
+    ControlSymbol(String, Property),
+    Binary(Vec<u8>),
+    Empty,
+}
+
+impl OwnedToken {
+    /// Get the borrowed Token, to be used by the parser
+    pub fn as_token(&self) -> Token<'_> {
+        #[rustfmt::skip]
+        return match self {
+            OwnedToken::PlainText(text)              => Token::PlainText(text),
+            OwnedToken::OpeningBracket               => Token::OpeningBracket,
+            OwnedToken::ClosingBracket               => Token::ClosingBracket,
+            OwnedToken::CRLF                         => Token::CRLF,
+            OwnedToken::IgnorableDestination         => Token::IgnorableDestination,
+            OwnedToken::ControlSymbol(name, property) => Token::ControlSymbol((ControlWord::from_name(name), *property)),
+            OwnedToken::Binary(data)                 => Token::Binary(data),
+            OwnedToken::Empty                        => Token::Empty,
+        };
+    }
+}
+
+impl From<&Token<'_>> for OwnedToken {
+    fn from(token: &Token<'_>) -> Self {
+        #[rustfmt::skip]
+        return match token {
+            Token::PlainText(text)                         => OwnedToken::PlainText(text.to_string()),
+            Token::OpeningBracket                          => OwnedToken::OpeningBracket,
+            Token::ClosingBracket                          => OwnedToken::ClosingBracket,
+            Token::CRLF                                    => OwnedToken::CRLF,
+            Token::IgnorableDestination                    => OwnedToken::IgnorableDestination,
+            Token::ControlSymbol((control_word, property)) => OwnedToken::ControlSymbol(control_word.name().to_string(), *property),
+            Token::Binary(data)                            => OwnedToken::Binary(data.to_vec()),
+            Token::Empty                                   => OwnedToken::Empty,
+        };
+    }
+}
+
 /// A control symbol is a pair (control_word, property)
 /// In the RTF specification, it refers to 'control word entity'
 pub type ControlSymbol<'a> = (ControlWord<'a>, Property);
 
 /// Parameters for a control word
 #[allow(dead_code)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Property {
     On,         // 1
     Off,        // 0
@@ -49,12 +156,13 @@ pub enum Property {
 }
 
 impl Property {
+    /// A toggle control word is turned on without parameter or with a non-zero one (\b, \b1), and off with a zero one (\b0)
     pub fn as_bool(&self) -> bool {
         match self {
             Property::On => true,
             Property::Off => false,
             Property::None => true,
-            Property::Value(val) => *val == 1,
+            Property::Value(val) => *val != 0,
         }
     }
 
@@ -73,6 +181,27 @@ impl Property {
         return self.get_value_as::<i32>().expect("i32 to i32 conversion should never fail");
     }
 
+    /// Return the value, or the given default if the control word has no parameter
+    pub fn get_value_or(&self, default: i32) -> i32 {
+        if let Property::None = self {
+            return default;
+        }
+        return self.get_value();
+    }
+
+    /// Return the length of a `Twips` control word, 0 without parameter
+    pub fn get_twips(&self) -> Twips {
+        return Twips(self.get_value());
+    }
+
+    /// Return the font size in half-points of a `HalfPoints` control word (ex: \fs24 is 12pt), or None without parameter or if it's negative
+    pub fn get_half_points(&self) -> Option<u16> {
+        return match self {
+            Property::Value(value) => u16::try_from(*value).ok(),
+            _ => None,
+        };
+    }
+
     /// Return the u16 corresponding value of the unicode
     pub fn get_unicode_value(&self) -> Result<u16, ParserError> {
         // RTF control words generally accept signed 16-bit numbers as arguments.
@@ -88,43 +217,165 @@ impl Property {
     }
 }
 
+/// The meaning of the parameter of a control word, declared for each control word in the table of `ControlWord::from_name`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParameterKind {
+    /// No parameter (ex: \par, \pard, the destinations)
+    Flag,
+    /// On without parameter or with 1, off with 0 (ex: \b, \b0)
+    Toggle,
+    /// A number, an index in a table of the header or a code (ex: \f2, \cf1, \ansicpg1252)
+    Value,
+    /// A signed length in twips, 1/20 of a point (ex: \li720, \fi-360)
+    Twips,
+    /// A length in half-points, which can't be negative (ex: \fs24 is 12pt)
+    HalfPoints,
+}
+
+impl ParameterKind {
+    /// Whether the parameter of a control word is valid for its kind, a missing parameter being its default value
+    pub fn accepts(&self, property: &Property) -> bool {
+        #[rustfmt::skip]
+        return match self {
+            ParameterKind::Flag       => *property == Property::None,
+            ParameterKind::Toggle     => matches!(property, Property::None | Property::On | Property::Off | Property::Value(0 | 1)),
+            ParameterKind::Value
+            | ParameterKind::Twips    => true,
+            ParameterKind::HalfPoints => *property == Property::None || property.get_half_points().is_some(),
+        };
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ControlWord<'a> {
     Rtf,
     Ansi,
+    Mac,
+    Pc,
+    Pca,
+    AnsiCodePage,
 
     Unicode,
+    HexByte, // \'hh : a byte of the codepage of the document
     UnicodeIgnoreCount,
+    UnicodePair,        // \upr : group of an ANSI destination followed by its Unicode version (\ud)
+    UnicodeDestination, // \ud
 
     FontTable,
     FontCharset,
     FontNumber,
     FontSize, // Expressed in half point
+    FontAlternate,
+    FontBias,
+    FontEmbedded,
+    FontFile,
+    FontTypeNil,
+    FontTypeTrueType,
     ColorNumber,
 
     ColorTable,
     FileTable,
     StyleSheet,
+    Generator, // The program which wrote the document
+    // Blobs written by Word
+    ThemeData,
+    ColorSchemeMapping,
+    DataStore,
+    // Password of a protected document
+    Password,
+    PasswordHash,
+    // Document formatting defaults
+    DefaultFont,
+    DefaultLanguage,
+    DefaultEastAsianLanguage,
+    DefaultTabWidth,
+    ViewKind,
 
     Italic,
     Bold,
     Underline,
     UnderlineNone,
+    UnderlineDouble,
+    UnderlineWord,
+    UnderlineDotted,
+    UnderlineDash,
+    UnderlineDashDot,
+    UnderlineDashDotDot,
+    UnderlineLongDash,
+    UnderlineThick,
+    UnderlineWave,
+    UnderlineDoubleWave,
+    UnderlineColor,
     Superscript, // 5th
     Subscript,   // H20
+    NoSuperSub,
     Smallcaps,
     Strikethrough,
+    BaselineUp,   // Expressed in half point, default to 6
+    BaselineDown, // Expressed in half point, default to 6
+    Expand,       // Expressed in quarter point
+    ExpandTwips,  // Expressed in twips
+    Outline,
+    Shadow,
+    Caps,
+    Hidden,
+    Deleted,
+    Language,
+    EastAsianLanguage,
 
-    Par,  // New paragraph
-    Pard, // Resets to default paragraph properties
+    Par,             // New paragraph
+    Line,            // Line break, without ending the paragraph
+    Pard,            // Resets to default paragraph properties
+    Page,            // Page break
+    SoftPage,        // Page break computed by the application that wrote the document
+    PageBreakBefore, // The paragraph starts on a new page
+    KeepTogether,    // The lines of the paragraph stay on the same page
+    KeepWithNext,    // The paragraph stays on the same page as the next one
+    WidowControl,
+    NoWidowControl,
+    OutlineLevel,
+    // Positioned paragraphs (frames) and drop caps
+    FrameWidth,             // \abswN
+    FrameHeight,            // \abshN
+    FrameAnchorColumn,      // \phcol
+    FrameAnchorMargin,      // \phmrg
+    FrameAnchorPage,        // \phpg
+    FrameAnchorTopMargin,   // \pvmrg
+    FrameAnchorTopPage,     // \pvpg
+    FrameAnchorParagraph,   // \pvpara
+    FramePositionX,         // \posxN
+    FramePositionNegativeX, // \posnegxN
+    FrameLeft,              // \posxl
+    FrameCenterX,           // \posxc
+    FrameRight,             // \posxr
+    FrameInsideX,           // \posxi
+    FrameOutsideX,          // \posxo
+    FramePositionY,         // \posyN
+    FramePositionNegativeY, // \posnegyN
+    FrameTop,               // \posyt
+    FrameCenterY,           // \posyc
+    FrameBottom,            // \posyb
+    FrameInsideY,           // \posyin
+    FrameOutsideY,          // \posyout
+    FrameInline,            // \posyil
+    FrameTextDistance,      // \dxfrtextN
+    FrameTextDistanceX,     // \dfrmtxtxN
+    FrameTextDistanceY,     // \dfrmtxtyN
+    FrameNoWrap,            // \nowrap
+    DropCapLines,           // \dropcapliN
+    DropCapType,            // \dropcaptN
     Sectd,
     Plain,
+    CharStyle, // Designates the character style of the run, N references an entry of the character styles of the stylesheet (\*\csN).
+    Additive,  // The character style is added to the paragraph style
     ParStyle,  // Designates paragraph style. If a paragraph style is specified, style properties must be specified with the paragraph. N references an entry in the stylesheet.
     ParDefTab, // Tab width
     // Paragraph indent
     FirstLineIdent,
     LeftIndent,
     RightIndent,
+    LeftIndentLogical,
+    RightIndentLogical,
     // Paragraph alignment
     LeftAligned,
     RightAligned,
@@ -135,104 +386,605 @@ pub enum ControlWord<'a> {
     SpaceAfter,
     SpaceBetweenLine,
     SpaceLineMul, // Line spacing multiple. Indicates that the current line spacing is a multiple of "Single" line spacing. This control word can follow only the \sl control word and works in conjunction with it.
+    // Tab stops
+    Tab, // Tab char
+    // Special chars
+    NonBreakingSpace,  // \~
+    OptionalHyphen,    // \-
+    NonBreakingHyphen, // \_
+    // Character fields, computed when the document is displayed (ex: in the headers and footers)
+    PageNumber,      // \chpgn
+    CurrentDate,     // \chdate
+    LongDate,        // \chdpl
+    AbbreviatedDate, // \chdpa
+    CurrentTime,     // \chtime
+    TabPosition,
+    TabBar,
+    TabRight,
+    TabCenter,
+    TabDecimal,
+    TabLeaderDot,
+    TabLeaderHyphen,
+    TabLeaderUnderline,
+    TabLeaderThick,
+    TabLeaderEqual,
 
     ColorRed,
     ColorGreen,
     ColorBlue,
+    // Lists
+    ListTable,
+    List,
+    ListId,
+    ListLevel,
+    LevelNumberFormat,
+    LevelStartAt,
+    LevelText,
+    ListOverrideTable,
+    ListOverride,
+    ListOverrideLevel,
+    ListOverrideRef,
+    ListLevelIndex,
+    // Tables
+    InTable,
+    TableNestingLevel,
+    NestedTableProperties,
+    TableRowDefaults,
+    TableCell,
+    TableNestedCell,
+    TableRow,
+    TableNestedRow,
+    RowHeight,
+    RowLeft,
+    RowGap,
+    RowHeader,
+    RowLeftAligned,
+    RowRightAligned,
+    RowCenter,
+    CellMergeFirst,
+    CellMerge,
+    CellVerticalMergeFirst,
+    CellVerticalMerge,
+    CellVerticalTop,
+    CellVerticalCenter,
+    CellVerticalBottom,
+    CellShading,
+    CellBackgroundColor,
+    CellBorderTop,
+    CellBorderLeft,
+    CellBorderBottom,
+    CellBorderRight,
+    CellRightBoundary,
+    BorderWidth,
+    BorderColor,
+    BorderNone,
+    BorderSingle,
+    BorderDouble,
+    BorderThick,
+    BorderDotted,
+    BorderDashed,
+    // Fields
+    Field,
+    FieldInstruction,
+    FieldResult,
+    // Form fields
+    FormField,
+    FormFieldType,
+    FormFieldResult,
+    FormFieldDefaultResult,
+    FormFieldName,
+    FormFieldDefaultText,
+    FormFieldListItem,
+    FormFieldMaxLength,
+    // Bookmarks
+    BookmarkStart,
+    BookmarkEnd,
+    // Fallback of the nested tables for the readers not supporting them
+    NoNestedTables,
+    // Pictures
+    Picture,
+    ShapePicture,
+    NonShapePicture,
+    PictureWidth,
+    PictureHeight,
+    PictureGoalWidth,
+    PictureGoalHeight,
+    PictureScaleX,
+    PictureScaleY,
+    PictureCropLeft,
+    PictureCropRight,
+    PictureCropTop,
+    PictureCropBottom,
+    PictureFormatPng,
+    PictureFormatJpeg,
+    PictureFormatEmf,
+    PictureFormatWmf,
+    PictureFormatPict,
+    PictureFormatDib,
+    PictureFormatBitmap,
+    // Shapes
+    Shape,
+    ShapeInstruction,
+    ShapeResult,
+    ShapeText,
+    ShapeLeft,
+    ShapeTop,
+    ShapeRight,
+    ShapeBottom,
+    ShapeAnchorXPage,
+    ShapeAnchorXMargin,
+    ShapeAnchorXColumn,
+    ShapeAnchorYPage,
+    ShapeAnchorYMargin,
+    ShapeAnchorYParagraph,
+    ShapeProperty,
+    ShapePropertyName,
+    ShapePropertyValue,
+    // Drawing objects
+    DrawingObject,
+    DrawingX,
+    DrawingY,
+    DrawingWidth,
+    DrawingHeight,
+    DrawingAnchorXPage,
+    DrawingAnchorXMargin,
+    DrawingAnchorXColumn,
+    DrawingAnchorYPage,
+    DrawingAnchorYMargin,
+    DrawingAnchorYParagraph,
+    DrawingLine,
+    DrawingRectangle,
+    DrawingEllipse,
+    DrawingTextBox,
+    DrawingTextBoxText,
+    // Expanded color table
+    ExpandedColorTable,
+    ColorSpaceGray,
+    ColorSpaceSrgb,
+    ColorSpaceGenericRgb,
+    ColorComponent, // Expressed in thousandth of percent
+    // HTML encapsulated by the mail clients
+    FromHtml,
+    FromText,
+    HtmlTag,
+    MhtmlTag,
+    HtmlRtf,
 
     Unknown(&'a str),
 }
 
-impl<'a> ControlWord<'a> {
-    // https://www.biblioscape.com/rtf15_spec.htm
-    // version 1.5 should be compatible with 1.9
-    pub fn from(input: &str) -> Result<ControlSymbol, LexerError> {
-        // Loop backward the string to get the number
-        let mut it = input.chars().rev();
-        let mut suffix_index = 0;
-        while let Some(c) = it.next() {
-            match c {
-                '0'..='9' | '-' => {
-                    suffix_index += 1;
-                }
-                _ => break,
+// Define the mapping between the names of the control words and their variants, in both directions
+macro_rules! control_words {
+    ($($name:literal => $variant:ident : $kind:ident,)*) => {
+        impl<'a> ControlWord<'a> {
+            /// Get the control word from its name, without parameter (ex: \b)
+            pub fn from_name(name: &'a str) -> Self {
+                return match name {
+                    $($name => ControlWord::$variant,)*
+                    _ => ControlWord::Unknown(name),
+                };
+            }
+
+            /// Get the control word from its name, ignoring the case of the letters (ex: \PAR)
+            pub fn from_name_ignore_case(name: &'a str) -> Self {
+                $(if name.eq_ignore_ascii_case($name) {
+                    return ControlWord::$variant;
+                })*
+                return ControlWord::Unknown(name);
+            }
+
+            /// Get the name of the control word as written in RTF, without parameter (ex: \b)
+            pub fn name(&self) -> &'a str {
+                // Some variants have several names, the first one is used
+                #[allow(unreachable_patterns)]
+                return match self {
+                    $(ControlWord::$variant => $name,)*
+                    ControlWord::Unknown(name) => name,
+                };
+            }
+
+            /// Get the meaning of the parameter of the control word, None for an unknown control word
+            pub fn parameter_kind(&self) -> Option<ParameterKind> {
+                #[allow(unreachable_patterns)]
+                return match self {
+                    $(ControlWord::$variant => Some(ParameterKind::$kind),)*
+                    ControlWord::Unknown(_) => None,
+                };
             }
         }
+    };
+}
 
-        // f0 -> prefix: f, suffix: 0
-        let index = input.len() - suffix_index;
-        let prefix = &input[..index];
-        let suffix = &input[index..];
+// https://www.biblioscape.com/rtf15_spec.htm
+// version 1.5 should be compatible with 1.9
+control_words! {
+    r"\rtf"                => Rtf                      : Value,
+    r"\ansi"               => Ansi                     : Flag,
+    r"\mac"                => Mac                      : Flag,
+    r"\pc"                 => Pc                       : Flag,
+    r"\pca"                => Pca                      : Flag,
+    r"\ansicpg"            => AnsiCodePage             : Value,
+    // Unicode
+    r"\u"                  => Unicode                  : Value,
+    r"\'"                  => HexByte                  : Value,
+    r"\uc"                 => UnicodeIgnoreCount       : Value,
+    r"\upr"                => UnicodePair              : Flag,
+    r"\ud"                 => UnicodeDestination       : Flag,
+    // Header
+    r"\fonttbl"            => FontTable                : Flag,
+    r"\colortbl"           => ColorTable               : Flag,
+    r"\filetbl"            => FileTable                : Flag,
+    r"\stylesheet"         => StyleSheet               : Flag,
+    r"\generator"          => Generator                : Flag,
+    r"\themedata"          => ThemeData                : Flag,
+    r"\colorschememapping" => ColorSchemeMapping       : Flag,
+    r"\datastore"          => DataStore                : Flag,
+    r"\password"           => Password                 : Flag,
+    r"\passwordhash"       => PasswordHash             : Flag,
+    // Document formatting defaults
+    r"\deff"               => DefaultFont              : Value,
+    r"\deflang"            => DefaultLanguage          : Value,
+    r"\deflangfe"          => DefaultEastAsianLanguage : Value,
+    r"\deftab"             => DefaultTabWidth          : Twips,
+    r"\viewkind"           => ViewKind                 : Value,
+    // Font
+    r"\fcharset"           => FontCharset              : Value,
+    r"\f"                  => FontNumber               : Value,
+    r"\fs"                 => FontSize                 : HalfPoints,
+    r"\falt"               => FontAlternate            : Flag,
+    r"\fbias"              => FontBias                 : Value,
+    r"\fontemb"            => FontEmbedded             : Flag,
+    r"\fontfile"           => FontFile                 : Flag,
+    r"\ftnil"              => FontTypeNil              : Flag,
+    r"\fttruetype"         => FontTypeTrueType         : Flag,
+    r"\cf"                 => ColorNumber              : Value,
+    // Format
+    r"\i"                  => Italic                   : Toggle,
+    r"\b"                  => Bold                     : Toggle,
+    r"\ul"                 => Underline                : Toggle,
+    r"\ulnone"             => UnderlineNone            : Flag,
+    r"\uldb"               => UnderlineDouble          : Toggle,
+    r"\ulw"                => UnderlineWord            : Toggle,
+    r"\uld"                => UnderlineDotted          : Toggle,
+    r"\uldash"             => UnderlineDash            : Toggle,
+    r"\uldashd"            => UnderlineDashDot         : Toggle,
+    r"\uldashdd"           => UnderlineDashDotDot      : Toggle,
+    r"\ulldash"            => UnderlineLongDash        : Toggle,
+    r"\ulth"               => UnderlineThick           : Toggle,
+    r"\ulwave"             => UnderlineWave            : Toggle,
+    r"\ululdbwave"         => UnderlineDoubleWave      : Toggle,
+    r"\ulc"                => UnderlineColor           : Value,
+    r"\super"              => Superscript              : Toggle,
+    r"\sub"                => Subscript                : Toggle,
+    r"\nosupersub"         => NoSuperSub               : Flag,
+    r"\scaps"              => Smallcaps                : Toggle,
+    r"\strike"             => Strikethrough            : Toggle,
+    r"\up"                 => BaselineUp               : HalfPoints,
+    r"\dn"                 => BaselineDown             : HalfPoints,
+    r"\expnd"              => Expand                   : Value,
+    r"\expndtw"            => ExpandTwips              : Twips,
+    r"\outl"               => Outline                  : Toggle,
+    r"\shad"               => Shadow                   : Toggle,
+    r"\caps"               => Caps                     : Toggle,
+    r"\v"                  => Hidden                   : Toggle,
+    r"\deleted"            => Deleted                  : Toggle,
+    r"\lang"               => Language                 : Value,
+    r"\langfe"             => EastAsianLanguage        : Value,
+    // Paragraph
+    r"\par"                => Par                      : Flag,
+    r"\line"               => Line                     : Flag,
+    r"\pard"               => Pard                     : Flag,
+    r"\page"               => Page                     : Flag,
+    r"\softpage"           => SoftPage                 : Flag,
+    r"\pagebb"             => PageBreakBefore          : Flag,
+    r"\keep"               => KeepTogether             : Flag,
+    r"\keepn"              => KeepWithNext             : Flag,
+    r"\widctlpar"          => WidowControl             : Flag,
+    r"\nowidctlpar"        => NoWidowControl           : Flag,
+    r"\outlinelevel"       => OutlineLevel             : Value,
+    r"\absw"               => FrameWidth               : Twips,
+    r"\absh"               => FrameHeight              : Twips,
+    r"\phcol"              => FrameAnchorColumn        : Flag,
+    r"\phmrg"              => FrameAnchorMargin        : Flag,
+    r"\phpg"               => FrameAnchorPage          : Flag,
+    r"\pvmrg"              => FrameAnchorTopMargin     : Flag,
+    r"\pvpg"               => FrameAnchorTopPage       : Flag,
+    r"\pvpara"             => FrameAnchorParagraph     : Flag,
+    r"\posx"               => FramePositionX           : Twips,
+    r"\posnegx"            => FramePositionNegativeX   : Twips,
+    r"\posxl"              => FrameLeft                : Flag,
+    r"\posxc"              => FrameCenterX             : Flag,
+    r"\posxr"              => FrameRight               : Flag,
+    r"\posxi"              => FrameInsideX             : Flag,
+    r"\posxo"              => FrameOutsideX            : Flag,
+    r"\posy"               => FramePositionY           : Twips,
+    r"\posnegy"            => FramePositionNegativeY   : Twips,
+    r"\posyt"              => FrameTop                 : Flag,
+    r"\posyc"              => FrameCenterY             : Flag,
+    r"\posyb"              => FrameBottom              : Flag,
+    r"\posyin"             => FrameInsideY             : Flag,
+    r"\posyout"            => FrameOutsideY            : Flag,
+    r"\posyil"             => FrameInline              : Flag,
+    r"\dxfrtext"           => FrameTextDistance        : Twips,
+    r"\dfrmtxtx"           => FrameTextDistanceX       : Twips,
+    r"\dfrmtxty"           => FrameTextDistanceY       : Twips,
+    r"\nowrap"             => FrameNoWrap              : Flag,
+    r"\dropcapli"          => DropCapLines             : Value,
+    r"\dropcapt"           => DropCapType              : Value,
+    r"\sectd"              => Sectd                    : Flag,
+    r"\plain"              => Plain                    : Flag,
+    r"\s"                  => ParStyle                 : Value,
+    r"\cs"                 => CharStyle                : Value,
+    r"\additive"           => Additive                 : Flag,
+    r"\pardeftab"          => ParDefTab                : Twips,
+    // Paragraph alignment
+    r"\ql"                 => LeftAligned              : Flag,
+    r"\qr"                 => RightAligned             : Flag,
+    r"\qj"                 => Justify                  : Flag,
+    r"\qc"                 => Center                   : Flag,
+    // Paragraph indent
+    r"\fi"                 => FirstLineIdent           : Twips,
+    r"\ri"                 => RightIndent              : Twips,
+    r"\li"                 => LeftIndent               : Twips,
+    r"\lin"                => LeftIndentLogical        : Twips,
+    r"\rin"                => RightIndentLogical       : Twips,
+    // Paragraph Spacing
+    r"\sb"                 => SpaceBefore              : Twips,
+    r"\sa"                 => SpaceAfter               : Twips,
+    r"\sl"                 => SpaceBetweenLine         : Twips,
+    r"\slmul"              => SpaceLineMul             : Value,
+    // Tab stops
+    r"\tab"                => Tab                      : Flag,
+    r"\~"                  => NonBreakingSpace         : Flag,
+    r"\-"                  => OptionalHyphen           : Flag,
+    r"\_"                  => NonBreakingHyphen        : Flag,
+    r"\chpgn"              => PageNumber               : Flag,
+    r"\chdate"             => CurrentDate              : Flag,
+    r"\chdpl"              => LongDate                 : Flag,
+    r"\chdpa"              => AbbreviatedDate          : Flag,
+    r"\chtime"             => CurrentTime              : Flag,
+    r"\tx"                 => TabPosition              : Twips,
+    r"\tb"                 => TabBar                   : Twips,
+    r"\tqr"                => TabRight                 : Flag,
+    r"\tqc"                => TabCenter                : Flag,
+    r"\tqdec"              => TabDecimal               : Flag,
+    r"\tldot"              => TabLeaderDot             : Flag,
+    r"\tlhyph"             => TabLeaderHyphen          : Flag,
+    r"\tlul"               => TabLeaderUnderline       : Flag,
+    r"\tlth"               => TabLeaderThick           : Flag,
+    r"\tleq"               => TabLeaderEqual           : Flag,
+    r"\red"                => ColorRed                 : Value,
+    r"\green"              => ColorGreen               : Value,
+    r"\blue"               => ColorBlue                : Value,
+    // Lists
+    r"\listtable"          => ListTable                : Flag,
+    r"\list"               => List                     : Flag,
+    r"\listid"             => ListId                   : Value,
+    r"\listlevel"          => ListLevel                : Flag,
+    r"\levelnfc"           => LevelNumberFormat        : Value,
+    r"\levelnfcn"          => LevelNumberFormat        : Value,
+    r"\levelstartat"       => LevelStartAt             : Value,
+    r"\leveltext"          => LevelText                : Flag,
+    r"\listoverridetable"  => ListOverrideTable        : Flag,
+    r"\listoverride"       => ListOverride             : Flag,
+    r"\lfolevel"           => ListOverrideLevel        : Flag,
+    r"\ls"                 => ListOverrideRef          : Value,
+    r"\ilvl"               => ListLevelIndex           : Value,
+    // Tables
+    r"\intbl"              => InTable                  : Flag,
+    r"\itap"               => TableNestingLevel        : Value,
+    r"\nesttableprops"     => NestedTableProperties    : Flag,
+    r"\trowd"              => TableRowDefaults         : Flag,
+    r"\cell"               => TableCell                : Flag,
+    r"\nestcell"           => TableNestedCell          : Flag,
+    r"\row"                => TableRow                 : Flag,
+    r"\nestrow"            => TableNestedRow           : Flag,
+    r"\trrh"               => RowHeight                : Twips,
+    r"\trleft"             => RowLeft                  : Twips,
+    r"\trgaph"             => RowGap                   : Twips,
+    r"\trhdr"              => RowHeader                : Flag,
+    r"\trql"               => RowLeftAligned           : Flag,
+    r"\trqr"               => RowRightAligned          : Flag,
+    r"\trqc"               => RowCenter                : Flag,
+    r"\clmgf"              => CellMergeFirst           : Flag,
+    r"\clmrg"              => CellMerge                : Flag,
+    r"\clvmgf"             => CellVerticalMergeFirst   : Flag,
+    r"\clvmrg"             => CellVerticalMerge        : Flag,
+    r"\clvertalt"          => CellVerticalTop          : Flag,
+    r"\clvertalc"          => CellVerticalCenter       : Flag,
+    r"\clvertalb"          => CellVerticalBottom       : Flag,
+    r"\clshdng"            => CellShading              : Value,
+    r"\clcbpat"            => CellBackgroundColor      : Value,
+    r"\clbrdrt"            => CellBorderTop            : Flag,
+    r"\clbrdrl"            => CellBorderLeft           : Flag,
+    r"\clbrdrb"            => CellBorderBottom         : Flag,
+    r"\clbrdrr"            => CellBorderRight          : Flag,
+    r"\cellx"              => CellRightBoundary        : Twips,
+    r"\brdrw"              => BorderWidth              : Twips,
+    r"\brdrcf"             => BorderColor              : Value,
+    r"\brdrnone"           => BorderNone               : Flag,
+    r"\brdrs"              => BorderSingle             : Flag,
+    r"\brdrdb"             => BorderDouble             : Flag,
+    r"\brdrth"             => BorderThick              : Flag,
+    r"\brdrdot"            => BorderDotted             : Flag,
+    r"\brdrdash"           => BorderDashed             : Flag,
+    // Fields
+    r"\field"              => Field                    : Flag,
+    r"\fldinst"            => FieldInstruction         : Flag,
+    r"\fldrslt"            => FieldResult              : Flag,
+    // Form fields
+    r"\formfield"          => FormField                : Flag,
+    r"\fftype"             => FormFieldType            : Value,
+    r"\ffres"              => FormFieldResult          : Value,
+    r"\ffdefres"           => FormFieldDefaultResult   : Value,
+    r"\ffname"             => FormFieldName            : Flag,
+    r"\ffdeftext"          => FormFieldDefaultText     : Flag,
+    r"\ffl"                => FormFieldListItem        : Flag,
+    r"\ffmaxlen"           => FormFieldMaxLength       : Value,
+    // Bookmarks
+    r"\bkmkstart"          => BookmarkStart            : Flag,
+    r"\bkmkend"            => BookmarkEnd              : Flag,
+    r"\nonesttables"       => NoNestedTables           : Flag,
+    // Pictures
+    r"\pict"               => Picture                  : Flag,
+    r"\shppict"            => ShapePicture             : Flag,
+    r"\nonshppict"         => NonShapePicture          : Flag,
+    r"\picw"               => PictureWidth             : Value,
+    r"\pich"               => PictureHeight            : Value,
+    r"\picwgoal"           => PictureGoalWidth         : Twips,
+    r"\pichgoal"           => PictureGoalHeight        : Twips,
+    r"\picscalex"          => PictureScaleX            : Value,
+    r"\picscaley"          => PictureScaleY            : Value,
+    r"\piccropl"           => PictureCropLeft          : Twips,
+    r"\piccropr"           => PictureCropRight         : Twips,
+    r"\piccropt"           => PictureCropTop           : Twips,
+    r"\piccropb"           => PictureCropBottom        : Twips,
+    r"\pngblip"            => PictureFormatPng         : Flag,
+    r"\jpegblip"           => PictureFormatJpeg        : Flag,
+    r"\emfblip"            => PictureFormatEmf         : Flag,
+    r"\wmetafile"          => PictureFormatWmf         : Value,
+    r"\macpict"            => PictureFormatPict        : Flag,
+    r"\dibitmap"           => PictureFormatDib         : Value,
+    r"\wbitmap"            => PictureFormatBitmap      : Value,
+    // Shapes
+    r"\shp"                => Shape                    : Flag,
+    r"\shpinst"            => ShapeInstruction         : Flag,
+    r"\shprslt"            => ShapeResult              : Flag,
+    r"\shptxt"             => ShapeText                : Flag,
+    r"\shpleft"            => ShapeLeft                : Twips,
+    r"\shptop"             => ShapeTop                 : Twips,
+    r"\shpright"           => ShapeRight               : Twips,
+    r"\shpbottom"          => ShapeBottom              : Twips,
+    r"\shpbxpage"          => ShapeAnchorXPage         : Flag,
+    r"\shpbxmargin"        => ShapeAnchorXMargin       : Flag,
+    r"\shpbxcolumn"        => ShapeAnchorXColumn       : Flag,
+    r"\shpbypage"          => ShapeAnchorYPage         : Flag,
+    r"\shpbymargin"        => ShapeAnchorYMargin       : Flag,
+    r"\shpbypara"          => ShapeAnchorYParagraph    : Flag,
+    r"\sp"                 => ShapeProperty            : Flag,
+    r"\sn"                 => ShapePropertyName        : Flag,
+    r"\sv"                 => ShapePropertyValue       : Flag,
+    // Drawing objects
+    r"\do"                 => DrawingObject            : Flag,
+    r"\dpx"                => DrawingX                 : Twips,
+    r"\dpy"                => DrawingY                 : Twips,
+    r"\dpxsize"            => DrawingWidth             : Twips,
+    r"\dpysize"            => DrawingHeight            : Twips,
+    r"\dobxpage"           => DrawingAnchorXPage       : Flag,
+    r"\dobxmargin"         => DrawingAnchorXMargin     : Flag,
+    r"\dobxcolumn"         => DrawingAnchorXColumn     : Flag,
+    r"\dobypage"           => DrawingAnchorYPage       : Flag,
+    r"\dobymargin"         => DrawingAnchorYMargin     : Flag,
+    r"\dobypara"           => DrawingAnchorYParagraph  : Flag,
+    r"\dpline"             => DrawingLine              : Flag,
+    r"\dprect"             => DrawingRectangle         : Flag,
+    r"\dpellipse"          => DrawingEllipse           : Flag,
+    r"\dptxbx"             => DrawingTextBox           : Flag,
+    r"\dptxbxtext"         => DrawingTextBoxText       : Flag,
+    // Expanded color table
+    r"\expandedcolortbl"   => ExpandedColorTable       : Flag,
+    r"\csgray"             => ColorSpaceGray           : Flag,
+    r"\cssrgb"             => ColorSpaceSrgb           : Flag,
+    r"\csgenericrgb"       => ColorSpaceGenericRgb     : Flag,
+    r"\c"                  => ColorComponent           : Value,
+    // HTML encapsulated by the mail clients
+    r"\fromhtml"           => FromHtml                 : Value,
+    r"\fromtext"           => FromText                 : Flag,
+    r"\htmltag"            => HtmlTag                  : Value,
+    r"\mhtmltag"           => MhtmlTag                 : Value,
+    r"\htmlrtf"            => HtmlRtf                  : Toggle,
+}
+
+/// How the control words are read by the lexer
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum ControlWordMatching {
+    /// As defined by the specification : case-sensitive names, parameters with an optional `-` sign
+    #[default]
+    Strict,
+    /// Case-insensitive names (\PAR) and parameters with a `+` sign (\fs+24), as emitted by some generators
+    Lenient,
+}
+
+impl<'a> ControlWord<'a> {
+    /// The control symbols (\'hh, \~, ...) have no delimiter : the spaces following them are text
+    pub fn is_symbol(&self) -> bool {
+        return matches!(
+            self,
+            ControlWord::HexByte | ControlWord::NonBreakingSpace | ControlWord::OptionalHyphen | ControlWord::NonBreakingHyphen
+        );
+    }
 
-        let property = if suffix == "" {
+    /// The text of the control words which write a char (\tab, \line, \~, ...)
+    #[rustfmt::skip]
+    pub fn get_text(&self) -> Option<&'static str> {
+        return match self {
+            ControlWord::Line              => Some("\n"),
+            ControlWord::Tab               => Some("\t"),
+            ControlWord::NonBreakingSpace  => Some("\u{a0}"),
+            ControlWord::OptionalHyphen    => Some("\u{ad}"),
+            ControlWord::NonBreakingHyphen => Some("\u{2011}"),
+            _ => None,
+        };
+    }
+
+    pub fn from(input: &str) -> Result<ControlSymbol<'_>, LexerError> {
+        return Self::from_with_matching(input, ControlWordMatching::Strict);
+    }
+
+    /// Get the control word and its parameter from its RTF representation without delimiter (ex: \f0)
+    pub fn from_with_matching(input: &str, matching: ControlWordMatching) -> Result<ControlSymbol<'_>, LexerError> {
+        // \f0 -> name: \f, parameter: 0
+        let name_end = 1 + input.bytes().skip(1).take_while(u8::is_ascii_alphabetic).count();
+        let name = &input[..name_end];
+        let parameter = &input[name_end..];
+        let property = if parameter.is_empty() {
             Property::None
         } else {
-            let Ok(value) = suffix.parse::<i32>() else {
-                return Err(LexerError::Error(format!("[Lexer] Unable to parse {} as integer", &suffix)));
+            let parameter = match matching {
+                ControlWordMatching::Strict => parameter,
+                ControlWordMatching::Lenient => parameter.strip_prefix('+').unwrap_or(parameter),
+            };
+            // i32::from_str accepts a + sign, which is not allowed by the specification
+            let Some(value) = parameter.parse::<i32>().ok().filter(|_| !parameter.starts_with('+')) else {
+                return Err(LexerError::Error(format!("[Lexer] Unable to parse {} as integer", &parameter)));
             };
             Property::Value(value)
         };
-
         #[rustfmt::skip]
-        let control_word = match prefix {
-            r"\rtf"           => ControlWord::Rtf,
-            r"\ansi"          => ControlWord::Ansi,
-            // Unicode
-            r"\u"             => ControlWord::Unicode,
-            r"\uc"            => ControlWord::UnicodeIgnoreCount,
-            // Header
-            r"\fonttbl"       => ControlWord::FontTable,
-            r"\colortbl"      => ControlWord::ColorTable,
-            r"\filetbl"       => ControlWord::FileTable,
-            r"\stylesheet"    => ControlWord::StyleSheet,
-            // Font
-            r"\fcharset"      => ControlWord::FontCharset,
-            r"\f"             => ControlWord::FontNumber,
-            r"\fs"            => ControlWord::FontSize,
-            r"\cf"            => ControlWord::ColorNumber,
-            // Format
-            r"\i"             => ControlWord::Italic,
-            r"\b"             => ControlWord::Bold,
-            r"\ul"            => ControlWord::Underline,
-            r"\ulnone"        => ControlWord::UnderlineNone,
-            r"\super"         => ControlWord::Superscript,
-            r"\sub"           => ControlWord::Subscript,
-            r"\scaps"         => ControlWord::Smallcaps,
-            r"\strike"        => ControlWord::Strikethrough,
-            // Paragraph
-            r"\par"           => ControlWord::Par,
-            r"\pard"          => ControlWord::Pard,
-            r"\sectd"         => ControlWord::Sectd,
-            r"\plain"         => ControlWord::Plain,
-            r"\s"             => ControlWord::ParStyle,
-            r"\pardeftab"     => ControlWord::ParDefTab,
-            // Paragraph alignment
-            r"\ql"            => ControlWord::LeftAligned,
-            r"\qr"            => ControlWord::RightAligned,
-            r"\qj"            => ControlWord::Justify,
-            r"\qc"            => ControlWord::Center,
-            // Paragraph indent
-            r"\fi"             => ControlWord::FirstLineIdent,
-            r"\ri"            => ControlWord::RightIndent,
-            r"\li"            => ControlWord::LeftIndent,
-            // Paragraph Spacing
-            r"\sb"            => ControlWord::SpaceBefore,
-            r"\sa"            => ControlWord::SpaceAfter,
-            r"\sl"            => ControlWord::SpaceBetweenLine,
-            r"\slmul"         => ControlWord::SpaceLineMul,
-            r"\red"           => ControlWord::ColorRed,
-            r"\green"         => ControlWord::ColorGreen,
-            r"\blue"          => ControlWord::ColorBlue,
-            // Unknown
-            _                 => ControlWord::Unknown(prefix),
+        let control_word = match ControlWord::from_name(name) {
+            ControlWord::Unknown(_) if matching == ControlWordMatching::Lenient => ControlWord::from_name_ignore_case(name),
+            control_word                                                        => control_word,
         };
         return Ok((control_word, property));
     }
+
+    /// Split a slice starting with a control word between the control word with its parameter and the following text
+    /// The name is made of letters, and the parameter of digits with an optional sign. Only a delimiting space is consumed.
+    /// ex : "\b0 text" -> ("\b0", "text"), "\b0,text" -> ("\b0", ",text"), "\blue0;" -> ("\blue0", ";")
+    pub fn split(slice: &str, matching: ControlWordMatching) -> (&str, &str) {
+        let bytes = slice.as_bytes();
+        let mut end = 1 + bytes.iter().skip(1).take_while(|c| c.is_ascii_alphabetic()).count();
+        let has_sign = match bytes.get(end) {
+            Some(b'-') => true,
+            Some(b'+') => matching == ControlWordMatching::Lenient,
+            _ => false,
+        };
+        let sign_len = usize::from(has_sign);
+        let digits = bytes.iter().skip(end + sign_len).take_while(|c| c.is_ascii_digit()).count();
+        // A sign without digits is not a parameter
+        if digits > 0 {
+            end += sign_len + digits;
+        }
+        let tail_start = if matches!(bytes.get(end), Some(b' ')) { end + 1 } else { end };
+        return (&slice[..end], &slice[tail_start..]);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tokens::{ControlWord, Property};
+    use crate::tokens::{ControlWord, ControlWordMatching, ParameterKind, Property};
 
     #[test]
     fn control_word_from_input_test() {
@@ -245,4 +997,41 @@ mod tests {
         let input = r"\rtf-1";
         assert_eq!(ControlWord::from(input).unwrap(), (ControlWord::Rtf, Property::Value(-1)))
     }
+
+    #[test]
+    fn split_control_word() {
+        let strict = ControlWordMatching::Strict;
+        assert_eq!(ControlWord::split(r"\b I'm a bold string", strict), (r"\b", "I'm a bold string"));
+        assert_eq!(ControlWord::split(r"\b0,text", strict), (r"\b0", ",text"));
+        assert_eq!(ControlWord::split(r"\f0Helvetica", strict), (r"\f0", "Helvetica"));
+        assert_eq!(ControlWord::split(r"\blue0;", strict), (r"\blue0", ";"));
+        assert_eq!(ControlWord::split(r"\li-", strict), (r"\li", "-"));
+        assert_eq!(ControlWord::split(r"\fs+24 text", strict), (r"\fs", "+24 text"));
+        assert_eq!(ControlWord::split(r"\fs+24 text", ControlWordMatching::Lenient), (r"\fs+24", "text"));
+    }
+
+    #[test]
+    fn control_word_lenient_matching() {
+        let lenient = ControlWordMatching::Lenient;
+        assert_eq!(ControlWord::from(r"\PAR").unwrap(), (ControlWord::Unknown(r"\PAR"), Property::None));
+        assert_eq!(ControlWord::from_with_matching(r"\PAR", lenient).unwrap(), (ControlWord::Par, Property::None));
+        assert_eq!(ControlWord::from_with_matching(r"\Fs+24", lenient).unwrap(), (ControlWord::FontSize, Property::Value(24)));
+        assert!(ControlWord::from(r"\fs+24").is_err());
+    }
+
+    #[test]
+    fn control_word_parameter_kinds() {
+        assert_eq!(ControlWord::Par.parameter_kind(), Some(ParameterKind::Flag));
+        assert_eq!(ControlWord::Bold.parameter_kind(), Some(ParameterKind::Toggle));
+        assert_eq!(ControlWord::LevelNumberFormat.parameter_kind(), Some(ParameterKind::Value));
+        assert_eq!(ControlWord::LeftIndent.parameter_kind(), Some(ParameterKind::Twips));
+        assert_eq!(ControlWord::FontSize.parameter_kind(), Some(ParameterKind::HalfPoints));
+        assert_eq!(ControlWord::Unknown(r"\vendor").parameter_kind(), None);
+        assert!(ParameterKind::Toggle.accepts(&Property::None) && ParameterKind::Toggle.accepts(&Property::Value(0)));
+        assert!(!ParameterKind::Toggle.accepts(&Property::Value(-1)));
+        assert!(!ParameterKind::Flag.accepts(&Property::Value(0)));
+        assert!(ParameterKind::HalfPoints.accepts(&Property::Value(24)) && !ParameterKind::HalfPoints.accepts(&Property::Value(-2)));
+        assert_eq!(Property::Value(24).get_half_points(), Some(24));
+        assert_eq!(Property::Value(70_000).get_half_points(), None);
+    }
 }
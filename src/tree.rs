@@ -0,0 +1,214 @@
+/// Define the tree of the groups of a document, built from the tokens of the lexer for the analyses of its structure.
+/// The parser reads the flat tokens : the tree is a view of them, which can be flattened back with `to_tokens`.
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+use core::slice::Iter;
+
+use crate::error::ParserError;
+use crate::tokens::{ControlSymbol, ControlWord, Token};
+
+/// A node of the tree : a group, or one of the tokens between the groups
+#[derive(Debug, Clone, PartialEq)]
+pub enum RtfNode<'a> {
+    Group(GroupTree<'a>),
+    ControlWord(ControlSymbol<'a>),
+    Text(&'a str),
+    /// An escaped line break (\ followed by a newline)
+    LineBreak,
+    /// The \* marking the group as an ignorable destination
+    IgnorableDestination,
+    /// The data following a \binN control word
+    Binary(&'a [u8]),
+}
+
+/// The content of a group, its brackets excluded. The root of the tree is the content of the document, usually one {\rtf1 ...} group.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GroupTree<'a> {
+    pub nodes: Vec<RtfNode<'a>>,
+}
+
+impl<'a> GroupTree<'a> {
+    /// Build the tree of the tokens, which must have balanced brackets
+    pub fn from_tokens(tokens: &[Token<'a>]) -> Result<Self, ParserError> {
+        // The groups being read, the root included
+        let mut stack: Vec<Vec<RtfNode<'a>>> = vec![vec![]];
+        for token in tokens {
+            let node = match token {
+                Token::OpeningBracket => {
+                    stack.push(vec![]);
+                    continue;
+                }
+                Token::ClosingBracket => {
+                    let nodes = stack.pop().filter(|_| !stack.is_empty()).ok_or(ParserError::MalformedPainterStack)?;
+                    RtfNode::Group(GroupTree { nodes })
+                }
+                Token::PlainText(text) => RtfNode::Text(text),
+                Token::CRLF => RtfNode::LineBreak,
+                Token::IgnorableDestination => RtfNode::IgnorableDestination,
+                Token::ControlSymbol(control_symbol) => RtfNode::ControlWord(*control_symbol),
+                Token::Binary(data) => RtfNode::Binary(data),
+                Token::Empty => continue,
+            };
+            let Some(current_group) = stack.last_mut() else {
+                return Err(ParserError::MalformedPainterStack);
+            };
+            current_group.push(node);
+        }
+        if stack.len() != 1 {
+            return Err(ParserError::MalformedPainterStack);
+        }
+        return Ok(Self {
+            nodes: stack.pop().unwrap_or_default(),
+        });
+    }
+
+    /// Flatten the tree in the tokens it has been built from, ex: to parse them with `Parser::new`
+    pub fn to_tokens(&self) -> Vec<Token<'a>> {
+        let mut tokens = vec![];
+        // The nodes left to read in each group being flattened : the walks of the tree don't recurse, so the deep trees don't overflow the stack
+        let mut stack: Vec<Iter<RtfNode<'a>>> = vec![self.nodes.iter()];
+        while let Some(nodes) = stack.last_mut() {
+            #[rustfmt::skip]
+            let token = match nodes.next() {
+                Some(RtfNode::Group(group))                 => {
+                    stack.push(group.nodes.iter());
+                    Token::OpeningBracket
+                }
+                Some(RtfNode::ControlWord(control_symbol))  => Token::ControlSymbol(*control_symbol),
+                Some(RtfNode::Text(text))                   => Token::PlainText(text),
+                Some(RtfNode::LineBreak)                    => Token::CRLF,
+                Some(RtfNode::IgnorableDestination)         => Token::IgnorableDestination,
+                Some(RtfNode::Binary(data))                 => Token::Binary(data),
+                None                                        => {
+                    stack.pop();
+                    // The root has no brackets
+                    if stack.is_empty() {
+                        break;
+                    }
+                    Token::ClosingBracket
+                }
+            };
+            tokens.push(token);
+        }
+        return tokens;
+    }
+
+    /// The group starts with \* : it can be skipped by the readers that don't know its destination
+    pub fn is_ignorable(&self) -> bool {
+        return self.nodes.first() == Some(&RtfNode::IgnorableDestination);
+    }
+
+    /// The first control word of the group, after the \* of an ignorable destination.
+    /// It's the destination of the groups like {\fonttbl ...} or {\*\bkmkstart ...}.
+    pub fn destination(&self) -> Option<&ControlWord<'a>> {
+        let first_node = if self.is_ignorable() { self.nodes.get(1) } else { self.nodes.first() };
+        return match first_node {
+            Some(RtfNode::ControlWord((control_word, _))) => Some(control_word),
+            _ => None,
+        };
+    }
+
+    /// The groups directly in this group
+    pub fn groups(&self) -> impl Iterator<Item = &GroupTree<'a>> {
+        return self.nodes.iter().filter_map(|node| match node {
+            RtfNode::Group(group) => Some(group),
+            _ => None,
+        });
+    }
+
+    /// Find the first group of the tree (depth-first) with the given destination
+    pub fn find_destination(&self, destination: &ControlWord) -> Option<&GroupTree<'a>> {
+        let mut stack: Vec<Iter<RtfNode<'a>>> = vec![self.nodes.iter()];
+        while let Some(nodes) = stack.last_mut() {
+            match nodes.next() {
+                Some(RtfNode::Group(group)) if group.destination() == Some(destination) => return Some(group),
+                Some(RtfNode::Group(group)) => stack.push(group.nodes.iter()),
+                Some(_) => {}
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        return None;
+    }
+
+    /// The plain text of the group and its nested groups, with no interpretation of the control words
+    pub fn get_text(&self) -> String {
+        let mut text = String::new();
+        let mut stack: Vec<Iter<RtfNode<'a>>> = vec![self.nodes.iter()];
+        while let Some(nodes) = stack.last_mut() {
+            match nodes.next() {
+                Some(RtfNode::Group(group)) => stack.push(group.nodes.iter()),
+                Some(RtfNode::Text(node_text)) => text.push_str(node_text),
+                Some(_) => {}
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        return text;
+    }
+}
+
+// The derived drop would recurse in the nested groups : they are moved to a stack to be dropped one by one
+impl Drop for GroupTree<'_> {
+    fn drop(&mut self) {
+        let mut groups = vec![mem::take(&mut self.nodes)];
+        while let Some(mut nodes) = groups.pop() {
+            for node in &mut nodes {
+                if let RtfNode::Group(group) = node {
+                    groups.push(mem::take(&mut group.nodes));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::tokens::Property;
+
+    #[test]
+    fn build_group_tree() {
+        let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}{\*\bkmkstart mark}\f0 Some {\b bold} text\par}";
+        let tokens = Lexer::scan(rtf).unwrap();
+        let tree = GroupTree::from_tokens(&tokens).unwrap();
+        assert_eq!(tree.nodes.len(), 1);
+        let RtfNode::Group(document) = &tree.nodes[0] else {
+            panic!("The document is not a group");
+        };
+        assert_eq!(document.destination(), Some(&ControlWord::Rtf));
+        assert_eq!(document.nodes[1], RtfNode::ControlWord((ControlWord::Ansi, Property::None)));
+        let font_table = tree.find_destination(&ControlWord::FontTable).unwrap();
+        assert_eq!(font_table.get_text(), "Helvetica;");
+        let bookmark = tree.find_destination(&ControlWord::BookmarkStart).unwrap();
+        assert!(bookmark.is_ignorable());
+        assert_eq!(document.groups().count(), 3);
+        assert_eq!(tree.to_tokens(), tokens);
+    }
+
+    #[test]
+    fn walk_deep_tree() {
+        let depth = 200_000;
+        let mut tokens = vec![Token::OpeningBracket; depth];
+        tokens.push(Token::ControlSymbol((ControlWord::FontTable, Property::None)));
+        tokens.push(Token::PlainText("deep"));
+        tokens.extend(vec![Token::ClosingBracket; depth]);
+        let tree = GroupTree::from_tokens(&tokens).unwrap();
+        assert_eq!(tree.get_text(), "deep");
+        assert_eq!(tree.find_destination(&ControlWord::FontTable).map(GroupTree::get_text).as_deref(), Some("deep"));
+        assert_eq!(tree.to_tokens(), tokens);
+        drop(tree);
+    }
+
+    #[test]
+    fn reject_unbalanced_brackets() {
+        let unclosed = [Token::OpeningBracket, Token::OpeningBracket, Token::ClosingBracket];
+        assert!(matches!(GroupTree::from_tokens(&unclosed), Err(ParserError::MalformedPainterStack)));
+        assert!(matches!(GroupTree::from_tokens(&[Token::ClosingBracket]), Err(ParserError::MalformedPainterStack)));
+    }
+}
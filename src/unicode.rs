@@ -0,0 +1,180 @@
+/// Define the decoding of the unicode chars of the body (\uN and their fallback), and the normalization of the text
+#[cfg(feature = "unicode-normalization")]
+use alloc::string::String;
+
+use crate::error::ParserError;
+use crate::tokens::Property;
+
+/// Decoder of the \uN control words : it accumulates the UTF-16 surrogate pairs, which may be split across groups,
+/// and skips the fallback chars following each \uN as defined by \ucN.
+/// The fallback is counted in the decoded chars (text, \'hh bytes and chars of control words as \tab), whatever the control words
+/// and the nested groups between them, and ends with the group of its \uN.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnicodeDecoder {
+    // High surrogate waiting for its low surrogate
+    pending_surrogate: Option<u16>,
+    // Number of fallback chars left to skip
+    skip_count: usize,
+    // Number of groups opened since the last \uN, inside of which the fallback goes on
+    nested_groups: usize,
+}
+
+impl UnicodeDecoder {
+    /// Decode the value of a \uN control word, `ignore_count` being the current \ucN
+    /// Returns None if the value is a high surrogate, which is completed by the next \uN
+    pub fn decode(&mut self, property: &Property, ignore_count: i32) -> Result<Option<char>, ParserError> {
+        // \u without parameter is ignored
+        if *property == Property::None {
+            return Ok(None);
+        }
+        let unit = property.get_unicode_value()?;
+        self.skip_count = ignore_count.max(0) as usize;
+        self.nested_groups = 0;
+        let char = match (self.pending_surrogate.take(), unit) {
+            (None, 0xD800..=0xDBFF) => {
+                self.pending_surrogate = Some(unit);
+                return Ok(None);
+            }
+            (Some(high), 0xDC00..=0xDFFF) => char::decode_utf16([high, unit]).next(),
+            (None, unit) => char::decode_utf16([unit]).next(),
+            // A high surrogate must be followed by a low surrogate
+            (Some(high), _) => return Err(ParserError::UnicodeParsingError(high as i32)),
+        };
+        #[cfg(feature = "tracing")]
+        tracing::trace!(unit, ?char, skip_count = self.skip_count, "unicode decoded");
+        return match char {
+            Some(Ok(char)) => Ok(Some(char)),
+            _ => Err(ParserError::UnicodeParsingError(unit as i32)),
+        };
+    }
+
+    pub fn begin_group(&mut self) {
+        if self.skip_count > 0 {
+            self.nested_groups += 1;
+        }
+    }
+
+    /// The fallback chars of a \uN don't go past the end of its group, unlike a pending surrogate
+    pub fn end_group(&mut self) {
+        if self.nested_groups > 0 {
+            self.nested_groups -= 1;
+        } else {
+            self.skip_count = 0;
+        }
+    }
+
+    /// Skip a char written by a \'hh byte or a control word (\tab, \~, ...) if it's part of the fallback of the last \uN
+    /// The bytes are counted before being paired in a char, as the fallback is counted in bytes
+    pub fn skip_char(&mut self) -> Result<bool, ParserError> {
+        return Ok(self.skip_fallback("?")?.is_empty());
+    }
+
+    /// Skip the beginning of the text if it's the fallback of the last \uN, and return the remaining text
+    pub fn skip_fallback<'a>(&mut self, text: &'a str) -> Result<&'a str, ParserError> {
+        let skipped = text.char_indices().nth(self.skip_count).map_or(text.len(), |(index, _)| index);
+        self.skip_count = self.skip_count.saturating_sub(text[..skipped].chars().count());
+        let text = &text[skipped..];
+        // A high surrogate must be followed by a low surrogate
+        if let Some(high) = self.pending_surrogate.filter(|_| !text.is_empty()) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(high, "unpaired high surrogate");
+            return Err(ParserError::UnicodeParsingError(high as i32));
+        }
+        return Ok(text);
+    }
+}
+
+/// Normalize the text to the Unicode Normalization Form C (NFC) : the decomposed sequences are composed (e + U+0301 is é),
+/// so the same text written with \'hh bytes or with \uN control words compares equal
+#[cfg(feature = "unicode-normalization")]
+pub fn to_nfc(text: &str) -> String {
+    use unicode_normalization::UnicodeNormalization;
+
+    return text.nfc().collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "parser")]
+    use crate::document::RtfDocument;
+
+    #[test]
+    fn decode_surrogate_pairs() {
+        let mut decoder = UnicodeDecoder::default();
+        assert_eq!(decoder.decode(&Property::Value(-10179), 1).unwrap(), None);
+        assert_eq!(decoder.skip_fallback("?").unwrap(), "");
+        assert_eq!(decoder.decode(&Property::Value(-9089), 1).unwrap(), Some('👿'));
+        assert_eq!(decoder.skip_fallback("?text").unwrap(), "text");
+        // Lone surrogates
+        assert!(decoder.decode(&Property::Value(-9089), 1).is_err());
+        assert_eq!(decoder.decode(&Property::Value(-10179), 1).unwrap(), None);
+        assert!(decoder.skip_fallback("text").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn skip_fallback_of_any_kind() {
+        // The fallback can be plain text, an escaped char or a \'xx byte, and the surrogates can be split across groups
+        let rtf = r"{\rtf1\ansi {\uc1\u233 ?t\u233\{t\uc2\u233\'e9\'e9 t}{\u-10179?}{\u-9089?}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "étété t👿");
+        assert!(RtfDocument::try_from(r"{\rtf1\ansi \u-10179 ? text}").is_err());
+        // The fallback count is reset at the end of the group
+        assert_eq!(RtfDocument::try_from(r"{\rtf1\ansi {\u233}abc}").unwrap().get_text(), "éabc");
+        assert_eq!(RtfDocument::try_from(r"{\rtf1\ansi \uc0{\uc3\u233}abcd}").unwrap().get_text(), "éabcd");
+    }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn skip_fallback_across_control_words_and_groups() {
+        #[rustfmt::skip]
+        let texts = [
+            // Outlook writes the surrogates with their fallbacks separated by control words and groups
+            (r"{\rtf1\ansi\uc1{\u-10179\f1\'5f}{\f2\u-9089\'5f}end}",     "👿end"),
+            (r"{\rtf1\ansi\uc1\u-10179\loch\f1\'5f\hich\u-9089\'5f end}",   "👿 end"),
+            // The fallback goes on in the nested groups, but ends with the group of its \uN
+            (r"{\rtf1\ansi\uc2\u8217{\b}\'92{\i\'92}end}",                   "’end"),
+            (r"{\rtf1\ansi{\uc2\u8217{\b\'92}}\'92end}",                      "’’end"),
+            // The chars of the control words are part of the fallback
+            (r"{\rtf1\ansi\u8212\emdash\tab end\u160\~\line}",               "—end\u{a0}\n"),
+            (r"{\rtf1\ansi\uc2\u8211\~\'96 end}",                             "– end"),
+        ];
+        for (rtf, text) in texts {
+            assert_eq!(RtfDocument::try_from(rtf).unwrap().get_text(), text, "{rtf}");
+            assert_eq!(crate::extract_text(rtf).unwrap(), text, "{rtf}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-normalization")]
+    fn normalize_to_nfc() {
+        assert_eq!(to_nfc("Cafe\u{301} cre\u{300}me"), "Café crème");
+        assert_eq!(to_nfc("plain text"), "plain text");
+        // The combining marks are ordered by class, the ones which can't be composed are kept
+        assert_eq!(to_nfc("a\u{323}\u{302}"), "\u{1ead}");
+        assert_eq!(to_nfc("a\u{302}\u{323}"), "\u{1ead}");
+        assert_eq!(to_nfc("q\u{307}\u{323}"), "q\u{323}\u{307}");
+        assert_eq!(to_nfc("e\u{301}\u{301}"), "\u{e9}\u{301}");
+        // The composed chars are decomposed first, the singletons are replaced and the composition exclusions are not composed
+        assert_eq!(to_nfc("\u{212b}\u{1e0b}\u{323}"), "\u{c5}\u{1e0d}\u{307}");
+        assert_eq!(to_nfc("\u{958}"), "\u{915}\u{93c}");
+        // Hangul
+        assert_eq!(to_nfc("\u{1112}\u{1161}\u{11ab}\u{1100}\u{1173}\u{11af}"), "한글");
+        assert_eq!(to_nfc("\u{d55c}\u{ae00}"), "한글");
+    }
+
+    #[test]
+    #[cfg(all(feature = "parser", feature = "unicode-normalization"))]
+    fn normalize_document_text() {
+        // The same text written with a codepage byte or a combining char
+        let rtf = r"{\rtf1\ansi\ansicpg1252 Caf\'e9 or Cafe\u769 ?}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        assert_eq!(document.get_text(), "Café or Cafe\u{301}");
+        let options = crate::document::TextOptions {
+            normalize_unicode: true,
+            ..crate::document::TextOptions::default()
+        };
+        assert_eq!(document.get_text_with_options(&options), "Café or Café");
+    }
+}
@@ -0,0 +1,116 @@
+/// Define the units of the lengths of the document
+use core::fmt;
+use core::ops::{Add, Neg, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+/// A length in twips, the unit of most lengths of RTF : 20 twips make a point, 1440 an inch.
+/// It's serialized as a number of twips.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(transparent))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Twips(pub i32);
+
+impl Twips {
+    pub const PER_POINT: i32 = 20;
+    pub const PER_INCH: i32 = 1440;
+
+    pub fn from_points(points: f32) -> Self {
+        return Self(round(points * Self::PER_POINT as f32));
+    }
+
+    pub fn from_inches(inches: f32) -> Self {
+        return Self(round(inches * Self::PER_INCH as f32));
+    }
+
+    pub fn from_millimeters(millimeters: f32) -> Self {
+        return Self::from_inches(millimeters / 25.4);
+    }
+
+    pub fn to_points(self) -> f32 {
+        return self.0 as f32 / Self::PER_POINT as f32;
+    }
+
+    pub fn to_inches(self) -> f32 {
+        return self.0 as f32 / Self::PER_INCH as f32;
+    }
+
+    pub fn to_millimeters(self) -> f32 {
+        return self.to_inches() * 25.4;
+    }
+
+    /// The length in pixels on a screen of `dpi` pixels per inch (ex: 96 for the CSS pixels)
+    pub fn to_pixels(self, dpi: f32) -> f32 {
+        return self.to_inches() * dpi;
+    }
+
+    pub fn abs(self) -> Self {
+        return Self(self.0.abs());
+    }
+}
+
+// Round half away from zero, as f32::round which is not in core
+fn round(value: f32) -> i32 {
+    return (value + if value < 0.0 { -0.5 } else { 0.5 }) as i32;
+}
+
+impl From<i32> for Twips {
+    fn from(value: i32) -> Self {
+        return Self(value);
+    }
+}
+
+impl Add for Twips {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        return Self(self.0 + other.0);
+    }
+}
+
+impl Sub for Twips {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        return Self(self.0 - other.0);
+    }
+}
+
+impl Neg for Twips {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        return Self(-self.0);
+    }
+}
+
+/// Write the number of twips, as in RTF (ex: \li720)
+impl fmt::Display for Twips {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_twips() {
+        assert_eq!(Twips(1440).to_inches(), 1.0);
+        assert_eq!(Twips(240).to_points(), 12.0);
+        assert_eq!(Twips(1440).to_pixels(96.0), 96.0);
+        assert!((Twips(567).to_millimeters() - 10.0).abs() < 0.01);
+        assert_eq!(Twips::from_millimeters(25.4), Twips(1440));
+        assert_eq!(Twips::from_points(-18.0), Twips(-360));
+        assert_eq!(Twips::from_points(0.025), Twips(1));
+        assert_eq!(Twips::from_points(-0.025), Twips(-1));
+        assert_eq!(Twips::from_points(0.02), Twips(0));
+        assert_eq!(Twips::from_inches(0.5) + Twips(20) - Twips(40), Twips(700));
+        assert_eq!(-Twips(360), Twips(-360));
+        assert_eq!(Twips(720).to_string(), "720");
+    }
+}
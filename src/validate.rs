@@ -0,0 +1,87 @@
+/// Define the validation of the references of the body to the tables of the header
+use alloc::vec::Vec;
+
+use crate::document::RtfDocument;
+use crate::header::{ColorRef, FontRef, StyleRef};
+
+/// A reference to an entry missing from a table of the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DanglingRef {
+    Font(FontRef),
+    Color(ColorRef),
+    UnderlineColor(ColorRef),
+    Style(StyleRef),
+    CharacterStyle(StyleRef),
+}
+
+/// A dangling reference of a style block, found by `RtfDocument::validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidReference {
+    /// The index of the style block in the body
+    pub block_index: usize,
+    pub reference: DanglingRef,
+}
+
+impl RtfDocument {
+    /// Report the style blocks referencing fonts, colors or styles missing from the header tables.
+    /// The automatic color (\cf0), the Normal style (\s0) and the default font of a document without font table are not reported.
+    /// To replace the dangling references while parsing, use `ParserOptions::clamp_invalid_refs`.
+    pub fn validate(&self) -> Vec<InvalidReference> {
+        let header = &self.header;
+        let mut invalid_references = Vec::new();
+        for (block_index, style_block) in self.body.iter().enumerate() {
+            let painter = &style_block.painter;
+            let is_default_font = header.font_table.is_empty() && painter.font_ref == header.defaults.font_ref;
+            let references = [
+                (!is_default_font && !header.font_table.contains_key(&painter.font_ref)).then_some(DanglingRef::Font(painter.font_ref)),
+                (painter.color_ref != 0 && header.get_color(&painter.color_ref).is_none()).then_some(DanglingRef::Color(painter.color_ref)),
+                (painter.underline_color != 0 && header.get_color(&painter.underline_color).is_none()).then_some(DanglingRef::UnderlineColor(painter.underline_color)),
+                (style_block.paragraph.style != 0 && !header.stylesheet.contains_key(&style_block.paragraph.style)).then_some(DanglingRef::Style(style_block.paragraph.style)),
+                painter
+                    .char_style_ref
+                    .filter(|style_ref| !header.character_styles.contains_key(style_ref))
+                    .map(DanglingRef::CharacterStyle),
+            ];
+            invalid_references.extend(references.into_iter().flatten().map(|reference| InvalidReference { block_index, reference }));
+        }
+        return invalid_references;
+    }
+
+    /// Whether all the references of the body are in the header tables
+    pub fn is_valid(&self) -> bool {
+        return self.validate().is_empty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::{Parser, ParserOptions, ParserWarning};
+
+    #[test]
+    fn validate_references() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0\fswiss Helvetica;}}{\colortbl;\red255\green0\blue0;}{\stylesheet{\s1 heading 1;}}
+            \f0\cf1 Valid {\s1 heading}\par {\f3 font}{\cf2 color}{\ulc4\ul under}{\s7 style}}";
+        let document = RtfDocument::try_from(rtf).unwrap();
+        let references: Vec<DanglingRef> = document.validate().iter().map(|invalid| invalid.reference).collect();
+        assert_eq!(references, [DanglingRef::Font(3), DanglingRef::Color(2), DanglingRef::UnderlineColor(4), DanglingRef::Style(7)]);
+        assert!(document.validate().iter().all(|invalid| document.body[invalid.block_index].text != "Valid "));
+        assert!(!document.is_valid());
+        assert!(RtfDocument::try_from(r"{\rtf1\ansi\deff2 No font table\par}").unwrap().is_valid());
+        // The dangling references are replaced by the defaults, and still reported as warnings
+        let options = ParserOptions {
+            clamp_invalid_refs: true,
+            ..ParserOptions::default()
+        };
+        let (document, warnings) = Parser::new(Lexer::scan(rtf).unwrap()).with_options(options).parse_with_warnings().unwrap();
+        assert!(document.is_valid());
+        assert_eq!(document.get_text(), "Valid headingfontcolorunderstyle");
+        assert_eq!(
+            warnings,
+            vec![ParserWarning::InvalidFontRef(3), ParserWarning::InvalidColorRef(2), ParserWarning::InvalidColorRef(4), ParserWarning::InvalidStyleRef(7)]
+        );
+    }
+}